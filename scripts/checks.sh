@@ -1,3 +1,3 @@
 #/usr/bin/env sh
 
-cargo fmt --check && cargo clippy && cargo test
+cargo fmt --check && cargo clippy && cargo clippy --all-features && cargo test && cargo test --all-features
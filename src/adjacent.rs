@@ -0,0 +1,108 @@
+// Keeping characters of a charset apart, e.g. no two symbols next to each other. Like the
+// position rules this only covers the random part, the affixes are fixed.
+//
+// The restricted characters are spread over slots that don't touch, picked uniformly among all
+// such placements, rather than reshuffling until the password happens to follow the rule. When
+// several charsets are kept apart or there are position rules as well the other rules are
+// checked afterwards and the characters drawn again if they don't hold.
+use rand::{seq::index, Rng};
+
+use crate::{
+    charset::Charset,
+    password::{GenerateError, PasswordSpec},
+};
+
+// most characters that fit in `length` slots without two of them touching
+pub(crate) fn most_apart(length: usize) -> usize {
+    (length + 1) / 2
+}
+
+impl PasswordSpec {
+    // no two characters from the charset may be next to each other
+    // kept sorted like the choices, so the order these are given in doesn't matter
+    pub fn no_adjacent_from(mut self, charset: Charset) -> Self {
+        let charset = charset.normalized(self.normalization);
+        if let Err(i) = self.no_adjacent.binary_search(&charset) {
+            self.no_adjacent.insert(i, charset);
+        }
+        self
+    }
+
+    // fewest characters of the charset every password has, from the choices drawing only from it
+    fn forced_count(&self, charset: &Charset) -> usize {
+        self.choices()
+            .filter(|choice| {
+                let pool = self.pool(choice);
+                !pool.is_empty() && pool.iter().all(|c| charset.contains(*c))
+            })
+            .fold(0, |sum, choice| sum.saturating_add(choice.min))
+    }
+
+    // whether the counts alone already rule out keeping the charsets apart
+    pub(crate) fn check_adjacency(&self, length: usize) -> Result<(), GenerateError> {
+        for charset in &self.no_adjacent {
+            let count = self.forced_count(charset);
+            if count > most_apart(length) {
+                return Err(GenerateError::AdjacencyInfeasible {
+                    charset: charset.clone(),
+                    count,
+                    length,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // positions in the random part, from 0, of characters right after one from the same
+    // restricted charset
+    pub(crate) fn adjacency_breaks(&self, core: &str) -> Vec<(usize, Charset)> {
+        let chars: Vec<char> = core.chars().collect();
+        let mut breaks = vec![];
+        for charset in &self.no_adjacent {
+            for (i, pair) in chars.windows(2).enumerate() {
+                if charset.contains(pair[0]) && charset.contains(pair[1]) {
+                    breaks.push((i + 1, charset.clone()));
+                }
+            }
+        }
+        breaks.sort_by_key(|(i, _)| *i);
+        breaks
+    }
+}
+
+// move the characters from the charset into slots that don't touch, keeping the order of both
+// the restricted and the other characters, `None` when there are too many of them
+pub(crate) fn spread<T, R: Rng + ?Sized>(
+    charset: &Charset,
+    drawn: Vec<(char, T)>,
+    rng: &mut R,
+) -> Option<Vec<(char, T)>> {
+    let length = drawn.len();
+    let (restricted, others): (Vec<_>, Vec<_>) =
+        drawn.into_iter().partition(|(c, _)| charset.contains(*c));
+    let count = restricted.len();
+    if count > most_apart(length) {
+        return None;
+    }
+    // choosing `count` of `length - count + 1` gaps between the other characters and shifting
+    // each by the ones before it gives every placement without neighbours equally often
+    let mut gaps = index::sample(rng, length - count + 1, count).into_vec();
+    gaps.sort_unstable();
+    let mut slots = gaps
+        .into_iter()
+        .enumerate()
+        .map(|(i, gap)| gap + i)
+        .peekable();
+    let mut restricted = restricted.into_iter();
+    let mut others = others.into_iter();
+    let mut arranged = Vec::with_capacity(length);
+    for position in 0..length {
+        if slots.peek() == Some(&position) {
+            slots.next();
+            arranged.push(restricted.next()?);
+        } else {
+            arranged.push(others.next()?);
+        }
+    }
+    Some(arranged)
+}
@@ -0,0 +1,233 @@
+// Bending a spec to fit a length instead of failing on it, e.g. for a length slider in a UI.
+// The adjustments are deterministic, the same spec and length always give the same result.
+use std::fmt::Display;
+
+use rand::Rng;
+
+use crate::{
+    charset::Charset,
+    choice::{Choice, Choices},
+    interval::Interval,
+    password::{Length, PasswordSpec},
+};
+
+// one change made while fitting a spec to a length
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Adjustment {
+    LoweredMin {
+        charset: Charset,
+        from: usize,
+        to: usize,
+    },
+    RaisedMax {
+        charset: Charset,
+        from: usize,
+        to: usize,
+    },
+    // the spec had no choices to raise, so one was added to fill the length
+    AddedFiller(Charset),
+}
+
+impl Display for Adjustment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Adjustment::LoweredMin { charset, from, to } => {
+                write!(
+                    f,
+                    "lowered the minimum of {} from {} to {}",
+                    charset, from, to
+                )
+            }
+            Adjustment::RaisedMax { charset, from, to } => {
+                write!(
+                    f,
+                    "raised the maximum of {} from {} to {}",
+                    charset, from, to
+                )
+            }
+            Adjustment::AddedFiller(charset) => write!(f, "added {} to fill the length", charset),
+        }
+    }
+}
+
+// one change `generate_best_effort` made to get a password out of a spec that couldn't give one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Relaxation {
+    RaisedLength { from: usize, to: usize },
+    // a change to the choices made at the spec's own length
+    Adjusted(Adjustment),
+    // nothing else gave a spec that generates, so the default spec was used instead
+    UsedDefault,
+}
+
+impl Display for Relaxation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Relaxation::RaisedLength { from, to } => {
+                write!(f, "increased the length from {} to {}", from, to)
+            }
+            Relaxation::Adjusted(adjustment) => write!(f, "{}", adjustment),
+            Relaxation::UsedDefault => write!(f, "used the default spec instead"),
+        }
+    }
+}
+
+// how far `generate_best_effort` raises the length before changing the choices instead, as a
+// multiple of the spec's length
+const MAX_LENGTH_FACTOR: usize = 2;
+
+// the charset added by `tighten_to_length` when there is nothing else to fill the length with
+const FILLER: Charset = Charset::Lower;
+
+fn set_min(choice: &mut Choice, min: usize) {
+    choice.min = min;
+    if let Some(first) = choice.union.first_mut() {
+        first.min = min;
+    }
+}
+
+fn set_max(choice: &mut Choice, max: usize) {
+    choice.max = max;
+    if let Some(last) = choice.union.last_mut() {
+        last.max = max;
+    }
+}
+
+impl PasswordSpec {
+    fn with_choices(&self, length: usize, choices: Vec<Choice>) -> PasswordSpec {
+        let mut spec = self.clone().length(length);
+        spec.choices = Choices::from(choices);
+        spec
+    }
+
+    // the spec with this length, and the minimums lowered until they fit in it
+    // a character at a time comes off the largest minimum, ties going to the first choice in
+    // spec order, so big requirements shrink first and small ones are kept as long as possible
+    // gaps between the intervals of a union aren't filled in, so the result can still be
+    // infeasible for those
+    pub fn relax_to_length(&self, length: usize) -> (PasswordSpec, Vec<Adjustment>) {
+        let relaxed = self.clone().length(length);
+        let core = relaxed.resolve_length().unwrap_or(0);
+        let mut choices: Vec<Choice> = self.choices().cloned().collect();
+        let original: Vec<usize> = choices.iter().map(|choice| choice.min).collect();
+        let mut total = choices
+            .iter()
+            .fold(Interval::exactly(0), |total, choice| {
+                total.add(&choice.interval())
+            })
+            .min;
+        while total > core {
+            let largest = choices
+                .iter_mut()
+                .rev()
+                .max_by_key(|choice| choice.min)
+                .filter(|choice| choice.min > 0);
+            match largest {
+                Some(choice) => {
+                    let min = choice.min - 1;
+                    set_min(choice, min);
+                    total -= 1;
+                }
+                None => break,
+            }
+        }
+        let changes = choices
+            .iter()
+            .zip(original)
+            .filter(|(choice, from)| choice.min != *from)
+            .map(|(choice, from)| Adjustment::LoweredMin {
+                charset: choice.chars.clone(),
+                from,
+                to: choice.min,
+            })
+            .collect();
+        (self.with_choices(length, choices), changes)
+    }
+
+    // the spec with this length, and the maximums raised until they can fill it
+    // the whole shortfall goes to the choice with the largest charset, ties going to the first
+    // choice in spec order, a spec without choices gets `:lower:` added
+    pub fn tighten_to_length(&self, length: usize) -> (PasswordSpec, Vec<Adjustment>) {
+        let tightened = self.clone().length(length);
+        let core = tightened.resolve_length().unwrap_or(0);
+        let mut choices: Vec<Choice> = self.choices().cloned().collect();
+        let total = choices
+            .iter()
+            .fold(Interval::exactly(0), |total, choice| {
+                total.add(&choice.interval())
+            })
+            .max;
+        let mut changes = vec![];
+        if total < core {
+            let shortfall = core - total;
+            let widest = choices
+                .iter_mut()
+                .rev()
+                .max_by_key(|choice| choice.chars.len());
+            match widest {
+                Some(choice) => {
+                    let from = choice.max;
+                    set_max(choice, from + shortfall);
+                    changes.push(Adjustment::RaisedMax {
+                        charset: choice.chars.clone(),
+                        from,
+                        to: choice.max,
+                    });
+                }
+                None => {
+                    choices.push(FILLER.at_least(0));
+                    changes.push(Adjustment::AddedFiller(FILLER));
+                }
+            }
+        }
+        (self.with_choices(length, choices), changes)
+    }
+
+    // a password even when the spec can't give one, with what was changed to get it
+    // a spec that generates gives the same password `generate_with_rng` does and no changes,
+    // otherwise these are tried in order until one generates:
+    // - the smallest length that fits, up to `MAX_LENGTH_FACTOR` times the spec's length
+    // - the spec's length with the minimums relaxed and the maximums tightened to fit it, see
+    //   `relax_to_length` and `tighten_to_length`
+    // - the default spec
+    pub fn generate_best_effort<R: Rng + ?Sized>(&self, rng: &mut R) -> (String, Vec<Relaxation>) {
+        if let Ok(password) = self.generate_once(rng) {
+            return (password, vec![]);
+        }
+        let length = match self.length {
+            Length::Chars(length) => Some(length),
+            // the length the bits come to, when they're reachable at all
+            Length::Bits(_) => self.resolve_length().ok().map(|core| {
+                if self.affixes_in_length {
+                    core + self.prefix.chars().count() + self.suffix.chars().count()
+                } else {
+                    core
+                }
+            }),
+        };
+        if let Some(from) = length {
+            let longest = from.saturating_mul(MAX_LENGTH_FACTOR);
+            let raised = self
+                .feasible_lengths(from..=longest)
+                .into_iter()
+                .find(|to| *to > from);
+            if let Some(to) = raised {
+                if let Ok(password) = self.clone().length(to).generate_once(rng) {
+                    return (password, vec![Relaxation::RaisedLength { from, to }]);
+                }
+            }
+            let (relaxed, mut changes) = self.relax_to_length(from);
+            let (adjusted, raised) = relaxed.tighten_to_length(from);
+            changes.extend(raised);
+            if let Ok(password) = adjusted.generate_once(rng) {
+                let relaxations = changes.into_iter().map(Relaxation::Adjusted).collect();
+                return (password, relaxations);
+            }
+        }
+        let password = PasswordSpec::default()
+            .generate_once(rng)
+            .or_else(|_| PasswordSpec::builtin_default().generate_once(rng))
+            .expect("the built in default spec always generates");
+        (password, vec![Relaxation::UsedDefault])
+    }
+}
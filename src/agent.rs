@@ -0,0 +1,175 @@
+// A long running process handing out passwords over a Unix socket, for tools that need so many
+// that starting a process for each one is the slow part.
+//
+// The protocol is one line per request and one line per response: the client sends a spec string,
+// or `default` for the default spec, and gets back a password or `error: <message>`.
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use rand::Rng;
+use thiserror::Error;
+
+use crate::password::PasswordSpec;
+
+#[derive(Debug, Error)]
+pub enum AgentError {
+    #[error("Socket path `{}` {1}", .0.display())]
+    BadPath(PathBuf, &'static str),
+    #[error("{0}")]
+    Io(std::io::Error),
+    #[error("The agent needs Unix domain sockets, which this platform doesn't have")]
+    Unsupported,
+}
+
+// the response line to one request line, without the newline
+pub fn respond<R: Rng + ?Sized>(request: &str, rng: &mut R) -> String {
+    let spec = match request.trim() {
+        "" => return "error: empty request, expect a spec or `default`".to_string(),
+        "default" => Ok(PasswordSpec::default()),
+        spec => spec.parse::<PasswordSpec>(),
+    };
+    match spec {
+        Ok(spec) => match spec.try_generate_with_rng(rng) {
+            Ok(password) => password,
+            Err(e) => error_line(&e),
+        },
+        Err(e) => error_line(&e),
+    }
+}
+
+// messages are single lines, but a stray newline would desync the client
+fn error_line(e: &dyn std::fmt::Display) -> String {
+    format!("error: {}", e.to_string().replace('\n', " "))
+}
+
+// answer requests from `input` on `output` until `input` ends, returns how many were answered
+pub fn handle<I: BufRead, O: Write, R: Rng + ?Sized>(
+    input: I,
+    mut output: O,
+    rng: &mut R,
+) -> std::io::Result<usize> {
+    let mut answered = 0;
+    for line in input.lines() {
+        writeln!(output, "{}", respond(&line?, rng))?;
+        output.flush()?;
+        answered += 1;
+    }
+    Ok(answered)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Agent {
+    socket: PathBuf,
+    once: bool,
+    idle_timeout: Option<Duration>,
+}
+
+impl Agent {
+    pub fn new(socket: impl Into<PathBuf>) -> Self {
+        Self {
+            socket: socket.into(),
+            once: false,
+            idle_timeout: None,
+        }
+    }
+
+    // stop after the first connection closes
+    pub fn once(mut self, once: bool) -> Self {
+        self.once = once;
+        self
+    }
+
+    // stop after this long without a connection, and drop connections idle for this long
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    // the directory has to exist and the path can't be taken by anything but a leftover socket,
+    // which is removed
+    #[cfg(unix)]
+    fn prepare_path(&self) -> Result<(), AgentError> {
+        use std::path::Path;
+
+        let bad = |reason| AgentError::BadPath(self.socket.clone(), reason);
+        let parent = self.socket.parent().unwrap_or(Path::new("."));
+        let parent = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        if !parent.is_dir() {
+            return Err(bad("is in a directory that doesn't exist"));
+        }
+        match std::fs::symlink_metadata(&self.socket) {
+            Err(_) => Ok(()),
+            Ok(metadata) if is_socket(&metadata) => {
+                std::fs::remove_file(&self.socket).map_err(AgentError::Io)
+            }
+            Ok(_) => Err(bad("already exists and isn't a socket")),
+        }
+    }
+
+    // listen on the socket, which only the owner can connect to, and answer connections one at a
+    // time until `once` or the idle timeout stops it, the socket file is removed when done
+    #[cfg(unix)]
+    pub fn serve<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<(), AgentError> {
+        use std::io::BufReader;
+        use std::os::unix::{fs::PermissionsExt, net::UnixListener};
+        use std::time::Instant;
+
+        self.prepare_path()?;
+        let listener = UnixListener::bind(&self.socket).map_err(AgentError::Io)?;
+        let result = (|| {
+            // there's no portable way to bind with these permissions, for no window at all put
+            // the socket in a directory only the owner can get into, like `$XDG_RUNTIME_DIR`
+            std::fs::set_permissions(&self.socket, std::fs::Permissions::from_mode(0o600))
+                .map_err(AgentError::Io)?;
+            // polled so the idle timeout can be noticed between connections
+            listener.set_nonblocking(true).map_err(AgentError::Io)?;
+            let mut last_active = Instant::now();
+            loop {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        stream.set_nonblocking(false).map_err(AgentError::Io)?;
+                        stream
+                            .set_read_timeout(self.idle_timeout)
+                            .map_err(AgentError::Io)?;
+                        let reader = BufReader::new(&stream);
+                        // a client going away or going quiet only ends its own connection
+                        let _ = handle(reader, &stream, rng);
+                        if self.once {
+                            return Ok(());
+                        }
+                        last_active = Instant::now();
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        if let Some(timeout) = self.idle_timeout {
+                            if last_active.elapsed() >= timeout {
+                                return Ok(());
+                            }
+                        }
+                        std::thread::sleep(Duration::from_millis(10));
+                    }
+                    Err(e) => return Err(AgentError::Io(e)),
+                }
+            }
+        })();
+        let _ = std::fs::remove_file(&self.socket);
+        result
+    }
+
+    #[cfg(not(unix))]
+    pub fn serve<R: Rng + ?Sized>(&self, _rng: &mut R) -> Result<(), AgentError> {
+        Err(AgentError::Unsupported)
+    }
+}
+
+#[cfg(unix)]
+fn is_socket(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+    metadata.file_type().is_socket()
+}
@@ -0,0 +1,314 @@
+//! The stable surface for applications embedding the generator, such as the pants vault.
+//!
+//! Everything outside this module can change shape between releases as the spec representation
+//! does. A [`Policy`] is its own type rather than a [`PasswordSpec`], so policies an application
+//! has stored keep loading and generating the same kind of password whatever happens to the spec
+//! internally. Changes to `Policy` are additions that older policies can do without, and
+//! [`POLICY_VERSION`] goes up with each one. With the `json` feature a policy (de)serializes with
+//! serde, named classes as `"upper"` and custom ones as `{"custom": "!@#"}`.
+//!
+//! ```rust
+//! use pants_gen::api::{self, CharClass, ClassRequirement, Policy};
+//!
+//! let mut policy = Policy::new(16);
+//! policy.classes.push(ClassRequirement::at_least(CharClass::Upper, 1));
+//! policy.classes.push(ClassRequirement::at_least(CharClass::Lower, 1));
+//! policy.exclude = "O".to_string();
+//! let password = api::generate(&policy)?;
+//! assert_eq!(password.expose().len(), 16);
+//! assert!(!password.expose().contains('O'));
+//!
+//! let imported = Policy::from_spec_string("12//2+|:upper://1+|:number:")?;
+//! assert_eq!(imported.to_spec()?.to_string(), "12//2+|:upper://1+|:number:");
+//! # Ok::<(), api::Error>(())
+//! ```
+use std::path::PathBuf;
+
+use rand::{thread_rng, Rng};
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    charset::Charset,
+    choice::Choice,
+    interval::Interval,
+    password::{GenerateError, PasswordParseError, PasswordSpec},
+    secret::Secret,
+    units::LengthUnit,
+    wordlist::{Wordlist, WordlistError},
+};
+
+// the version of the policy format this generator writes and understands
+// 2 added the `number-no-zero` class, and `number` went from 1-9 to 0-9 with it, version 1
+// policies keep getting 1-9
+pub const POLICY_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Policy {
+    // policies saved before versions were written are version 1
+    #[cfg_attr(feature = "json", serde(default = "first_version"))]
+    pub version: u32,
+    pub length: usize,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub classes: Vec<ClassRequirement>,
+    // characters that never appear, whichever class they're in
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "String::is_empty")
+    )]
+    pub exclude: String,
+    // a passphrase of words from a wordlist instead, length, classes and exclusions don't apply
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub wordlist: Option<WordlistOptions>,
+}
+
+#[cfg(feature = "json")]
+fn first_version() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "kebab-case"))]
+#[non_exhaustive]
+pub enum CharClass {
+    Upper,
+    Lower,
+    Number,
+    NumberNoZero,
+    Symbol,
+    Base58,
+    UpperLatin1,
+    LowerLatin1,
+    Custom(String),
+}
+
+// how many characters of the class a password has, `None` for no upper bound
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct ClassRequirement {
+    pub class: CharClass,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub min: usize,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub max: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct WordlistOptions {
+    // one word per line
+    pub path: PathBuf,
+    pub words: usize,
+    #[cfg_attr(feature = "json", serde(default = "default_separator"))]
+    pub separator: String,
+}
+
+#[cfg(feature = "json")]
+fn default_separator() -> String {
+    " ".to_string()
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("{0}")]
+    Parse(PasswordParseError),
+    #[error("Policies can't express {0}")]
+    Unsupported(&'static str),
+    #[error("Policy version {0} is newer than this generator understands, which is up to {POLICY_VERSION}")]
+    UnknownVersion(u32),
+    #[error("Class `{0}` has a min of {1} above its max of {2}")]
+    BadBounds(String, usize, usize),
+    #[error("Custom classes need at least one character")]
+    EmptyCustom,
+    #[error("The policy is for a passphrase from a wordlist, which a spec can't describe")]
+    Passphrase,
+    #[error("A passphrase needs at least one word")]
+    NoWords,
+    #[error("{0}")]
+    Generate(GenerateError),
+    #[error("{0}")]
+    Wordlist(WordlistError),
+}
+
+impl CharClass {
+    // the charset the class meant in a policy of `version`
+    fn to_charset(&self, version: u32) -> Result<Charset, Error> {
+        match self {
+            CharClass::Number if version < 2 => Ok(Charset::NumberNoZero),
+            CharClass::Upper => Ok(Charset::Upper),
+            CharClass::Lower => Ok(Charset::Lower),
+            CharClass::Number => Ok(Charset::Number),
+            CharClass::NumberNoZero => Ok(Charset::NumberNoZero),
+            CharClass::Symbol => Ok(Charset::Symbol),
+            CharClass::Base58 => Ok(Charset::Base58),
+            CharClass::UpperLatin1 => Ok(Charset::UpperLatin1),
+            CharClass::LowerLatin1 => Ok(Charset::LowerLatin1),
+            CharClass::Custom(chars) if chars.is_empty() => Err(Error::EmptyCustom),
+            CharClass::Custom(chars) => Ok(Charset::Custom(distinct(chars))),
+        }
+    }
+
+    fn from_charset(charset: &Charset) -> Self {
+        match charset {
+            Charset::Upper => CharClass::Upper,
+            Charset::Lower => CharClass::Lower,
+            Charset::Number => CharClass::Number,
+            Charset::NumberNoZero => CharClass::NumberNoZero,
+            Charset::Symbol => CharClass::Symbol,
+            Charset::Base58 => CharClass::Base58,
+            Charset::UpperLatin1 => CharClass::UpperLatin1,
+            Charset::LowerLatin1 => CharClass::LowerLatin1,
+            Charset::Custom(chars) => CharClass::Custom(chars.iter().collect()),
+        }
+    }
+}
+
+impl ClassRequirement {
+    pub fn new(class: CharClass, min: usize, max: Option<usize>) -> Self {
+        Self { class, min, max }
+    }
+
+    pub fn at_least(class: CharClass, min: usize) -> Self {
+        Self::new(class, min, None)
+    }
+
+    pub fn exactly(class: CharClass, count: usize) -> Self {
+        Self::new(class, count, Some(count))
+    }
+
+    fn to_choice(&self, version: u32) -> Result<Choice, Error> {
+        let charset = self.class.to_charset(version)?;
+        let max = self.max.unwrap_or(usize::MAX);
+        let interval = Interval::new(self.min, max)
+            .ok_or_else(|| Error::BadBounds(charset.to_string(), self.min, max))?;
+        Ok(Choice::from_interval(interval, charset))
+    }
+}
+
+impl Policy {
+    // a policy of the current version for passwords of `length` characters, with no classes yet
+    pub fn new(length: usize) -> Self {
+        Self {
+            version: POLICY_VERSION,
+            length,
+            classes: vec![],
+            exclude: String::new(),
+            wordlist: None,
+        }
+    }
+
+    // a policy from a spec string, for importing specs written for the command line
+    // the classes come in the order the spec lists its choices, a length in bits is resolved to
+    // the number of characters it takes and labels are dropped, counts given as several ranges,
+    // allowed characters and anything beyond a length in characters and the classes have no place
+    // in a policy
+    pub fn from_spec_string(s: &str) -> Result<Self, Error> {
+        let spec: PasswordSpec = s.parse().map_err(Error::Parse)?;
+        if spec.allowed.is_some() {
+            return Err(Error::Unsupported("a set of allowed characters"));
+        }
+        if spec.unit() != LengthUnit::Chars {
+            return Err(Error::Unsupported("a length in bytes or graphemes"));
+        }
+        if spec.max_bytes.is_some() {
+            return Err(Error::Unsupported("a limit on the size in bytes"));
+        }
+        if spec.total_length.is_some() {
+            return Err(Error::Unsupported("bounds on the total length"));
+        }
+        if spec.min_entropy.is_some() {
+            return Err(Error::Unsupported("a minimum entropy"));
+        }
+        if !spec.prefix.is_empty() || !spec.suffix.is_empty() {
+            return Err(Error::Unsupported("a prefix or suffix"));
+        }
+        if !spec.position_rules.is_empty() || !spec.no_adjacent.is_empty() {
+            return Err(Error::Unsupported("rules on where characters go"));
+        }
+        if spec.choices().any(|choice| choice.weights.is_some()) {
+            return Err(Error::Unsupported("weighted characters"));
+        }
+        let mut policy = Policy::new(spec.resolve_length().map_err(Error::Generate)?);
+        for choice in spec.choices() {
+            if choice.intervals().len() > 1 {
+                return Err(Error::Unsupported("a class with several count ranges"));
+            }
+            match &choice.chars {
+                Charset::Custom(chars) if choice.excluded() => policy.exclude.extend(chars),
+                charset => policy.classes.push(ClassRequirement {
+                    class: CharClass::from_charset(charset),
+                    min: choice.min,
+                    max: Some(choice.max).filter(|max| *max != usize::MAX),
+                }),
+            }
+        }
+        Ok(policy)
+    }
+
+    // the spec generating passwords for the policy, the exclusions are a custom charset of none
+    pub fn to_spec(&self) -> Result<PasswordSpec, Error> {
+        if self.version > POLICY_VERSION {
+            return Err(Error::UnknownVersion(self.version));
+        }
+        if self.wordlist.is_some() {
+            return Err(Error::Passphrase);
+        }
+        let mut spec = PasswordSpec::new().length(self.length);
+        for class in &self.classes {
+            spec = spec.include(class.to_choice(self.version)?);
+        }
+        if !self.exclude.is_empty() {
+            spec = spec.include(Charset::Custom(distinct(&self.exclude)).exactly(0));
+        }
+        Ok(spec)
+    }
+}
+
+pub fn generate(policy: &Policy) -> Result<Secret<String>, Error> {
+    generate_with_rng(policy, &mut thread_rng())
+}
+
+pub fn generate_with_rng<R: Rng + ?Sized>(
+    policy: &Policy,
+    rng: &mut R,
+) -> Result<Secret<String>, Error> {
+    match &policy.wordlist {
+        Some(options) => {
+            if policy.version > POLICY_VERSION {
+                return Err(Error::UnknownVersion(policy.version));
+            }
+            if options.words == 0 {
+                return Err(Error::NoWords);
+            }
+            let wordlist = Wordlist::from_file(&options.path).map_err(Error::Wordlist)?;
+            Ok(Secret::new(wordlist.passphrase_with_rng(
+                options.words,
+                &options.separator,
+                rng,
+            )))
+        }
+        None => policy
+            .to_spec()?
+            .generate_detailed_with_rng(rng)
+            .map(|generated| generated.secret())
+            .map_err(Error::Generate),
+    }
+}
+
+// the characters in order of first appearance, without repeats
+fn distinct(chars: &str) -> Vec<char> {
+    let mut res: Vec<char> = vec![];
+    for c in chars.chars() {
+        if !res.contains(&c) {
+            res.push(c);
+        }
+    }
+    res
+}
@@ -0,0 +1,105 @@
+// What this build can do, for `--version --verbose` and support tickets. The features come from a
+// table checked with `cfg!` when the crate is compiled, so the report follows the build rather
+// than being written by hand.
+use std::fmt::Display;
+
+use crate::{password::PasswordSpec, store::Backend};
+
+// every cargo feature of the crate and whether this build has it
+const FEATURES: &[(&str, bool)] = &[
+    ("hashing", cfg!(feature = "hashing")),
+    ("history", cfg!(feature = "history")),
+    ("json", cfg!(feature = "json")),
+    ("keychain", cfg!(feature = "keychain")),
+    ("metadata", cfg!(feature = "metadata")),
+    ("num-bigint", cfg!(feature = "num-bigint")),
+    ("schema", cfg!(feature = "schema")),
+    ("secret-service", cfg!(feature = "secret-service")),
+    ("tracing", cfg!(feature = "tracing")),
+    ("transcript", cfg!(feature = "transcript")),
+    ("wincred", cfg!(feature = "wincred")),
+];
+
+const BACKENDS: [Backend; 4] = [
+    Backend::Memory,
+    Backend::WinCred,
+    Backend::SecretService,
+    Backend::Keychain,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    // every feature with whether it's enabled, in alphabetical order
+    pub features: Vec<(&'static str, bool)>,
+    // wordlists built into the crate with their number of words, passphrases otherwise need a
+    // wordlist file
+    pub wordlists: Vec<(&'static str, usize)>,
+    // secret stores this build can write to
+    pub stores: Vec<&'static str>,
+    pub default_spec: String,
+}
+
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        features: FEATURES.to_vec(),
+        wordlists: vec![],
+        stores: BACKENDS
+            .iter()
+            .filter(|backend| backend.compiled_in())
+            .map(|backend| backend.name())
+            .collect(),
+        default_spec: PasswordSpec::default().to_string(),
+    }
+}
+
+impl BuildInfo {
+    pub fn enabled_features(&self) -> Vec<&'static str> {
+        self.features
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect()
+    }
+}
+
+// one `name: value` line each, lists comma separated and `none` when empty
+impl Display for BuildInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |items: Vec<String>| {
+            if items.is_empty() {
+                "none".to_string()
+            } else {
+                items.join(", ")
+            }
+        };
+        writeln!(f, "pants-gen {}", self.version)?;
+        writeln!(
+            f,
+            "features: {}",
+            list(
+                self.enabled_features()
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect()
+            )
+        )?;
+        writeln!(
+            f,
+            "wordlists: {}",
+            list(
+                self.wordlists
+                    .iter()
+                    .map(|(name, words)| format!("{} ({} words)", name, words))
+                    .collect()
+            )
+        )?;
+        writeln!(
+            f,
+            "stores: {}",
+            list(self.stores.iter().map(|name| name.to_string()).collect())
+        )?;
+        write!(f, "default spec: {}", self.default_spec)
+    }
+}
@@ -0,0 +1,48 @@
+// Raw random bytes, e.g. for keyfiles, and writing secrets to files only the owner can read.
+use std::{fs::OpenOptions, io::Write, path::Path};
+
+use rand::RngCore;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BytesError {
+    #[error("Refusing to write raw bytes to a terminal, redirect the output or force it")]
+    Terminal,
+    #[error("{0}")]
+    Io(std::io::Error),
+}
+
+pub fn generate_bytes<R: RngCore + ?Sized>(n: usize, rng: &mut R) -> Vec<u8> {
+    let mut bytes = vec![0; n];
+    rng.fill_bytes(&mut bytes);
+    bytes
+}
+
+// raw bytes garble a terminal, so they only go to one when forced
+pub fn check_raw_output(is_tty: bool, force: bool) -> Result<(), BytesError> {
+    if is_tty && !force {
+        Err(BytesError::Terminal)
+    } else {
+        Ok(())
+    }
+}
+
+// write `contents` to `path` readable and writable by the owner only (0600 on unix), an existing
+// file is truncated and gets its permissions tightened before anything is written
+pub fn write_secret_file(path: impl AsRef<Path>, contents: &[u8]) -> Result<(), BytesError> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path).map_err(BytesError::Io)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .map_err(BytesError::Io)?;
+    }
+    file.write_all(contents).map_err(BytesError::Io)
+}
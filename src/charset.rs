@@ -2,32 +2,185 @@ use std::{fmt::Display, str::FromStr};
 
 use thiserror::Error;
 
-use crate::{choice::Choice, interval::Interval};
+use crate::{choice::Choice, interval::Interval, unicode::Normalization};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+// ordered by variant then characters, which is the order choices are written in specs
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Charset {
     Upper,
     Lower,
     Number,
+    // the digits without 0, which is easily mistaken for O
+    NumberNoZero,
     Symbol,
+    // bitcoin's base58 alphabet
+    Base58,
+    // the letters of Latin-1, ASCII plus the accented ones, for sites that take more than ASCII
+    // ß and ÿ have no capital in Latin-1 so they're only in the lowercase set
+    UpperLatin1,
+    LowerLatin1,
     Custom(Vec<char>),
 }
 
+// the characters of the named charsets, in the order `to_charset` gives them
+pub const UPPER: &[char] = &[
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+];
+pub const LOWER: &[char] = &[
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+    't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+pub const NUMBER: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+pub const NUMBER_NO_ZERO: &[char] = &['1', '2', '3', '4', '5', '6', '7', '8', '9'];
+// no real standard for allowed character sets for symbols
+// there are likely a few obvious ones that are concerns with escaping and are interpretted as
+// special characters at the command line that are removed
+pub const SYMBOL: &[char] = &[
+    '!', '@', '%', '^', '&', '*', '-', '_', '=', '+', ':', ';', ',', '.', '?', '~',
+];
+// bitcoin's base58 alphabet, alphanumerics without 0, O, I and l
+pub const BASE58: &[char] = &[
+    '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'J', 'K',
+    'L', 'M', 'N', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', 'a', 'b', 'c', 'd', 'e',
+    'f', 'g', 'h', 'i', 'j', 'k', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y',
+    'z',
+];
+pub const UPPER_LATIN1: &[char] = &[
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '\u{c0}', '\u{c1}', '\u{c2}', '\u{c3}', '\u{c4}', '\u{c5}',
+    '\u{c6}', '\u{c7}', '\u{c8}', '\u{c9}', '\u{ca}', '\u{cb}', '\u{cc}', '\u{cd}', '\u{ce}',
+    '\u{cf}', '\u{d0}', '\u{d1}', '\u{d2}', '\u{d3}', '\u{d4}', '\u{d5}', '\u{d6}', '\u{d8}',
+    '\u{d9}', '\u{da}', '\u{db}', '\u{dc}', '\u{dd}', '\u{de}',
+];
+pub const LOWER_LATIN1: &[char] = &[
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+    't', 'u', 'v', 'w', 'x', 'y', 'z', '\u{df}', '\u{e0}', '\u{e1}', '\u{e2}', '\u{e3}', '\u{e4}',
+    '\u{e5}', '\u{e6}', '\u{e7}', '\u{e8}', '\u{e9}', '\u{ea}', '\u{eb}', '\u{ec}', '\u{ed}',
+    '\u{ee}', '\u{ef}', '\u{f0}', '\u{f1}', '\u{f2}', '\u{f3}', '\u{f4}', '\u{f5}', '\u{f6}',
+    '\u{f8}', '\u{f9}', '\u{fa}', '\u{fb}', '\u{fc}', '\u{fd}', '\u{fe}', '\u{ff}',
+];
+
+// the named charsets in charset order
+const NAMED: [Charset; 8] = [
+    Charset::Upper,
+    Charset::Lower,
+    Charset::Number,
+    Charset::NumberNoZero,
+    Charset::Symbol,
+    Charset::Base58,
+    Charset::UpperLatin1,
+    Charset::LowerLatin1,
+];
+// characters a custom charset can be missing or have on top of a named one and still be taken
+// for a mistyped copy of it
+const NEAR_MISS: usize = 2;
+
+fn is_upper_latin1(c: char) -> bool {
+    c.is_ascii_uppercase() || (('\u{c0}'..='\u{de}').contains(&c) && c != '\u{d7}')
+}
+
+fn is_lower_latin1(c: char) -> bool {
+    c.is_ascii_lowercase() || (('\u{df}'..='\u{ff}').contains(&c) && c != '\u{f7}')
+}
+
 impl Charset {
-    pub fn to_charset(&self) -> Vec<char> {
+    // the characters of the charset, the named ones are the constants above
+    pub fn to_charset(&self) -> &[char] {
         match self {
-            Self::Upper => ('A'..='Z').collect(),
-            Self::Lower => ('a'..='z').collect(),
-            Self::Number => ('1'..='9').collect(),
-            Self::Symbol => {
-                // no real standard for allowed character sets for symbols
-                // there are likely a few obvious ones that are concerns with escaping and are
-                // interpretted as special characters at the command line that are removed
-                vec![
-                    '!', '@', '%', '^', '&', '*', '-', '_', '=', '+', ':', ';', ',', '.', '?', '~',
-                ]
+            Self::Upper => UPPER,
+            Self::Lower => LOWER,
+            Self::Number => NUMBER,
+            Self::NumberNoZero => NUMBER_NO_ZERO,
+            Self::Symbol => SYMBOL,
+            Self::Base58 => BASE58,
+            Self::UpperLatin1 => UPPER_LATIN1,
+            Self::LowerLatin1 => LOWER_LATIN1,
+            Self::Custom(v) => v,
+        }
+    }
+
+    // how many characters the charset has, repeats in a custom one included
+    pub fn len(&self) -> usize {
+        self.to_charset().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_charset().is_empty()
+    }
+
+    // membership test that doesn't build the charset, must agree with `to_charset`
+    pub fn contains(&self, c: char) -> bool {
+        match self {
+            Self::Upper => c.is_ascii_uppercase(),
+            Self::Lower => c.is_ascii_lowercase(),
+            Self::Number => c.is_ascii_digit(),
+            Self::NumberNoZero => ('1'..='9').contains(&c),
+            Self::Symbol => SYMBOL.contains(&c),
+            Self::Base58 => BASE58.contains(&c),
+            Self::UpperLatin1 => is_upper_latin1(c),
+            Self::LowerLatin1 => is_lower_latin1(c),
+            Self::Custom(v) => v.contains(&c),
+        }
+    }
+
+    // the named charset a custom one has exactly the characters of, in any order and with any
+    // repeats, e.g. `:lower:` for `abcdefghijklmnopqrstuvwxyz`
+    pub fn as_named(&self) -> Option<Charset> {
+        match self.nearest_named() {
+            Some((named, missing, extra)) if missing.is_empty() && extra.is_empty() => Some(named),
+            _ => None,
+        }
+    }
+
+    // the named charset closest to a custom one, with the characters the custom one is missing
+    // from it and has on top of it, as long as that's at most `NEAR_MISS` characters
+    pub(crate) fn nearest_named(&self) -> Option<(Charset, Vec<char>, Vec<char>)> {
+        let chars = match self {
+            Charset::Custom(chars) => chars,
+            _ => return None,
+        };
+        NAMED
+            .iter()
+            .map(|named| {
+                let mut missing: Vec<char> = named
+                    .to_charset()
+                    .iter()
+                    .copied()
+                    .filter(|c| !chars.contains(c))
+                    .collect();
+                missing.sort_unstable();
+                let mut extra: Vec<char> = chars
+                    .iter()
+                    .copied()
+                    .filter(|c| !named.contains(*c))
+                    .collect();
+                extra.sort_unstable();
+                extra.dedup();
+                (named.clone(), missing, extra)
+            })
+            .filter(|(_, missing, extra)| missing.len() + extra.len() <= NEAR_MISS)
+            .min_by_key(|(_, missing, extra)| missing.len() + extra.len())
+    }
+
+    // the distinct characters of a custom charset in order, `None` for the named ones
+    pub(crate) fn custom_set(&self) -> Option<Vec<char>> {
+        match self {
+            Charset::Custom(chars) => {
+                let mut chars = chars.clone();
+                chars.sort_unstable();
+                chars.dedup();
+                Some(chars)
             }
-            Self::Custom(v) => v.to_vec(),
+            _ => None,
+        }
+    }
+
+    // a custom charset's characters normalized, the named charsets are in NFC already
+    pub(crate) fn normalized(self, normalization: Normalization) -> Charset {
+        match self {
+            Charset::Custom(chars) => Charset::Custom(normalization.chars(&chars)),
+            named => named,
         }
     }
 
@@ -58,7 +211,11 @@ impl Display for Charset {
             Charset::Upper => write!(f, ":upper:")?,
             Charset::Lower => write!(f, ":lower:")?,
             Charset::Number => write!(f, ":number:")?,
+            Charset::NumberNoZero => write!(f, ":number-no-zero:")?,
             Charset::Symbol => write!(f, ":symbol:")?,
+            Charset::Base58 => write!(f, ":base58:")?,
+            Charset::UpperLatin1 => write!(f, ":upper-latin1:")?,
+            Charset::LowerLatin1 => write!(f, ":lower-latin1:")?,
             Charset::Custom(c) => write!(f, "{}", c.iter().collect::<String>())?,
         }
         Ok(())
@@ -69,28 +226,91 @@ impl Display for Charset {
 pub enum CharsetParseError {
     #[error("No character set")]
     NoCharset,
-    #[error("Specified a :pattern:, but `{0}` isn't recognized")]
-    UnrecognizedPattern(String),
+    #[error("Specified a :pattern:, but `{0}` isn't recognized{}", suggestion(.1))]
+    UnrecognizedPattern(String, Option<String>),
+}
+
+fn suggestion(name: &Option<String>) -> String {
+    match name {
+        Some(name) => format!(", did you mean `{}`?", name),
+        None => String::new(),
+    }
+}
+
+// names accepted between colons, matched case-insensitively, `Display` uses the first name listed
+// for each charset
+fn named(name: &str) -> Option<Charset> {
+    match name {
+        "upper" => Some(Charset::Upper),
+        "lower" => Some(Charset::Lower),
+        "number" | "num" | "digit" | "digits" => Some(Charset::Number),
+        "number-no-zero" => Some(Charset::NumberNoZero),
+        "symbol" | "special" | "punct" => Some(Charset::Symbol),
+        "base58" => Some(Charset::Base58),
+        "upper-latin1" => Some(Charset::UpperLatin1),
+        "lower-latin1" => Some(Charset::LowerLatin1),
+        _ => None,
+    }
+}
+
+const NAMES: &[&str] = &[
+    "upper",
+    "lower",
+    "number",
+    "num",
+    "digit",
+    "digits",
+    "number-no-zero",
+    "symbol",
+    "special",
+    "punct",
+    "base58",
+    "upper-latin1",
+    "lower-latin1",
+];
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(ca != *cb);
+            row.push(substitute.min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+// closest known name, as long as it's near enough to plausibly be a typo of it
+fn closest_name(name: &str) -> Option<String> {
+    NAMES
+        .iter()
+        .filter_map(|known| Some((edit_distance(name, known), named(known)?.to_string())))
+        .filter(|(distance, _)| *distance <= 2.max(name.len() / 3))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, canonical)| canonical)
 }
 
 impl FromStr for Charset {
     type Err = CharsetParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            ":upper:" => Ok(Charset::Upper),
-            ":lower:" => Ok(Charset::Lower),
-            ":number:" => Ok(Charset::Number),
-            ":symbol:" => Ok(Charset::Symbol),
-            _ => {
-                let chars = s.chars().collect::<Vec<_>>();
-                if s.is_empty() {
-                    Err(CharsetParseError::NoCharset)
-                } else if chars[0] == ':' && chars[s.len() - 1] == ':' {
-                    Err(CharsetParseError::UnrecognizedPattern(s.to_string()))
-                } else {
-                    Ok(Charset::Custom(chars))
-                }
+        let chars = s.chars().collect::<Vec<_>>();
+        if s.is_empty() {
+            return Err(CharsetParseError::NoCharset);
+        }
+        if chars[0] == ':' && chars[chars.len() - 1] == ':' {
+            let name = s[1..].strip_suffix(':').unwrap_or_default().to_lowercase();
+            match named(&name) {
+                Some(charset) => Ok(charset),
+                None => Err(CharsetParseError::UnrecognizedPattern(
+                    s.to_string(),
+                    closest_name(&name),
+                )),
             }
+        } else {
+            Ok(Charset::Custom(chars))
         }
     }
 }
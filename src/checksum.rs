@@ -0,0 +1,68 @@
+// Check characters appended to generated values so typos can be caught.
+
+// Luhn check digit for a string of decimal digits, the digit that makes `payload` followed by it
+// pass `luhn_valid`. `None` if anything other than ascii digits is given.
+pub fn luhn_check_digit(payload: &str) -> Option<char> {
+    let sum = luhn_sum(payload, true)?;
+    char::from_digit((10 - sum % 10) % 10, 10)
+}
+
+// whether the last digit of `digits` is the Luhn check digit of the rest
+pub fn luhn_valid(digits: &str) -> bool {
+    !digits.is_empty() && luhn_sum(digits, false).map_or(false, |sum| sum % 10 == 0)
+}
+
+// digits are doubled every other position counting from the right, starting with the rightmost
+// when a check digit is still to be appended
+fn luhn_sum(digits: &str, double_first: bool) -> Option<u32> {
+    let mut sum = 0;
+    for (i, c) in digits.chars().rev().enumerate() {
+        if !c.is_ascii_digit() {
+            return None;
+        }
+        let mut d = c.to_digit(10)?;
+        if (i % 2 == 0) == double_first {
+            d *= 2;
+            if d > 9 {
+                d -= 9;
+            }
+        }
+        sum += d;
+    }
+    Some(sum)
+}
+
+// alphabet of the transcription check character, uppercase so it can't be confused with the
+// lowercase letters next to it
+const CHECK_ALPHABET: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// Check character for a password that gets written down, ISO 7064 MOD 37,36 over each character's
+// code point mod 36. Catches any single substituted character and any swap of neighbours, except
+// between characters whose code points differ by a multiple of 36 (e.g. `a` and `=`).
+pub fn check_character(s: &str) -> char {
+    let mut p = 36;
+    for c in s.chars() {
+        let mut t = (p + c as u32 % 36) % 36;
+        if t == 0 {
+            t = 36;
+        }
+        p = t * 2 % 37;
+    }
+    char::from(CHECK_ALPHABET[((37 - p) % 36) as usize])
+}
+
+pub fn append_check_character(s: &str) -> String {
+    let mut res = s.to_string();
+    res.push(check_character(s));
+    res
+}
+
+// whether the last character is the check character of the rest, lowercase is accepted for it
+// since it's easily written down that way
+pub fn check_character_valid(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next_back() {
+        Some(last) => check_character(chars.as_str()).eq_ignore_ascii_case(&last),
+        None => false,
+    }
+}
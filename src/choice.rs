@@ -1,31 +1,36 @@
-use rand::prelude::SliceRandom;
-use std::hash::Hash;
-use std::{collections::HashSet, fmt::Display, str::FromStr};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
 
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
 use thiserror::Error;
 
 use crate::{
     charset::{Charset, CharsetParseError},
     interval::{Interval, IntervalParseError},
+    password::GenerateError,
+    sampling,
+    weights::Weights,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+// at most one choice per charset, kept in the charset order so specs have one canonical string and
+// seeded generation is repeatable, the order choices were added in doesn't matter
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Choices {
-    pub choices: HashSet<Choice>,
+    pub choices: BTreeMap<Charset, Choice>,
 }
 
 impl From<Vec<Choice>> for Choices {
     fn from(value: Vec<Choice>) -> Self {
-        Choices {
-            choices: HashSet::from_iter(value),
+        let mut choices = Choices::new();
+        for choice in value {
+            choices.push(choice);
         }
+        choices
     }
 }
 
 impl Display for Choices {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for choice in &self.choices {
+        for choice in self.sorted() {
             write!(f, "//{}", choice)?;
         }
         Ok(())
@@ -54,42 +59,51 @@ impl Default for Choices {
 impl Choices {
     pub fn new() -> Self {
         Self {
-            choices: HashSet::new(),
+            choices: BTreeMap::new(),
         }
     }
 
+    // replaces any choice for the same charset
     pub fn push(&mut self, choice: Choice) {
-        self.choices.replace(choice);
+        self.choices.insert(choice.chars.clone(), choice);
+    }
+
+    // whether there was a choice for the charset
+    pub fn remove(&mut self, chars: &Charset) -> bool {
+        self.choices.remove(chars).is_some()
+    }
+
+    // ordered by charset
+    pub fn sorted(&self) -> Vec<&Choice> {
+        self.choices.values().collect()
     }
 }
 
 impl IntoIterator for Choices {
     type Item = Choice;
-    type IntoIter = std::collections::hash_set::IntoIter<Self::Item>;
+    type IntoIter = std::collections::btree_map::IntoValues<Charset, Self::Item>;
     fn into_iter(self) -> Self::IntoIter {
-        self.choices.into_iter()
+        self.choices.into_values()
     }
 }
 
-#[derive(Debug, Clone)]
+/// A character set along with how many of its characters should appear in a password.
+///
+/// This is plain data, generating never modifies it. The `Iterator` impl is deprecated, it's
+/// only kept so code written against 0.3 still builds; to sample characters directly use
+/// [`Choice::sample`] with the rng of your choice.
+// equal when everything about them is, the charset, counts, label and weights
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Choice {
     pub(crate) min: usize,
     pub(crate) max: usize,
     pub chars: Charset,
-}
-
-// don't care about min and max count only care about the character sets being chosen from
-impl PartialEq for Choice {
-    fn eq(&self, other: &Self) -> bool {
-        self.chars == other.chars
-    }
-}
-impl Eq for Choice {}
-
-impl Hash for Choice {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.chars.hash(state)
-    }
+    pub(crate) weights: Option<Weights>,
+    // a name for the charset in messages, written as `label=charset` in v2 spec strings
+    pub(crate) label: Option<String>,
+    // disjoint intervals the count has to fall in, sorted, when the count isn't just anywhere in
+    // min..=max (which are then the smallest and largest allowed counts)
+    pub(crate) union: Vec<Interval>,
 }
 
 #[derive(Debug, Error)]
@@ -102,40 +116,68 @@ pub enum ChoiceParseError {
     Charset(CharsetParseError),
 }
 
-// interval|charset -> Choice
+// comma separated intervals, e.g. `0,2` or `1-3,6+`
+pub(crate) fn parse_intervals(s: &str) -> Result<Vec<Interval>, IntervalParseError> {
+    s.split(',').map(|interval| interval.parse()).collect()
+}
+
+// a letter followed by letters, digits, `-` and `_`, so a label is never read as a :pattern: or
+// an interval
+pub(crate) fn is_label(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().map_or(false, |c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+// `label=charset` split at the first `=` when what's before it is a label and something follows,
+// otherwise it's all charset
+// only v2 reads labels, see `format::v2`, a `=` in v1 is one of the characters as it always was
+pub(crate) fn split_label(s: &str) -> (Option<&str>, &str) {
+    match s.split_once('=') {
+        Some((label, charset)) if is_label(label) && !charset.is_empty() => (Some(label), charset),
+        _ => (None, s),
+    }
+}
+
+// what messages call a choice's charset, its label if it has one, for messages that keep the
+// charset and label rather than the choice
+pub(crate) fn name(charset: &Charset, label: &Option<String>) -> String {
+    label.clone().unwrap_or_else(|| charset.to_string())
+}
+
+// interval|charset -> Choice, as v1 specs write choices so there's no label
 impl FromStr for Choice {
     type Err = ChoiceParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let pos = s
             .find('|')
             .ok_or_else(|| ChoiceParseError::BadFormat(s.to_string()))?;
-        let interval = s[..pos].parse().map_err(ChoiceParseError::BadInterval)?;
+        let intervals = parse_intervals(&s[..pos]).map_err(ChoiceParseError::BadInterval)?;
         let chars: Charset = s[pos + 1..].parse().map_err(ChoiceParseError::Charset)?;
-        Ok(Choice::from_interval(interval, chars))
+        Choice::any_of(intervals, chars).ok_or_else(|| ChoiceParseError::BadFormat(s.to_string()))
     }
 }
 
+// the label is left out like `FromStr` leaves it, specs with labels are written as v2
 impl Display for Choice {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.min == self.max {
-            write!(f, "{}", self.min)?;
-        } else if self.min == usize::MIN {
-            write!(f, "{}-", self.max)?;
-        } else if self.max == usize::MAX {
-            write!(f, "{}+", self.min)?;
-        } else {
-            write!(f, "{}-{}", self.min, self.max)?;
-        }
-        write!(f, "|")?;
-        write!(f, "{}", self.chars)?;
-        Ok(())
+        self.write_intervals(f)?;
+        write!(f, "|{}", self.chars)
     }
 }
 
 impl Choice {
+    // `None` when the interval is backwards or there are no characters to choose from
     pub fn new(min: usize, max: usize, chars: Charset) -> Option<Self> {
-        if max >= min {
-            Some(Self { min, max, chars })
+        if max >= min && !chars.is_empty() {
+            Some(Self {
+                min,
+                max,
+                chars,
+                weights: None,
+                label: None,
+                union: vec![],
+            })
         } else {
             None
         }
@@ -146,6 +188,9 @@ impl Choice {
             min: interval.min,
             max: interval.max,
             chars,
+            weights: None,
+            label: None,
+            union: vec![],
         }
     }
 
@@ -154,6 +199,9 @@ impl Choice {
             min: count,
             max: count,
             chars,
+            weights: None,
+            label: None,
+            union: vec![],
         }
     }
 
@@ -162,6 +210,9 @@ impl Choice {
             min: count,
             max: usize::MAX,
             chars,
+            weights: None,
+            label: None,
+            union: vec![],
         }
     }
 
@@ -170,32 +221,214 @@ impl Choice {
             min: usize::MIN,
             max: count,
             chars,
+            weights: None,
+            label: None,
+            union: vec![],
         }
     }
 
-    pub(crate) fn active(&self) -> bool {
-        self.max > 0
+    // weights aren't part of the spec string, so they don't survive a Display/parse round trip
+    pub fn weighted(mut self, weights: Weights) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    // `None` if the label isn't a letter followed by letters, digits, `-` and `_`
+    pub fn labeled(mut self, label: impl Into<String>) -> Option<Self> {
+        let label = label.into();
+        if is_label(&label) {
+            self.label = Some(label);
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    // what messages call the charset, the label if there is one
+    pub fn name(&self) -> String {
+        name(&self.chars, &self.label)
+    }
+
+    // entropy in bits of a single character drawn from this choice
+    pub fn entropy(&self) -> f64 {
+        self.entropy_from(self.chars.to_charset())
+    }
+
+    // entropy of a character drawn from `pool`, the part of the charset a spec leaves to draw from
+    pub(crate) fn entropy_from(&self, pool: &[char]) -> f64 {
+        match &self.weights {
+            Some(weights) => weights.entropy(pool),
+            None if pool.is_empty() => 0.0,
+            None => (pool.len() as f64).log2(),
+        }
+    }
+
+    // an interval of exactly 0, the charset is deliberately left out of passwords, including
+    // where it overlaps other charsets of the spec
+    pub fn excluded(&self) -> bool {
+        self.max == 0
+    }
+
+    // count has to land in one of the intervals, e.g. exactly 0 or exactly 2 digits
+    // overlapping and touching intervals are merged, `None` if there are no intervals
+    pub fn any_of(mut intervals: Vec<Interval>, chars: Charset) -> Option<Self> {
+        intervals.sort_by_key(|interval| interval.min);
+        let mut merged: Vec<Interval> = vec![];
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if interval.min <= last.max.saturating_add(1) => {
+                    last.max = last.max.max(interval.max);
+                }
+                _ => merged.push(interval),
+            }
+        }
+        let first = merged.first()?;
+        let mut choice = Self::at_least(first.min, chars);
+        choice.max = merged[merged.len() - 1].max;
+        if merged.len() > 1 {
+            choice.union = merged;
+        }
+        Some(choice)
+    }
+
+    // the intervals the count can be in, a single one unless made with `any_of`
+    pub fn intervals(&self) -> Vec<Interval> {
+        if self.union.is_empty() {
+            vec![self.interval()]
+        } else {
+            self.union.clone()
+        }
+    }
+
+    // a character of the charset drawn from `rng`, following the weights if there are any, the
+    // counts aren't involved, `None` for an empty charset
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<char> {
+        let pool = self.chars.to_charset();
+        match &self.weights {
+            Some(weights) => weights.choose(pool, rng),
+            None => sampling::uniform(pool, rng),
+        }
+    }
+
+    // the characters its minimum asks for drawn from `rng`, `BadBounds` for a minimum above the
+    // maximum, e.g. from `from_interval` with an interval built by hand, and `EmptyCharset` when
+    // there's nothing to draw them from
+    pub fn get_required<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<Vec<char>, GenerateError> {
+        ChoiceSampler::new(self, self.interval(), self.chars.to_charset().to_vec())
+            .get_required(rng)
     }
 
-    pub(crate) fn required(&self) -> bool {
-        self.min > 0
+    // whether `count` characters from this choice are allowed
+    pub fn admits(&self, count: usize) -> bool {
+        self.intervals()
+            .iter()
+            .any(|interval| interval.min <= count && count <= interval.max)
     }
 
-    pub(crate) fn get_required(&mut self) -> Vec<char> {
-        let mut res = vec![];
-        while self.required() {
-            if let Some(c) = self.next() {
-                res.push(c);
+    pub(crate) fn write_intervals(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, interval) in self.intervals().iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
             }
+            write!(f, "{}", interval)?;
+        }
+        Ok(())
+    }
+
+    // smallest and largest allowed counts, the gaps between intervals of `any_of` aren't reflected
+    pub fn interval(&self) -> Interval {
+        Interval {
+            min: self.min,
+            max: self.max,
         }
-        res
     }
 }
 
+/// Deprecated since 0.4, use [`Choice::sample`], which leaves the choice as it is.
+///
+/// Draws a character and uses up one of the counts, as 0.3 did, so iterating a choice stored in a
+/// spec still changes the spec. `#[deprecated]` can't be put on a trait impl, hence only the note.
 impl Iterator for Choice {
     type Item = char;
 
     fn next(&mut self) -> Option<Self::Item> {
+        let mut sampler =
+            ChoiceSampler::new(self, self.interval(), self.chars.to_charset().to_vec());
+        let c = sampler.sample(&mut thread_rng());
+        let (min, max) = (sampler.min, sampler.max);
+        self.min = min;
+        self.max = max;
+        c
+    }
+}
+
+// tracks how many characters are still required/allowed from a choice while generating, so the
+// `Choice` itself is never mutated
+pub(crate) struct ChoiceSampler<'a> {
+    choice: &'a Choice,
+    pool: Vec<char>,
+    min: usize,
+    max: usize,
+}
+
+impl<'a> ChoiceSampler<'a> {
+    // `interval` is the one of the choice's intervals picked for this password, `pool` is what's
+    // left of the charset to draw from
+    pub(crate) fn new(choice: &'a Choice, interval: Interval, pool: Vec<char>) -> Self {
+        Self {
+            choice,
+            pool,
+            min: interval.min,
+            max: interval.max,
+        }
+    }
+
+    pub(crate) fn active(&self) -> bool {
+        self.max > 0 && !self.pool.is_empty()
+    }
+
+    // how many more characters can be drawn
+    pub(crate) fn capacity(&self) -> usize {
+        if self.pool.is_empty() {
+            0
+        } else {
+            self.max
+        }
+    }
+
+    // the characters the minimum asks for, exactly `min` draws from `rng` after checking they can
+    // be made, the sampler is then left with what's allowed beyond them
+    pub(crate) fn get_required<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<Vec<char>, GenerateError> {
+        if self.min > self.max {
+            return Err(GenerateError::BadBounds {
+                charset: self.choice.chars.clone(),
+                min: self.min,
+                max: self.max,
+            });
+        }
+        if self.min > 0 && self.pool.is_empty() {
+            return Err(GenerateError::EmptyCharset(self.choice.chars.clone()));
+        }
+        let required = (0..self.min)
+            .map(|_| match &self.choice.weights {
+                Some(weights) => weights.choose(&self.pool, rng),
+                None => sampling::uniform(&self.pool, rng),
+            })
+            .collect::<Option<Vec<char>>>()
+            .ok_or_else(|| GenerateError::EmptyCharset(self.choice.chars.clone()))?;
+        self.max -= self.min;
+        self.min = 0;
+        Ok(required)
+    }
+
+    pub(crate) fn sample<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<char> {
         if self.active() {
             if self.min > 0 {
                 self.min -= 1;
@@ -203,7 +436,10 @@ impl Iterator for Choice {
             if self.max > 0 {
                 self.max -= 1;
             }
-            self.chars.to_charset().choose(&mut thread_rng()).copied()
+            match &self.choice.weights {
+                Some(weights) => weights.choose(&self.pool, rng),
+                None => sampling::uniform(&self.pool, rng),
+            }
         } else {
             None
         }
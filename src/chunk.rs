@@ -0,0 +1,220 @@
+// Passwords written in groups like `XXXX-XXXX-XXXX` for reading out or typing from paper, with an
+// optional rule every group has to meet on its own, e.g. a letter and a digit in each so no group
+// is all lookalike digits.
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use thiserror::Error;
+
+use crate::{
+    charset::Charset,
+    choice::Choice,
+    length_policy::Decorations,
+    password::{GenerateError, PasswordSpec},
+};
+
+// arrangements tried before giving up on meeting the rule in every chunk
+const MAX_ATTEMPTS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkSpec {
+    size: usize,
+    separator: String,
+    // counts every chunk has to meet, the chunks of the random core only, the affixes are left
+    // as they are
+    chunk_rule: Option<Vec<Choice>>,
+}
+
+#[derive(Debug, Error)]
+pub enum ChunkError {
+    #[error("Chunks have to be at least one character long")]
+    ZeroSize,
+    #[error(
+        "Every chunk needs {needed} characters for the rule but the last chunk has only {size}"
+    )]
+    ChunkTooSmall { needed: usize, size: usize },
+    #[error("The rule needs {needed} characters from `{charset}` over {chunks} chunks but the spec only guarantees {guaranteed}, {} short", .needed - .guaranteed)]
+    Shortfall {
+        charset: Charset,
+        needed: usize,
+        guaranteed: usize,
+        chunks: usize,
+    },
+    #[error("No arrangement met the chunk rule after {attempts} attempts")]
+    AttemptsExhausted { attempts: usize },
+    #[error("{0}")]
+    Generate(GenerateError),
+}
+
+// the password without the spec's affixes
+fn core<'a>(spec: &PasswordSpec, password: &'a str) -> &'a str {
+    password
+        .strip_prefix(spec.prefix.as_str())
+        .and_then(|core| core.strip_suffix(spec.suffix.as_str()))
+        .unwrap_or(password)
+}
+
+impl ChunkSpec {
+    // chunks of `size` characters joined with `-`, the last chunk has what's left over
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            separator: "-".to_string(),
+            chunk_rule: None,
+        }
+    }
+
+    pub fn separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    pub fn chunk_rule(mut self, rule: Vec<Choice>) -> Self {
+        self.chunk_rule = Some(rule);
+        self
+    }
+
+    // the separators this adds, for checking a total length
+    pub fn decorations(&self) -> Decorations {
+        Decorations::new().chunked(self.size, self.separator.clone())
+    }
+
+    // the password with the separator between chunks of its random core
+    pub fn chunk(&self, spec: &PasswordSpec, password: &str) -> String {
+        let chars: Vec<char> = core(spec, password).chars().collect();
+        let chunks: Vec<String> = chars
+            .chunks(self.size.max(1))
+            .map(|chunk| chunk.iter().collect())
+            .collect();
+        format!(
+            "{}{}{}",
+            spec.prefix,
+            chunks.join(&self.separator),
+            spec.suffix
+        )
+    }
+
+    // whether every password of the spec has enough characters for the rule in every chunk and
+    // fits the spec's total length with the separators, checked before generating so a spec that
+    // can't meet it fails with what's missing
+    // a charset counts what the spec's choices drawing only from it have to give
+    pub fn check(&self, spec: &PasswordSpec) -> Result<(), ChunkError> {
+        if self.size == 0 {
+            return Err(ChunkError::ZeroSize);
+        }
+        spec.check_total_length(&self.decorations())
+            .map_err(ChunkError::Generate)?;
+        let rule = match &self.chunk_rule {
+            Some(rule) => rule,
+            None => return Ok(()),
+        };
+        let length = spec.resolve_length().map_err(ChunkError::Generate)?;
+        let chunks = (length + self.size - 1) / self.size;
+        let last = length - (chunks.max(1) - 1) * self.size;
+        let needed = rule.iter().map(|choice| choice.min).sum();
+        if chunks > 0 && needed > last {
+            return Err(ChunkError::ChunkTooSmall { needed, size: last });
+        }
+        for required in rule {
+            let guaranteed = spec
+                .drawn_choices()
+                .iter()
+                .filter(|choice| {
+                    spec.pool(choice)
+                        .iter()
+                        .all(|c| required.chars.contains(*c))
+                })
+                .map(|choice| choice.min)
+                .sum();
+            let needed = required.min * chunks;
+            if guaranteed < needed {
+                return Err(ChunkError::Shortfall {
+                    charset: required.chars.clone(),
+                    needed,
+                    guaranteed,
+                    chunks,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn generate(&self, spec: &PasswordSpec) -> Result<String, ChunkError> {
+        self.generate_with_rng(spec, &mut thread_rng())
+    }
+
+    // a password of the spec, chunked, with the characters arranged so each chunk meets the rule
+    // the characters needed by the rule are handed out to the chunks first and the rest fill them
+    // up, then each chunk is shuffled, arrangements breaking the spec's position or adjacency
+    // rules are tried again
+    pub fn generate_with_rng<R: Rng + ?Sized>(
+        &self,
+        spec: &PasswordSpec,
+        rng: &mut R,
+    ) -> Result<String, ChunkError> {
+        self.check(spec)?;
+        // the total is for the chunked password, checked above
+        let spec = &spec.without_total_length();
+        let rule = match &self.chunk_rule {
+            Some(rule) => rule,
+            None => {
+                let password = spec
+                    .generate_detailed_with_rng(rng)
+                    .map_err(ChunkError::Generate)?
+                    .password();
+                return Ok(self.chunk(spec, &password));
+            }
+        };
+        for _ in 0..MAX_ATTEMPTS {
+            let password = spec
+                .generate_detailed_with_rng(rng)
+                .map_err(ChunkError::Generate)?
+                .password();
+            let core = core(spec, &password);
+            if let Some(arranged) = self.arrange(rule, core, rng) {
+                let password = format!("{}{}{}", spec.prefix, arranged, spec.suffix);
+                if spec.matches(&password) {
+                    return Ok(self.chunk(spec, &password));
+                }
+            }
+        }
+        Err(ChunkError::AttemptsExhausted {
+            attempts: MAX_ATTEMPTS,
+        })
+    }
+
+    // the core's characters reordered so every chunk meets the rule, `None` when the ones drawn
+    // don't allow it
+    fn arrange<R: Rng + ?Sized>(&self, rule: &[Choice], core: &str, rng: &mut R) -> Option<String> {
+        let mut remaining: Vec<char> = core.chars().collect();
+        remaining.shuffle(rng);
+        let sizes: Vec<usize> = remaining
+            .chunks(self.size)
+            .map(|chunk| chunk.len())
+            .collect();
+        let mut chunks: Vec<Vec<char>> = vec![vec![]; sizes.len()];
+        for required in rule {
+            for chunk in chunks.iter_mut() {
+                for _ in 0..required.min {
+                    let i = remaining.iter().position(|c| required.chars.contains(*c))?;
+                    chunk.push(remaining.swap_remove(i));
+                }
+            }
+        }
+        for (chunk, size) in chunks.iter_mut().zip(&sizes) {
+            while chunk.len() < *size {
+                chunk.push(remaining.pop()?);
+            }
+            chunk.shuffle(rng);
+        }
+        let meets = chunks.iter().all(|chunk| {
+            rule.iter().all(|required| {
+                required.admits(
+                    chunk
+                        .iter()
+                        .filter(|c| required.chars.contains(**c))
+                        .count(),
+                )
+            })
+        });
+        meets.then(|| chunks.concat().into_iter().collect())
+    }
+}
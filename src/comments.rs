@@ -0,0 +1,69 @@
+// Comments in the files specs and profiles are read from, so a policy can sit next to the reason
+// for it. Every file format built on lines goes through `lines` so they all agree on the rules:
+//  - `#` at the start of a line, after any indentation, comments out the whole line
+//  - `#` after whitespace starts a trailing comment
+//  - `#` anywhere else is kept, so `//1|a#b` still has `#` in its charset
+//  - `\#` is a literal `#` even after whitespace or at the start of a line
+//  - surrounding whitespace is trimmed and lines left empty are skipped
+use std::{fs, path::Path};
+
+use thiserror::Error;
+
+use crate::password::{PasswordParseError, PasswordSpec};
+
+// the line with any comment taken out and `\#` unescaped, trimmed
+pub fn strip_comment(line: &str) -> String {
+    let mut res = String::new();
+    let mut chars = line.chars().peekable();
+    // start of the line counts as whitespace, so full line comments are the same as trailing ones
+    let mut after_space = true;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'#') => {
+                chars.next();
+                res.push('#');
+            }
+            '#' if after_space => break,
+            _ => res.push(c),
+        }
+        after_space = c.is_whitespace();
+    }
+    res.trim().to_string()
+}
+
+// the lines left after stripping comments, with their line numbers counting from 1 for errors
+pub fn lines(s: &str) -> Vec<(usize, String)> {
+    s.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, strip_comment(line)))
+        .filter(|(_, line)| !line.is_empty())
+        .collect()
+}
+
+#[derive(Debug, Error)]
+pub enum SpecFileError {
+    #[error("{0}")]
+    Io(std::io::Error),
+    #[error("Spec file has no spec, only comments and blank lines")]
+    Empty,
+    #[error("Spec file has a second spec on line {0}, expect a single spec")]
+    MultipleSpecs(usize),
+    #[error("Line {0}: {1}")]
+    BadSpec(usize, PasswordParseError),
+}
+
+impl PasswordSpec {
+    // a file holding a single spec, along with any comments
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, SpecFileError> {
+        let contents = fs::read_to_string(path).map_err(SpecFileError::Io)?;
+        Self::from_file_contents(&contents)
+    }
+
+    pub fn from_file_contents(s: &str) -> Result<Self, SpecFileError> {
+        match lines(s).as_slice() {
+            [] => Err(SpecFileError::Empty),
+            [(i, spec)] => spec.parse().map_err(|e| SpecFileError::BadSpec(*i, e)),
+            [_, (i, _), ..] => Err(SpecFileError::MultipleSpecs(*i)),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+// Comparing how strict specs are, e.g. to warn when an edited policy is weaker than before.
+//
+// A spec is read as the set of passwords `matches` accepts, and one spec is at least as strict as
+// another when its set is contained in the other's. The checks only go by the constraints, so
+// they're conservative: when containment can't be shown from them the specs are taken to be
+// incomparable, even if every password happens to be accepted by both.
+//
+// Counts are of every character in a charset, wherever it came from, which is what keeps
+// overlapping charsets sound. The price is that a charset one spec doesn't mention but that
+// overlaps its characters only compares when the other spec allows any count of it.
+//
+// Lengths are only compared in the same unit, and the byte cap, bounds on the total length,
+// minimum entropy, position rules and charsets kept from being adjacent aren't compared at all,
+// specs differing in any of them are incomparable.
+use crate::{choice::Choice, interval::Interval, password::PasswordSpec};
+
+// whether every count `a` allows, up to the length, is allowed by `b`
+fn counts_contained(a: &[Interval], b: &Choice, length: usize) -> bool {
+    a.iter()
+        .filter(|interval| interval.min <= length)
+        .all(|interval| {
+            let max = interval.max.min(length);
+            b.intervals()
+                .iter()
+                .any(|allowed| allowed.min <= interval.min && max <= allowed.max)
+        })
+}
+
+// whether the constraints `contained` doesn't look at are the same for both specs
+fn comparable(a: &PasswordSpec, b: &PasswordSpec) -> bool {
+    a.unit() == b.unit()
+        && a.max_bytes == b.max_bytes
+        && a.total_length == b.total_length
+        && a.min_entropy == b.min_entropy
+        && a.position_rules == b.position_rules
+        && a.no_adjacent == b.no_adjacent
+}
+
+// whether every password `a` accepts is also accepted by `b`, see the module comment
+fn contained(a: &PasswordSpec, b: &PasswordSpec) -> bool {
+    if a.prefix != b.prefix || a.suffix != b.suffix {
+        return false;
+    }
+    let length = match (a.resolve_length(), b.resolve_length()) {
+        (Ok(x), Ok(y)) if x == y => x,
+        _ => return false,
+    };
+    // every character `matches` takes, which is more than the pools generation draws from
+    let excluded = a.excluded();
+    let allowed: Vec<char> = a
+        .drawn_choices()
+        .iter()
+        .flat_map(|choice| choice.chars.to_charset().iter().copied())
+        .filter(|c| a.is_allowed(*c) && !excluded.iter().any(|charset| charset.contains(*c)))
+        .collect();
+    if !allowed.iter().all(|c| b.is_allowed(*c)) {
+        return false;
+    }
+    b.choices().all(
+        |required| match a.choices().find(|choice| choice.chars == required.chars) {
+            Some(choice) => counts_contained(&choice.intervals(), required, length),
+            None if !allowed.iter().any(|c| required.chars.contains(*c)) => required.admits(0),
+            None => counts_contained(&[Interval::at_least(0)], required, length),
+        },
+    )
+}
+
+impl PasswordSpec {
+    // `Some(true)` when every password this spec accepts is accepted by `other` too, `Some(false)`
+    // when it's the other way around and this spec is strictly looser, and `None` when neither
+    // contains the other (or that can't be shown, see the module comment)
+    // equal specs are at least as strict as each other
+    pub fn is_at_least_as_strict_as(&self, other: &PasswordSpec) -> Option<bool> {
+        if !comparable(self, other) {
+            return None;
+        }
+        match (contained(self, other), contained(other, self)) {
+            (true, _) => Some(true),
+            (false, true) => Some(false),
+            (false, false) => None,
+        }
+    }
+}
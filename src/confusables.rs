@@ -0,0 +1,107 @@
+// Groups of characters that are easily mistaken for each other, used to drop lookalikes from
+// charsets. The file format is one group per line, e.g. `0Oo` or `1lI|`, blank lines and
+// comments are skipped, see `comments`.
+use std::{fs, path::Path};
+
+use thiserror::Error;
+
+use crate::{charset::Charset, comments, pwgen::AMBIGUOUS};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Confusables {
+    groups: Vec<Vec<char>>,
+}
+
+#[derive(Debug, Error)]
+pub enum ConfusablesError {
+    #[error("{0}")]
+    Io(std::io::Error),
+}
+
+impl Confusables {
+    // groups sharing a character are merged into one
+    pub fn new(groups: Vec<Vec<char>>) -> Self {
+        let mut res = Self::default();
+        for group in groups {
+            res.add(group);
+        }
+        res
+    }
+
+    // pwgen's ambiguous characters grouped by what they look like
+    pub fn builtin() -> Self {
+        let groups = ["B8", "G6", "I1l", "0OQD", "S5", "Z2"];
+        debug_assert_eq!(groups.concat().len(), AMBIGUOUS.len());
+        Self::new(groups.iter().map(|g| g.chars().collect()).collect())
+    }
+
+    // an empty file gives no groups, so nothing gets filtered
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ConfusablesError> {
+        let contents = fs::read_to_string(path).map_err(ConfusablesError::Io)?;
+        Ok(Self::parse(&contents))
+    }
+
+    pub fn parse(s: &str) -> Self {
+        Self::new(
+            comments::lines(s)
+                .into_iter()
+                .map(|(_, line)| line.chars().filter(|c| !c.is_whitespace()).collect())
+                .collect(),
+        )
+    }
+
+    fn add(&mut self, group: Vec<char>) {
+        let mut merged: Vec<char> = vec![];
+        for c in group {
+            if !merged.contains(&c) {
+                merged.push(c);
+            }
+        }
+        if merged.is_empty() {
+            return;
+        }
+        let (overlapping, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.groups)
+            .into_iter()
+            .partition(|g| g.iter().any(|c| merged.contains(c)));
+        for g in overlapping {
+            for c in g {
+                if !merged.contains(&c) {
+                    merged.push(c);
+                }
+            }
+        }
+        self.groups = rest;
+        self.groups.push(merged);
+    }
+
+    // add another set of groups to these, e.g. user groups on top of the built in ones
+    pub fn extend(mut self, other: Confusables) -> Self {
+        for group in other.groups {
+            self.add(group);
+        }
+        self
+    }
+
+    pub fn groups(&self) -> &[Vec<char>] {
+        &self.groups
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.groups.iter().any(|g| g.contains(&c))
+    }
+
+    // the charset without any character that's in a group
+    pub fn filter(&self, charset: &Charset) -> Charset {
+        if self.groups.is_empty() {
+            return charset.clone();
+        }
+        Charset::Custom(
+            charset
+                .to_charset()
+                .iter()
+                .copied()
+                .filter(|c| !self.contains(*c))
+                .collect(),
+        )
+    }
+}
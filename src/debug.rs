@@ -0,0 +1,152 @@
+// `Debug` for intervals, choices and specs in the spec grammar rather than field by field, so a
+// spec in a log reads `PasswordSpec(32//1+|:upper:)` instead of a wall of fields with
+// `max: 18446744073709551615` for every unbounded count. What a spec string can't hold, like the
+// affixes or position rules, follows the string when it isn't the default.
+//
+// `raw_debug` gives the fields as they are for when the representation itself is in question.
+use std::fmt::{Debug, Formatter, Result};
+
+use crate::{
+    choice::{Choice, Choices},
+    interval::Interval,
+    password::PasswordSpec,
+};
+
+// the fields of an interval, choice or spec, from `raw_debug`
+pub struct RawDebug<'a, T>(&'a T);
+
+impl Interval {
+    pub fn raw_debug(&self) -> RawDebug<'_, Interval> {
+        RawDebug(self)
+    }
+}
+
+impl Choice {
+    pub fn raw_debug(&self) -> RawDebug<'_, Choice> {
+        RawDebug(self)
+    }
+}
+
+impl PasswordSpec {
+    pub fn raw_debug(&self) -> RawDebug<'_, PasswordSpec> {
+        RawDebug(self)
+    }
+}
+
+impl Debug for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "Interval({})", self)
+    }
+}
+
+// weights aren't part of the spec string, only whether there are any is shown
+impl Debug for Choice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "Choice({}", self)?;
+        if self.weights.is_some() {
+            write!(f, ", weighted")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl Debug for Choices {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_list().entries(self.sorted()).finish()
+    }
+}
+
+impl Debug for PasswordSpec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let default = PasswordSpec::new();
+        write!(f, "PasswordSpec({}", self)?;
+        if !self.prefix.is_empty() {
+            write!(f, ", prefix: {:?}", self.prefix)?;
+        }
+        if !self.suffix.is_empty() {
+            write!(f, ", suffix: {:?}", self.suffix)?;
+        }
+        if self.affixes_in_length {
+            write!(f, ", affixes in length")?;
+        }
+        for (start, end, rule) in &self.position_rules {
+            write!(f, ", {}..{}: {:?}", start, end, rule)?;
+        }
+        if !self.no_adjacent.is_empty() {
+            write!(f, ", no adjacent: {:?}", self.no_adjacent)?;
+        }
+        if self.fill != default.fill {
+            write!(f, ", fill: {:?}", self.fill)?;
+        }
+        if self.sampling != default.sampling {
+            write!(f, ", sampling: {:?}", self.sampling)?;
+        }
+        if self.normalization != default.normalization {
+            write!(f, ", normalization: {:?}", self.normalization)?;
+        }
+        if let Some(max) = self.max_bytes {
+            write!(f, ", max bytes: {}", max)?;
+        }
+        if let Some(total) = &self.total_length {
+            write!(f, ", total length: {}", total)?;
+        }
+        if let Some(bits) = self.required_entropy() {
+            write!(f, ", min entropy: {}", bits)?;
+        }
+        // a unit on a length in bits is ignored and not written in the string
+        if self.unit != self.unit() {
+            write!(f, ", unit: {:?}", self.unit)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl Debug for RawDebug<'_, Interval> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_struct("Interval")
+            .field("min", &self.0.min)
+            .field("max", &self.0.max)
+            .finish()
+    }
+}
+
+impl Debug for RawDebug<'_, Choice> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let union: Vec<_> = self.0.union.iter().map(Interval::raw_debug).collect();
+        f.debug_struct("Choice")
+            .field("min", &self.0.min)
+            .field("max", &self.0.max)
+            .field("chars", &self.0.chars)
+            .field("weights", &self.0.weights)
+            .field("label", &self.0.label)
+            .field("union", &union)
+            .finish()
+    }
+}
+
+impl Debug for RawDebug<'_, PasswordSpec> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let spec = self.0;
+        let choices: Vec<_> = spec.choices().map(Choice::raw_debug).collect();
+        f.debug_struct("PasswordSpec")
+            .field("length", &spec.length)
+            .field("choices", &choices)
+            .field("prefix", &spec.prefix)
+            .field("suffix", &spec.suffix)
+            .field("affixes_in_length", &spec.affixes_in_length)
+            .field("position_rules", &spec.position_rules)
+            .field("no_adjacent", &spec.no_adjacent)
+            .field("allowed", &spec.allowed)
+            .field("fill", &spec.fill)
+            .field("sampling", &spec.sampling)
+            .field("normalization", &spec.normalization)
+            .field("unit", &spec.unit)
+            .field("max_bytes", &spec.max_bytes)
+            .field(
+                "total_length",
+                &spec.total_length.as_ref().map(Interval::raw_debug),
+            )
+            .field("min_entropy", &spec.required_entropy())
+            .finish()
+    }
+}
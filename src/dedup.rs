@@ -0,0 +1,97 @@
+// Finding passwords that came up more than once in batches generated separately and merged, e.g.
+// on several machines. The lines are read one at a time and only a fingerprint of each is kept, so
+// memory grows with the number of lines rather than their size and the passwords themselves aren't
+// held, other than the ones that turn out to be duplicated.
+//
+// The fingerprints are 128 bits, the chance of two different lines sharing one is negligible even
+// for billions of lines.
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt::Display,
+    hash::{Hash, Hasher},
+    io::{self, BufRead},
+};
+
+use crate::secret::Secret;
+
+// a line that appeared more than once, line numbers count from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DupReport {
+    pub fingerprint: u128,
+    pub lines: Vec<usize>,
+    password: Secret<String>,
+}
+
+impl DupReport {
+    pub fn count(&self) -> usize {
+        self.lines.len()
+    }
+
+    // the duplicated password, only shown when asked for
+    pub fn password(&self) -> &str {
+        self.password.expose()
+    }
+
+    // like Display but with the password in place of the fingerprint
+    pub fn revealed(&self) -> String {
+        format!("`{}` {}", self.password.expose(), self.occurrences())
+    }
+
+    fn occurrences(&self) -> String {
+        let lines: Vec<String> = self.lines.iter().map(|line| line.to_string()).collect();
+        format!("appears {} times, lines {}", self.count(), lines.join(", "))
+    }
+}
+
+// the fingerprint rather than the password, so a report can be shared
+impl Display for DupReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:032x} {}", self.fingerprint, self.occurrences())
+    }
+}
+
+// two independent 64 bit hashes of the line
+fn fingerprint(line: &str) -> u128 {
+    let mut first = DefaultHasher::new();
+    line.hash(&mut first);
+    let mut second = DefaultHasher::new();
+    (0x5au8, line).hash(&mut second);
+    (u128::from(first.finish()) << 64) | u128::from(second.finish())
+}
+
+// the duplicated lines in the order they were first seen, blank lines are skipped and line endings
+// aren't part of the password
+pub fn find_duplicates<R: BufRead>(mut reader: R) -> io::Result<Vec<DupReport>> {
+    // fingerprint to the line it was first seen on and, once seen again, where it is in the report
+    let mut seen: HashMap<u128, (usize, Option<usize>)> = HashMap::new();
+    let mut reports: Vec<DupReport> = vec![];
+    let mut buffer = String::new();
+    let mut number = 0;
+    loop {
+        buffer.clear();
+        if reader.read_line(&mut buffer)? == 0 {
+            break;
+        }
+        number += 1;
+        let line = buffer.trim_end_matches(['\n', '\r']);
+        if line.is_empty() {
+            continue;
+        }
+        let key = fingerprint(line);
+        match seen.get_mut(&key) {
+            None => {
+                seen.insert(key, (number, None));
+            }
+            Some((_, Some(report))) => reports[*report].lines.push(number),
+            Some((first, report)) => {
+                *report = Some(reports.len());
+                reports.push(DupReport {
+                    fingerprint: key,
+                    lines: vec![*first, number],
+                    password: Secret::new(line.to_string()),
+                });
+            }
+        }
+    }
+    Ok(reports)
+}
@@ -0,0 +1,439 @@
+// Shannon entropy of generating from a spec, what a password from it is worth on average, where
+// `entropy` is what the least likely composition guarantees. A password is the counts drawn for
+// each choice, the characters drawn for those counts and the order they're shuffled into, so its
+// entropy is the entropy of the counts, plus the log of the ways to arrange them, plus the bits of
+// each character drawn.
+//
+// The counts follow generation: an interval picked for each choice whose count is a union, the
+// minimums, then the rest spread over the choices at random or evenly with `FillMode::Balanced`,
+// or under `SamplingMode::Uniform` in proportion to the strings with them. Pools are taken as
+// telling the characters apart, as `search_space` does, so with overlapping charsets this is an
+// upper bound. Position and adjacency rules aren't taken into account, and like the other analyses
+// a length in bytes or graphemes is taken as a number of characters.
+use std::collections::HashMap;
+
+use crate::{
+    choice::Choice,
+    fill::FillMode,
+    interval::Interval,
+    password::{reachable_suffixes, reaches, PasswordSpec},
+    sampling::{Completions, SamplingMode},
+};
+
+// most compositions worked out one by one, when choices are capped and the rest of the password is
+// long there can be too many, the guaranteed entropy is given instead
+const COMPOSITION_LIMIT: usize = 20_000;
+
+// longest length the model is worked out for, its tables grow with the length, beyond it the
+// guaranteed entropy is given instead as well
+const MODEL_LENGTH_LIMIT: usize = 1 << 20;
+
+// log2 of n! for every n up to the length
+pub(crate) struct LogFactorials(Vec<f64>);
+
+impl LogFactorials {
+    // `None` when the table can't have `n + 1` entries
+    pub(crate) fn new(n: usize) -> Option<Self> {
+        let mut table = vec![0.0; n.checked_add(1)?];
+        for k in 1..=n {
+            table[k] = table[k - 1] + (k as f64).log2();
+        }
+        Some(Self(table))
+    }
+
+    pub(crate) fn get(&self, n: usize) -> f64 {
+        self.0[n]
+    }
+}
+
+// how the counts of the choices come out for one pick of intervals
+enum Compositions {
+    // the rest of the password spread at random over choices that can all take any of it, the
+    // extra counts are multinomial
+    Uniform {
+        min: Vec<usize>,
+        active: Vec<usize>,
+        extra: usize,
+    },
+    // every composition with its probability
+    Listed(HashMap<Vec<usize>, f64>),
+}
+
+// what a pick of intervals contributes
+struct Stats {
+    // of the counts
+    entropy: f64,
+    // expected log2 of the ways to arrange them
+    arrangements: f64,
+    // expected count for each choice
+    counts: Vec<f64>,
+}
+
+impl Compositions {
+    fn stats(&self, lf: &LogFactorials, length: usize) -> Stats {
+        match self {
+            Compositions::Uniform { min, active, extra } => {
+                let mut counts: Vec<f64> = min.iter().map(|m| *m as f64).collect();
+                let mut arrangements = lf.get(length);
+                let mut entropy = 0.0;
+                if !active.is_empty() {
+                    let pmf = binomial(*extra, active.len(), lf);
+                    entropy = *extra as f64 * (active.len() as f64).log2() - lf.get(*extra);
+                    for i in active {
+                        entropy += expect(&pmf, |x| lf.get(x));
+                        arrangements -= expect(&pmf, |x| lf.get(min[*i] + x));
+                        counts[*i] += *extra as f64 / active.len() as f64;
+                    }
+                }
+                for (i, m) in min.iter().enumerate() {
+                    if !active.contains(&i) {
+                        arrangements -= lf.get(*m);
+                    }
+                }
+                Stats {
+                    entropy,
+                    arrangements,
+                    counts,
+                }
+            }
+            Compositions::Listed(listed) => {
+                let width = listed.keys().next().map_or(0, Vec::len);
+                let mut stats = Stats {
+                    entropy: 0.0,
+                    arrangements: 0.0,
+                    counts: vec![0.0; width],
+                };
+                for (composition, p) in listed {
+                    stats.entropy -= p * p.log2();
+                    let ways = lf.get(length) - composition.iter().map(|k| lf.get(*k)).sum::<f64>();
+                    stats.arrangements += p * ways;
+                    for (count, k) in stats.counts.iter_mut().zip(composition) {
+                        *count += p * *k as f64;
+                    }
+                }
+                stats
+            }
+        }
+    }
+
+    fn probability(&self, composition: &[usize], lf: &LogFactorials) -> f64 {
+        match self {
+            Compositions::Uniform { min, active, extra } => {
+                let mut bits = lf.get(*extra) - *extra as f64 * (active.len() as f64).log2();
+                let mut total = 0;
+                for (i, (k, m)) in composition.iter().zip(min).enumerate() {
+                    if k < m || (k > m && !active.contains(&i)) {
+                        return 0.0;
+                    }
+                    total += k - m;
+                    bits -= lf.get(k - m);
+                }
+                if total == *extra {
+                    bits.exp2()
+                } else {
+                    0.0
+                }
+            }
+            Compositions::Listed(listed) => listed.get(composition).copied().unwrap_or(0.0),
+        }
+    }
+}
+
+// probabilities of 0..=n successes in n tries of 1 in `ways`
+fn binomial(n: usize, ways: usize, lf: &LogFactorials) -> Vec<f64> {
+    if ways == 1 {
+        let mut pmf = vec![0.0; n + 1];
+        pmf[n] = 1.0;
+        return pmf;
+    }
+    let hit = (1.0 / ways as f64).log2();
+    let miss = (1.0 - 1.0 / ways as f64).log2();
+    (0..=n)
+        .map(|x| {
+            (lf.get(n) - lf.get(x) - lf.get(n - x) + x as f64 * hit + (n - x) as f64 * miss).exp2()
+        })
+        .collect()
+}
+
+fn expect(pmf: &[f64], f: impl Fn(usize) -> f64) -> f64 {
+    pmf.iter().enumerate().map(|(x, p)| p * f(x)).sum()
+}
+
+// how generating from a spec with its length resolved comes out
+struct Model {
+    length: usize,
+    drawable: Vec<(Choice, Vec<char>)>,
+    picks: Vec<(f64, Compositions)>,
+    lf: LogFactorials,
+}
+
+impl Model {
+    fn entropy(&self) -> f64 {
+        let bits_each: Vec<f64> = self
+            .drawable
+            .iter()
+            .map(|(choice, pool)| choice.entropy_from(pool))
+            .collect();
+        self.picks
+            .iter()
+            .map(|(p, compositions)| {
+                let stats = compositions.stats(&self.lf, self.length);
+                let drawn: f64 = stats
+                    .counts
+                    .iter()
+                    .zip(&bits_each)
+                    .map(|(k, h)| k * h)
+                    .sum();
+                p * (-p.log2() + stats.entropy + stats.arrangements + drawn)
+            })
+            .sum()
+    }
+}
+
+impl PasswordSpec {
+    // bits of entropy of the passwords the spec generates, on average, see above for what's taken
+    // into account, 0 when the spec can't generate
+    // the guaranteed entropy when the model can't be worked out, e.g. for lengths beyond 2^20
+    pub fn entropy_bits(&self) -> f64 {
+        let spec = match self.resolved() {
+            Ok(spec) if spec.precheck().is_ok() => spec,
+            _ => return 0.0,
+        };
+        match spec.model() {
+            Some(model) => model.entropy(),
+            None => spec.entropy().unwrap_or(0.0),
+        }
+    }
+
+    // bits it took to generate this password, `-log2` of the chance of drawing it
+    // `None` when it doesn't match the spec, when generating couldn't have given it, e.g. counts a
+    // balanced fill never makes, or there are too many compositions or characters to work it out
+    // a character in more than one charset is put down to the first choice still short of its
+    // minimum, or else the first one with room for it
+    pub fn password_entropy_bits(&self, password: &str) -> Option<f64> {
+        if !self.matches(password) {
+            return None;
+        }
+        let password = self.normalization.apply(password);
+        let core = password
+            .strip_prefix(self.prefix.as_str())
+            .and_then(|rest| rest.strip_suffix(self.suffix.as_str()))?;
+        let model = self.resolved().ok()?.model()?;
+        let mut counts = vec![0; model.drawable.len()];
+        let mut bits = 0.0;
+        for c in core.chars() {
+            let holding: Vec<usize> = (0..model.drawable.len())
+                .filter(|i| model.drawable[*i].1.contains(&c))
+                .collect();
+            let short = |i: &&usize| counts[**i] < model.drawable[**i].0.min;
+            let room = |i: &&usize| counts[**i] < model.drawable[**i].0.max;
+            let i = *holding
+                .iter()
+                .find(short)
+                .or_else(|| holding.iter().find(room))?;
+            counts[i] += 1;
+            let (choice, pool) = &model.drawable[i];
+            bits -= match &choice.weights {
+                Some(weights) => {
+                    let total: f64 = pool.iter().map(|c| weights.weight(*c)).sum();
+                    (weights.weight(c) / total).log2()
+                }
+                None => (1.0 / pool.len() as f64).log2(),
+            };
+        }
+        let p: f64 = model
+            .picks
+            .iter()
+            .map(|(p, compositions)| p * compositions.probability(&counts, &model.lf))
+            .sum();
+        if p == 0.0 {
+            return None;
+        }
+        let arrangements =
+            model.lf.get(model.length) - counts.iter().map(|k| model.lf.get(*k)).sum::<f64>();
+        Some(bits - p.log2() + arrangements)
+    }
+
+    // the spec has to have its length resolved and be able to generate
+    fn model(&self) -> Option<Model> {
+        let length = self.resolve_length().ok()?;
+        if length > MODEL_LENGTH_LIMIT {
+            return None;
+        }
+        let drawable = self.drawable();
+        let intervals: Vec<Vec<Interval>> = drawable
+            .iter()
+            .map(|(choice, _)| choice.intervals())
+            .collect();
+        let mut picks = vec![];
+        if self.sampling == SamplingMode::Uniform {
+            let listed = Completions::new(&drawable, length)?.compositions(COMPOSITION_LIMIT)?;
+            picks.push((1.0, Compositions::Listed(listed)));
+        } else {
+            for (p, picked) in interval_picks(&intervals, length)? {
+                let compositions = self.compositions(&drawable, &picked, length)?;
+                picks.push((p, compositions));
+            }
+        }
+        Some(Model {
+            length,
+            drawable,
+            picks,
+            lf: LogFactorials::new(length)?,
+        })
+    }
+
+    // the counts the characters beyond the minimums can make, as `draw_from` spreads them
+    fn compositions(
+        &self,
+        drawable: &[(Choice, Vec<char>)],
+        picked: &[Interval],
+        length: usize,
+    ) -> Option<Compositions> {
+        let min: Vec<usize> = picked.iter().map(|interval| interval.min).collect();
+        let capacities: Vec<usize> = drawable
+            .iter()
+            .zip(picked)
+            .map(|((_, pool), interval)| {
+                if pool.is_empty() {
+                    0
+                } else {
+                    interval.max - interval.min
+                }
+            })
+            .collect();
+        let extra = length.checked_sub(min.iter().sum())?;
+        let active: Vec<usize> = (0..capacities.len())
+            .filter(|i| capacities[*i] > 0)
+            .collect();
+        match self.fill {
+            FillMode::Random if active.iter().all(|i| capacities[*i] >= extra) => {
+                Some(Compositions::Uniform { min, active, extra })
+            }
+            FillMode::Random => random_fill(&min, &capacities, extra).map(Compositions::Listed),
+            FillMode::Balanced => balanced_fill(&min, &capacities, extra).map(Compositions::Listed),
+        }
+    }
+}
+
+// every pick of an interval for each choice with its probability, picked in turn uniformly
+// among those that can still make the length, as `pick_intervals` does
+fn interval_picks(intervals: &[Vec<Interval>], length: usize) -> Option<Vec<(f64, Vec<Interval>)>> {
+    let after = reachable_suffixes(intervals, length);
+    let mut picks = vec![(1.0, vec![])];
+    let mut totals = vec![Interval::exactly(0)];
+    for (i, options) in intervals.iter().enumerate() {
+        let mut next = vec![];
+        let mut next_totals = vec![];
+        for ((p, picked), total) in picks.iter().zip(&totals) {
+            let open: Vec<&Interval> = options
+                .iter()
+                .filter(|interval| reaches(&after[i + 1], length, &total.add(interval)))
+                .collect();
+            for interval in &open {
+                let mut picked = picked.clone();
+                picked.push((*interval).clone());
+                next.push((p / open.len() as f64, picked));
+                next_totals.push(total.add(interval));
+            }
+        }
+        if next.len() > COMPOSITION_LIMIT {
+            return None;
+        }
+        picks = next;
+        totals = next_totals;
+    }
+    Some(picks)
+}
+
+// a choice with room left picked uniformly for each character, choices drop out as they fill up
+fn random_fill(
+    min: &[usize],
+    capacities: &[usize],
+    extra: usize,
+) -> Option<HashMap<Vec<usize>, f64>> {
+    // the ways to split `extra` over the open choices, fewer when they're capped
+    let open = capacities.iter().filter(|capacity| **capacity > 0).count();
+    let splits = (1..open).fold(1.0, |ways: f64, k| ways * (extra + k) as f64 / k as f64);
+    if splits > COMPOSITION_LIMIT as f64 {
+        return None;
+    }
+    let mut states: HashMap<Vec<usize>, f64> = HashMap::from([(min.to_vec(), 1.0)]);
+    for _ in 0..extra {
+        let mut next = HashMap::new();
+        for (counts, p) in states {
+            let open: Vec<usize> = (0..counts.len())
+                .filter(|i| counts[*i] - min[*i] < capacities[*i])
+                .collect();
+            for i in &open {
+                let mut counts = counts.clone();
+                counts[*i] += 1;
+                *next.entry(counts).or_insert(0.0) += p / open.len() as f64;
+            }
+        }
+        if next.len() > COMPOSITION_LIMIT {
+            return None;
+        }
+        states = next;
+    }
+    Some(states)
+}
+
+// the even split of `fill::balanced`, with each way of handing out the leftover equally likely
+fn balanced_fill(
+    min: &[usize],
+    capacities: &[usize],
+    mut remaining: usize,
+) -> Option<HashMap<Vec<usize>, f64>> {
+    let mut counts = vec![0; capacities.len()];
+    loop {
+        let open: Vec<usize> = (0..capacities.len())
+            .filter(|i| counts[*i] < capacities[*i])
+            .collect();
+        if remaining == 0 || open.is_empty() {
+            let total: Vec<usize> = min.iter().zip(&counts).map(|(m, k)| m + k).collect();
+            return Some(HashMap::from([(total, 1.0)]));
+        }
+        let share = remaining / open.len();
+        if share == 0 {
+            let subsets = subsets(&open, remaining, COMPOSITION_LIMIT)?;
+            let p = 1.0 / subsets.len() as f64;
+            return Some(
+                subsets
+                    .into_iter()
+                    .map(|subset| {
+                        let mut total: Vec<usize> =
+                            min.iter().zip(&counts).map(|(m, k)| m + k).collect();
+                        for i in subset {
+                            total[i] += 1;
+                        }
+                        (total, p)
+                    })
+                    .collect(),
+            );
+        }
+        for i in open {
+            let given = share.min(capacities[i] - counts[i]);
+            counts[i] += given;
+            remaining -= given;
+        }
+    }
+}
+
+// the ways to pick `size` of the items, `None` beyond `limit` of them
+fn subsets(items: &[usize], size: usize, limit: usize) -> Option<Vec<Vec<usize>>> {
+    if size == 0 {
+        return Some(vec![vec![]]);
+    }
+    let mut all = vec![];
+    for (i, item) in items.iter().enumerate() {
+        for mut rest in subsets(&items[i + 1..], size - 1, limit)? {
+            rest.insert(0, *item);
+            all.push(rest);
+            if all.len() > limit {
+                return None;
+            }
+        }
+    }
+    Some(all)
+}
@@ -0,0 +1,77 @@
+// Handing a password straight to another program, so it never shows up on stdout or in a shell.
+// The password goes in the child's environment or on its stdin, never in its arguments where
+// other users can see it in the process list.
+use std::{
+    io::{ErrorKind, Write},
+    process::{Command, ExitStatus, Stdio},
+};
+
+use thiserror::Error;
+
+// environment variable the password is put in with `Delivery::Env`
+pub const PASSWORD_VAR: &str = "PANTS_GEN_PASSWORD";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    // in `PASSWORD_VAR`, only in the child's environment, ours is never touched
+    Env,
+    // written to the child's stdin followed by a newline, then stdin is closed
+    Stdin,
+}
+
+// none of these mention the password
+#[derive(Debug, Error)]
+pub enum ExecError {
+    #[error("No command given to run")]
+    NoCommand,
+    #[error("Couldn't start `{0}`: {1}")]
+    Spawn(String, std::io::Error),
+    #[error("Couldn't write the password to the stdin of `{0}`: {1}")]
+    Stdin(String, std::io::Error),
+    #[error("Couldn't wait for `{0}` to finish: {1}")]
+    Wait(String, std::io::Error),
+}
+
+// a command line split on whitespace, there's no quoting, for anything more involved build the
+// `Command` directly
+pub fn command(line: &str) -> Result<Command, ExecError> {
+    let mut words = line.split_whitespace();
+    let mut command = Command::new(words.next().ok_or(ExecError::NoCommand)?);
+    command.args(words);
+    Ok(command)
+}
+
+// run the command with the password and wait for it, its exit status is handed back so callers
+// can exit with it
+pub fn run(
+    command: &mut Command,
+    password: &str,
+    delivery: Delivery,
+) -> Result<ExitStatus, ExecError> {
+    let program = command.get_program().to_string_lossy().to_string();
+    match delivery {
+        Delivery::Env => {
+            command.env(PASSWORD_VAR, password).stdin(Stdio::inherit());
+        }
+        Delivery::Stdin => {
+            // a value inherited from our own environment would be a second, stale copy
+            command.env_remove(PASSWORD_VAR).stdin(Stdio::piped());
+        }
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| ExecError::Spawn(program.clone(), e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        match stdin.write_all(format!("{}\n", password).as_bytes()) {
+            // the child is done with its input early, its status says whether that's a problem
+            Err(e) if e.kind() == ErrorKind::BrokenPipe => {}
+            Err(e) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(ExecError::Stdin(program, e));
+            }
+            Ok(()) => {}
+        }
+    }
+    child.wait().map_err(|e| ExecError::Wait(program, e))
+}
@@ -0,0 +1,103 @@
+// Generation from an rng that can fail, e.g. an approved DRBG that reports running out of entropy,
+// where a failure has to surface as an error rather than a panic or a weaker password.
+//
+// `TryRng` feeds generation through `try_fill_bytes`. The first failure is recorded and zeros are
+// handed out from then on so generation can run to its end, after which the whole result is
+// thrown away in favour of the error. Nothing generated with bad randomness is ever returned.
+// The infallible paths don't go through any of this.
+use rand::{Error, RngCore};
+
+use crate::password::{GenerateError, PasswordSpec};
+
+pub struct TryRng<'a, R: RngCore + ?Sized> {
+    inner: &'a mut R,
+    failure: Option<Error>,
+}
+
+impl<'a, R: RngCore + ?Sized> TryRng<'a, R> {
+    pub fn new(inner: &'a mut R) -> Self {
+        Self {
+            inner,
+            failure: None,
+        }
+    }
+
+    // the result of `f`, unless the rng failed somewhere along the way
+    pub fn run<T>(
+        inner: &'a mut R,
+        f: impl FnOnce(&mut Self) -> Result<T, GenerateError>,
+    ) -> Result<T, GenerateError> {
+        let mut rng = Self::new(inner);
+        let result = f(&mut rng);
+        match rng.failure.take() {
+            Some(e) => Err(GenerateError::RngFailure(e)),
+            None => result,
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        self.failure.is_some()
+    }
+}
+
+impl<'a, R: RngCore + ?Sized> RngCore for TryRng<'a, R> {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if self.failure.is_none() {
+            if let Err(e) = self.inner.try_fill_bytes(dest) {
+                self.failure = Some(e);
+            }
+        }
+        if self.failure.is_some() {
+            dest.fill(0);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        match &self.failure {
+            Some(_) => Err(Error::new("an earlier draw from the rng failed")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl PasswordSpec {
+    pub fn generate_with_try_rng<R: RngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<String, GenerateError> {
+        TryRng::run(rng, |rng| self.generate_once(rng))
+    }
+
+    // all `count` passwords or an error, never some of them
+    pub fn generate_batch_with_try_rng<R: RngCore + ?Sized>(
+        &self,
+        count: usize,
+        rng: &mut R,
+    ) -> Result<Vec<String>, GenerateError> {
+        TryRng::run(rng, |rng| {
+            let mut passwords = Vec::with_capacity(count);
+            for _done in 0..count {
+                #[cfg(feature = "tracing")]
+                tracing::trace!(done = _done, total = count, "batch progress");
+                passwords.push(self.generate_once(rng)?);
+                if rng.failed() {
+                    break;
+                }
+            }
+            Ok(passwords)
+        })
+    }
+}
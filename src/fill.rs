@@ -0,0 +1,43 @@
+// How the characters beyond each choice's minimum are spread over the choices.
+//
+// The default picks a choice at random for every one of them, so a long password can now and then
+// end up mostly one class. That's fine randomness, but it looks suspicious to some. Balanced
+// splits them evenly instead, only the leftover after dividing equally is random. Knowing the
+// composition up to a character costs the entropy of how the counts could have fallen.
+use rand::{seq::index, Rng};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FillMode {
+    #[default]
+    Random,
+    Balanced,
+}
+
+// how many of `remaining` characters each choice gets, as evenly as their capacities allow
+pub(crate) fn balanced<R: Rng + ?Sized>(
+    capacities: &[usize],
+    mut remaining: usize,
+    rng: &mut R,
+) -> Vec<usize> {
+    let mut counts = vec![0; capacities.len()];
+    loop {
+        let open: Vec<usize> = (0..capacities.len())
+            .filter(|i| counts[*i] < capacities[*i])
+            .collect();
+        if remaining == 0 || open.is_empty() {
+            return counts;
+        }
+        let share = remaining / open.len();
+        if share == 0 {
+            for i in index::sample(rng, open.len(), remaining) {
+                counts[open[i]] += 1;
+            }
+            return counts;
+        }
+        for i in open {
+            let given = share.min(capacities[i] - counts[i]);
+            counts[i] += given;
+            remaining -= given;
+        }
+    }
+}
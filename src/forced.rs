@@ -0,0 +1,91 @@
+// When the length leaves no room, e.g. it's the sum of the minimums, every password has the same
+// number of characters from each choice. That's fine when it's what the spec says, but a choice
+// like `0-2|:symbol:` next to it then never adds anything, and rules that don't fit those counts
+// would only show up as failed attempts.
+use crate::{
+    adjacent,
+    choice::Choice,
+    interval::Interval,
+    password::{GenerateError, PasswordSpec},
+    position,
+};
+
+impl PasswordSpec {
+    // how many characters each choice has in every password, when the length is the sum of the
+    // minimums or of the maximums, `None` when the counts can vary
+    pub fn forced_composition(&self) -> Option<Vec<(Choice, usize)>> {
+        let length = self.resolve_length().ok()?;
+        let total = self
+            .drawn_choices()
+            .iter()
+            .fold(Interval::exactly(0), |sum, c| sum.add(&c.interval()));
+        let count = |choice: &Choice| {
+            if length == total.min {
+                Some(choice.min)
+            } else if length == total.max {
+                Some(choice.max)
+            } else {
+                None
+            }
+        };
+        self.choices()
+            .map(|choice| count(choice).map(|n| (choice.clone(), n)))
+            .collect()
+    }
+
+    // the forced composition when some choice asks for a range of counts it can't make use of
+    pub(crate) fn unused_ranges(&self) -> Option<Vec<(Choice, usize)>> {
+        self.forced_composition().filter(|composition| {
+            composition
+                .iter()
+                .any(|(choice, _)| choice.min < choice.max)
+        })
+    }
+
+    // the position and adjacency rules checked against the forced composition before drawing
+    // anything, rather than failing every attempt to place the characters
+    pub(crate) fn check_forced(
+        &self,
+        sets: &[Option<Vec<char>>],
+        length: usize,
+    ) -> Result<(), GenerateError> {
+        let composition = match self.forced_composition() {
+            Some(composition) => composition,
+            None => return Ok(()),
+        };
+        for charset in &self.no_adjacent {
+            let count = composition
+                .iter()
+                .filter(|(choice, _)| {
+                    let pool = self.pool(choice);
+                    !pool.is_empty() && pool.iter().all(|c| charset.contains(*c))
+                })
+                .fold(0usize, |sum, (_, n)| sum.saturating_add(*n));
+            if count > adjacent::most_apart(length) {
+                return Err(GenerateError::AdjacencyInfeasible {
+                    charset: charset.clone(),
+                    count,
+                    length,
+                });
+            }
+        }
+        let constrained: Vec<usize> = (0..sets.len()).filter(|p| sets[*p].is_some()).collect();
+        if constrained.is_empty() {
+            return Ok(());
+        }
+        // one item per forced character, fitting a position when its choice has a character
+        // the position allows
+        let pools: Vec<Vec<char>> = composition
+            .iter()
+            .flat_map(|(choice, n)| std::iter::repeat(self.pool(choice)).take(*n))
+            .collect();
+        let fits = |position: usize, item: usize| {
+            sets[position]
+                .as_ref()
+                .map_or(true, |set| pools[item].iter().any(|c| set.contains(c)))
+        };
+        position::assign(&constrained, pools.len(), &fits)
+            .map(|_| ())
+            .map_err(GenerateError::PositionConflict)
+    }
+}
@@ -0,0 +1,37 @@
+// The spec format from before `//` separators: `length[interval|charset][interval|charset]...`,
+// e.g. `16[3+|:upper:][1|:symbol:]`. It's only read, specs are always written in the current
+// format.
+//
+// It's told apart by the length being followed directly by `[`, which the current format never
+// allows. Choices are split at every `][`, so a custom charset containing `][` can't be given in
+// this format, while lone brackets in a custom charset are fine (`8[8|[]]` is 8 of `[` and `]`).
+use crate::password::{PasswordParseError, PasswordSpec};
+
+use super::parse_length;
+
+pub(super) fn detect(s: &str) -> bool {
+    let s = s.trim();
+    let digits = s.chars().take_while(|c| c.is_ascii_digit()).count();
+    digits > 0 && s[digits..].starts_with('[')
+}
+
+pub(super) fn parse(s: &str) -> Result<PasswordSpec, PasswordParseError> {
+    let s = s.trim();
+    let digits = s.chars().take_while(|c| c.is_ascii_digit()).count();
+    let mut spec = super::raw_spec();
+    (spec.length, spec.unit) = parse_length(&s[..digits])?;
+    let choices = s[digits..]
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or(PasswordParseError::ImproperFormat)?;
+    for segment in choices.split("][") {
+        if segment.is_empty() {
+            return Err(PasswordParseError::EmptySegment);
+        }
+        spec = super::include(
+            spec,
+            segment.parse().map_err(PasswordParseError::BadChoice)?,
+        )?;
+    }
+    Ok(spec)
+}
@@ -0,0 +1,129 @@
+// Spec strings come in two versions: the original grammar (v1) which has no prefix, and v2 which
+// is prefixed with `v2:`, lets custom charsets escape characters with `\` and gives choices labels.
+// Display only uses v2 when a spec can't be written as v1, so older readers keep working.
+// The bracketed format that came before v1 is still read, see `legacy`.
+mod legacy;
+mod v1;
+mod v2;
+
+use std::fmt;
+
+use crate::{
+    charset::CharsetParseError,
+    choice::{Choice, ChoiceParseError},
+    password::{Length, PasswordParseError, PasswordSpec},
+    unicode::Normalization,
+    units::LengthUnit,
+};
+
+const V2_PREFIX: &str = "v2:";
+// in place of the interval, the segment is then the allowed characters rather than a choice
+const ALLOWED: &str = "*|";
+
+pub(crate) fn parse(
+    s: &str,
+    normalization: Normalization,
+) -> Result<PasswordSpec, PasswordParseError> {
+    let parsed = match s.trim_start().strip_prefix(V2_PREFIX) {
+        Some(rest) => v2::parse(rest),
+        None if legacy::detect(s) => legacy::parse(s),
+        // a v1 failure with escapes that would have been fine as v2 is most likely a missing
+        // prefix, without escapes v2 reads the same and the v1 error is the one to give
+        None => v1::parse(s).map_err(|e| match v2::parse(s) {
+            Ok(_) if s.contains('\\') => PasswordParseError::MissingVersion(Box::new(e)),
+            _ => e,
+        }),
+    }
+    .and_then(|spec| normalized(spec, normalization));
+    #[cfg(feature = "tracing")]
+    match &parsed {
+        Ok(spec) => tracing::debug!(spec = %spec, "parsed spec"),
+        Err(e) => tracing::debug!(error = e.code(), "spec didn't parse"),
+    }
+    parsed
+}
+
+// the parsers take charsets as written, `parse` normalizes them once the whole spec is read
+fn raw_spec() -> PasswordSpec {
+    PasswordSpec::new().normalization(Normalization::None)
+}
+
+// normalizing can leave a custom charset with nothing in it, e.g. one that's only a combining
+// accent, which is as much a charset that wasn't given as one written empty
+fn normalized(
+    spec: PasswordSpec,
+    normalization: Normalization,
+) -> Result<PasswordSpec, PasswordParseError> {
+    let spec = spec.normalization(normalization);
+    if spec.choices().any(|choice| choice.chars.is_empty())
+        || spec
+            .allowed
+            .as_ref()
+            .map_or(false, |allowed| allowed.is_empty())
+    {
+        Err(no_charset())
+    } else {
+        Ok(spec)
+    }
+}
+
+fn no_charset() -> PasswordParseError {
+    PasswordParseError::BadChoice(ChoiceParseError::Charset(CharsetParseError::NoCharset))
+}
+
+// every version adds its choices through `try_include`, a charset with nothing in it is a charset
+// that wasn't given
+fn include(spec: PasswordSpec, choice: Choice) -> Result<PasswordSpec, PasswordParseError> {
+    spec.try_include(choice).map_err(|_| no_charset())
+}
+
+pub(crate) fn is_legacy(s: &str) -> bool {
+    legacy::detect(s)
+}
+
+pub(crate) fn write(spec: &PasswordSpec, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if spec
+        .choices()
+        .all(|choice| v1::can_write(&choice.chars) && choice.label.is_none())
+        && spec.allowed.as_ref().map_or(true, v1::can_write)
+    {
+        v1::write(spec, f)
+    } else {
+        write!(f, "{}", V2_PREFIX)?;
+        v2::write(spec, f)
+    }
+}
+
+// the length segment is the same in both versions, a number of characters or `Nb` for bits, the
+// number can be followed by its unit, see `units`
+fn parse_length(s: &str) -> Result<(Length, LengthUnit), PasswordParseError> {
+    let number = |digits: &str| {
+        if digits
+            .chars()
+            .any(|c| c.is_numeric() && !c.is_ascii_digit())
+        {
+            return Err(PasswordParseError::NonAsciiLength(s.to_string()));
+        }
+        digits.parse().map_err(|e: std::num::ParseIntError| {
+            if *e.kind() == std::num::IntErrorKind::PosOverflow {
+                PasswordParseError::LengthOverflow {
+                    text: s.to_string(),
+                    max: usize::MAX,
+                }
+            } else {
+                PasswordParseError::InvalidLength(s.to_string())
+            }
+        })
+    };
+    if let Some(bits) = s.strip_suffix('b') {
+        return Ok((Length::Bits(number(bits)?), LengthUnit::Chars));
+    }
+    match s.chars().last().and_then(LengthUnit::from_suffix) {
+        Some(unit) => Ok((Length::Chars(number(&s[..s.len() - 1])?), unit)),
+        None => Ok((Length::Chars(number(s)?), LengthUnit::Chars)),
+    }
+}
+
+fn write_length(spec: &PasswordSpec, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}{}", spec.length, spec.unit().suffix())
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+
+use super::{parse_length, write_length, ALLOWED};
+use crate::{
+    charset::Charset,
+    choice::ChoiceParseError,
+    password::{PasswordParseError, PasswordSpec},
+};
+
+// password spec specified as a string would look something like
+// 16//1+|:upper://5-|:lower://2|Aa
+// (Upper, at least 1) (Lower, at most 5) (Custom(Aa), exactly 2) length=16
+// a charset can be labeled for messages, e.g. 2|pair=Aa
+
+pub(super) fn parse(s: &str) -> Result<PasswordSpec, PasswordParseError> {
+    let s = s.trim_start();
+    let sep = "//".to_string();
+    let sep_char = sep.chars().last().unwrap();
+    // let second_sep = "|".to_string();
+    let mut spec = super::raw_spec();
+    let mut stack = String::new();
+    let chars: Vec<char> = s.chars().collect();
+    // parse length first
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        stack.push(c);
+        i += 1;
+        if stack.ends_with(&sep) {
+            (spec.length, spec.unit) = parse_length(&stack[..stack.len() - sep.len()])?;
+            stack = String::new();
+            break;
+        }
+    }
+
+    // parse choices
+    while i < chars.len() {
+        let c = chars[i];
+        if c != sep_char && stack.ends_with(&sep) {
+            spec = segment(spec, &stack[..stack.len() - sep.len()])?;
+            stack = String::new();
+        }
+        stack.push(c);
+        i += 1;
+    }
+
+    // since parsing requires a peek, need to handle the very end of the string
+    // having a trailing // is valid
+    if stack.ends_with(&sep) {
+        spec = segment(spec, &stack[..stack.len() - sep.len()])?;
+        stack = String::new();
+    }
+
+    if !stack.is_empty() {
+        spec = segment(spec, &stack)?;
+        // stack = String::new();
+    }
+
+    Ok(spec)
+}
+
+// a choice, or `*|charset` for the allowed characters
+fn segment(spec: PasswordSpec, s: &str) -> Result<PasswordSpec, PasswordParseError> {
+    match s.strip_prefix(ALLOWED) {
+        Some(charset) => Ok(spec.allowed(
+            charset
+                .parse()
+                .map_err(|e| PasswordParseError::BadChoice(ChoiceParseError::Charset(e)))?,
+        )),
+        None => super::include(spec, s.parse().map_err(PasswordParseError::BadChoice)?),
+    }
+}
+
+pub(super) fn write(spec: &PasswordSpec, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write_length(spec, f)?;
+    write!(f, "{}", spec.choices)?;
+    if let Some(allowed) = &spec.allowed {
+        write!(f, "//{}{}", ALLOWED, allowed)?;
+    } else if spec.choices.choices.is_empty() {
+        // the length alone would be read back as a choice, the separator keeps it a length
+        write!(f, "//")?;
+    }
+    Ok(())
+}
+
+// custom charsets containing the separator or looking like a :pattern: can't be written in v1
+pub(super) fn can_write(charset: &Charset) -> bool {
+    match charset {
+        Charset::Custom(chars) => {
+            let s: String = chars.iter().collect();
+            let pattern_like = s.starts_with(':') && s.ends_with(':');
+            !s.is_empty() && !s.contains("//") && !pattern_like
+        }
+        _ => true,
+    }
+}
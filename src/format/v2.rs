@@ -0,0 +1,151 @@
+use std::fmt;
+
+use super::{parse_length, write_length, ALLOWED};
+use crate::{
+    charset::Charset,
+    choice::{is_label, parse_intervals, split_label, Choice, ChoiceParseError},
+    password::{PasswordParseError, PasswordSpec},
+};
+
+// same layout as v1, length//interval|charset//interval|charset, but any character can be
+// escaped with `\` so custom charsets can contain `//`, look like a :pattern:, etc.
+// Display escapes every `\`, `/`, `|` and `:` in custom charsets.
+// A choice can be labeled, `2+|label=charset`, an escaped `=` is part of the charset instead.
+// Labels are only read here, in v1 `ab=cd` has always been the five characters.
+
+const ESCAPED: [char; 4] = ['\\', '/', '|', ':'];
+
+// characters paired with whether they were escaped
+type Token = (char, bool);
+
+fn tokenize(s: &str) -> Result<Vec<Token>, PasswordParseError> {
+    let mut tokens = vec![];
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let escaped = chars.next().ok_or(PasswordParseError::DanglingEscape)?;
+            tokens.push((escaped, true));
+        } else {
+            tokens.push((c, false));
+        }
+    }
+    Ok(tokens)
+}
+
+fn is_unescaped(token: Option<&Token>, c: char) -> bool {
+    token == Some(&(c, false))
+}
+
+fn text(tokens: &[Token]) -> String {
+    tokens.iter().map(|(c, _)| c).collect()
+}
+
+pub(super) fn parse(s: &str) -> Result<PasswordSpec, PasswordParseError> {
+    let tokens = tokenize(s.trim_start())?;
+    let mut segments: Vec<&[Token]> = vec![];
+    let mut start = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        if is_unescaped(tokens.get(i), '/') && is_unescaped(tokens.get(i + 1), '/') {
+            segments.push(&tokens[start..i]);
+            i += 2;
+            start = i;
+        } else {
+            i += 1;
+        }
+    }
+    // having a trailing // is valid
+    if start < tokens.len() {
+        segments.push(&tokens[start..]);
+    }
+
+    let mut segments = segments.into_iter();
+    let mut spec = super::raw_spec();
+    if let Some(length) = segments.next() {
+        (spec.length, spec.unit) = parse_length(&text(length))?;
+    }
+    for segment in segments {
+        if segment.is_empty() {
+            return Err(PasswordParseError::EmptySegment);
+        }
+        if let [('*', false), ('|', false), charset @ ..] = segment {
+            spec = spec.allowed(parse_charset(charset).map_err(PasswordParseError::BadChoice)?);
+            continue;
+        }
+        spec = super::include(
+            spec,
+            parse_choice(segment).map_err(PasswordParseError::BadChoice)?,
+        )?;
+    }
+    Ok(spec)
+}
+
+fn parse_choice(tokens: &[Token]) -> Result<Choice, ChoiceParseError> {
+    let pos = tokens
+        .iter()
+        .position(|token| *token == ('|', false))
+        .ok_or_else(|| ChoiceParseError::BadFormat(text(tokens)))?;
+    let intervals =
+        parse_intervals(&text(&tokens[..pos])).map_err(ChoiceParseError::BadInterval)?;
+    let rest = &tokens[pos + 1..];
+    // only an unescaped `=` after unescaped label characters ends a label
+    let (label, charset) = match rest.iter().position(|token| *token == ('=', false)) {
+        Some(eq)
+            if eq + 1 < rest.len()
+                && rest[..eq].iter().all(|(_, escaped)| !*escaped)
+                && is_label(&text(&rest[..eq])) =>
+        {
+            (Some(text(&rest[..eq])), &rest[eq + 1..])
+        }
+        _ => (None, rest),
+    };
+    let mut choice = Choice::any_of(intervals, parse_charset(charset)?)
+        .ok_or_else(|| ChoiceParseError::BadFormat(text(tokens)))?;
+    choice.label = label;
+    Ok(choice)
+}
+
+// any escape makes it a custom charset, `\:upper:` is the characters rather than the pattern
+fn parse_charset(tokens: &[Token]) -> Result<Charset, ChoiceParseError> {
+    if tokens.iter().any(|(_, escaped)| *escaped) {
+        Ok(Charset::Custom(tokens.iter().map(|(c, _)| *c).collect()))
+    } else {
+        text(tokens).parse().map_err(ChoiceParseError::Charset)
+    }
+}
+
+fn write_charset(charset: &Charset, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match charset {
+        Charset::Custom(chars) => {
+            // the `=` of characters that would read as a label gets escaped as well
+            let s: String = chars.iter().collect();
+            let label_end = split_label(&s).0.map(|label| label.chars().count());
+            for (i, c) in chars.iter().enumerate() {
+                if ESCAPED.contains(c) || Some(i) == label_end {
+                    write!(f, "\\")?;
+                }
+                write!(f, "{}", c)?;
+            }
+            Ok(())
+        }
+        named => write!(f, "{}", named),
+    }
+}
+
+pub(super) fn write(spec: &PasswordSpec, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write_length(spec, f)?;
+    for choice in spec.choices() {
+        write!(f, "//")?;
+        choice.write_intervals(f)?;
+        write!(f, "|")?;
+        if let Some(label) = &choice.label {
+            write!(f, "{}=", label)?;
+        }
+        write_charset(&choice.chars, f)?;
+    }
+    if let Some(allowed) = &spec.allowed {
+        write!(f, "//{}", ALLOWED)?;
+        write_charset(allowed, f)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,185 @@
+// A generated password that remembers which charset each character came from, so parts of it can
+// be drawn again, e.g. when a password is fine except for one awkward character.
+use std::fmt::{Debug, Display};
+
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use thiserror::Error;
+
+use crate::{
+    charset::Charset,
+    password::{GenerateError, PasswordSpec},
+    secret::Secret,
+    violation::Violation,
+};
+
+// redraws tried before giving up on a reroll keeping the password within the spec
+const MAX_REROLL_ATTEMPTS: usize = 1000;
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct GeneratedPassword {
+    spec: PasswordSpec,
+    // the random core, the affixes come from the spec
+    chars: Vec<char>,
+    sources: Vec<Charset>,
+}
+
+#[derive(Debug, Error)]
+pub enum RerollError {
+    #[error("Position {0} is past the end of the password")]
+    OutOfRange(usize),
+    #[error("Position {0} is part of the fixed prefix or suffix")]
+    Fixed(usize),
+    #[error("Position {position} comes from `{charset}`, which has no other character to draw")]
+    NoAlternative { position: usize, charset: Charset },
+    #[error(
+        "Rerolling would leave {count} characters from `{charset}`, which the spec doesn't allow"
+    )]
+    Violates { charset: Charset, count: usize },
+    #[error("Rerolling would break the spec: {0}")]
+    Breaks(Violation),
+}
+
+// the password redacted, and without the sources since they'd give away its composition
+impl Debug for GeneratedPassword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GeneratedPassword")
+            .field("spec", &self.spec)
+            .field("password", &self.secret())
+            .finish()
+    }
+}
+
+impl Display for GeneratedPassword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.password())
+    }
+}
+
+impl PasswordSpec {
+    pub fn generate_detailed(&self) -> Result<GeneratedPassword, GenerateError> {
+        self.generate_detailed_with_rng(&mut thread_rng())
+    }
+
+    // the same password `generate_with_rng` gives for the same rng state, with where each
+    // character came from
+    pub fn generate_detailed_with_rng<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<GeneratedPassword, GenerateError> {
+        let (chars, sources): (Vec<char>, Vec<Charset>) =
+            self.generate_drawn(rng)?.into_iter().unzip();
+        self.checked_length(chars.iter().collect())?;
+        Ok(GeneratedPassword {
+            spec: self.clone(),
+            chars,
+            sources,
+        })
+    }
+}
+
+impl GeneratedPassword {
+    pub fn password(&self) -> String {
+        format!(
+            "{}{}{}",
+            self.spec.prefix,
+            self.chars.iter().collect::<String>(),
+            self.spec.suffix
+        )
+    }
+
+    // the password, kept out of `Debug` output for passing around
+    pub fn secret(&self) -> Secret<String> {
+        Secret::new(self.password())
+    }
+
+    pub fn spec(&self) -> &PasswordSpec {
+        &self.spec
+    }
+
+    // the charset each character of the random core was drawn from
+    pub fn sources(&self) -> &[Charset] {
+        &self.sources
+    }
+
+    // draw new characters for the given positions, counting from 1 over the whole password like
+    // `r 7` in a picker, each from the charset that produced it and different from the one there
+    // before
+    // the whole password has to still match the spec afterwards, with overlapping charsets a new
+    // character can change the counts of other charsets and it can land where a position rule or
+    // `no_adjacent` doesn't allow it, if no redraw fits the password is left as it was
+    pub fn reroll_positions<R: Rng + ?Sized>(
+        &mut self,
+        positions: impl IntoIterator<Item = usize>,
+        rng: &mut R,
+    ) -> Result<(), RerollError> {
+        let prefix = self.spec.prefix.chars().count();
+        let total = prefix + self.chars.len() + self.spec.suffix.chars().count();
+        let mut targets = vec![];
+        for position in positions {
+            if position == 0 || position > total {
+                return Err(RerollError::OutOfRange(position));
+            }
+            if position <= prefix || position > prefix + self.chars.len() {
+                return Err(RerollError::Fixed(position));
+            }
+            let index = position - prefix - 1;
+            let charset = &self.sources[index];
+            let choices = self.spec.drawn_choices();
+            let choice = choices.iter().find(|choice| &choice.chars == charset);
+            let pool = choice
+                .map(|choice| self.spec.pool(choice))
+                .unwrap_or_default();
+            let alternatives: Vec<char> = pool
+                .into_iter()
+                .filter(|c| *c != self.chars[index])
+                .collect();
+            if alternatives.is_empty() {
+                return Err(RerollError::NoAlternative {
+                    position,
+                    charset: charset.clone(),
+                });
+            }
+            let weights = choice.and_then(|choice| choice.weights.clone());
+            targets.push((index, alternatives, weights));
+        }
+        let mut violation = None;
+        for _ in 0..MAX_REROLL_ATTEMPTS {
+            let mut chars = self.chars.clone();
+            for (index, alternatives, weights) in &targets {
+                let c = match weights {
+                    Some(weights) => weights.choose(alternatives, rng),
+                    None => alternatives.choose(rng).copied(),
+                };
+                if let Some(c) = c {
+                    chars[*index] = c;
+                }
+            }
+            let core: String = chars.iter().collect();
+            let password = format!("{}{}{}", self.spec.prefix, core, self.spec.suffix);
+            if self.spec.matches(&password) {
+                self.chars = chars;
+                return Ok(());
+            }
+            // a count is what overlapping charsets break, the rest of the spec after that
+            let counts = self
+                .spec
+                .choices()
+                .zip(self.spec.count_by_charset(&core))
+                .find(|(choice, (_, count))| !choice.admits(*count))
+                .map(|(_, (charset, count))| RerollError::Violates { charset, count });
+            violation = counts
+                .or_else(|| {
+                    self.spec
+                        .violations(&password)
+                        .into_iter()
+                        .next()
+                        .map(RerollError::Breaks)
+                })
+                .or(violation);
+        }
+        match violation {
+            Some(violation) => Err(violation),
+            None => Ok(()),
+        }
+    }
+}
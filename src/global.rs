@@ -0,0 +1,44 @@
+// An application wide default spec, set once from the application's own config so every caller of
+// `PasswordSpec::default()` gets the same policy without passing it around.
+//
+// Trade-offs:
+//  - it can only be set once, there's no way to change or clear it afterwards, so set it early
+//    before anything generates
+//  - it's global to the process, libraries should take a spec rather than set this, and tests
+//    setting it affect every other test in the same binary
+//  - `PasswordSpec::default()` clones it each time, which is a lock and a copy of a small spec
+//
+// A mutex rather than `OnceLock` since that needs rust 1.70.
+use std::sync::Mutex;
+
+use thiserror::Error;
+
+use crate::password::PasswordSpec;
+
+static DEFAULT_SPEC: Mutex<Option<PasswordSpec>> = Mutex::new(None);
+
+#[derive(Debug, Error)]
+#[error("The default spec was already set")]
+pub struct AlreadySet;
+
+// `default_spec` has the one that was set first when this errors
+pub fn set_default_spec(spec: PasswordSpec) -> Result<(), AlreadySet> {
+    // nothing panics while holding the lock, but a poisoned one still has a usable value
+    let mut current = DEFAULT_SPEC.lock().unwrap_or_else(|e| e.into_inner());
+    match &*current {
+        Some(_) => Err(AlreadySet),
+        None => {
+            *current = Some(spec);
+            Ok(())
+        }
+    }
+}
+
+// the spec given to `set_default_spec`, or the built in one when none was
+pub fn default_spec() -> PasswordSpec {
+    DEFAULT_SPEC
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(PasswordSpec::builtin_default)
+}
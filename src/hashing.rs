@@ -0,0 +1,143 @@
+// Hashing generated passwords for provisioning, where the hash is what gets stored.
+use std::{fmt::Display, str::FromStr};
+
+use argon2::{
+    password_hash::{PasswordHasher, SaltString},
+    Argon2,
+};
+use rand::{thread_rng, RngCore};
+use thiserror::Error;
+
+// Defaults follow OWASP's recommendations for argon2id and bcrypt, sha512-crypt uses glibc's
+// default rounds so hashes look like any other `/etc/shadow` entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Argon2 {
+        m_cost: u32,
+        t_cost: u32,
+        p_cost: u32,
+    },
+    Bcrypt {
+        cost: u32,
+    },
+    Sha512Crypt {
+        rounds: u32,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum HashError {
+    #[error("Unknown hash algorithm `{0}`, expect one of argon2, bcrypt or sha512-crypt")]
+    UnknownAlgorithm(String),
+    #[error("Hash parameter `{0}` isn't of the form name=value")]
+    BadParam(String),
+    #[error("{algorithm} has no parameter `{param}`, expect {expected}")]
+    UnknownParam {
+        algorithm: HashAlgorithm,
+        param: String,
+        expected: &'static str,
+    },
+    #[error("Hash parameter `{param}` expects a whole number, got `{value}`")]
+    BadValue { param: String, value: String },
+    #[error("Hashing with {0} failed: {1}")]
+    Hash(HashAlgorithm, String),
+}
+
+impl Display for HashAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HashAlgorithm::Argon2 { .. } => write!(f, "argon2"),
+            HashAlgorithm::Bcrypt { .. } => write!(f, "bcrypt"),
+            HashAlgorithm::Sha512Crypt { .. } => write!(f, "sha512-crypt"),
+        }
+    }
+}
+
+// the algorithm with its default parameters
+impl FromStr for HashAlgorithm {
+    type Err = HashError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "argon2" => Ok(HashAlgorithm::Argon2 {
+                m_cost: 19456,
+                t_cost: 2,
+                p_cost: 1,
+            }),
+            "bcrypt" => Ok(HashAlgorithm::Bcrypt {
+                cost: bcrypt::DEFAULT_COST,
+            }),
+            "sha512-crypt" => Ok(HashAlgorithm::Sha512Crypt {
+                rounds: pwhash::sha512_crypt::DEFAULT_ROUNDS,
+            }),
+            _ => Err(HashError::UnknownAlgorithm(s.to_string())),
+        }
+    }
+}
+
+impl HashAlgorithm {
+    // override parameters with a comma separated list like `m=19456,t=2,p=1`
+    // argon2 takes m, t and p, bcrypt takes cost and sha512-crypt takes rounds
+    pub fn with_params(mut self, params: &str) -> Result<Self, HashError> {
+        for param in params.split(',').filter(|p| !p.trim().is_empty()) {
+            let (name, value) = param
+                .split_once('=')
+                .ok_or_else(|| HashError::BadParam(param.to_string()))?;
+            let (name, value) = (name.trim(), value.trim());
+            let parsed = value.parse().map_err(|_| HashError::BadValue {
+                param: name.to_string(),
+                value: value.to_string(),
+            })?;
+            let algorithm = self;
+            let unknown = |expected| HashError::UnknownParam {
+                algorithm,
+                param: name.to_string(),
+                expected,
+            };
+            match (&mut self, name) {
+                (HashAlgorithm::Argon2 { m_cost, .. }, "m") => *m_cost = parsed,
+                (HashAlgorithm::Argon2 { t_cost, .. }, "t") => *t_cost = parsed,
+                (HashAlgorithm::Argon2 { p_cost, .. }, "p") => *p_cost = parsed,
+                (HashAlgorithm::Argon2 { .. }, _) => return Err(unknown("m, t or p")),
+                (HashAlgorithm::Bcrypt { cost }, "cost") => *cost = parsed,
+                (HashAlgorithm::Bcrypt { .. }, _) => return Err(unknown("cost")),
+                (HashAlgorithm::Sha512Crypt { rounds }, "rounds") => *rounds = parsed,
+                (HashAlgorithm::Sha512Crypt { .. }, _) => return Err(unknown("rounds")),
+            }
+        }
+        Ok(self)
+    }
+
+    // hash in the algorithm's usual string format (PHC for argon2, `$2b$` for bcrypt and `$6$`
+    // for sha512-crypt) with a fresh random salt
+    pub fn hash(&self, password: &str) -> Result<String, HashError> {
+        let failed = |e: &dyn Display| HashError::Hash(*self, e.to_string());
+        match *self {
+            HashAlgorithm::Argon2 {
+                m_cost,
+                t_cost,
+                p_cost,
+            } => {
+                let params =
+                    argon2::Params::new(m_cost, t_cost, p_cost, None).map_err(|e| failed(&e))?;
+                let argon2 =
+                    Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+                let mut salt = [0; 16];
+                thread_rng().fill_bytes(&mut salt);
+                let salt = SaltString::encode_b64(&salt).map_err(|e| failed(&e))?;
+                argon2
+                    .hash_password(password.as_bytes(), &salt)
+                    .map(|hash| hash.to_string())
+                    .map_err(|e| failed(&e))
+            }
+            HashAlgorithm::Bcrypt { cost } => bcrypt::hash(password, cost).map_err(|e| failed(&e)),
+            HashAlgorithm::Sha512Crypt { rounds } => pwhash::sha512_crypt::hash_with(
+                pwhash::HashSetup {
+                    salt: None,
+                    rounds: Some(rounds),
+                },
+                password,
+            )
+            .map_err(|e| failed(&e)),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+// Hex for the SHA-256 digests the history and transcript files keep.
+
+pub(crate) type Digest = [u8; 32];
+
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn decode(s: &str) -> Option<Digest> {
+    // `from_str_radix` would take a sign like `+f` as well
+    if s.len() != 64 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut res = [0; 32];
+    for (i, byte) in res.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(res)
+}
@@ -0,0 +1,183 @@
+use std::{collections::VecDeque, fmt::Debug, fs, io, path::Path};
+
+use hmac::{Hmac, Mac};
+use rand::{thread_rng, Rng};
+use sha2::Sha256;
+use thiserror::Error;
+
+use crate::{
+    bytes::{write_secret_file, BytesError},
+    hex::{self, Digest},
+    password::{GenerateError, PasswordSpec},
+};
+
+const HEADER: &str = "pants-gen-history v1";
+// how many times generation re-rolls on a history hit before giving up
+const MAX_REROLLS: usize = 100;
+
+// Record of recently issued passwords so they aren't handed out again.
+// Only keyed hashes (HMAC-SHA256 with a random per-file key) are kept so the passwords aren't in
+// the file as they are. The key is on the file's first line though, so anyone who can read the
+// file can check guesses against it and recover passwords with little entropy, like PINs. The
+// file is written readable by its owner only. Once more than `max_entries` are recorded the
+// oldest are dropped.
+#[derive(Clone)]
+pub struct History {
+    key: Digest,
+    entries: VecDeque<Digest>,
+    max_entries: usize,
+    recovered: bool,
+}
+
+// without the key, which is all it takes to check guesses against the entries
+impl Debug for History {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("History")
+            .field("key", &format_args!("[REDACTED]"))
+            .field("entries", &self.entries.len())
+            .field("max_entries", &self.max_entries)
+            .field("recovered", &self.recovered)
+            .finish()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    #[error("{0}")]
+    Io(io::Error),
+    #[error("{0}")]
+    Write(BytesError),
+}
+
+impl History {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            key: thread_rng().gen(),
+            entries: VecDeque::new(),
+            max_entries,
+            recovered: false,
+        }
+    }
+
+    // a missing file gives an empty history
+    // a corrupt file is recovered from rather than treated as an error: unreadable entries are
+    // skipped and an unreadable header starts over with a new key, `recovered()` reports this
+    pub fn load(path: impl AsRef<Path>, max_entries: usize) -> Result<Self, HistoryError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::new(max_entries)),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                let mut history = Self::new(max_entries);
+                history.recovered = true;
+                return Ok(history);
+            }
+            Err(e) => return Err(HistoryError::Io(e)),
+        };
+        let mut lines = contents.lines();
+        let key = lines
+            .next()
+            .and_then(|header| header.strip_prefix(HEADER))
+            .and_then(|key| hex::decode(key.trim()));
+        let mut history = Self::new(max_entries);
+        match key {
+            Some(key) => history.key = key,
+            None => {
+                history.recovered = true;
+                return Ok(history);
+            }
+        }
+        for line in lines {
+            match hex::decode(line.trim()) {
+                Some(entry) => history.entries.push_back(entry),
+                None => history.recovered = true,
+            }
+        }
+        history.trim();
+        Ok(history)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), HistoryError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(HistoryError::Io)?;
+        }
+        let mut contents = format!("{} {}\n", HEADER, hex::encode(&self.key));
+        for entry in &self.entries {
+            contents.push_str(&hex::encode(entry));
+            contents.push('\n');
+        }
+        write_secret_file(path, contents.as_bytes()).map_err(HistoryError::Write)
+    }
+
+    pub fn contains(&self, password: &str) -> bool {
+        let digest = self.digest(password);
+        self.entries.contains(&digest)
+    }
+
+    pub fn record(&mut self, password: &str) {
+        let digest = self.digest(password);
+        self.entries.push_back(digest);
+        self.trim();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn recovered(&self) -> bool {
+        self.recovered
+    }
+
+    // generate a password that isn't in the history and record it, re-rolling on hits
+    pub fn generate(&mut self, spec: &PasswordSpec) -> Option<String> {
+        self.generate_with_rng(spec, &mut thread_rng())
+    }
+
+    pub fn generate_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        spec: &PasswordSpec,
+        rng: &mut R,
+    ) -> Option<String> {
+        self.try_generate_with_rng(spec, rng).ok()
+    }
+
+    // like `generate` but with why no password came out, running out of re-rolls because
+    // everything generated was already issued is `AttemptsExhausted`
+    pub fn try_generate(&mut self, spec: &PasswordSpec) -> Result<String, GenerateError> {
+        self.try_generate_with_rng(spec, &mut thread_rng())
+    }
+
+    pub fn try_generate_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        spec: &PasswordSpec,
+        rng: &mut R,
+    ) -> Result<String, GenerateError> {
+        for _ in 0..MAX_REROLLS {
+            let password = spec.try_generate_with_rng(rng)?;
+            if !self.contains(&password) {
+                self.record(&password);
+                return Ok(password);
+            }
+        }
+        Err(GenerateError::AttemptsExhausted {
+            attempts: MAX_REROLLS,
+        })
+    }
+
+    fn trim(&mut self) {
+        while self.entries.len() > self.max_entries {
+            self.entries.pop_front();
+        }
+    }
+
+    fn digest(&self, password: &str) -> Digest {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(password.as_bytes());
+        mac.finalize().into_bytes().into()
+    }
+}
@@ -0,0 +1,208 @@
+// One spec for passwords that have to be accepted by several policies at once.
+use thiserror::Error;
+
+use crate::{
+    charset::Charset,
+    choice::{Choice, Choices},
+    interval::Interval,
+    password::{GenerateError, PasswordSpec},
+    units::LengthUnit,
+};
+
+#[derive(Debug, Error)]
+pub enum IntersectError {
+    #[error("The specs have different prefixes or suffixes")]
+    Affixes,
+    #[error("The specs ask for different lengths, {0} and {1}")]
+    Length(usize, usize),
+    #[error("The specs measure their lengths in different units, {0} and {1}")]
+    Units(LengthUnit, LengthUnit),
+    #[error("The specs normalize passwords differently")]
+    Normalization,
+    #[error("The specs allow no common total length")]
+    TotalLength,
+    #[error("The specs allow no common count of `{0}`")]
+    Counts(Charset),
+    #[error("The specs have no character in common")]
+    NoCharacters,
+    #[error("No password fits the counts of both specs at once")]
+    Infeasible,
+    #[error("{0}")]
+    Generate(GenerateError),
+}
+
+fn intersect_intervals(a: &[Interval], b: &[Interval]) -> Vec<Interval> {
+    a.iter()
+        .flat_map(|x| {
+            b.iter()
+                .filter_map(|y| Interval::new(x.min.max(y.min), x.max.min(y.max)))
+        })
+        .collect()
+}
+
+// every character the spec accepts, the allowed ones or those some charset of the spec has
+fn universe(spec: &PasswordSpec) -> Vec<char> {
+    if let Some(allowed) = &spec.allowed {
+        let mut chars = allowed.to_charset().to_vec();
+        chars.sort_unstable();
+        chars.dedup();
+        return chars;
+    }
+    let mut chars: Vec<char> = spec
+        .choices()
+        .flat_map(|choice| choice.chars.to_charset().iter().copied())
+        .collect();
+    chars.sort_unstable();
+    chars.dedup();
+    chars
+}
+
+// one choice with the counts both allow, the charset, weights and label of `first` unless it has
+// none
+fn merge(first: &Choice, second: &Choice) -> Result<Choice, IntersectError> {
+    let intervals = intersect_intervals(&first.intervals(), &second.intervals());
+    let mut both = Choice::any_of(intervals, first.chars.clone())
+        .ok_or_else(|| IntersectError::Counts(first.chars.clone()))?;
+    both.weights = first.weights.clone().or_else(|| second.weights.clone());
+    both.label = first.label.clone().or_else(|| second.label.clone());
+    Ok(both)
+}
+
+impl PasswordSpec {
+    // custom charsets with exactly the characters of a named one, like `abc…z` for `:lower:`,
+    // become the named charset and are merged with its choice if the spec already has one, then
+    // duplicate customs are merged as by `merge_duplicate_customs`
+    // the named charset's own choice sorts first so its label and weights are kept, a custom
+    // `allowed` is renamed the same way
+    pub fn normalize(&self) -> Result<PasswordSpec, IntersectError> {
+        let mut renamed: Vec<Choice> = vec![];
+        for choice in self.choices() {
+            let mut choice = choice.clone();
+            if let Some(named) = choice.chars.as_named() {
+                choice.chars = named;
+            }
+            match renamed.iter_mut().find(|first| first.chars == choice.chars) {
+                Some(first) => *first = merge(first, &choice)?,
+                None => renamed.push(choice),
+            }
+        }
+        let mut spec = self.clone();
+        spec.choices = Choices::from(renamed);
+        if let Some(named) = spec.allowed.as_ref().and_then(Charset::as_named) {
+            spec.allowed = Some(named);
+        }
+        spec.merge_duplicate_customs()
+    }
+
+    // custom charsets with the same characters in whatever order, e.g. from `2|abc` and `3|cba`,
+    // are kept apart by the spec and each draws its own count, this merges them into one choice
+    // with the counts both allow, the charset and label are those of the one that sorts first
+    pub fn merge_duplicate_customs(&self) -> Result<PasswordSpec, IntersectError> {
+        let mut merged: Vec<(Vec<char>, Choice)> = vec![];
+        let mut choices = Choices::new();
+        for choice in self.choices() {
+            let set = match choice.chars.custom_set() {
+                Some(set) => set,
+                None => {
+                    choices.push(choice.clone());
+                    continue;
+                }
+            };
+            match merged.iter_mut().find(|(other, _)| *other == set) {
+                Some((_, first)) => *first = merge(first, choice)?,
+                None => merged.push((set, choice.clone())),
+            }
+        }
+        for (_, choice) in merged {
+            choices.push(choice);
+        }
+        let mut spec = self.clone();
+        spec.choices = choices;
+        Ok(spec)
+    }
+
+    // a spec whose passwords are accepted by `matches` of both specs
+    //  - the lengths, resolved, have to be the same and so do the affixes, the units the
+    //    lengths are measured in and the normalization
+    //  - the byte caps and total lengths get what both allow, the minimum entropy is the larger
+    //  - charsets in both specs get the counts both allow
+    //  - charsets in only one spec are carried over as they are
+    //  - characters only one spec allows are excluded with an interval of exactly 0
+    // weights and labels come from this spec
+    pub fn intersect(&self, other: &PasswordSpec) -> Result<PasswordSpec, IntersectError> {
+        if self.prefix != other.prefix || self.suffix != other.suffix {
+            return Err(IntersectError::Affixes);
+        }
+        if self.unit() != other.unit() {
+            return Err(IntersectError::Units(self.unit(), other.unit()));
+        }
+        if self.normalization != other.normalization {
+            return Err(IntersectError::Normalization);
+        }
+        let total_length = match (self.total_length.as_ref(), other.total_length.as_ref()) {
+            (Some(a), Some(b)) => Some(
+                Interval::new(a.min.max(b.min), a.max.min(b.max))
+                    .ok_or(IntersectError::TotalLength)?,
+            ),
+            (a, b) => a.or(b).cloned(),
+        };
+        let length = self.resolve_length().map_err(IntersectError::Generate)?;
+        let other_length = other.resolve_length().map_err(IntersectError::Generate)?;
+        if length != other_length {
+            return Err(IntersectError::Length(length, other_length));
+        }
+        let mut choices = Choices::new();
+        for choice in self.choices() {
+            match other.choices().find(|c| c.chars == choice.chars) {
+                Some(theirs) => choices.push(merge(choice, theirs)?),
+                None => choices.push(choice.clone()),
+            }
+        }
+        for choice in other.choices() {
+            if !self.choices().any(|c| c.chars == choice.chars) {
+                choices.push(choice.clone());
+            }
+        }
+        let (ours, theirs) = (universe(self), universe(other));
+        let (common, mut only_one): (Vec<char>, Vec<char>) = ours
+            .iter()
+            .chain(theirs.iter().filter(|c| !ours.contains(c)))
+            .partition(|c| ours.contains(c) && theirs.contains(c));
+        if common.is_empty() {
+            return Err(IntersectError::NoCharacters);
+        }
+        only_one.sort_unstable();
+        if !only_one.is_empty() {
+            choices.push(Charset::Custom(only_one).exactly(0));
+        }
+        let mut spec = self.clone().length(length);
+        spec.affixes_in_length = false;
+        spec.choices = choices;
+        spec.max_bytes = match (self.max_bytes, other.max_bytes) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        spec.total_length = total_length;
+        spec.min_entropy = match (self.required_entropy(), other.required_entropy()) {
+            (Some(a), Some(b)) => Some(a.max(b).to_bits()),
+            (a, b) => a.or(b).map(f64::to_bits),
+        };
+        if self.allowed.is_some() || other.allowed.is_some() {
+            let mut common = common;
+            common.sort_unstable();
+            spec.allowed = Some(Charset::Custom(common));
+        }
+        // a password both accept follows the position rules of both
+        for (start, end, rule) in &other.position_rules {
+            spec = spec.position_range_rule(*start, *end, rule.clone());
+        }
+        for charset in &other.no_adjacent {
+            spec = spec.no_adjacent_from(charset.clone());
+        }
+        if spec.check().map_err(IntersectError::Generate)? {
+            Ok(spec)
+        } else {
+            Err(IntersectError::Infeasible)
+        }
+    }
+}
@@ -1,8 +1,8 @@
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
 use thiserror::Error;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Interval {
     pub min: usize,
     pub max: usize,
@@ -45,6 +45,70 @@ impl Interval {
             Self { min: b, max: a }
         }
     }
+
+    // a max of `usize::MAX` is unbounded, the arithmetic keeps it that way rather than treating it
+    // as a number
+    pub fn is_unbounded(&self) -> bool {
+        self.max == usize::MAX
+    }
+
+    // the counts a total of one count from each can have, sums too large for a usize saturate,
+    // which makes the max unbounded
+    pub fn add(&self, other: &Interval) -> Interval {
+        Interval {
+            min: self.min.saturating_add(other.min),
+            max: if self.is_unbounded() || other.is_unbounded() {
+                usize::MAX
+            } else {
+                self.max.saturating_add(other.max)
+            },
+        }
+    }
+
+    // the counts still needed once `n` are already there, neither bound goes below 0 and an
+    // unbounded max stays unbounded
+    pub fn sub_min(&self, n: usize) -> Interval {
+        Interval {
+            min: self.min.saturating_sub(n),
+            max: if self.is_unbounded() {
+                usize::MAX
+            } else {
+                self.max.saturating_sub(n)
+            },
+        }
+    }
+
+    // both bounds multiplied by `factor`, the min rounding down and the max up so the result
+    // contains every scaled count, results too large for a usize saturate and an unbounded max
+    // stays unbounded
+    // `None` for a factor that's negative or not finite
+    pub fn scale(&self, factor: f64) -> Option<Interval> {
+        if !factor.is_finite() || factor < 0.0 {
+            return None;
+        }
+        // float to int casts saturate
+        let min = (self.min as f64 * factor).floor() as usize;
+        let max = if self.is_unbounded() {
+            usize::MAX
+        } else {
+            (self.max as f64 * factor).ceil() as usize
+        };
+        Some(Interval { min, max })
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.min)
+        } else if self.max == usize::MAX {
+            write!(f, "{}+", self.min)
+        } else if self.min == usize::MIN {
+            write!(f, "{}-", self.max)
+        } else {
+            write!(f, "{}-{}", self.min, self.max)
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -53,6 +117,28 @@ pub enum IntervalParseError {
     BadBounds(usize, usize),
     #[error("got `{0}`, expect the format for an interval to be: N, N+, N-, or A-B")]
     ImproperFormat(String),
+    #[error("`{text}` is too large for a count, the largest is {max}")]
+    Overflow { text: String, max: usize },
+    #[error("got `{0}`, only the ASCII digits 0-9 are accepted in counts")]
+    NonAsciiDigit(String),
+}
+
+// a count in an interval, `interval` is the whole interval for the error messages
+fn parse_count(digits: Vec<char>, interval: &str) -> Result<usize, IntervalParseError> {
+    if digits.iter().any(|c| !c.is_ascii_digit()) {
+        return Err(IntervalParseError::NonAsciiDigit(interval.to_string()));
+    }
+    let digits: String = digits.into_iter().collect();
+    digits.parse().map_err(|e: std::num::ParseIntError| {
+        if *e.kind() == std::num::IntErrorKind::PosOverflow {
+            IntervalParseError::Overflow {
+                text: digits.clone(),
+                max: usize::MAX,
+            }
+        } else {
+            IntervalParseError::ImproperFormat(interval.to_string())
+        }
+    })
 }
 
 impl FromStr for Interval {
@@ -68,13 +154,14 @@ impl FromStr for Interval {
         let mut first = vec![];
         let mut second = vec![];
         let mut style = Style::Exact;
+        let len = s.chars().count();
         for (i, c) in s.chars().enumerate() {
             if matches!(style, Style::Exact) {
                 if c.is_numeric() {
                     first.push(c);
-                } else if c == '+' && i + 1 == s.len() {
+                } else if c == '+' && i + 1 == len {
                     style = Style::AtLeast;
-                } else if c == '-' && i + 1 == s.len() {
+                } else if c == '-' && i + 1 == len {
                     style = Style::AtMost;
                 } else if c == '-' {
                     style = Style::Range;
@@ -91,46 +178,26 @@ impl FromStr for Interval {
         }
         match style {
             Style::Exact => {
-                let n = first
-                    .into_iter()
-                    .collect::<String>()
-                    .parse()
-                    .map_err(|_| IntervalParseError::ImproperFormat(s.to_string()))?;
+                let n = parse_count(first, s)?;
                 Ok(Interval { min: n, max: n })
             }
             Style::AtLeast => {
-                let n = first
-                    .into_iter()
-                    .collect::<String>()
-                    .parse()
-                    .map_err(|_| IntervalParseError::ImproperFormat(s.to_string()))?;
+                let n = parse_count(first, s)?;
                 Ok(Interval {
                     min: n,
                     max: usize::MAX,
                 })
             }
             Style::AtMost => {
-                let n = first
-                    .into_iter()
-                    .collect::<String>()
-                    .parse()
-                    .map_err(|_| IntervalParseError::ImproperFormat(s.to_string()))?;
+                let n = parse_count(first, s)?;
                 Ok(Interval {
                     min: usize::MIN,
                     max: n,
                 })
             }
             Style::Range => {
-                let a = first
-                    .into_iter()
-                    .collect::<String>()
-                    .parse()
-                    .map_err(|_| IntervalParseError::ImproperFormat(s.to_string()))?;
-                let b = second
-                    .into_iter()
-                    .collect::<String>()
-                    .parse()
-                    .map_err(|_| IntervalParseError::ImproperFormat(s.to_string()))?;
+                let a = parse_count(first, s)?;
+                let b = parse_count(second, s)?;
                 if a <= b {
                     Ok(Interval { min: a, max: b })
                 } else {
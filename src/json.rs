@@ -0,0 +1,500 @@
+// JSON form of a spec, for policy files that are easier to write and check than spec strings.
+//
+// {
+//   "length": 16,                      // or {"bits": 80}, {"bytes": 64}, {"graphemes": 24}
+//   "choices": [
+//     {"charset": "upper", "intervals": [{"min": 1, "max": null}]},
+//     {"charset": {"custom": "!@#"}, "intervals": [{"min": 0, "max": 0}, {"min": 2, "max": 2}]}
+//   ],
+//   "prefix": "", "suffix": "", "length_includes_affixes": false, "max_bytes": 64,
+//   "total_length": {"min": 0, "max": 40}, "min_entropy": 80.0
+// }
+//
+// A `max` of null means unbounded. Weights aren't part of the JSON, like the spec string.
+// A choice can have a `label` naming its charset in messages.
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+#[cfg(feature = "metadata")]
+use crate::metadata::Metadata;
+use crate::{
+    charset::Charset,
+    choice::Choice,
+    generated::GeneratedPassword,
+    interval::Interval,
+    password::{GenerateError, Length, PasswordParseError, PasswordSpec},
+    units::LengthUnit,
+    violation::Violation,
+};
+
+#[derive(Debug, Error)]
+pub enum JsonSpecError {
+    #[error("Interval has a min of {0} above its max of {1}")]
+    BadBounds(usize, usize),
+    #[error("Choice for `{0}` has no intervals")]
+    NoIntervals(Charset),
+    #[error("Custom charsets need at least one character")]
+    EmptyCustom,
+    #[error("Label `{0}` isn't a letter followed by letters, digits, `-` and `_`")]
+    BadLabel(String),
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "PasswordSpec"))]
+#[serde(deny_unknown_fields)]
+struct SpecJson {
+    /// Number of characters, `{"bits": n}` for the shortest length guaranteeing n bits, or
+    /// `{"bytes": n}` or `{"graphemes": n}` for a length in those.
+    length: LengthJson,
+    choices: Vec<ChoiceJson>,
+    /// Fixed text before the random part.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    prefix: String,
+    /// Fixed text after the random part.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    suffix: String,
+    /// Whether the length counts the prefix and suffix.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    length_includes_affixes: bool,
+    /// Every character has to be from this charset, the ones beyond what the choices require
+    /// are drawn from it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    allowed: Option<CharsetJson>,
+    /// Most UTF-8 bytes the whole password can take, affixes included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_bytes: Option<usize>,
+    /// Bounds on the length of the whole password with its decorations: the affixes, separators
+    /// between chunks and a check character, in the unit of the length.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    total_length: Option<IntervalJson>,
+    /// Least entropy in bits the spec has to guarantee for passwords to be generated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min_entropy: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(untagged)]
+enum LengthJson {
+    Chars(usize),
+    Bits { bits: usize },
+    Bytes { bytes: usize },
+    Graphemes { graphemes: usize },
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+struct ChoiceJson {
+    /// Position of the choice in the spec's order, from 0. Written for referring to a choice,
+    /// ignored when reading.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    index: Option<usize>,
+    charset: CharsetJson,
+    /// The count of characters from the charset has to be in one of these.
+    #[cfg_attr(feature = "schema", schemars(length(min = 1)))]
+    intervals: Vec<IntervalJson>,
+    /// Name for the charset in messages, a letter followed by letters, digits, `-` and `_`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+struct IntervalJson {
+    #[serde(default)]
+    min: usize,
+    /// `null` for no upper bound.
+    max: Option<usize>,
+}
+
+impl From<&Interval> for IntervalJson {
+    fn from(interval: &Interval) -> Self {
+        IntervalJson {
+            min: interval.min,
+            max: Some(interval.max).filter(|max| *max != usize::MAX),
+        }
+    }
+}
+
+impl TryFrom<IntervalJson> for Interval {
+    type Error = JsonSpecError;
+    fn try_from(interval: IntervalJson) -> Result<Self, Self::Error> {
+        let max = interval.max.unwrap_or(usize::MAX);
+        Interval::new(interval.min, max).ok_or(JsonSpecError::BadBounds(interval.min, max))
+    }
+}
+
+// an interval in details, `{"min": 1, "max": null}` like in specs
+fn interval_json(interval: &Interval) -> Value {
+    json!(IntervalJson::from(interval))
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(untagged)]
+enum CharsetJson {
+    Named(NamedCharset),
+    Custom(CustomCharset),
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+enum NamedCharset {
+    Upper,
+    Lower,
+    Number,
+    #[serde(rename = "number-no-zero")]
+    NumberNoZero,
+    Symbol,
+    Base58,
+    #[serde(rename = "upper-latin1")]
+    UpperLatin1,
+    #[serde(rename = "lower-latin1")]
+    LowerLatin1,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+struct CustomCharset {
+    /// The characters to choose from.
+    #[cfg_attr(feature = "schema", schemars(length(min = 1)))]
+    custom: String,
+}
+
+impl From<&PasswordSpec> for SpecJson {
+    fn from(spec: &PasswordSpec) -> Self {
+        Self {
+            length: match (spec.length, spec.unit()) {
+                (Length::Chars(n), LengthUnit::Chars) => LengthJson::Chars(n),
+                (Length::Chars(bytes), LengthUnit::Bytes) => LengthJson::Bytes { bytes },
+                (Length::Chars(graphemes), LengthUnit::Graphemes) => {
+                    LengthJson::Graphemes { graphemes }
+                }
+                (Length::Bits(bits), _) => LengthJson::Bits { bits },
+            },
+            choices: spec
+                .choices()
+                .enumerate()
+                .map(|(index, choice)| ChoiceJson {
+                    index: Some(index),
+                    charset: charset_json(&choice.chars),
+                    intervals: choice.intervals().iter().map(IntervalJson::from).collect(),
+                    label: choice.label.clone(),
+                })
+                .collect(),
+            prefix: spec.prefix.clone(),
+            suffix: spec.suffix.clone(),
+            length_includes_affixes: spec.affixes_in_length,
+            allowed: spec.allowed.as_ref().map(charset_json),
+            max_bytes: spec.max_bytes,
+            total_length: spec.total_length.as_ref().map(IntervalJson::from),
+            min_entropy: spec.required_entropy(),
+        }
+    }
+}
+
+fn unit_json(unit: LengthUnit) -> &'static str {
+    match unit {
+        LengthUnit::Chars => "chars",
+        LengthUnit::Bytes => "bytes",
+        LengthUnit::Graphemes => "graphemes",
+    }
+}
+
+fn charset_json(charset: &Charset) -> CharsetJson {
+    match charset {
+        Charset::Upper => CharsetJson::Named(NamedCharset::Upper),
+        Charset::Lower => CharsetJson::Named(NamedCharset::Lower),
+        Charset::Number => CharsetJson::Named(NamedCharset::Number),
+        Charset::NumberNoZero => CharsetJson::Named(NamedCharset::NumberNoZero),
+        Charset::Symbol => CharsetJson::Named(NamedCharset::Symbol),
+        Charset::Base58 => CharsetJson::Named(NamedCharset::Base58),
+        Charset::UpperLatin1 => CharsetJson::Named(NamedCharset::UpperLatin1),
+        Charset::LowerLatin1 => CharsetJson::Named(NamedCharset::LowerLatin1),
+        Charset::Custom(chars) => CharsetJson::Custom(CustomCharset {
+            custom: chars.iter().collect(),
+        }),
+    }
+}
+
+fn json_charset(charset: CharsetJson) -> Result<Charset, JsonSpecError> {
+    Ok(match charset {
+        CharsetJson::Named(NamedCharset::Upper) => Charset::Upper,
+        CharsetJson::Named(NamedCharset::Lower) => Charset::Lower,
+        CharsetJson::Named(NamedCharset::Number) => Charset::Number,
+        CharsetJson::Named(NamedCharset::NumberNoZero) => Charset::NumberNoZero,
+        CharsetJson::Named(NamedCharset::Symbol) => Charset::Symbol,
+        CharsetJson::Named(NamedCharset::Base58) => Charset::Base58,
+        CharsetJson::Named(NamedCharset::UpperLatin1) => Charset::UpperLatin1,
+        CharsetJson::Named(NamedCharset::LowerLatin1) => Charset::LowerLatin1,
+        CharsetJson::Custom(CustomCharset { custom }) if custom.is_empty() => {
+            return Err(JsonSpecError::EmptyCustom)
+        }
+        CharsetJson::Custom(CustomCharset { custom }) => Charset::Custom(custom.chars().collect()),
+    })
+}
+
+impl TryFrom<SpecJson> for PasswordSpec {
+    type Error = JsonSpecError;
+    fn try_from(json: SpecJson) -> Result<Self, Self::Error> {
+        let mut spec = match json.length {
+            LengthJson::Chars(n) => PasswordSpec::new().length(n),
+            LengthJson::Bits { bits } => PasswordSpec::new().length_bits(bits),
+            LengthJson::Bytes { bytes } => PasswordSpec::new()
+                .length(bytes)
+                .length_unit(LengthUnit::Bytes),
+            LengthJson::Graphemes { graphemes } => PasswordSpec::new()
+                .length(graphemes)
+                .length_unit(LengthUnit::Graphemes),
+        };
+        spec.max_bytes = json.max_bytes;
+        spec.total_length = json.total_length.map(Interval::try_from).transpose()?;
+        spec.min_entropy = json.min_entropy.map(f64::to_bits);
+        for choice in json.choices {
+            let chars = json_charset(choice.charset)?;
+            let intervals = choice
+                .intervals
+                .into_iter()
+                .map(Interval::try_from)
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut built = Choice::any_of(intervals, chars.clone())
+                .ok_or(JsonSpecError::NoIntervals(chars))?;
+            if let Some(label) = choice.label {
+                built = built
+                    .labeled(label.clone())
+                    .ok_or(JsonSpecError::BadLabel(label))?;
+            }
+            spec = spec
+                .try_include(built)
+                .map_err(|_| JsonSpecError::EmptyCustom)?;
+        }
+        if let Some(allowed) = json.allowed {
+            spec = spec.allowed(json_charset(allowed)?);
+        }
+        Ok(spec
+            .prefix(json.prefix)
+            .suffix(json.suffix)
+            .length_includes_affixes(json.length_includes_affixes))
+    }
+}
+
+impl Serialize for PasswordSpec {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SpecJson::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PasswordSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let json = SpecJson::deserialize(deserializer)?;
+        PasswordSpec::try_from(json).map_err(serde::de::Error::custom)
+    }
+}
+
+impl PasswordSpec {
+    pub fn to_json(&self) -> String {
+        // serializing plain data to a string can't fail
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+
+    // JSON Schema (draft 7) of the JSON form, for validating policy files with other tools
+    #[cfg(feature = "schema")]
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(SpecJson)).unwrap_or_default()
+    }
+}
+
+// Errors as JSON for machine consumers, `{"error": code, "message": ..., "detail": {...}}` where
+// `error` is the error's `code()` and `detail` has the numbers behind the message, if any.
+fn error_json(code: &str, message: String, detail: Value) -> Value {
+    json!({"error": code, "message": message, "detail": detail})
+}
+
+impl PasswordParseError {
+    pub fn to_json(&self) -> Value {
+        let detail = match self {
+            PasswordParseError::InvalidLength(segment)
+            | PasswordParseError::NonAsciiLength(segment)
+            | PasswordParseError::BadInterval(segment)
+            | PasswordParseError::BadCharset(segment) => json!({"segment": segment}),
+            PasswordParseError::LengthOverflow { text, max } => {
+                json!({"segment": text, "max": max})
+            }
+            PasswordParseError::MissingVersion(inner) => json!({"cause": inner.to_json()}),
+            _ => json!({}),
+        };
+        error_json(self.code(), self.to_string(), detail)
+    }
+}
+
+impl GenerateError {
+    pub fn to_json(&self) -> Value {
+        let detail = match self {
+            GenerateError::Infeasible
+            | GenerateError::NoChoices
+            | GenerateError::LengthOverflow
+            | GenerateError::RngFailure(_) => json!({}),
+            GenerateError::EmptyCharset(charset) => json!({"charset": charset.to_string()}),
+            GenerateError::LengthBelowMinimum { required, length } => {
+                json!({"required": required, "length": length})
+            }
+            GenerateError::LengthAboveMaximum { allowed, length } => {
+                json!({"allowed": allowed, "length": length})
+            }
+            GenerateError::WrongLength {
+                expected,
+                generated,
+            } => json!({"expected": expected, "generated": generated}),
+            GenerateError::EntropyUnreachable { bits, achievable } => {
+                json!({"bits": bits, "achievable": achievable})
+            }
+            GenerateError::AffixesTooLong { affixes, length } => {
+                json!({"affixes": affixes, "length": length})
+            }
+            GenerateError::AttemptsExhausted { attempts }
+            | GenerateError::PositionRulesUnmet { attempts } => json!({"attempts": attempts}),
+            GenerateError::PositionOutOfRange { index, length } => {
+                json!({"index": index, "length": length})
+            }
+            GenerateError::PositionConflict(position) => json!({"position": position}),
+            GenerateError::AdjacencyInfeasible {
+                charset,
+                count,
+                length,
+            } => json!({"charset": charset.to_string(), "count": count, "length": length}),
+            GenerateError::TooFewDistinct {
+                requested,
+                possible,
+            } => json!({"requested": requested, "possible": possible}),
+            GenerateError::BadBounds { charset, min, max } => {
+                json!({"charset": charset.to_string(), "min": min, "max": max})
+            }
+            GenerateError::EntropyBelowMinimum { required, achieved } => {
+                json!({"required": required, "achieved": achieved})
+            }
+            GenerateError::TotalLengthUnmet { allowed, length } => {
+                json!({"allowed": interval_json(allowed), "length": length})
+            }
+            GenerateError::UniformTooLong { length, max } => {
+                json!({"length": length, "max": max})
+            }
+            GenerateError::MeasuredTooLong { unit, length, max } => {
+                json!({"unit": unit.to_string(), "length": length, "max": max})
+            }
+            GenerateError::TimedOut { attempts, elapsed } => {
+                json!({"attempts": attempts, "elapsed_ms": elapsed.as_millis() as u64})
+            }
+        };
+        error_json(self.code(), self.to_string(), detail)
+    }
+}
+
+// a choice's label goes in the detail only when it has one
+fn with_label(mut detail: Value, label: &Option<String>) -> Value {
+    if let Some(label) = label {
+        detail["label"] = json!(label);
+    }
+    detail
+}
+
+impl Violation {
+    // `{"code": ..., "message": ..., "detail": {...}}`, like the errors but keyed by `code` since
+    // a check usually reports several
+    pub fn to_json(&self) -> Value {
+        let detail = match self {
+            Violation::MissingPrefix(affix) | Violation::MissingSuffix(affix) => {
+                json!({"expected": affix})
+            }
+            Violation::TooShort {
+                expected,
+                actual,
+                unit,
+            }
+            | Violation::TooLong {
+                expected,
+                actual,
+                unit,
+            } => json!({"expected": expected, "actual": actual, "unit": unit_json(*unit)}),
+            Violation::TooManyBytes { max, actual } => json!({"max": max, "actual": actual}),
+            Violation::TotalLength {
+                allowed,
+                actual,
+                unit,
+            } => json!({
+                "allowed": interval_json(allowed),
+                "actual": actual,
+                "unit": unit_json(*unit)
+            }),
+            Violation::MissingClass {
+                charset,
+                label,
+                min,
+                count,
+            } => with_label(
+                json!({"charset": charset.to_string(), "min": min, "count": count}),
+                label,
+            ),
+            Violation::ExcessClass {
+                charset,
+                label,
+                max,
+                count,
+            } => with_label(
+                json!({"charset": charset.to_string(), "max": max, "count": count}),
+                label,
+            ),
+            Violation::Adjacent { charset, position } => {
+                json!({"charset": charset.to_string(), "position": position})
+            }
+            Violation::CountNotAllowed {
+                charset,
+                label,
+                count,
+            } => with_label(
+                json!({"charset": charset.to_string(), "count": count}),
+                label,
+            ),
+            Violation::DisallowedChar { c, position } | Violation::PositionRule { c, position } => {
+                json!({"char": c.to_string(), "position": position})
+            }
+        };
+        json!({"code": self.code(), "message": self.to_string(), "detail": detail})
+    }
+}
+
+impl GeneratedPassword {
+    // `{"password": ...}`, the spec and sources stay out of it
+    pub fn to_json(&self) -> Value {
+        json!({"password": self.password()})
+    }
+
+    // `{"password": ..., "metadata": {...}}` for logging where a password came from
+    #[cfg(feature = "metadata")]
+    pub fn to_json_with_metadata(&self, metadata: &Metadata) -> Value {
+        json!({"password": self.password(), "metadata": metadata.to_json()})
+    }
+}
+
+#[cfg(feature = "metadata")]
+impl Metadata {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "generator_version": self.generator_version,
+            "rng_source": self.rng_source,
+            "spec_canonical": self.spec_canonical,
+            "timestamp": self.timestamp,
+        })
+    }
+}
@@ -0,0 +1,163 @@
+// What the length of a password means once there's more to it than the random core. The spec's
+// length is the core, what the choices and charsets govern. The prefix and suffix, separators
+// between chunks and a check character are decorations around it, and the total length bounds the
+// string with all of them, in the unit of the spec's length.
+//
+// Everything that decorates a password works out how long it makes it with `decorated_length`
+// and checks that against the total before drawing anything, so a combination that can't fit is
+// an error up front rather than a password that's too long.
+//
+// `length_includes_affixes` stays beside the policy rather than going through it. It shrinks the
+// core to make room for the affixes, where the policy fixes the core and bounds the total, and
+// it's a field of the JSON format. A core length from the policy turns it off, so the two never
+// disagree about what the core is.
+use crate::{
+    interval::Interval,
+    password::{GenerateError, PasswordSpec},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct LengthPolicy {
+    core_length: Option<usize>,
+    total_length: Option<Interval>,
+}
+
+impl LengthPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // the length of the random core, decorations aren't counted whatever else the spec says
+    pub fn core_length(mut self, length: usize) -> Self {
+        self.core_length = Some(length);
+        self
+    }
+
+    // bounds on the decorated password
+    pub fn total_length(mut self, total: Interval) -> Self {
+        self.total_length = Some(total);
+        self
+    }
+
+    pub fn core(&self) -> Option<usize> {
+        self.core_length
+    }
+
+    pub fn total(&self) -> Option<&Interval> {
+        self.total_length.as_ref()
+    }
+}
+
+// what's added to the random core beyond the spec's own prefix and suffix
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Decorations {
+    chunks: Option<(usize, String)>,
+    check_character: bool,
+}
+
+impl Decorations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // `separator` between chunks of `size` characters of the core, see `ChunkSpec`
+    pub fn chunked(mut self, size: usize, separator: impl Into<String>) -> Self {
+        self.chunks = Some((size, separator.into()));
+        self
+    }
+
+    // a check character at the end, see `checksum::check_character`
+    pub fn check_character(mut self) -> Self {
+        self.check_character = true;
+        self
+    }
+}
+
+// the interval in words for messages, `N-` reads badly there
+pub(crate) fn bounds(interval: &Interval) -> String {
+    if interval.min == interval.max {
+        format!("exactly {}", interval.min)
+    } else if interval.is_unbounded() {
+        format!("at least {}", interval.min)
+    } else if interval.min == 0 {
+        format!("at most {}", interval.max)
+    } else {
+        format!("between {} and {}", interval.min, interval.max)
+    }
+}
+
+impl PasswordSpec {
+    // the core length and total length of the policy, whichever it sets
+    // a core length replaces the spec's length and stops it counting the affixes
+    pub fn length_policy(mut self, policy: LengthPolicy) -> Self {
+        if let Some(length) = policy.core_length {
+            self = self.length(length);
+            self.affixes_in_length = false;
+        }
+        if let Some(total) = policy.total_length {
+            self.total_length = Some(total);
+        }
+        self
+    }
+
+    pub fn total_length(&self) -> Option<&Interval> {
+        self.total_length.as_ref()
+    }
+
+    // how long a password of the spec is with the affixes and `decorations`, in the unit of the
+    // length, `LengthOverflow` when that's more than a `usize` holds
+    pub fn decorated_length(&self, decorations: &Decorations) -> Result<usize, GenerateError> {
+        let unit = self.unit();
+        let core = self.resolve_length()?;
+        let mut added = vec![unit.measure(&self.prefix), unit.measure(&self.suffix)];
+        if let Some((size, separator)) = &decorations.chunks {
+            let size = (*size).max(1);
+            let chunks = core / size + usize::from(core % size != 0);
+            added.push(
+                chunks
+                    .saturating_sub(1)
+                    .checked_mul(unit.measure(separator))
+                    .ok_or(GenerateError::LengthOverflow)?,
+            );
+        }
+        if decorations.check_character {
+            added.push(1);
+        }
+        added
+            .into_iter()
+            .try_fold(core, usize::checked_add)
+            .ok_or(GenerateError::LengthOverflow)
+    }
+
+    // `TotalLengthUnmet` when the spec with `decorations` can't be within the total length
+    pub fn check_total_length(&self, decorations: &Decorations) -> Result<(), GenerateError> {
+        let allowed = match &self.total_length {
+            Some(total) => total,
+            None => return Ok(()),
+        };
+        let length = self.decorated_length(decorations)?;
+        if allowed.min <= length && length <= allowed.max {
+            Ok(())
+        } else {
+            Err(GenerateError::TotalLengthUnmet {
+                allowed: allowed.clone(),
+                length,
+            })
+        }
+    }
+
+    // whether a decorated password is within the total length
+    pub(crate) fn within_total_length(&self, password: &str) -> bool {
+        let length = self.unit().measure(password);
+        self.total_length
+            .as_ref()
+            .map_or(true, |total| total.min <= length && length <= total.max)
+    }
+
+    // the spec for the undecorated password, once the total has been checked with the decorations
+    pub(crate) fn without_total_length(&self) -> PasswordSpec {
+        let mut spec = self.clone();
+        spec.total_length = None;
+        spec
+    }
+}
@@ -41,11 +41,10 @@
 //!
 //! To generate a password build up the spec and then call `generate` to produce the password. This
 //! function returns an `Option` since the constraints on the provided choices can't always meet
-//! the length requirement given.
+//! the length requirement given, `try_generate` returns the reason instead.
+//! Everything needed is in the [`prelude`], the same types are also at the crate root.
 //! ```rust
-//! use pants_gen::charset::Charset;
-//! use pants_gen::password::PasswordSpec;
-//! use pants_gen::interval::Interval;
+//! use pants_gen::prelude::*;
 //! let spec = PasswordSpec::new()
 //!     .length(16)
 //!     .upper_at_least(1)
@@ -58,7 +57,90 @@
 //!     println!("Couldn't meet constraints of spec");
 //! }
 //! ```
+//!
+//! # Embedding
+//! Applications that store policies, like a password vault, should go through [`api`], the part
+//! of the crate that stays stable across releases.
+#![deny(rustdoc::broken_intra_doc_links)]
+
+mod adjacent;
+pub mod adjust;
+pub mod agent;
+pub mod api;
+pub mod build_info;
+pub mod bytes;
 pub mod charset;
+pub mod checksum;
 pub mod choice;
+pub mod chunk;
+pub mod comments;
+mod compare;
+pub mod confusables;
+pub mod debug;
+pub mod dedup;
+mod entropy;
+pub mod exec;
+pub mod fallible;
+pub mod fill;
+mod forced;
+mod format;
+pub mod generated;
+pub mod global;
+#[cfg(feature = "hashing")]
+pub mod hashing;
+#[cfg(any(feature = "history", feature = "transcript"))]
+mod hex;
+#[cfg(feature = "history")]
+pub mod history;
+pub mod intersect;
 pub mod interval;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod length_policy;
+pub mod lint;
+#[cfg(feature = "metadata")]
+pub mod metadata;
+pub mod multi;
+pub mod options;
+pub mod output;
 pub mod password;
+pub mod pattern;
+pub mod pin;
+pub mod position;
+pub mod prelude;
+pub mod preset;
+pub mod pwgen;
+pub mod quirks;
+pub mod quote;
+pub mod sampling;
+#[cfg(feature = "num-bigint")]
+pub mod search_space;
+pub mod secret;
+pub mod selftest;
+pub mod separators;
+pub mod specset;
+pub mod store;
+pub mod stream;
+pub mod strength;
+pub mod template;
+pub mod token;
+#[cfg(feature = "transcript")]
+pub mod transcript;
+pub mod unicode;
+pub mod units;
+pub mod verbosity;
+pub mod violation;
+pub mod weak;
+pub mod weights;
+pub mod wordlist;
+
+// runs the examples in the readme with the doctests
+#[cfg(doctest)]
+#[doc = include_str!("../README.md")]
+pub struct ReadmeDoctests;
+
+pub use charset::Charset;
+pub use choice::Choice;
+pub use global::{default_spec, set_default_spec, AlreadySet};
+pub use interval::Interval;
+pub use password::{GenerateError, PasswordParseError, PasswordSpec};
@@ -0,0 +1,273 @@
+// Checks for specs that are valid but weaker than they look.
+use std::fmt::Display;
+
+use crate::{
+    charset::Charset,
+    choice::{name, Choice},
+    password::PasswordSpec,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LintWarning {
+    // most of the password has to come from a charset with few characters
+    TinyCharset {
+        charset: Charset,
+        // the choice's label, shown in place of the charset
+        label: Option<String>,
+        pool: usize,
+        bits_per_char: f64,
+        forced: usize,
+        length: usize,
+    },
+    // two custom charsets sharing characters, those are drawn for both and count toward both
+    // so the counts no longer say how many of them there are
+    OverlappingCustom {
+        first: Charset,
+        first_label: Option<String>,
+        second: Charset,
+        second_label: Option<String>,
+        shared: Vec<char>,
+    },
+    // a custom charset with the characters of a named one, `PasswordSpec::normalize` turns it
+    // into the named one, or with nearly those characters, which is likely a mistake
+    CustomLikeNamed {
+        charset: Charset,
+        label: Option<String>,
+        named: Charset,
+        // characters of the named charset the custom one doesn't have
+        missing: Vec<char>,
+        // characters of the custom charset the named one doesn't have
+        extra: Vec<char>,
+    },
+    // a custom charset with characters in it more than once, those are drawn more often than the
+    // rest, with normalization two forms of the same character count as a repeat
+    RepeatedChars {
+        charset: Charset,
+        label: Option<String>,
+        repeated: Vec<char>,
+    },
+    // the length fixes every count although some choice asks for a range, see
+    // `PasswordSpec::forced_composition`
+    ForcedComposition {
+        composition: Vec<(Choice, usize)>,
+    },
+}
+
+impl Display for LintWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintWarning::TinyCharset {
+                charset,
+                label,
+                pool,
+                bits_per_char,
+                forced,
+                length,
+            } => write!(
+                f,
+                "`{}` has only {} characters ({:.1} bits each) but controls {} of {} characters ({:.0}%) of the password",
+                name(charset, label),
+                pool,
+                bits_per_char,
+                forced,
+                length,
+                100.0 * *forced as f64 / *length as f64
+            ),
+            LintWarning::OverlappingCustom {
+                first,
+                first_label,
+                second,
+                second_label,
+                shared,
+            } => write!(
+                f,
+                "`{}` and `{}` share `{}`, those characters count toward both",
+                name(first, first_label),
+                name(second, second_label),
+                shared.iter().collect::<String>()
+            ),
+            LintWarning::CustomLikeNamed {
+                charset,
+                label,
+                named,
+                missing,
+                extra,
+            } => {
+                let custom = name(charset, label);
+                if missing.is_empty() && extra.is_empty() {
+                    return write!(
+                        f,
+                        "`{}` has the same characters as `{}`, normalizing the spec uses `{}` instead",
+                        custom, named, named
+                    );
+                }
+                let mut differences = vec![];
+                if !missing.is_empty() {
+                    differences.push(format!("without `{}`", missing.iter().collect::<String>()));
+                }
+                if !extra.is_empty() {
+                    differences.push(format!("with `{}` as well", extra.iter().collect::<String>()));
+                }
+                write!(
+                    f,
+                    "`{}` is `{}` {}, is that on purpose?",
+                    custom,
+                    named,
+                    differences.join(" and ")
+                )
+            }
+            LintWarning::RepeatedChars {
+                charset,
+                label,
+                repeated,
+            } => write!(
+                f,
+                "`{}` has `{}` more than once, those are drawn more often than the rest",
+                name(charset, label),
+                repeated.iter().collect::<String>()
+            ),
+            LintWarning::ForcedComposition { composition } => write!(
+                f,
+                "The length leaves no room for the ranges, every password has {}",
+                composition
+                    .iter()
+                    .map(|(choice, n)| format!("{} from `{}`", n, choice.name()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+// Thresholds for the lints, a charset is tiny below `min_bits_per_char` and a warning is given
+// when it's forced to fill more than `max_share` of the length.
+#[derive(Debug, Clone)]
+pub struct Lint {
+    min_bits_per_char: f64,
+    max_share: f64,
+}
+
+impl Default for Lint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lint {
+    // below 16 characters to choose from over at least half the password
+    pub fn new() -> Self {
+        Self {
+            min_bits_per_char: 4.0,
+            max_share: 0.5,
+        }
+    }
+
+    pub fn min_bits_per_char(mut self, bits: f64) -> Self {
+        self.min_bits_per_char = bits;
+        self
+    }
+
+    pub fn max_share(mut self, share: f64) -> Self {
+        self.max_share = share;
+        self
+    }
+
+    // specs that can't be generated give no warnings, generating reports those
+    pub fn check(&self, spec: &PasswordSpec) -> Vec<LintWarning> {
+        let length = match spec.resolve_length() {
+            Ok(length) if length > 0 => length,
+            _ => return vec![],
+        };
+        let choices: Vec<_> = spec.choices().collect();
+        let mut warnings = vec![];
+        // the allowed characters no choice has can take up the rest as well
+        let drawn = spec.drawn_choices();
+        for (i, choice) in drawn.iter().enumerate() {
+            // characters this choice has to provide, either as its minimum or because the other
+            // choices can't take them
+            let others: usize = drawn
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(0, |sum, (_, other)| sum.saturating_add(other.max));
+            let forced = choice.min.max(length.saturating_sub(others)).min(length);
+            let pool = spec.pool(choice);
+            let bits_per_char = choice.entropy_from(&pool);
+            if bits_per_char < self.min_bits_per_char
+                && forced as f64 > self.max_share * length as f64
+            {
+                warnings.push(LintWarning::TinyCharset {
+                    charset: choice.chars.clone(),
+                    label: choice.label.clone(),
+                    pool: pool.len(),
+                    bits_per_char,
+                    forced,
+                    length,
+                });
+            }
+        }
+        for choice in &choices {
+            if let Some((named, missing, extra)) = choice.chars.nearest_named() {
+                warnings.push(LintWarning::CustomLikeNamed {
+                    charset: choice.chars.clone(),
+                    label: choice.label.clone(),
+                    named,
+                    missing,
+                    extra,
+                });
+            }
+        }
+        for choice in &choices {
+            let chars = match &choice.chars {
+                Charset::Custom(chars) => chars,
+                _ => continue,
+            };
+            let mut sorted = chars.clone();
+            sorted.sort_unstable();
+            let mut repeated: Vec<char> = sorted
+                .windows(2)
+                .filter(|pair| pair[0] == pair[1])
+                .map(|pair| pair[0])
+                .collect();
+            repeated.dedup();
+            if !repeated.is_empty() {
+                warnings.push(LintWarning::RepeatedChars {
+                    charset: choice.chars.clone(),
+                    label: choice.label.clone(),
+                    repeated,
+                });
+            }
+        }
+        if let Some(composition) = spec.unused_ranges() {
+            warnings.push(LintWarning::ForcedComposition { composition });
+        }
+        for (i, first) in choices.iter().enumerate() {
+            let first_chars = match &first.chars {
+                Charset::Custom(chars) => chars,
+                _ => continue,
+            };
+            for second in choices.iter().skip(i + 1) {
+                if !matches!(second.chars, Charset::Custom(_)) {
+                    continue;
+                }
+                let mut shared: Vec<char> = first_chars
+                    .iter()
+                    .copied()
+                    .filter(|c| second.chars.contains(*c))
+                    .collect();
+                shared.sort_unstable();
+                shared.dedup();
+                if !shared.is_empty() {
+                    warnings.push(LintWarning::OverlappingCustom {
+                        first: first.chars.clone(),
+                        first_label: first.label.clone(),
+                        second: second.chars.clone(),
+                        second_label: second.label.clone(),
+                        shared,
+                    });
+                }
+            }
+        }
+        warnings
+    }
+}
@@ -0,0 +1,112 @@
+// What produced a password, for logging alongside it so an incident can be traced back to the
+// generator version, the rng and the spec in use. Only with the `metadata` feature, the plain
+// generation paths don't carry any of it.
+use std::fmt::Display;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::{
+    rngs::{OsRng, StdRng},
+    thread_rng, SeedableRng,
+};
+
+use crate::{
+    generated::GeneratedPassword,
+    password::{GenerateError, PasswordSpec},
+};
+
+pub const GENERATOR_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// where the randomness comes from, seeded generation repeats itself so it's only for tests and
+// is labeled as such
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RngSource {
+    Thread,
+    Os,
+    Seeded(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    pub generator_version: &'static str,
+    pub rng_source: &'static str,
+    // `None` when the spec has settings a spec string can't record, see
+    // `PasswordSpec::to_spec_string`
+    pub spec_canonical: Option<String>,
+    // seconds since the unix epoch
+    pub timestamp: u64,
+}
+
+// where timestamps come from, the system clock unless a test wants output it can compare exactly
+pub trait Clock {
+    // seconds since the unix epoch
+    fn now(&self) -> u64;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+// always the same time, for tests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub u64);
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs())
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        self.0
+    }
+}
+
+impl RngSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RngSource::Thread => "thread",
+            RngSource::Os => "os",
+            RngSource::Seeded(_) => "insecure-seeded",
+        }
+    }
+}
+
+impl Display for RngSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+impl PasswordSpec {
+    pub fn generate_with_metadata(
+        &self,
+        source: RngSource,
+    ) -> Result<(GeneratedPassword, Metadata), GenerateError> {
+        self.generate_with_metadata_and_clock(source, &SystemClock)
+    }
+
+    // with the timestamp taken from `clock`, together with `RngSource::Seeded` the whole output
+    // is fixed
+    pub fn generate_with_metadata_and_clock<C: Clock + ?Sized>(
+        &self,
+        source: RngSource,
+        clock: &C,
+    ) -> Result<(GeneratedPassword, Metadata), GenerateError> {
+        let generated = match source {
+            RngSource::Thread => self.generate_detailed_with_rng(&mut thread_rng()),
+            RngSource::Os => self.generate_detailed_with_rng(&mut OsRng),
+            RngSource::Seeded(seed) => {
+                self.generate_detailed_with_rng(&mut StdRng::seed_from_u64(seed))
+            }
+        }?;
+        let metadata = Metadata {
+            generator_version: GENERATOR_VERSION,
+            rng_source: source.label(),
+            spec_canonical: self.to_spec_string(),
+            timestamp: clock.now(),
+        };
+        Ok((generated, metadata))
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use rand::Rng;
+use thiserror::Error;
+
+use crate::{
+    comments,
+    password::{GenerateError, PasswordParseError, PasswordSpec},
+};
+
+// Several named specs generated together, e.g. a username, password and pin for onboarding.
+// As a string it's one `name: spec` per line, with comments allowed, see `comments`.
+// Profile files loaded with `MultiSpec::load` can also start from other profiles with
+// `include = "base.profile"` (or `extends = ...`) lines, see `load`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MultiSpec {
+    fields: BTreeMap<String, PasswordSpec>,
+}
+
+#[derive(Debug, Error)]
+pub enum MultiSpecError {
+    #[error("Field `{name}` has a spec whose constraints couldn't be met: {source}")]
+    Infeasible {
+        name: String,
+        #[source]
+        source: GenerateError,
+    },
+    #[error("Line {0} isn't of the form `name: spec`")]
+    BadLine(usize),
+    #[error("Field `{0}` is defined more than once")]
+    Duplicate(String),
+    #[error("Field `{0}`: {1}")]
+    BadSpec(String, PasswordParseError),
+    #[error("Line {0} includes another profile, which only works when loading from a file")]
+    Include(usize),
+}
+
+// includes nested deeper than this are taken to be a mistake
+pub const MAX_INCLUDE_DEPTH: usize = 16;
+
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("Couldn't read `{}`: {1}", .0.display())]
+    Io(PathBuf, std::io::Error),
+    #[error("In `{}`: {1}", .0.display())]
+    Parse(PathBuf, MultiSpecError),
+    #[error("Profiles include each other: {}", chain(.0))]
+    Cycle(Vec<PathBuf>),
+    #[error("Profiles are included more than {} deep: {}", MAX_INCLUDE_DEPTH, chain(.0))]
+    TooDeep(Vec<PathBuf>),
+}
+
+fn chain(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+// a profile with its includes resolved, along with the file each field was finally set by
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedProfile {
+    pub spec: MultiSpec,
+    pub sources: BTreeMap<String, PathBuf>,
+}
+
+// the path of an `include = "path"` or `extends = "path"` line, quotes are optional
+fn include_path(line: &str) -> Option<&str> {
+    let (key, path) = line.split_once('=')?;
+    if !matches!(key.trim(), "include" | "extends") {
+        return None;
+    }
+    let path = path.trim();
+    Some(
+        path.strip_prefix('"')
+            .and_then(|p| p.strip_suffix('"'))
+            .unwrap_or(path),
+    )
+}
+
+// the fields a profile sets itself and the paths it includes, in order
+type Parsed<'a> = (BTreeMap<String, PasswordSpec>, Vec<(usize, &'a str)>);
+
+fn parse_profile(lines: &[(usize, String)]) -> Result<Parsed<'_>, MultiSpecError> {
+    let mut fields = BTreeMap::new();
+    let mut includes = vec![];
+    for (i, line) in lines {
+        let i = *i;
+        if let Some(path) = include_path(line) {
+            includes.push((i, path));
+            continue;
+        }
+        // names can't contain `:` so the first one ends the name, specs can contain them
+        let (name, spec) = line.split_once(':').ok_or(MultiSpecError::BadLine(i))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(MultiSpecError::BadLine(i));
+        }
+        let spec = spec
+            .trim()
+            .parse()
+            .map_err(|e| MultiSpecError::BadSpec(name.to_string(), e))?;
+        if fields.insert(name.to_string(), spec).is_some() {
+            return Err(MultiSpecError::Duplicate(name.to_string()));
+        }
+    }
+    Ok((fields, includes))
+}
+
+// loads `path` and what it includes into `profile`, `chain` is the files currently being loaded
+fn load_into(
+    path: PathBuf,
+    chain: &mut Vec<PathBuf>,
+    profile: &mut LoadedProfile,
+) -> Result<(), ProfileError> {
+    if chain.contains(&path) {
+        chain.push(path);
+        return Err(ProfileError::Cycle(chain.clone()));
+    }
+    if chain.len() > MAX_INCLUDE_DEPTH {
+        chain.push(path);
+        return Err(ProfileError::TooDeep(chain.clone()));
+    }
+    let contents = fs::read_to_string(&path).map_err(|e| ProfileError::Io(path.clone(), e))?;
+    let lines = comments::lines(&contents);
+    let (fields, includes) =
+        parse_profile(&lines).map_err(|e| ProfileError::Parse(path.clone(), e))?;
+    chain.push(path.clone());
+    let dir = path.parent().unwrap_or(Path::new(""));
+    for (_, include) in includes {
+        let included = dir.join(include);
+        let included = fs::canonicalize(&included).map_err(|e| ProfileError::Io(included, e))?;
+        load_into(included, chain, profile)?;
+    }
+    chain.pop();
+    for (name, spec) in fields {
+        profile.sources.insert(name.clone(), path.clone());
+        profile.spec.fields.insert(name, spec);
+    }
+    Ok(())
+}
+
+impl MultiSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn field(mut self, name: impl Into<String>, spec: PasswordSpec) -> Self {
+        self.fields.insert(name.into(), spec);
+        self
+    }
+
+    pub fn fields(&self) -> &BTreeMap<String, PasswordSpec> {
+        &self.fields
+    }
+
+    // a profile file with its includes resolved, relative include paths are relative to the
+    // directory of the file they're in
+    // includes are applied in order and then the file's own fields, each replacing whole fields
+    // set before it, so a team profile only has to list the fields it changes from the base
+    // paths in errors and `sources` are canonical, which is also how cycles are spotted
+    pub fn load(path: impl AsRef<Path>) -> Result<LoadedProfile, ProfileError> {
+        let path = path.as_ref();
+        let path = fs::canonicalize(path).map_err(|e| ProfileError::Io(path.to_path_buf(), e))?;
+        let mut profile = LoadedProfile {
+            spec: MultiSpec::new(),
+            sources: BTreeMap::new(),
+        };
+        load_into(path, &mut vec![], &mut profile)?;
+        Ok(profile)
+    }
+
+    // the first field that can't be generated is named in the error along with why, no partial
+    // set of secrets is returned
+    pub fn generate_all<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<BTreeMap<String, String>, MultiSpecError> {
+        let mut res = BTreeMap::new();
+        for (name, spec) in &self.fields {
+            let password =
+                spec.try_generate_with_rng(rng)
+                    .map_err(|source| MultiSpecError::Infeasible {
+                        name: name.clone(),
+                        source,
+                    })?;
+            res.insert(name.clone(), password);
+        }
+        Ok(res)
+    }
+}
+
+impl FromStr for MultiSpec {
+    type Err = MultiSpecError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = comments::lines(s);
+        let (fields, includes) = parse_profile(&lines)?;
+        match includes.first() {
+            Some((i, _)) => Err(MultiSpecError::Include(*i)),
+            None => Ok(Self { fields }),
+        }
+    }
+}
+
+impl Display for MultiSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, spec) in &self.fields {
+            writeln!(f, "{}: {}", name, spec)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use std::{fmt::Debug, sync::Arc, time::Duration};
+
+use crate::length_policy::LengthPolicy;
+
+// how many candidates are tried before giving up when constraints keep rejecting them
+const DEFAULT_MAX_ATTEMPTS: usize = 1000;
+
+type Check = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+// A named check a generated password has to pass, failing candidates are thrown away and
+// generation is retried.
+#[derive(Clone)]
+pub struct Constraint {
+    name: String,
+    check: Check,
+}
+
+impl Constraint {
+    pub fn new(
+        name: impl Into<String>,
+        check: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            check: Arc::new(check),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn check(&self, password: &str) -> bool {
+        (self.check)(password)
+    }
+}
+
+impl Debug for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Constraint({})", self.name)
+    }
+}
+
+// How generation retries when constraints reject candidates.
+// The timeout is only checked between attempts, so a password that passes on the first try never
+// looks at the clock.
+#[derive(Debug, Clone)]
+pub struct GenerateOptions {
+    pub(crate) max_attempts: usize,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) constraints: Vec<Constraint>,
+    // applied to the spec before generating, see `PasswordSpec::length_policy`
+    pub(crate) length_policy: Option<LengthPolicy>,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GenerateOptions {
+    pub fn new() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            timeout: None,
+            constraints: vec![],
+            length_policy: None,
+        }
+    }
+
+    pub fn max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn constraint(mut self, constraint: Constraint) -> Self {
+        self.constraints.push(constraint);
+        self
+    }
+
+    pub fn length_policy(mut self, policy: LengthPolicy) -> Self {
+        self.length_policy = Some(policy);
+        self
+    }
+
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+}
@@ -0,0 +1,247 @@
+// Formatting generated passwords for output.
+use std::{io::Write, str::FromStr};
+
+use thiserror::Error;
+
+use crate::{
+    bytes::{check_raw_output, BytesError},
+    password::PasswordSpec,
+    pwgen::PwgenOptions,
+    stream::PROGRESS_THRESHOLD,
+    verbosity::{Logger, Verbosity},
+};
+
+// version of the crate, recorded next to specs so they can be traced back to the grammar used
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Error)]
+#[error("The spec has settings a spec string can't record, like affixes or position rules, so it can't be written with the passwords")]
+pub struct UnrecordableSpec;
+
+// a comment line recording the spec, consumers skipping `#` lines only see the passwords
+// the recorded spec has to parse back to the same spec, see `PasswordSpec::to_spec_string`
+pub fn spec_comment(spec: &PasswordSpec) -> Result<String, UnrecordableSpec> {
+    spec.to_spec_string()
+        .map(|recorded| format!("# spec: {}", recorded))
+        .ok_or(UnrecordableSpec)
+}
+
+// one password per line with the spec recorded, on the line after a single password or once as a
+// header before a batch
+pub fn with_spec(spec: &PasswordSpec, passwords: &[String]) -> Result<String, UnrecordableSpec> {
+    let comment = spec_comment(spec)?;
+    let mut res = String::new();
+    if passwords.len() > 1 {
+        res.push_str(&comment);
+        res.push('\n');
+    }
+    for password in passwords {
+        res.push_str(password);
+        res.push('\n');
+    }
+    if passwords.len() == 1 {
+        res.push_str(&comment);
+        res.push('\n');
+    }
+    Ok(res)
+}
+
+// width assumed when the terminal's isn't known
+pub const DEFAULT_WIDTH: usize = 80;
+
+// how many passwords go on a line when showing a batch to a person
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Columns {
+    // as many as fit the width
+    Auto,
+    // this many, or fewer if they don't fit
+    Fixed(usize),
+}
+
+#[derive(Debug, Error)]
+#[error("Expected `auto` or a number of columns, got `{0}`")]
+pub struct ColumnsParseError(String);
+
+impl FromStr for Columns {
+    type Err = ColumnsParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Columns::Auto),
+            _ => s
+                .parse()
+                .ok()
+                .filter(|n| *n > 0)
+                .map(Columns::Fixed)
+                .ok_or_else(|| ColumnsParseError(s.to_string())),
+        }
+    }
+}
+
+// items in rows, left to right, padded to the widest item and separated by a space, with as many
+// columns as fit in `width` characters
+// items wider than `width` get a line each, like output that isn't laid out
+pub fn columnize(items: &[String], width: usize) -> String {
+    let widest = items
+        .iter()
+        .map(|item| item.chars().count())
+        .max()
+        .unwrap_or(0);
+    let per_row = ((width + 1) / (widest + 1)).max(1);
+    let mut res = String::new();
+    for row in items.chunks(per_row) {
+        for (i, item) in row.iter().enumerate() {
+            res.push_str(item);
+            // no padding after the last, passwords can end in a space so nothing is trimmed
+            if i + 1 < row.len() {
+                let padding = widest - item.chars().count() + 1;
+                res.extend(std::iter::repeat(' ').take(padding));
+            }
+        }
+        res.push('\n');
+    }
+    res
+}
+
+// passwords laid out for whoever reads them, in columns on a terminal of `width` characters and
+// one per line otherwise so pipes get one password per line
+pub fn layout(passwords: &[String], columns: Columns, width: usize, is_tty: bool) -> String {
+    if !is_tty {
+        return columnize(passwords, 0);
+    }
+    let width = match columns {
+        Columns::Auto => width,
+        Columns::Fixed(n) => {
+            let widest = passwords
+                .iter()
+                .map(|password| password.chars().count())
+                .max()
+                .unwrap_or(0);
+            width.min(n.saturating_mul(widest + 1).saturating_sub(1))
+        }
+    };
+    columnize(passwords, width)
+}
+
+// when to color output, `Auto` colors only what goes to a terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Error)]
+#[error("Expected `auto`, `always` or `never`, got `{0}`")]
+pub struct ColorChoiceParseError(String);
+
+impl FromStr for ColorChoice {
+    type Err = ColorChoiceParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(ColorChoiceParseError(s.to_string())),
+        }
+    }
+}
+
+// where output is going, worked out once when the CLI starts and passed to everything that writes
+// anything decorative, columns, colors, progress and prompts only show on a terminal so they never
+// end up in a pipe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputContext {
+    pub stdout_is_tty: bool,
+    pub stderr_is_tty: bool,
+    pub color: ColorChoice,
+    pub width: usize,
+    pub verbosity: Verbosity,
+}
+
+impl OutputContext {
+    pub fn new(stdout_is_tty: bool, stderr_is_tty: bool) -> Self {
+        Self {
+            stdout_is_tty,
+            stderr_is_tty,
+            color: ColorChoice::Auto,
+            width: DEFAULT_WIDTH,
+            verbosity: Verbosity::Normal,
+        }
+    }
+
+    // output to a terminal whatever was detected, for `--assume-tty`
+    pub fn assume_tty(mut self) -> Self {
+        self.stdout_is_tty = true;
+        self.stderr_is_tty = true;
+        self
+    }
+
+    // output to a pipe whatever was detected, for `--assume-pipe`
+    pub fn assume_pipe(mut self) -> Self {
+        self.stdout_is_tty = false;
+        self.stderr_is_tty = false;
+        self
+    }
+
+    pub fn color(mut self, color: ColorChoice) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    // messages to `sink` at the verbosity, normally stderr
+    pub fn logger<W: Write>(&self, sink: W) -> Logger<W> {
+        Logger::new(self.verbosity, sink)
+    }
+
+    pub fn color_stdout(&self) -> bool {
+        self.colors(self.stdout_is_tty)
+    }
+
+    pub fn color_stderr(&self) -> bool {
+        self.colors(self.stderr_is_tty)
+    }
+
+    fn colors(&self, is_tty: bool) -> bool {
+        match self.color {
+            ColorChoice::Auto => is_tty,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+
+    // whether to draw a progress bar on stderr for a batch of `count`, never when quiet
+    pub fn progress(&self, count: usize) -> bool {
+        self.stderr_is_tty && count >= PROGRESS_THRESHOLD && self.verbosity > Verbosity::Quiet
+    }
+
+    // whether to ask for confirmation on stderr, nobody is there to answer when it's piped
+    pub fn prompts(&self) -> bool {
+        self.stderr_is_tty
+    }
+
+    // passwords for stdout, see `layout`
+    pub fn layout(&self, passwords: &[String], columns: Columns) -> String {
+        layout(passwords, columns, self.width, self.stdout_is_tty)
+    }
+
+    // refuses raw bytes for a terminal unless forced, see `check_raw_output`
+    pub fn check_raw_output(&self, force: bool) -> Result<(), BytesError> {
+        check_raw_output(self.stdout_is_tty, force)
+    }
+
+    // pwgen's defaults, which differ for a terminal
+    pub fn pwgen_options(&self) -> PwgenOptions {
+        PwgenOptions::new(self.stdout_is_tty)
+    }
+}
@@ -1,24 +1,117 @@
 use std::fmt::Display;
+use std::ops::RangeInclusive;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 use rand::{
     seq::{IteratorRandom, SliceRandom},
-    thread_rng,
+    thread_rng, Rng,
 };
 use thiserror::Error;
 
-use crate::choice::{ChoiceParseError, Choices};
+use crate::adjacent;
+use crate::choice::{ChoiceParseError, ChoiceSampler, Choices};
+use crate::fill::{self, FillMode};
+use crate::global;
 use crate::interval::Interval;
-use crate::{charset::Charset, choice::Choice};
+use crate::length_policy::{self, Decorations};
+use crate::lint::{Lint, LintWarning};
+use crate::options::GenerateOptions;
+use crate::position::{self, PositionRule};
+use crate::sampling::{self, Completions, SamplingMode, MAX_UNIFORM_LENGTH};
+use crate::unicode::Normalization;
+use crate::units::LengthUnit;
+use crate::{checksum, format};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+pub use crate::{charset::Charset, choice::Choice};
+
+// the name the charsets went by in older docs
+pub type CharStyle = Charset;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct PasswordSpec {
-    length: usize,
-    choices: Choices,
+    pub(crate) length: Length,
+    pub(crate) choices: Choices,
+    // fixed strings around the random core, they aren't part of the spec string
+    pub(crate) prefix: String,
+    pub(crate) suffix: String,
+    pub(crate) affixes_in_length: bool,
+    // what may go at particular positions of the random core, not part of the spec string either
+    pub(crate) position_rules: Vec<(isize, isize, PositionRule)>,
+    // charsets whose characters can't be next to each other
+    pub(crate) no_adjacent: Vec<Charset>,
+    // every character has to be from this, when it's set the characters beyond what the choices
+    // require can come from any of it, not just the choices' charsets
+    pub(crate) allowed: Option<Charset>,
+    pub(crate) fill: FillMode,
+    pub(crate) sampling: SamplingMode,
+    pub(crate) normalization: Normalization,
+    // what the length counts and a cap on the UTF-8 size, see `units`
+    pub(crate) unit: LengthUnit,
+    pub(crate) max_bytes: Option<usize>,
+    // bounds on the password with its decorations, see `length_policy`
+    pub(crate) total_length: Option<Interval>,
+    // the least guaranteed entropy generating accepts, as the bits of the f64 so the spec stays
+    // `Eq` and `Hash`
+    pub(crate) min_entropy: Option<u64>,
+}
+
+// the length is either given directly or as a target entropy in bits that gets resolved into a
+// length whenever the spec is used, so it follows changes to the choices
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Length {
+    Chars(usize),
+    Bits(usize),
+}
+
+impl Display for Length {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Length::Chars(n) => write!(f, "{}", n),
+            Length::Bits(n) => write!(f, "{}b", n),
+        }
+    }
+}
+
+// one line of `PasswordSpec::entropy_report`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChoiceEntropy {
+    // position in `PasswordSpec::choices`, `None` for the characters only `allowed` adds
+    pub index: Option<usize>,
+    pub name: String,
+    // size of the pool drawn from, after exclusions
+    pub characters: usize,
+    pub bits_each: f64,
+    // characters from this choice in the worst case composition
+    pub count: usize,
 }
 
+impl ChoiceEntropy {
+    pub fn bits(&self) -> f64 {
+        self.count as f64 * self.bits_each
+    }
+}
+
+// the canonical string of `PasswordSpec::default()`, for docs, config templates and CLI defaults
+pub const DEFAULT_SPEC: &str = "32//1+|:upper://1+|:lower://1+|:number://1+|:symbol:";
+
+// how many times fresh characters are drawn when they can't be placed for the position rules or
+// kept apart
+const ARRANGE_ATTEMPTS: usize = 1000;
+// how many times characters are drawn again when overlapping charsets put a choice's count
+// outside its interval
+const COUNT_ATTEMPTS: usize = 1000;
+
+// the spec set with `set_default_spec`, or `builtin_default` when there isn't one
 impl Default for PasswordSpec {
     fn default() -> Self {
+        global::default_spec()
+    }
+}
+
+impl PasswordSpec {
+    // at least one of each of the named charsets, with the default length
+    pub fn builtin_default() -> Self {
         let mut choices = Choices::new();
         choices.push(Charset::Upper.at_least(1));
         choices.push(Charset::Lower.at_least(1));
@@ -26,7 +119,7 @@ impl Default for PasswordSpec {
         choices.push(Charset::Symbol.at_least(1));
         PasswordSpec {
             choices,
-            length: 32,
+            ..PasswordSpec::new()
         }
     }
 }
@@ -37,143 +130,1071 @@ pub enum PasswordParseError {
     ImproperFormat,
     #[error("Couldn't parse the length segment of the spec `{0}`, expects it to be the first segment of the spec (length//...).")]
     InvalidLength(String),
+    #[error("The length `{text}` is too large, the largest is {max}")]
+    LengthOverflow { text: String, max: usize },
+    #[error("The length `{0}` has digits other than 0-9, only ASCII digits are accepted")]
+    NonAsciiLength(String),
     #[error("Couldn't parse the interval `{0}`.")]
     BadInterval(String),
     #[error("Couldn't parse the charset `{0}`.")]
     BadCharset(String),
     #[error("{0}")]
     BadChoice(ChoiceParseError),
+    #[error("{0} (did you mean to prefix the spec with `v2:` to use escapes?)")]
+    MissingVersion(Box<PasswordParseError>),
+    #[error("Spec has an empty segment between `//` separators")]
+    EmptySegment,
+    #[error("Spec ends with an escape `\\` that has nothing to escape")]
+    DanglingEscape,
 }
 
-// password spec specified as a string would look something like
-// 16//1+|:upper://5-|:lower://2|Aa
-// (Upper, at least 1) (Lower, at most 5) (Custom(Aa), exactly 2) length=16
+#[derive(Debug, Error)]
+pub enum GenerateError {
+    #[error("Constraints couldn't be met")]
+    Infeasible,
+    #[error("No choices remain, every charset was removed from the spec")]
+    NoChoices,
+    #[error("The charset `{0}` is empty, there are no characters to choose from")]
+    EmptyCharset(Charset),
+    #[error("The choices need at least {required} characters but the length is only {length}")]
+    LengthBelowMinimum { required: usize, length: usize },
+    #[error("The choices allow at most {allowed} characters but the length is {length}")]
+    LengthAboveMaximum { allowed: usize, length: usize },
+    #[error("Generated {generated} characters when the spec asks for {expected}")]
+    WrongLength { expected: usize, generated: usize },
+    #[error("The spec can guarantee at most {achievable:.1} bits of entropy, short of the {bits} bits asked for")]
+    EntropyUnreachable { bits: usize, achievable: f64 },
+    #[error(
+        "The prefix and suffix take up {affixes} characters, more than the length of {length}"
+    )]
+    AffixesTooLong { affixes: usize, length: usize },
+    #[error("No candidate passed the constraints after {attempts} attempts")]
+    AttemptsExhausted { attempts: usize },
+    #[error("Timed out after {elapsed:?} and {attempts} attempts without a candidate passing the constraints")]
+    TimedOut { attempts: usize, elapsed: Duration },
+    #[error("Position {index} is outside a password of {length} characters")]
+    PositionOutOfRange { index: isize, length: usize },
+    #[error("The position rules can't all be met, position {0} has no characters left for it")]
+    PositionConflict(usize),
+    #[error("Couldn't place the characters to meet the position rules after {attempts} attempts")]
+    PositionRulesUnmet { attempts: usize },
+    #[error(
+        "At least {count} characters from `{charset}` can't be kept apart in {length} characters"
+    )]
+    AdjacencyInfeasible {
+        charset: Charset,
+        count: usize,
+        length: usize,
+    },
+    #[error("The spec can make at most {possible} different passwords, fewer than the {requested} asked for")]
+    TooFewDistinct { requested: usize, possible: usize },
+    #[error("The choice for `{charset}` needs at least {min} characters but allows at most {max}")]
+    BadBounds {
+        charset: Charset,
+        min: usize,
+        max: usize,
+    },
+    #[error("The spec guarantees {achieved:.1} bits of entropy, short of the minimum of {required:.1} bits")]
+    EntropyBelowMinimum { required: f64, achieved: f64 },
+    #[error("With its decorations the password is {length} long, the total length has to be {}", length_policy::bounds(.allowed))]
+    TotalLengthUnmet { allowed: Interval, length: usize },
+    #[error("With its decorations the password would be longer than the largest length there is")]
+    LengthOverflow,
+    #[error(
+        "Uniform sampling works out counts for at most {max} characters, the length is {length}"
+    )]
+    UniformTooLong { length: usize, max: usize },
+    #[error("A length in {unit} can be at most {max}, the length is {length}")]
+    MeasuredTooLong {
+        unit: LengthUnit,
+        length: usize,
+        max: usize,
+    },
+    #[error("The random number generator failed: {0}")]
+    RngFailure(#[source] rand::Error),
+}
 
-impl FromStr for PasswordSpec {
-    type Err = PasswordParseError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim_start();
-        let sep = "//".to_string();
-        let sep_char = sep.chars().last().unwrap();
-        // let second_sep = "|".to_string();
-        let mut spec = PasswordSpec::new();
-        let mut stack = String::new();
-        let chars: Vec<char> = s.chars().collect();
-        // parse length first
-        let mut i = 0;
-        while i < chars.len() {
-            let c = chars[i];
-            stack.push(c);
-            i += 1;
-            if stack.ends_with(&sep) {
-                let length: usize = stack[..stack.len() - sep.len()].parse().map_err(|_| {
-                    PasswordParseError::InvalidLength(stack[..stack.len() - sep.len()].to_string())
-                })?;
-                spec = spec.length(length);
-                stack = String::new();
-                break;
-            }
-        }
+#[derive(Debug, Error)]
+pub enum VerifyError {
+    #[error("Constraints couldn't be met")]
+    Infeasible,
+    #[error("Generated password doesn't satisfy the spec it was generated from")]
+    Mismatch,
+}
 
-        // parse choices
-        while i < chars.len() {
-            let c = chars[i];
-            if c != sep_char && stack.ends_with(&sep) {
-                let choice = stack[..stack.len() - sep.len()]
-                    .parse()
-                    .map_err(PasswordParseError::BadChoice)?;
-                spec = spec.include(choice);
-                stack = String::new();
-            }
-            stack.push(c);
-            i += 1;
-        }
+#[derive(Debug, Error)]
+pub enum TranscriptionError {
+    #[error("Check character doesn't match, character {0} looks mistyped")]
+    Mistyped(usize),
+    #[error("Check character doesn't match, something was mistyped")]
+    ChecksumMismatch,
+    #[error("Checksum is fine but the password doesn't satisfy the spec")]
+    Mismatch,
+}
 
-        // since parsing requires a peek, need to handle the very end of the string
-        // having a trailing // is valid
-        if stack.ends_with(&sep) {
-            let choice = stack[..stack.len() - sep.len()]
-                .parse()
-                .map_err(PasswordParseError::BadChoice)?;
-            spec = spec.include(choice);
-            stack = String::new();
+// Stable identifiers for the errors, for programs that need to tell them apart without matching
+// on the messages. They're part of the public interface, existing codes don't change.
+impl PasswordParseError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            PasswordParseError::ImproperFormat => "improper_format",
+            PasswordParseError::InvalidLength(_) => "invalid_length",
+            PasswordParseError::LengthOverflow { .. } => "length_overflow",
+            PasswordParseError::NonAsciiLength(_) => "non_ascii_length",
+            PasswordParseError::BadInterval(_) => "bad_interval",
+            PasswordParseError::BadCharset(_) => "bad_charset",
+            PasswordParseError::BadChoice(_) => "bad_choice",
+            PasswordParseError::MissingVersion(_) => "missing_version",
+            PasswordParseError::EmptySegment => "empty_segment",
+            PasswordParseError::DanglingEscape => "dangling_escape",
         }
+    }
+}
 
-        if !stack.is_empty() {
-            let choice = stack[..stack.len()]
-                .parse()
-                .map_err(PasswordParseError::BadChoice)?;
-            spec = spec.include(choice);
-            // stack = String::new();
+impl GenerateError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            GenerateError::Infeasible => "infeasible",
+            GenerateError::NoChoices => "no_choices",
+            GenerateError::EmptyCharset(_) => "empty_charset",
+            GenerateError::LengthBelowMinimum { .. } => "length_below_minimum",
+            GenerateError::LengthAboveMaximum { .. } => "length_above_maximum",
+            GenerateError::WrongLength { .. } => "wrong_length",
+            GenerateError::EntropyUnreachable { .. } => "entropy_unreachable",
+            GenerateError::AffixesTooLong { .. } => "affixes_too_long",
+            GenerateError::AttemptsExhausted { .. } => "attempts_exhausted",
+            GenerateError::TimedOut { .. } => "timed_out",
+            GenerateError::PositionOutOfRange { .. } => "position_out_of_range",
+            GenerateError::PositionConflict(_) => "position_conflict",
+            GenerateError::PositionRulesUnmet { .. } => "position_rules_unmet",
+            GenerateError::AdjacencyInfeasible { .. } => "adjacency_infeasible",
+            GenerateError::TooFewDistinct { .. } => "too_few_distinct",
+            GenerateError::BadBounds { .. } => "bad_bounds",
+            GenerateError::EntropyBelowMinimum { .. } => "entropy_below_minimum",
+            GenerateError::TotalLengthUnmet { .. } => "total_length_unmet",
+            GenerateError::LengthOverflow => "length_overflow",
+            GenerateError::UniformTooLong { .. } => "uniform_too_long",
+            GenerateError::MeasuredTooLong { .. } => "measured_too_long",
+            GenerateError::RngFailure(_) => "rng_failure",
         }
+    }
+}
 
-        Ok(spec)
+impl FromStr for PasswordSpec {
+    type Err = PasswordParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        format::parse(s, Normalization::Nfc)
     }
 }
 
 impl Display for PasswordSpec {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.length)?;
-        write!(f, "{}", self.choices)
+        format::write(self, f)
     }
 }
 
 impl PasswordSpec {
+    // the spec string when it parses back to this same spec, `Display` leaves out what the grammar
+    // has no place for, like affixes, position rules or a byte cap, and specs with any of those
+    // give `None`
+    pub fn to_spec_string(&self) -> Option<String> {
+        let s = self.to_string();
+        (s.parse::<PasswordSpec>().ok().as_ref() == Some(self)).then_some(s)
+    }
+
+    // whether a spec string is in the deprecated bracketed format, it still parses but callers
+    // may want to tell users to update it
+    pub fn is_legacy_format(s: &str) -> bool {
+        format::is_legacy(s)
+    }
+
     pub fn new() -> Self {
         Self {
             choices: Choices::new(),
-            length: 32,
+            length: Length::Chars(32),
+            prefix: String::new(),
+            suffix: String::new(),
+            affixes_in_length: false,
+            position_rules: vec![],
+            no_adjacent: vec![],
+            allowed: None,
+            fill: FillMode::Random,
+            sampling: SamplingMode::PerChoice,
+            normalization: Normalization::Nfc,
+            unit: LengthUnit::Chars,
+            max_bytes: None,
+            total_length: None,
+            min_entropy: None,
         }
     }
+
     pub fn generate(&self) -> Option<String> {
-        if self.check() {
-            let mut characters = vec![];
-            let mut active = Choices::new();
-            for mut choice in self.choices.clone() {
-                characters.extend(choice.get_required());
-                active.push(choice);
+        self.generate_with_rng(&mut thread_rng())
+    }
+
+    pub fn generate_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<String> {
+        self.try_generate_with_rng(rng).ok()
+    }
+
+    // like `generate` but with why the spec couldn't be met
+    pub fn try_generate(&self) -> Result<String, GenerateError> {
+        self.try_generate_with_rng(&mut thread_rng())
+    }
+
+    pub fn try_generate_with_rng<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<String, GenerateError> {
+        self.generate_once(rng)
+    }
+
+    pub(crate) fn generate_once<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<String, GenerateError> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("generate").entered();
+        let drawn = self.generate_drawn(rng)?;
+        let core = self.checked_length(drawn.into_iter().map(|(c, _)| c).collect())?;
+        Ok(format!("{}{}{}", self.prefix, core, self.suffix))
+    }
+
+    // the characters of the random core along with the charset each was drawn from
+    pub(crate) fn generate_drawn<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<Vec<(char, Charset)>, GenerateError> {
+        if self.measured() {
+            return self.draw_measured(rng);
+        }
+        self.precheck()?;
+        let length = self.resolve_length()?;
+        #[cfg(feature = "tracing")]
+        tracing::trace!(length, "resolved length");
+        if self.position_rules.is_empty() && self.no_adjacent.is_empty() {
+            return self.draw(length, rng);
+        }
+        let sets = self.position_sets(length)?;
+        self.check_adjacency(length)?;
+        self.check_forced(&sets, length)?;
+        for _attempt in 0..ARRANGE_ATTEMPTS {
+            let mut characters = Some(self.draw(length, rng)?);
+            if let Some(charset) = self.no_adjacent.first() {
+                characters = characters.and_then(|drawn| adjacent::spread(charset, drawn, rng));
+            }
+            // placing for the position rules can undo the spreading, so the result is checked
+            let characters = characters
+                .and_then(|drawn| position::arrange(&sets, drawn))
+                .filter(|arranged| {
+                    let core: String = arranged.iter().map(|(c, _)| c).collect();
+                    self.adjacency_breaks(&core).is_empty()
+                });
+            if let Some(characters) = characters {
+                return Ok(characters);
+            }
+            #[cfg(feature = "tracing")]
+            tracing::trace!(attempt = _attempt + 1, "arrangement rejected");
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            attempts = ARRANGE_ATTEMPTS,
+            "arrangement attempts exhausted"
+        );
+        if self.no_adjacent.is_empty() {
+            Err(GenerateError::PositionRulesUnmet {
+                attempts: ARRANGE_ATTEMPTS,
+            })
+        } else {
+            Err(GenerateError::AttemptsExhausted {
+                attempts: ARRANGE_ATTEMPTS,
+            })
+        }
+    }
+
+    // what makes a spec unable to generate whatever is drawn, for lengths in characters
+    pub(crate) fn precheck(&self) -> Result<(), GenerateError> {
+        if self.choices.choices.is_empty() {
+            return Err(GenerateError::NoChoices);
+        }
+        if let Some(choice) = self.choices().find(|c| c.min > c.max) {
+            return Err(GenerateError::BadBounds {
+                charset: choice.chars.clone(),
+                min: choice.min,
+                max: choice.max,
+            });
+        }
+        if let Some(choice) = self
+            .choices()
+            .find(|c| c.chars.is_empty() || (c.min > 0 && self.pool(c).is_empty()))
+        {
+            return Err(GenerateError::EmptyCharset(choice.chars.clone()));
+        }
+        self.check_uniform_length()?;
+        if !self.check()? {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("spec is infeasible");
+            return Err(self.infeasibility()?);
+        }
+        self.check_min_entropy()?;
+        self.check_total_length(&Decorations::new())
+    }
+
+    // `UniformTooLong` when uniform sampling would have to work out counts for too long a password
+    fn check_uniform_length(&self) -> Result<(), GenerateError> {
+        if self.sampling != SamplingMode::Uniform {
+            return Ok(());
+        }
+        let length = self.resolve_length()?;
+        if length > MAX_UNIFORM_LENGTH {
+            return Err(GenerateError::UniformTooLong {
+                length,
+                max: MAX_UNIFORM_LENGTH,
+            });
+        }
+        Ok(())
+    }
+
+    // characters for the counts in a random order, not yet placed for any position rules
+    fn draw<R: Rng + ?Sized>(
+        &self,
+        length: usize,
+        rng: &mut R,
+    ) -> Result<Vec<(char, Charset)>, GenerateError> {
+        self.draw_from(&self.drawable(), length, rng)
+    }
+
+    // the choices drawn from with their pools, which only depend on the spec so a batch works them
+    // out once
+    pub(crate) fn drawable(&self) -> Vec<(Choice, Vec<char>)> {
+        self.drawn_choices()
+            .into_iter()
+            .map(|choice| {
+                let pool = self.pool(&choice);
+                (choice, pool)
+            })
+            .collect()
+    }
+
+    // a choice's count is every character of its charset in the password, whichever choice drew
+    // it, so when charsets overlap the characters are drawn again until every count is allowed
+    pub(crate) fn draw_from<R: Rng + ?Sized>(
+        &self,
+        drawable: &[(Choice, Vec<char>)],
+        length: usize,
+        rng: &mut R,
+    ) -> Result<Vec<(char, Charset)>, GenerateError> {
+        for _ in 0..COUNT_ATTEMPTS {
+            let drawn = self.draw_candidate(drawable, length, rng)?;
+            if self.counts_allowed(&drawn) {
+                return Ok(drawn);
+            }
+            #[cfg(feature = "tracing")]
+            tracing::trace!("overlapping charsets broke a count");
+        }
+        Err(GenerateError::AttemptsExhausted {
+            attempts: COUNT_ATTEMPTS,
+        })
+    }
+
+    fn counts_allowed(&self, drawn: &[(char, Charset)]) -> bool {
+        self.choices().all(|choice| {
+            choice.admits(
+                drawn
+                    .iter()
+                    .filter(|(c, _)| choice.chars.contains(*c))
+                    .count(),
+            )
+        })
+    }
+
+    fn draw_candidate<R: Rng + ?Sized>(
+        &self,
+        drawable: &[(Choice, Vec<char>)],
+        length: usize,
+        rng: &mut R,
+    ) -> Result<Vec<(char, Charset)>, GenerateError> {
+        if self.sampling == SamplingMode::Uniform {
+            return draw_uniform(drawable, length, rng);
+        }
+        let intervals = pick_intervals(drawable, length, rng).ok_or(GenerateError::Infeasible)?;
+        let mut characters = vec![];
+        let mut active = vec![];
+        for ((choice, pool), interval) in drawable.iter().zip(intervals) {
+            let mut sampler = ChoiceSampler::new(choice, interval, pool.clone());
+            characters.extend(
+                sampler
+                    .get_required(rng)?
+                    .into_iter()
+                    .map(|c| (c, choice.chars.clone())),
+            );
+            if sampler.active() {
+                active.push((sampler, &choice.chars));
             }
+        }
 
-            let remaining = self.length - characters.len();
-            let mut active: Vec<_> = active.into_iter().filter(|x| x.active()).collect();
+        let remaining = length - characters.len();
 
-            for _ in 0..remaining {
-                if let Some(index) = (0..active.len()).choose(&mut thread_rng()) {
-                    let c = active[index].next().unwrap();
-                    characters.push(c);
-                    if !active[index].active() {
-                        active.remove(index);
+        match self.fill {
+            FillMode::Random => {
+                for _ in 0..remaining {
+                    if let Some(index) = sampling::pick(active.len(), rng) {
+                        let c = active[index].0.sample(rng).unwrap();
+                        characters.push((c, active[index].1.clone()));
+                        if !active[index].0.active() {
+                            active.remove(index);
+                        }
+                    }
+                }
+            }
+            FillMode::Balanced => {
+                let capacities: Vec<usize> = active
+                    .iter()
+                    .map(|(sampler, _)| sampler.capacity())
+                    .collect();
+                let counts = fill::balanced(&capacities, remaining, rng);
+                for ((sampler, chars), count) in active.iter_mut().zip(counts) {
+                    for _ in 0..count {
+                        if let Some(c) = sampler.sample(rng) {
+                            characters.push((c, (*chars).clone()));
+                        }
                     }
                 }
             }
+        }
+
+        characters.shuffle(rng);
+        Ok(characters)
+    }
 
-            characters.shuffle(&mut thread_rng());
-            Some(characters.into_iter().collect())
+    // last line of defense against the generation bookkeeping disagreeing with the spec, a
+    // password of the wrong length is an error rather than being handed out
+    // public so tests can exercise it directly
+    #[doc(hidden)]
+    pub fn checked_length(&self, password: String) -> Result<String, GenerateError> {
+        let expected = self.resolve_length()?;
+        let generated = self.unit().measure(&password);
+        if generated == expected {
+            Ok(password)
         } else {
-            None
+            Err(GenerateError::WrongLength {
+                expected,
+                generated,
+            })
         }
     }
 
-    fn check(&self) -> bool {
-        let mut min_length: usize = 0;
-        let mut max_length: usize = 0;
-        for choice in &self.choices.choices {
-            min_length = min_length.saturating_add(choice.min);
-            max_length = max_length.saturating_add(choice.max);
+    // generate candidates until one passes every constraint in `options`
+    pub fn generate_with_options<R: Rng + ?Sized>(
+        &self,
+        options: &GenerateOptions,
+        rng: &mut R,
+    ) -> Result<String, GenerateError> {
+        if let Some(policy) = &options.length_policy {
+            let mut options = options.clone();
+            options.length_policy = None;
+            return self
+                .clone()
+                .length_policy(policy.clone())
+                .generate_with_options(&options, rng);
         }
-        min_length <= self.length && self.length <= max_length
+        let start = options.timeout.map(|_| Instant::now());
+        for attempt in 1..=options.max_attempts {
+            let password = self.generate_once(rng)?;
+            let _failed = match options.constraints.iter().find(|c| !c.check(&password)) {
+                None => return Ok(password),
+                Some(constraint) => constraint,
+            };
+            #[cfg(feature = "tracing")]
+            tracing::debug!(attempt, constraint = _failed.name(), "candidate rejected");
+            if let (Some(start), Some(timeout)) = (start, options.timeout) {
+                let elapsed = start.elapsed();
+                if elapsed >= timeout {
+                    return Err(GenerateError::TimedOut {
+                        attempts: attempt,
+                        elapsed,
+                    });
+                }
+            }
+        }
+        Err(GenerateError::AttemptsExhausted {
+            attempts: options.max_attempts,
+        })
+    }
+
+    // generate and then re-validate the result with `matches`, which counts characters
+    // independently of the bookkeeping done during generation
+    pub fn generate_verified(&self) -> Result<String, VerifyError> {
+        self.generate_verified_with_rng(&mut thread_rng())
+    }
+
+    pub fn generate_verified_with_rng<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<String, VerifyError> {
+        self.verified(self.generate_with_rng(rng))
+    }
+
+    // the verification step on its own, public so tests can push a deliberately broken password
+    // through it
+    #[doc(hidden)]
+    pub fn verified(&self, generated: Option<String>) -> Result<String, VerifyError> {
+        let password = generated.ok_or(VerifyError::Infeasible)?;
+        if self.matches(&password) {
+            Ok(password)
+        } else {
+            Err(VerifyError::Mismatch)
+        }
+    }
+
+    // generate and append a check character, see `checksum::check_character`, it doesn't count
+    // toward the length or any charset, only the total length
+    pub fn generate_with_check_character(&self) -> Option<String> {
+        self.generate_with_check_character_with_rng(&mut thread_rng())
+    }
+
+    pub fn generate_with_check_character_with_rng<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Option<String> {
+        self.check_total_length(&Decorations::new().check_character())
+            .ok()?;
+        self.without_total_length()
+            .generate_with_rng(rng)
+            .map(|password| checksum::append_check_character(&password))
+    }
+
+    // check a written down password that ends in a check character, the checksum is checked
+    // first so a typo can be pointed at before the full spec is
+    pub fn check_transcribed(&self, transcribed: &str) -> Result<(), TranscriptionError> {
+        let mut chars = transcribed.chars();
+        chars.next_back();
+        let password = chars.as_str();
+        if !checksum::check_character_valid(transcribed) {
+            // a character outside every charset is the most likely culprit
+            let position = password.chars().position(|c| !self.is_allowed(c));
+            return Err(match position {
+                Some(i) => TranscriptionError::Mistyped(i + 1),
+                None => TranscriptionError::ChecksumMismatch,
+            });
+        }
+        if self.without_total_length().matches(password) && self.within_total_length(transcribed) {
+            Ok(())
+        } else {
+            Err(TranscriptionError::Mismatch)
+        }
+    }
+
+    // does the password have the right length, only use characters from the spec's charsets and
+    // have a count within each choice's interval
+    // the prefix and suffix have to be there and the rest is checked as the random core
+    // the password is normalized first like the spec's charsets, see `Normalization`
+    pub fn matches(&self, password: &str) -> bool {
+        let password = self.normalization.apply(password);
+        let core = match password
+            .strip_prefix(self.prefix.as_str())
+            .and_then(|rest| rest.strip_suffix(self.suffix.as_str()))
+        {
+            Some(core) => core,
+            None => return false,
+        };
+        self.resolve_length().ok() == Some(self.unit().measure(core))
+            && self.within_max_bytes(&password)
+            && self.within_total_length(&password)
+            && self.count_unmatched(core) == 0
+            && self
+                .choices()
+                .zip(self.count_by_charset(core))
+                .all(|(choice, (_, count))| choice.admits(count))
+            && self.follows_position_rules(core)
+            && self.adjacency_breaks(core).is_empty()
+    }
+
+    // for each choice how many characters of `s` are in its charset
+    // charsets can overlap, a character is counted for every charset it's in
+    pub fn count_by_charset(&self, s: &str) -> Vec<(Charset, usize)> {
+        self.choices()
+            .map(|choice| {
+                let count = s.chars().filter(|c| choice.chars.contains(*c)).count();
+                (choice.chars.clone(), count)
+            })
+            .collect()
+    }
+
+    // how many characters of `s` aren't in any of the spec's charsets, or outside the allowed
+    // characters when those are set
+    pub fn count_unmatched(&self, s: &str) -> usize {
+        s.chars().filter(|c| !self.is_allowed(*c)).count()
+    }
+
+    pub(crate) fn is_allowed(&self, c: char) -> bool {
+        match &self.allowed {
+            Some(allowed) => allowed.contains(c),
+            None => self.choices().any(|choice| choice.chars.contains(c)),
+        }
+    }
+
+    pub(crate) fn check(&self) -> Result<bool, GenerateError> {
+        let length = self.resolve_length()?;
+        let intervals: Vec<_> = self
+            .drawn_choices()
+            .iter()
+            .map(|choice| choice.intervals())
+            .collect();
+        Ok(reaches(
+            &reachable_totals(&intervals, length),
+            length,
+            &Interval::exactly(0),
+        ))
+    }
+
+    // why the choices can't add up to the length, the length of the random part is the one
+    // compared, `Infeasible` when it's between what they need and allow but falls in a gap
+    fn infeasibility(&self) -> Result<GenerateError, GenerateError> {
+        let length = self.resolve_length()?;
+        let choices = self.drawn_choices();
+        let required = choices
+            .iter()
+            .fold(0usize, |sum, choice| sum.saturating_add(choice.min));
+        let allowed = choices
+            .iter()
+            .fold(0usize, |sum, choice| sum.saturating_add(choice.max));
+        Ok(if length < required {
+            GenerateError::LengthBelowMinimum { required, length }
+        } else if length > allowed {
+            GenerateError::LengthAboveMaximum { allowed, length }
+        } else {
+            GenerateError::Infeasible
+        })
+    }
+
+    // the lengths in the range the spec could be given with `length` and still generate, worked out
+    // from the intervals without generating anything, so it's cheap enough for a UI to call on
+    // every change
+    // unions of intervals can leave gaps, e.g. `0,4|a//0,4|b` can only make 0, 4 or 8 characters
+    // affixes counted in the length are measured in the spec's unit, as `resolve_length` does
+    pub fn feasible_lengths(&self, range: RangeInclusive<usize>) -> Vec<usize> {
+        let reachable = self.reachable_lengths(*range.end());
+        let affixes = if self.affixes_in_length {
+            self.unit().measure(&self.prefix) + self.unit().measure(&self.suffix)
+        } else {
+            0
+        };
+        range
+            .filter(|length| match length.checked_sub(affixes) {
+                Some(core) => {
+                    reachable
+                        .iter()
+                        .any(|reach| (reach.min..=reach.max).contains(&core))
+                        && self.check_adjacency(core).is_ok()
+                        && (self.position_rules.is_empty() || self.position_sets(core).is_ok())
+                }
+                None => false,
+            })
+            .collect()
+    }
+
+    // the lengths of the random part up to `cap` the choices can add up to, as disjoint ranges in
+    // order
+    pub(crate) fn reachable_lengths(&self, cap: usize) -> Vec<Interval> {
+        let intervals: Vec<_> = self
+            .drawn_choices()
+            .iter()
+            .map(|choice| choice.intervals())
+            .collect();
+        reachable_totals(&intervals, cap)
+    }
+
+    // the spec's choices plus, when there are allowed characters outside all of their charsets, a
+    // choice of any number of those, it fills up the length like the other choices but isn't
+    // counted or shown as one
+    pub(crate) fn drawn_choices(&self) -> Vec<Choice> {
+        let mut choices: Vec<Choice> = self.choices().cloned().collect();
+        if let Some(allowed) = &self.allowed {
+            let rest: Vec<char> = allowed
+                .to_charset()
+                .iter()
+                .copied()
+                .filter(|c| !self.choices().any(|choice| choice.chars.contains(*c)))
+                .collect();
+            if !rest.is_empty() {
+                choices.push(Charset::Custom(rest).at_least(0));
+            }
+        }
+        choices
+    }
+
+    // charsets the spec leaves out with an interval of exactly 0
+    pub fn excluded(&self) -> Vec<&Charset> {
+        self.choices()
+            .filter(|choice| choice.excluded())
+            .map(|choice| &choice.chars)
+            .collect()
+    }
+
+    // the characters a choice is drawn from, its charset without any excluded characters
+    // characters it shares with another choice that has a maximum are left to that choice, so
+    // drawing them here can't take its count over, unless that leaves nothing to draw
+    pub(crate) fn pool(&self, choice: &Choice) -> Vec<char> {
+        if choice.excluded() {
+            return vec![];
+        }
+        let excluded = self.excluded();
+        let pool: Vec<char> = choice
+            .chars
+            .to_charset()
+            .iter()
+            .copied()
+            .filter(|c| !excluded.iter().any(|charset| charset.contains(*c)))
+            .filter(|c| {
+                self.allowed
+                    .as_ref()
+                    .map_or(true, |allowed| allowed.contains(*c))
+            })
+            .collect();
+        let capped: Vec<&Charset> = self
+            .choices()
+            .filter(|other| other.chars != choice.chars && other.max != usize::MAX)
+            .map(|other| &other.chars)
+            .collect();
+        let left: Vec<char> = pool
+            .iter()
+            .copied()
+            .filter(|c| !capped.iter().any(|charset| charset.contains(*c)))
+            .collect();
+        if left.is_empty() {
+            pool
+        } else {
+            left
+        }
+    }
+
+    // a line for the length, each choice and the affixes, in plain words, e.g.
+    // `at least 1 from :upper: (26 characters, 4.7 bits each)` or `excluded :symbol:`
+    pub fn describe(&self) -> String {
+        let mut lines = vec![match self.length {
+            Length::Chars(n) if self.affixes_in_length => {
+                format!("{} {} including the prefix and suffix", n, self.unit)
+            }
+            Length::Chars(n) => format!("{} {}", n, self.unit),
+            Length::Bits(bits) => match self.resolve_length() {
+                Ok(n) => format!("{} characters for at least {} bits", n, bits),
+                Err(_) => format!(
+                    "enough characters for {} bits, which can't be reached",
+                    bits
+                ),
+            },
+        }];
+        for choice in self.choices() {
+            if choice.excluded() {
+                lines.push(format!("excluded {}", choice.name()));
+                continue;
+            }
+            let counts = choice
+                .intervals()
+                .iter()
+                .map(|interval| match (interval.min, interval.max) {
+                    (min, max) if min == max => format!("exactly {}", min),
+                    (min, usize::MAX) => format!("at least {}", min),
+                    (0, max) => format!("at most {}", max),
+                    (min, max) => format!("{} to {}", min, max),
+                })
+                .collect::<Vec<_>>()
+                .join(" or ");
+            let pool = self.pool(choice);
+            lines.push(format!(
+                "{} from {} ({} characters, {:.1} bits each)",
+                counts,
+                choice.name(),
+                pool.len(),
+                choice.entropy_from(&pool)
+            ));
+        }
+        if let Some(allowed) = &self.allowed {
+            lines.push(format!("only characters from {}", allowed));
+        }
+        if let Some(max) = self.max_bytes {
+            lines.push(format!("at most {} bytes in all", max));
+        }
+        if let Some(total) = &self.total_length {
+            lines.push(format!(
+                "{} {} in all with the decorations",
+                length_policy::bounds(total),
+                self.unit()
+            ));
+        }
+        if let Some(bits) = self.required_entropy() {
+            lines.push(format!("at least {:.1} bits of entropy guaranteed", bits));
+        }
+        if let Some(composition) = self.unused_ranges() {
+            let counts: Vec<String> = composition
+                .iter()
+                .map(|(choice, n)| format!("{} from {}", n, choice.name()))
+                .collect();
+            lines.push(format!(
+                "the length leaves no room, always {}",
+                counts.join(", ")
+            ));
+        }
+        if !self.prefix.is_empty() {
+            lines.push(format!("prefix `{}`", self.prefix));
+        }
+        if !self.suffix.is_empty() {
+            lines.push(format!("suffix `{}`", self.suffix));
+        }
+        lines.join("\n")
+    }
+
+    // warnings about the spec being weaker than it looks, with the default thresholds of `Lint`
+    pub fn lint(&self) -> Vec<LintWarning> {
+        Lint::new().check(self)
+    }
+
+    // the length of the random core of a password, for a length given in bits this is the smallest
+    // length whose guaranteed entropy reaches the target
+    //
+    // affixes add no entropy, so they only matter here when the length includes them
+    //
+    // the guaranteed entropy of a length assumes the worst case composition: the minimum of every
+    // choice and then the remaining characters from the lowest entropy choices that can still
+    // take them
+    pub fn resolve_length(&self) -> Result<usize, GenerateError> {
+        let bits = match self.length {
+            Length::Chars(length) if self.affixes_in_length => {
+                let affixes = self.unit().measure(&self.prefix) + self.unit().measure(&self.suffix);
+                return length
+                    .checked_sub(affixes)
+                    .ok_or(GenerateError::AffixesTooLong { affixes, length });
+            }
+            Length::Chars(length) => return Ok(length),
+            Length::Bits(bits) => bits as f64,
+        };
+        // tolerance so exact multiples of a charset's entropy don't round up a character
+        let epsilon = 1e-9;
+        let mut length: usize = 0;
+        let mut entropy = 0.0;
+        let mut pools = vec![];
+        for choice in &self.drawn_choices() {
+            let h = choice.entropy_from(&self.pool(choice));
+            length = length.saturating_add(choice.min);
+            entropy += choice.min as f64 * h;
+            pools.push((
+                h,
+                choice.max.saturating_sub(choice.min),
+                choice.max == usize::MAX,
+            ));
+        }
+        pools.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for (h, capacity, unbounded) in pools {
+            if entropy + epsilon >= bits {
+                return Ok(length);
+            }
+            if h > 0.0 {
+                let needed = ((bits - entropy) / h - epsilon).ceil() as usize;
+                if unbounded || needed <= capacity {
+                    return length.checked_add(needed).ok_or(GenerateError::Infeasible);
+                }
+            } else if unbounded {
+                // more characters from this choice never guarantee more entropy
+                break;
+            }
+            length = length.saturating_add(capacity);
+            entropy += capacity as f64 * h;
+        }
+        if entropy + epsilon >= bits {
+            Ok(length)
+        } else {
+            Err(GenerateError::EntropyUnreachable {
+                bits: bits as usize,
+                achievable: entropy,
+            })
+        }
+    }
+
+    // bits of entropy guaranteed by the spec, every password drawn has at least this much
+    // the worst case composition is the same as for `resolve_length`
+    pub fn entropy(&self) -> Result<f64, GenerateError> {
+        let length = self.resolve_length()?;
+        let mut remaining = length;
+        let mut entropy = 0.0;
+        let mut pools = vec![];
+        for choice in &self.drawn_choices() {
+            let h = choice.entropy_from(&self.pool(choice));
+            let min = choice.min.min(remaining);
+            remaining -= min;
+            entropy += min as f64 * h;
+            pools.push((h, choice.max.saturating_sub(choice.min)));
+        }
+        pools.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for (h, capacity) in pools {
+            let taken = capacity.min(remaining);
+            remaining -= taken;
+            entropy += taken as f64 * h;
+        }
+        Ok(entropy)
+    }
+
+    // how `entropy` comes about, a line per choice in the order of `choices` with the characters
+    // it gives in the worst case composition, then the characters only `allowed` adds, if any,
+    // without an index
+    pub fn entropy_report(&self) -> Result<Vec<ChoiceEntropy>, GenerateError> {
+        let mut remaining = self.resolve_length()?;
+        let choices = self.drawn_choices();
+        let listed = self.choices.choices.len();
+        let mut report = vec![];
+        for (index, choice) in choices.iter().enumerate() {
+            let pool = self.pool(choice);
+            let count = choice.min.min(remaining);
+            remaining -= count;
+            report.push(ChoiceEntropy {
+                index: Some(index).filter(|index| *index < listed),
+                name: choice.name(),
+                characters: pool.len(),
+                bits_each: choice.entropy_from(&pool),
+                count,
+            });
+        }
+        let mut order: Vec<usize> = (0..report.len()).collect();
+        order.sort_by(|a, b| report[*a].bits_each.total_cmp(&report[*b].bits_each));
+        for i in order {
+            let capacity = choices[i].max.saturating_sub(choices[i].min);
+            let taken = capacity.min(remaining);
+            remaining -= taken;
+            report[i].count += taken;
+        }
+        Ok(report)
+    }
+
+    // copy of the spec with the length resolved, e.g. for displaying the length actually used
+    pub fn resolved(&self) -> Result<PasswordSpec, GenerateError> {
+        let mut spec = self.clone().length(self.resolve_length()?);
+        spec.affixes_in_length = false;
+        Ok(spec)
     }
 
     pub fn length(mut self, length: usize) -> Self {
-        self.length = length;
+        self.length = Length::Chars(length);
         self
     }
 
-    pub fn include(mut self, choice: Choice) -> Self {
+    // length chosen to give at least this many bits of entropy, see `resolve_length`
+    pub fn length_bits(mut self, bits: usize) -> Self {
+        self.length = Length::Bits(bits);
+        self
+    }
+
+    // refuse to generate when the spec guarantees fewer bits of entropy than this, the guaranteed
+    // entropy being `entropy`, what every password has rather than the average `entropy_bits`
+    // unlike `length_bits` the length stays as it is, a spec too weak for it is an error
+    // NaN sets no minimum
+    pub fn min_entropy(mut self, bits: f64) -> Self {
+        self.min_entropy = Some(bits).filter(|bits| !bits.is_nan()).map(f64::to_bits);
+        self
+    }
+
+    pub fn required_entropy(&self) -> Option<f64> {
+        self.min_entropy.map(f64::from_bits)
+    }
+
+    // `EntropyBelowMinimum` when the spec doesn't guarantee its minimum entropy
+    pub(crate) fn check_min_entropy(&self) -> Result<(), GenerateError> {
+        let required = match self.required_entropy() {
+            Some(required) => required,
+            None => return Ok(()),
+        };
+        let achieved = self.entropy()?;
+        // the same allowance for rounding as `resolve_length`, 8 digits are 8 * log2(10) bits
+        if achieved + 1e-9 >= required {
+            Ok(())
+        } else {
+            Err(GenerateError::EntropyBelowMinimum { required, achieved })
+        }
+    }
+
+    // fixed text put in front of every password
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = self.normalization.apply(&prefix.into()).into_owned();
+        self
+    }
+
+    // fixed text put after every password
+    pub fn suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = self.normalization.apply(&suffix.into()).into_owned();
+        self
+    }
+
+    // by default the length is the length of the random core and affixes come on top, with this
+    // the affixes are taken out of the length instead, only affects lengths given in characters
+    pub fn length_includes_affixes(mut self, includes: bool) -> Self {
+        self.affixes_in_length = includes;
+        self
+    }
+
+    // in charset order, the named charsets as `Charset` lists them and then custom ones by their
+    // characters, which doesn't depend on how the spec was built
+    // everything that lists choices uses this order: the spec string, `describe`, the JSON form
+    // and its `index`, `entropy_report`, `violations` and `choice`
+    pub fn choices(&self) -> impl Iterator<Item = &Choice> {
+        self.choices.sorted().into_iter()
+    }
+
+    // the choice at this position of `choices`, counting from 0
+    pub fn choice(&self, index: usize) -> Option<&Choice> {
+        self.choices().nth(index)
+    }
+
+    // the characters a password may use at all, the choices then only say how many have to come
+    // from each of their charsets
+    pub fn allowed(mut self, charset: Charset) -> Self {
+        self.allowed = Some(charset.normalized(self.normalization));
+        self
+    }
+
+    // how the characters beyond the minimums are spread over the choices, see `FillMode`
+    pub fn fill_mode(mut self, mode: FillMode) -> Self {
+        self.fill = mode;
+        self
+    }
+
+    // how the counts of the choices are drawn, see `SamplingMode`
+    pub fn sampling_mode(mut self, mode: SamplingMode) -> Self {
+        self.sampling = mode;
+        self
+    }
+
+    // a choice with an empty charset is only caught when generating, `try_include` refuses it
+    pub fn include(mut self, mut choice: Choice) -> Self {
+        choice.chars = choice.chars.normalized(self.normalization);
         self.choices.push(choice);
         self
     }
 
+    // `EmptyCharset` when the choice has no characters, which the parsers go through
+    pub fn try_include(self, choice: Choice) -> Result<Self, GenerateError> {
+        if choice.chars.is_empty() {
+            Err(GenerateError::EmptyCharset(choice.chars))
+        } else {
+            Ok(self.include(choice))
+        }
+    }
+
+    // drop the charset from the spec entirely, unlike an interval of exactly 0 which keeps it
+    // listed as excluded
+    // normalized as `include` does, and a custom charset matches whatever the order of its
+    // characters or repeats in them
+    pub fn remove(mut self, chars: Charset) -> Self {
+        let chars = chars.normalized(self.normalization);
+        match chars.custom_set() {
+            Some(set) => self
+                .choices
+                .choices
+                .retain(|charset, _| charset.custom_set().as_ref() != Some(&set)),
+            None => {
+                self.choices.remove(&chars);
+            }
+        }
+        self
+    }
+
     pub fn upper(mut self, interval: Interval) -> Self {
         self.choices
             .push(Choice::from_interval(interval, Charset::Upper));
@@ -246,22 +1267,129 @@ impl PasswordSpec {
         self
     }
 
-    pub fn custom(mut self, chars: Vec<char>, interval: Interval) -> Self {
-        self.choices
-            .push(Choice::from_interval(interval, Charset::Custom(chars)));
+    pub fn custom(self, chars: Vec<char>, interval: Interval) -> Self {
+        self.include(Choice::from_interval(interval, Charset::Custom(chars)))
+    }
+    // `EmptyCharset` when there are no characters, the other `custom` builders leave that to
+    // generating
+    pub fn try_custom(self, chars: Vec<char>, interval: Interval) -> Result<Self, GenerateError> {
+        self.try_include(Choice::from_interval(interval, Charset::Custom(chars)))
+    }
+    pub fn custom_at_least(self, chars: Vec<char>, size: usize) -> Self {
+        self.include(Charset::Custom(chars).at_least(size))
+    }
+    pub fn custom_at_most(self, chars: Vec<char>, size: usize) -> Self {
+        self.include(Charset::Custom(chars).at_most(size))
+    }
+    pub fn custom_exactly(self, chars: Vec<char>, size: usize) -> Self {
+        self.include(Charset::Custom(chars).exactly(size))
+    }
+}
 
-        self
+// the counts drawn all at once so every string they allow is as likely, see `SamplingMode`
+fn draw_uniform<R: Rng + ?Sized>(
+    drawable: &[(Choice, Vec<char>)],
+    length: usize,
+    rng: &mut R,
+) -> Result<Vec<(char, Charset)>, GenerateError> {
+    let completions = Completions::new(drawable, length).ok_or(GenerateError::UniformTooLong {
+        length,
+        max: MAX_UNIFORM_LENGTH,
+    })?;
+    let counts = completions.draw(rng).ok_or(GenerateError::Infeasible)?;
+    let mut characters = vec![];
+    for ((choice, pool), count) in drawable.iter().zip(counts) {
+        let mut sampler = ChoiceSampler::new(choice, Interval::exactly(count), pool.clone());
+        characters.extend(
+            sampler
+                .get_required(rng)?
+                .into_iter()
+                .map(|c| (c, choice.chars.clone())),
+        );
     }
-    pub fn custom_at_least(mut self, chars: Vec<char>, size: usize) -> Self {
-        self.choices.push(Charset::Custom(chars).at_least(size));
-        self
+    characters.shuffle(rng);
+    Ok(characters)
+}
+
+// one interval for each choice, picked uniformly among the ones that still leave the length
+// reachable by the choices after it
+fn pick_intervals<R: Rng + ?Sized>(
+    drawable: &[(Choice, Vec<char>)],
+    length: usize,
+    rng: &mut R,
+) -> Option<Vec<Interval>> {
+    let intervals: Vec<_> = drawable
+        .iter()
+        .map(|(choice, _)| choice.intervals())
+        .collect();
+    let after = reachable_suffixes(&intervals, length);
+    let mut picked = vec![];
+    let mut total = Interval::exactly(0);
+    for (i, options) in intervals.iter().enumerate() {
+        let interval = options
+            .iter()
+            .filter(|interval| reaches(&after[i + 1], length, &total.add(interval)))
+            .choose(rng)?
+            .clone();
+        total = total.add(&interval);
+        picked.push(interval);
     }
-    pub fn custom_at_most(mut self, chars: Vec<char>, size: usize) -> Self {
-        self.choices.push(Charset::Custom(chars).at_most(size));
-        self
+    Some(picked)
+}
+
+// the totals up to `cap` that picking one interval from each list and a count in each can add up
+// to, as disjoint ranges in order, built list by list so the work stays bounded by `cap` instead
+// of growing with every combination of intervals
+pub(crate) fn reachable_totals(intervals: &[Vec<Interval>], cap: usize) -> Vec<Interval> {
+    let mut reachable = vec![Interval::exactly(0)];
+    for options in intervals {
+        reachable = add_options(&reachable, options, cap);
     }
-    pub fn custom_exactly(mut self, chars: Vec<char>, size: usize) -> Self {
-        self.choices.push(Charset::Custom(chars).exactly(size));
-        self
+    reachable
+}
+
+// `reachable_totals` of every suffix of `intervals`, the one at `i` for the lists from `i` on, so
+// the last is for none of them
+pub(crate) fn reachable_suffixes(intervals: &[Vec<Interval>], cap: usize) -> Vec<Vec<Interval>> {
+    let mut suffixes = vec![vec![Interval::exactly(0)]];
+    for options in intervals.iter().rev() {
+        let next = add_options(suffixes.last().unwrap(), options, cap);
+        suffixes.push(next);
+    }
+    suffixes.reverse();
+    suffixes
+}
+
+fn add_options(reachable: &[Interval], options: &[Interval], cap: usize) -> Vec<Interval> {
+    let mut sums: Vec<Interval> = reachable
+        .iter()
+        .flat_map(|total| options.iter().map(move |interval| total.add(interval)))
+        .filter(|sum| sum.min <= cap)
+        .map(|sum| Interval {
+            min: sum.min,
+            max: sum.max.min(cap),
+        })
+        .collect();
+    sums.sort_unstable_by_key(|sum| (sum.min, sum.max));
+    let mut merged: Vec<Interval> = vec![];
+    for sum in sums {
+        match merged.last_mut() {
+            Some(last) if sum.min <= last.max.saturating_add(1) => last.max = last.max.max(sum.max),
+            _ => merged.push(sum),
+        }
+    }
+    merged
+}
+
+// whether adding `total`, the sum of the intervals picked so far, to one of the `reachable` totals
+// of the rest can make `length`
+pub(crate) fn reaches(reachable: &[Interval], length: usize, total: &Interval) -> bool {
+    if total.min > length {
+        return false;
     }
+    let low = length.saturating_sub(total.max);
+    let high = length - total.min;
+    reachable
+        .iter()
+        .any(|reach| reach.min <= high && low <= reach.max)
 }
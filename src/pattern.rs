@@ -0,0 +1,206 @@
+use rand::{thread_rng, Rng};
+use thiserror::Error;
+
+use crate::{charset::Charset, sampling};
+
+// most a `{n}` can repeat, far beyond any password and small enough that a typo can't exhaust
+// memory
+pub const MAX_REPEAT: usize = 1024;
+
+// A password described position by position, each position is drawn from its own charset.
+// Literal characters are single character custom charsets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternSpec {
+    positions: Vec<Charset>,
+}
+
+#[derive(Debug, Error)]
+pub enum KeePassParseError {
+    #[error("`{0}` isn't a KeePass placeholder")]
+    UnknownPlaceholder(char),
+    #[error("KeePass patterns using {0} aren't supported")]
+    Unsupported(String),
+    #[error("Custom character class starting at position {0} is never closed with `]`")]
+    UnclosedClass(usize),
+    #[error("Custom character class starting at position {0} is empty")]
+    EmptyClass(usize),
+    #[error("Repeat count `{{{0}}}` isn't a number")]
+    BadRepeat(String),
+    #[error("Repeat count {0} is more than the {MAX_REPEAT} allowed")]
+    RepeatTooLarge(usize),
+    #[error("Repeat count at position {0} isn't closed with `}}`")]
+    UnclosedRepeat(usize),
+    #[error("Repeat count at position {0} doesn't follow anything to repeat")]
+    NothingToRepeat(usize),
+    #[error("Pattern ends with an escape `\\` that has nothing to escape")]
+    DanglingEscape,
+}
+
+const SPECIAL: &str = "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+
+fn placeholder(c: char) -> Option<Vec<char>> {
+    let lower = || ('a'..='z').collect::<Vec<_>>();
+    let upper = || ('A'..='Z').collect::<Vec<_>>();
+    let digits = || ('0'..='9').collect::<Vec<_>>();
+    let consonants = || "bcdfghjklmnpqrstvwxyz".chars().collect::<Vec<_>>();
+    let upper_consonants = || "BCDFGHJKLMNPQRSTVWXYZ".chars().collect::<Vec<_>>();
+    let chars = match c {
+        'd' => digits(),
+        'l' => lower(),
+        'u' => upper(),
+        'L' => [lower(), upper()].concat(),
+        'a' => [lower(), digits()].concat(),
+        'U' => [upper(), digits()].concat(),
+        'A' => [lower(), upper(), digits()].concat(),
+        'h' => "0123456789abcdef".chars().collect(),
+        'H' => "0123456789ABCDEF".chars().collect(),
+        'v' => "aeiou".chars().collect(),
+        'Z' => "AEIOU".chars().collect(),
+        'V' => "aeiouAEIOU".chars().collect(),
+        'c' => consonants(),
+        'z' => upper_consonants(),
+        'C' => [consonants(), upper_consonants()].concat(),
+        'p' => ",.;:".chars().collect(),
+        'b' => "()[]{}<>".chars().collect(),
+        's' => SPECIAL.chars().collect(),
+        'S' => [upper(), lower(), digits(), SPECIAL.chars().collect()].concat(),
+        _ => return None,
+    };
+    Some(chars)
+}
+
+// characters that KeePass doesn't treat as placeholders are literals, but unknown ASCII letters
+// are rejected since they're more likely a typo or a placeholder from a newer KeePass version,
+// placeholders are only ever ASCII letters so digits and other letters are literals
+fn literal_or_placeholder(c: char) -> Result<Vec<char>, KeePassParseError> {
+    if c == 'x' {
+        Err(KeePassParseError::Unsupported(
+            "the high ANSI placeholder `x`".to_string(),
+        ))
+    } else if let Some(chars) = placeholder(c) {
+        Ok(chars)
+    } else if c.is_ascii_alphabetic() {
+        Err(KeePassParseError::UnknownPlaceholder(c))
+    } else {
+        Ok(vec![c])
+    }
+}
+
+impl PatternSpec {
+    // `None` when a position has no characters to draw from
+    pub fn new(positions: Vec<Charset>) -> Option<Self> {
+        if positions.iter().any(Charset::is_empty) {
+            None
+        } else {
+            Some(Self { positions })
+        }
+    }
+
+    pub fn positions(&self) -> &[Charset] {
+        &self.positions
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    // KeePass' pattern syntax, e.g. `uullA{6}d{2}` or `HHHH-HHHH`
+    // supports the placeholders d l u L a U A h H v V Z c C z p b s S, `\` escapes, `{n}`
+    // repetition of the previous element and `[...]` custom classes made of placeholders and
+    // literals
+    pub fn from_keepass(pattern: &str) -> Result<Self, KeePassParseError> {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut positions: Vec<Charset> = vec![];
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                '\\' => {
+                    let escaped = chars.get(i + 1).ok_or(KeePassParseError::DanglingEscape)?;
+                    positions.push(Charset::Custom(vec![*escaped]));
+                    i += 2;
+                }
+                '[' => {
+                    let start = i;
+                    let mut class: Vec<char> = vec![];
+                    i += 1;
+                    loop {
+                        match chars.get(i) {
+                            None => return Err(KeePassParseError::UnclosedClass(start)),
+                            Some(']') => break,
+                            Some('\\') => {
+                                let escaped =
+                                    chars.get(i + 1).ok_or(KeePassParseError::DanglingEscape)?;
+                                class.push(*escaped);
+                                i += 2;
+                            }
+                            Some('^') => {
+                                return Err(KeePassParseError::Unsupported(
+                                    "exclusions with `^` in a custom class".to_string(),
+                                ))
+                            }
+                            Some(c) => {
+                                class.extend(literal_or_placeholder(*c)?);
+                                i += 1;
+                            }
+                        }
+                    }
+                    let mut deduped = vec![];
+                    for c in class {
+                        if !deduped.contains(&c) {
+                            deduped.push(c);
+                        }
+                    }
+                    if deduped.is_empty() {
+                        return Err(KeePassParseError::EmptyClass(start));
+                    }
+                    positions.push(Charset::Custom(deduped));
+                    i += 1;
+                }
+                '{' => {
+                    let start = i;
+                    let end = chars[i..]
+                        .iter()
+                        .position(|c| *c == '}')
+                        .map(|p| p + i)
+                        .ok_or(KeePassParseError::UnclosedRepeat(start))?;
+                    let count: String = chars[i + 1..end].iter().collect();
+                    let count: usize = count
+                        .parse()
+                        .map_err(|_| KeePassParseError::BadRepeat(count.clone()))?;
+                    if count > MAX_REPEAT {
+                        return Err(KeePassParseError::RepeatTooLarge(count));
+                    }
+                    let last = positions
+                        .pop()
+                        .ok_or(KeePassParseError::NothingToRepeat(start))?;
+                    positions.extend(std::iter::repeat(last).take(count));
+                    i = end + 1;
+                }
+                c => {
+                    positions.push(Charset::Custom(literal_or_placeholder(c)?));
+                    i += 1;
+                }
+            }
+        }
+        Ok(Self { positions })
+    }
+
+    pub fn generate(&self) -> String {
+        self.generate_with_rng(&mut thread_rng())
+    }
+
+    // every position has characters, `new` and `from_keepass` see to that
+    pub fn generate_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        self.positions
+            .iter()
+            .map(|charset| {
+                sampling::uniform(charset.to_charset(), rng).expect("positions aren't empty")
+            })
+            .collect()
+    }
+}
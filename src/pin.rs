@@ -0,0 +1,156 @@
+use rand::{thread_rng, Rng};
+
+// candidates drawn before giving up, only reachable when nearly every pin is excluded
+const MAX_ATTEMPTS: usize = 10_000;
+
+// A numeric code with the policies PINs usually come with. Candidates breaking a rule are
+// redrawn, so every allowed pin is equally likely.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinSpec {
+    pub digits: usize,
+    pub allow_leading_zero: bool,
+    // longest run of one repeated digit, e.g. 2 allows `1123` but not `1112`
+    pub max_repeat_run: usize,
+    // rejects pins where every digit is one more (or one less) than the previous, like `1234`
+    // or `9876`, only applies to pins of three or more digits
+    pub forbid_sequential: bool,
+}
+
+impl Default for PinSpec {
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+fn is_sequential(pin: &[u8]) -> bool {
+    pin.len() >= 3
+        && (pin.windows(2).all(|w| w[1] == w[0] + 1) || pin.windows(2).all(|w| w[0] == w[1] + 1))
+}
+
+impl PinSpec {
+    // no restrictions beyond the number of digits
+    pub fn new(digits: usize) -> Self {
+        Self {
+            digits,
+            allow_leading_zero: true,
+            max_repeat_run: usize::MAX,
+            forbid_sequential: false,
+        }
+    }
+
+    fn allowed_digits(&self, pin: &[u8]) -> bool {
+        if pin.len() != self.digits {
+            return false;
+        }
+        if !self.allow_leading_zero && pin.first() == Some(&0) {
+            return false;
+        }
+        let mut run = 0;
+        for (i, d) in pin.iter().enumerate() {
+            run = if i > 0 && pin[i - 1] == *d {
+                run + 1
+            } else {
+                1
+            };
+            if run > self.max_repeat_run {
+                return false;
+            }
+        }
+        !(self.forbid_sequential && is_sequential(pin))
+    }
+
+    pub fn allowed(&self, pin: &str) -> bool {
+        let digits: Option<Vec<u8>> = pin
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as u8))
+            .collect();
+        digits.map_or(false, |digits| self.allowed_digits(&digits))
+    }
+
+    // number of pins the rules allow, saturating at `u128::MAX` which pins of 39 or more digits
+    // can pass
+    pub fn count(&self) -> u128 {
+        self.tally(1, u128::saturating_add, u128::saturating_sub)
+    }
+
+    // bits of entropy of a uniformly chosen allowed pin, 0 when no pin is allowed
+    // worked out in floating point so it keeps growing past where `count` saturates
+    pub fn entropy(&self) -> f64 {
+        let count = self.tally(1.0, |a: f64, b| a + b, |a, b| a - b);
+        if count > 0.0 {
+            count.log2()
+        } else {
+            0.0
+        }
+    }
+
+    // counts runs of repeated digits position by position and then takes out the sequential pins
+    fn tally<T: Copy + Default>(
+        &self,
+        one: T,
+        add: impl Fn(T, T) -> T,
+        sub: impl Fn(T, T) -> T,
+    ) -> T {
+        let zero = T::default();
+        if self.digits == 0 {
+            return one;
+        }
+        let max_run = self.max_repeat_run.min(self.digits);
+        if max_run == 0 {
+            return zero;
+        }
+        let sum = |counts: &[T]| counts.iter().fold(zero, |sum, count| add(sum, *count));
+        // counts[d][r]: pins so far ending in digit d repeated r + 1 times
+        let mut counts = vec![vec![zero; max_run]; 10];
+        for (d, count) in counts.iter_mut().enumerate() {
+            if d != 0 || self.allow_leading_zero {
+                count[0] = one;
+            }
+        }
+        for _ in 1..self.digits {
+            let total = sum(&counts.concat());
+            let mut next = vec![vec![zero; max_run]; 10];
+            for d in 0..10 {
+                next[d][0] = sub(total, sum(&counts[d]));
+                for r in 1..max_run {
+                    next[d][r] = counts[d][r - 1];
+                }
+            }
+            counts = next;
+        }
+        let mut total = sum(&counts.concat());
+        if self.forbid_sequential && self.digits >= 3 && self.digits <= 10 {
+            let unrestricted = Self {
+                forbid_sequential: false,
+                ..self.clone()
+            };
+            for start in 0..=(10 - self.digits) as u8 {
+                let up: Vec<u8> = (0..self.digits as u8).map(|i| start + i).collect();
+                let down: Vec<u8> = up.iter().rev().copied().collect();
+                for pin in [up, down] {
+                    if unrestricted.allowed_digits(&pin) {
+                        total = sub(total, one);
+                    }
+                }
+            }
+        }
+        total
+    }
+
+    pub fn generate(&self) -> Option<String> {
+        self.generate_with_rng(&mut thread_rng())
+    }
+
+    pub fn generate_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<String> {
+        if self.count() == 0 {
+            return None;
+        }
+        for _ in 0..MAX_ATTEMPTS {
+            let pin: Vec<u8> = (0..self.digits).map(|_| rng.gen_range(0..10)).collect();
+            if self.allowed_digits(&pin) {
+                return Some(pin.iter().map(|d| char::from(b'0' + d)).collect());
+            }
+        }
+        None
+    }
+}
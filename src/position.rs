@@ -0,0 +1,206 @@
+// Rules pinning what can go at particular positions of the random part, on top of the counts of
+// the spec, e.g. the first character has to be a letter or the last can't be a symbol.
+//
+// Positions count from 0 at the start of the random part, negative ones count back from its end
+// so -1 is the last character. The affixes aren't covered, they're fixed anyway.
+//
+// Characters are drawn for the counts as usual and then placed so the rules hold, with the
+// unconstrained positions shuffled as before. Only when the drawn characters can't be placed is
+// a new set drawn.
+use crate::{
+    charset::Charset,
+    password::{GenerateError, PasswordSpec},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PositionRule {
+    MustBe(Charset),
+    MustNotBe(Charset),
+}
+
+impl PositionRule {
+    pub fn allows(&self, c: char) -> bool {
+        match self {
+            PositionRule::MustBe(charset) => charset.contains(c),
+            PositionRule::MustNotBe(charset) => !charset.contains(c),
+        }
+    }
+}
+
+// the position an index refers to in a random part of `length` characters
+fn resolve(index: isize, length: usize) -> Option<usize> {
+    if index < 0 {
+        length.checked_sub(index.unsigned_abs())
+    } else {
+        Some(index as usize).filter(|i| *i < length)
+    }
+}
+
+impl PasswordSpec {
+    pub fn position_rule(self, index: isize, rule: PositionRule) -> Self {
+        self.position_range_rule(index, index, rule)
+    }
+
+    // the rule for every position from `start` to `end` inclusive, e.g. `1, -2` for all but the
+    // first and last
+    // the rules are kept sorted so specs with the same rules are equal whatever order they were
+    // given in
+    pub fn position_range_rule(mut self, start: isize, end: isize, rule: PositionRule) -> Self {
+        let rule = (start, end, rule.normalized(self.normalization));
+        if let Err(i) = self.position_rules.binary_search(&rule) {
+            self.position_rules.insert(i, rule);
+        }
+        self
+    }
+
+    // for each position the characters its rules allow, `None` when it has no rules
+    pub(crate) fn position_sets(
+        &self,
+        length: usize,
+    ) -> Result<Vec<Option<Vec<char>>>, GenerateError> {
+        let mut sets: Vec<Option<Vec<char>>> = vec![None; length];
+        if self.position_rules.is_empty() {
+            return Ok(sets);
+        }
+        let choices = self.drawn_choices();
+        let universe: Vec<char> = choices
+            .iter()
+            .flat_map(|choice| self.pool(choice))
+            .collect();
+        for (start, end, rule) in &self.position_rules {
+            let out_of_range = |index: isize| GenerateError::PositionOutOfRange { index, length };
+            let start = resolve(*start, length).ok_or(out_of_range(*start))?;
+            let end = resolve(*end, length).ok_or(out_of_range(*end))?;
+            for set in sets.iter_mut().take(end + 1).skip(start) {
+                let allowed = set.get_or_insert_with(|| universe.clone());
+                allowed.retain(|c| rule.allows(*c));
+            }
+        }
+        for (position, set) in sets.iter().enumerate() {
+            if let Some(set) = set {
+                if set.is_empty() {
+                    return Err(GenerateError::PositionConflict(position));
+                }
+                // the most characters fitting here the choices could ever draw, when fewer than
+                // the positions needing them there's no point drawing
+                let supply = choices
+                    .iter()
+                    .filter(|choice| self.pool(choice).iter().any(|c| set.contains(c)))
+                    .fold(0usize, |sum, choice| sum.saturating_add(choice.max));
+                let demand = sets
+                    .iter()
+                    .flatten()
+                    .filter(|other| other.iter().all(|c| set.contains(c)))
+                    .count();
+                if demand > supply {
+                    return Err(GenerateError::PositionConflict(position));
+                }
+            }
+        }
+        Ok(sets)
+    }
+
+    // the characters of the random part, with their positions, that a rule covering them doesn't
+    // allow, rules reaching outside the random part are left to the length check
+    pub(crate) fn position_rule_breaks(&self, core: &str) -> Vec<(usize, char)> {
+        let chars: Vec<char> = core.chars().collect();
+        chars
+            .iter()
+            .enumerate()
+            .filter(|(i, c)| {
+                self.position_rules.iter().any(|(start, end, rule)| {
+                    match (resolve(*start, chars.len()), resolve(*end, chars.len())) {
+                        (Some(start), Some(end)) => (start..=end).contains(i) && !rule.allows(**c),
+                        _ => false,
+                    }
+                })
+            })
+            .map(|(i, c)| (i, *c))
+            .collect()
+    }
+
+    // whether the random part follows the position rules
+    pub(crate) fn follows_position_rules(&self, core: &str) -> bool {
+        let chars: Vec<char> = core.chars().collect();
+        self.position_rules.iter().all(|(start, end, rule)| {
+            match (resolve(*start, chars.len()), resolve(*end, chars.len())) {
+                (Some(start), Some(end)) => chars
+                    .iter()
+                    .take(end + 1)
+                    .skip(start)
+                    .all(|c| rule.allows(*c)),
+                _ => false,
+            }
+        })
+    }
+}
+
+// match every constrained position to a different item it fits by augmenting paths, giving the
+// position each item went to, or the first position that couldn't get one
+pub(crate) fn assign(
+    constrained: &[usize],
+    items: usize,
+    fits: &dyn Fn(usize, usize) -> bool,
+) -> Result<Vec<Option<usize>>, usize> {
+    fn augment(
+        position: usize,
+        fits: &dyn Fn(usize, usize) -> bool,
+        owner: &mut Vec<Option<usize>>,
+        seen: &mut Vec<bool>,
+    ) -> bool {
+        for item in 0..owner.len() {
+            if seen[item] || !fits(position, item) {
+                continue;
+            }
+            seen[item] = true;
+            let free = match owner[item] {
+                None => true,
+                Some(other) => augment(other, fits, owner, seen),
+            };
+            if free {
+                owner[item] = Some(position);
+                return true;
+            }
+        }
+        false
+    }
+    let mut owner: Vec<Option<usize>> = vec![None; items];
+    for position in constrained {
+        let mut seen = vec![false; items];
+        if !augment(*position, fits, &mut owner, &mut seen) {
+            return Err(*position);
+        }
+    }
+    Ok(owner)
+}
+
+// place the shuffled characters so every constrained position gets one its set allows, the rest
+// keep their shuffled order, `None` if there's no such placement
+pub(crate) fn arrange<T: Clone>(
+    sets: &[Option<Vec<char>>],
+    drawn: Vec<(char, T)>,
+) -> Option<Vec<(char, T)>> {
+    let constrained: Vec<usize> = (0..sets.len()).filter(|p| sets[*p].is_some()).collect();
+    if constrained.is_empty() {
+        return Some(drawn);
+    }
+    let fits = |position: usize, item: usize| {
+        sets[position]
+            .as_ref()
+            .map_or(true, |set| set.contains(&drawn[item].0))
+    };
+    let owner = assign(&constrained, drawn.len(), &fits).ok()?;
+    let mut placed: Vec<Option<(char, T)>> = vec![None; sets.len()];
+    let mut rest = vec![];
+    for (item, entry) in drawn.into_iter().enumerate() {
+        match owner[item] {
+            Some(position) => placed[position] = Some(entry),
+            None => rest.push(entry),
+        }
+    }
+    let mut rest = rest.into_iter();
+    placed
+        .into_iter()
+        .map(|slot| slot.or_else(|| rest.next()))
+        .collect()
+}
@@ -0,0 +1,24 @@
+//! The types most uses need, so one import covers building a spec and generating from it.
+//!
+//! ```rust
+//! use pants_gen::prelude::*;
+//!
+//! let spec = PasswordSpec::new()
+//!     .length(12)
+//!     .include(Charset::Upper.at_least(1))
+//!     .include(Choice::from_interval(Interval::new(2, 4).unwrap(), Charset::Number))
+//!     .include(Charset::Lower.at_least(0));
+//! let password = spec.generate().unwrap();
+//! assert!(spec.matches(&password));
+//!
+//! let parsed: Result<PasswordSpec, PasswordParseError> = "8//1+|:upper:".parse();
+//! let spec: PasswordSpec = "8//9+|:upper:".parse()?;
+//! let error: GenerateError = spec.try_generate().unwrap_err();
+//! assert_eq!(error.code(), "length_below_minimum");
+//! # assert!(parsed.is_ok());
+//! # Ok::<(), PasswordParseError>(())
+//! ```
+pub use crate::charset::{Charset, CharsetParseError};
+pub use crate::choice::{Choice, ChoiceParseError};
+pub use crate::interval::{Interval, IntervalParseError};
+pub use crate::password::{GenerateError, PasswordParseError, PasswordSpec, VerifyError};
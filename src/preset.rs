@@ -0,0 +1,28 @@
+// Ready made specs for common kinds of secrets, the length can be overridden with
+// `PasswordSpec::length` afterwards.
+use crate::{charset::Charset, password::PasswordSpec, pin::PinSpec};
+
+// 44 base58 characters, around 258 bits, e.g. for keys and tokens
+pub fn base58_key() -> PasswordSpec {
+    PasswordSpec::new()
+        .length(44)
+        .include(Charset::Base58.at_least(0))
+}
+
+// 4 digit pin that isn't a single repeated digit or a sequence like `1234`, the digits and leading
+// zero rule are left for the caller to adjust
+pub fn pin() -> PinSpec {
+    PinSpec {
+        max_repeat_run: 3,
+        forbid_sequential: true,
+        ..PinSpec::new(4)
+    }
+}
+
+// look up a preset by the name used on the command line
+pub fn by_name(name: &str) -> Option<PasswordSpec> {
+    match name {
+        "base58-key" => Some(base58_key()),
+        _ => None,
+    }
+}
@@ -0,0 +1,168 @@
+// Translation of pwgen's command line flags into a `PasswordSpec` and pwgen's output layout, for
+// replacing pwgen in scripts.
+//
+// Only pwgen's fully random (`-s`) style is generated, pronounceable passwords aren't emulated, so
+// `-s` is accepted but doesn't change anything.
+use thiserror::Error;
+
+use crate::{charset::Charset, confusables::Confusables, password::PasswordSpec};
+
+// characters removed by `-B`, the same set pwgen uses
+pub const AMBIGUOUS: &str = "B8G6I1l0OQDS5Z2";
+// pwgen's layout width for columns
+const LINE_WIDTH: usize = 80;
+// rows printed when no count is given and output is in columns
+const DEFAULT_ROWS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PwgenOptions {
+    pub length: usize,
+    pub count: Option<usize>,
+    pub capitalize: bool,
+    pub numerals: bool,
+    pub symbols: bool,
+    pub no_ambiguous: bool,
+    pub secure: bool,
+    pub columns: bool,
+    // characters removed by `-B`, pwgen's ambiguous characters unless replaced
+    pub confusables: Confusables,
+}
+
+#[derive(Debug, Error)]
+pub enum PwgenError {
+    #[error("Unrecognized pwgen option `{0}`")]
+    UnknownOption(String),
+    #[error("Expected a number for the {0}, got `{1}`")]
+    BadNumber(&'static str, String),
+    #[error("Unexpected extra argument `{0}`, expect at most [length] [count]")]
+    ExtraArgument(String),
+}
+
+impl PwgenOptions {
+    // pwgen's defaults depend on whether stdout is a terminal: capitals, numerals and columns are
+    // only on by default for terminals
+    pub fn new(is_tty: bool) -> Self {
+        Self {
+            length: 8,
+            count: None,
+            capitalize: is_tty,
+            numerals: is_tty,
+            symbols: false,
+            no_ambiguous: false,
+            secure: false,
+            columns: is_tty,
+            confusables: Confusables::builtin(),
+        }
+    }
+
+    // `[options] [length] [count]` like pwgen, short flags can be combined (`-cny1`)
+    pub fn from_args<I, S>(args: I, is_tty: bool) -> Result<Self, PwgenError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut options = Self::new(is_tty);
+        let mut positional = vec![];
+        for arg in args {
+            let arg = arg.as_ref();
+            if let Some(long) = arg.strip_prefix("--") {
+                match long {
+                    "capitalize" => options.capitalize = true,
+                    "no-capitalize" => options.capitalize = false,
+                    "numerals" => options.numerals = true,
+                    "no-numerals" => options.numerals = false,
+                    "symbols" => options.symbols = true,
+                    "ambiguous" => options.no_ambiguous = true,
+                    "secure" => options.secure = true,
+                    _ => return Err(PwgenError::UnknownOption(arg.to_string())),
+                }
+            } else if let Some(short) = arg.strip_prefix('-').filter(|s| !s.is_empty()) {
+                for flag in short.chars() {
+                    match flag {
+                        'c' => options.capitalize = true,
+                        'A' => options.capitalize = false,
+                        'n' => options.numerals = true,
+                        '0' => options.numerals = false,
+                        'y' => options.symbols = true,
+                        'B' => options.no_ambiguous = true,
+                        's' => options.secure = true,
+                        '1' => options.columns = false,
+                        'C' => options.columns = true,
+                        _ => return Err(PwgenError::UnknownOption(format!("-{}", flag))),
+                    }
+                }
+            } else {
+                positional.push(arg.to_string());
+            }
+        }
+        let mut positional = positional.into_iter();
+        if let Some(length) = positional.next() {
+            options.length = length
+                .parse()
+                .map_err(|_| PwgenError::BadNumber("length", length))?;
+        }
+        if let Some(count) = positional.next() {
+            options.count = Some(
+                count
+                    .parse()
+                    .map_err(|_| PwgenError::BadNumber("count", count))?,
+            );
+        }
+        if let Some(extra) = positional.next() {
+            return Err(PwgenError::ExtraArgument(extra));
+        }
+        Ok(options)
+    }
+
+    // lowercase letters are always allowed, each enabled class must appear at least once
+    pub fn spec(&self) -> PasswordSpec {
+        let charset = |charset: Charset| {
+            if self.no_ambiguous {
+                self.confusables.filter(&charset)
+            } else {
+                charset
+            }
+        };
+        let mut spec = PasswordSpec::new()
+            .length(self.length)
+            .include(charset(Charset::Lower).at_least(0));
+        if self.capitalize {
+            spec = spec.include(charset(Charset::Upper).at_least(1));
+        }
+        if self.numerals {
+            spec = spec.include(charset(Charset::Number).at_least(1));
+        }
+        if self.symbols {
+            spec = spec.include(charset(Charset::Symbol).at_least(1));
+        }
+        spec
+    }
+
+    fn columns_per_row(&self) -> usize {
+        (LINE_WIDTH / self.length.saturating_add(1)).max(1)
+    }
+
+    // number of passwords to generate when none is given: a screenful in columns, otherwise one
+    pub fn count(&self) -> usize {
+        match self.count {
+            Some(count) => count,
+            None if self.columns => self.columns_per_row() * DEFAULT_ROWS,
+            None => 1,
+        }
+    }
+
+    // pwgen's layout: rows of space separated passwords fitting in 80 columns, or one per line
+    pub fn format(&self, passwords: &[String]) -> String {
+        let per_row = if self.columns {
+            self.columns_per_row()
+        } else {
+            1
+        };
+        let mut res = String::new();
+        for row in passwords.chunks(per_row) {
+            res.push_str(&row.join(" "));
+            res.push('\n');
+        }
+        res
+    }
+}
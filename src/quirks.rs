@@ -0,0 +1,134 @@
+// Characters particular systems choke on, e.g. `&` in a config embedded in XML or `$` in a shell
+// heredoc, so a spec can be checked against where its passwords end up or have them left out.
+//
+// The built in targets are a starting point, more can be added to a registry for systems the
+// builtins don't know about.
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use crate::{charset::Charset, password::PasswordSpec};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum QuirkError {
+    #[error("No target named `{0}`, known targets are {}", .1.join(", "))]
+    UnknownTarget(String, Vec<String>),
+    #[error("`{target}` rejects `{}` which the spec could use", .chars.iter().collect::<String>())]
+    Rejected { target: String, chars: Vec<char> },
+    #[error("Without the characters `{target}` rejects `{charset}` has none left")]
+    EmptiedCharset { target: String, charset: Charset },
+    #[error("The prefix or suffix has `{c}` which `{target}` rejects")]
+    InAffix { target: String, c: char },
+}
+
+// target name to the characters it rejects
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    targets: BTreeMap<String, Vec<char>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builtin() -> Self {
+        Self::new()
+            // entities and attribute quoting
+            .target("xml", "&<>\"'")
+            // expansion and escapes in an unquoted heredoc
+            .target("sh-heredoc", "$`\\")
+            // separators and escapes of a query string
+            .target("url-query", "&=+#%? /")
+            // field separators and quoting
+            .target("csv", ",\"\n\r;")
+    }
+
+    // add a target or replace the characters of one
+    pub fn target(mut self, name: impl Into<String>, rejects: &str) -> Self {
+        let mut chars: Vec<char> = rejects.chars().collect();
+        chars.sort_unstable();
+        chars.dedup();
+        self.targets.insert(name.into(), chars);
+        self
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.targets.keys().map(String::as_str).collect()
+    }
+
+    pub fn rejects(&self, target: &str) -> Result<&[char], QuirkError> {
+        self.targets.get(target).map(Vec::as_slice).ok_or_else(|| {
+            QuirkError::UnknownTarget(
+                target.to_string(),
+                self.names().into_iter().map(String::from).collect(),
+            )
+        })
+    }
+}
+
+impl PasswordSpec {
+    // every character a password could have, from the affixes or drawn for a choice
+    fn emittable(&self) -> Vec<char> {
+        let mut chars: Vec<char> = self.prefix.chars().chain(self.suffix.chars()).collect();
+        for choice in self.drawn_choices() {
+            if choice.max > 0 {
+                chars.extend(self.pool(&choice));
+            }
+        }
+        chars.sort_unstable();
+        chars.dedup();
+        chars
+    }
+
+    // the characters the target rejects that the spec could put in a password
+    fn offending(&self, rejects: &[char]) -> Vec<char> {
+        self.emittable()
+            .into_iter()
+            .filter(|c| rejects.contains(c))
+            .collect()
+    }
+
+    // an error listing the characters the target rejects when the spec could use any of them
+    pub fn compatible_with(&self, registry: &Registry, target: &str) -> Result<(), QuirkError> {
+        let offending = self.offending(registry.rejects(target)?);
+        if offending.is_empty() {
+            Ok(())
+        } else {
+            Err(QuirkError::Rejected {
+                target: target.to_string(),
+                chars: offending,
+            })
+        }
+    }
+
+    // the spec with the characters the target rejects excluded, fails when that leaves a choice
+    // needing characters with none or the affixes have one
+    pub fn for_target(&self, registry: &Registry, target: &str) -> Result<Self, QuirkError> {
+        let rejects = registry.rejects(target)?;
+        if let Some(c) = self
+            .prefix
+            .chars()
+            .chain(self.suffix.chars())
+            .find(|c| rejects.contains(c))
+        {
+            return Err(QuirkError::InAffix {
+                target: target.to_string(),
+                c,
+            });
+        }
+        let offending = self.offending(rejects);
+        if offending.is_empty() {
+            return Ok(self.clone());
+        }
+        if let Some(choice) = self.choices().find(|choice| {
+            choice.min > 0 && self.pool(choice).iter().all(|c| offending.contains(c))
+        }) {
+            return Err(QuirkError::EmptiedCharset {
+                target: target.to_string(),
+                charset: choice.chars.clone(),
+            });
+        }
+        Ok(self.clone().include(Charset::Custom(offending).exactly(0)))
+    }
+}
@@ -0,0 +1,29 @@
+// Quoting a password so it can be pasted into a shell command as a single literal argument.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellDialect {
+    // sh, bash, zsh, ...: single quotes with `'\''` for embedded single quotes
+    Posix,
+    // single quotes with embedded single quotes doubled, PowerShell also treats the typographic
+    // single quotes as quotes so those are doubled as well
+    PowerShell,
+}
+
+pub fn shell_quote(s: &str, dialect: ShellDialect) -> String {
+    let mut res = String::with_capacity(s.len() + 2);
+    res.push('\'');
+    for c in s.chars() {
+        match dialect {
+            ShellDialect::Posix if c == '\'' => res.push_str("'\\''"),
+            ShellDialect::PowerShell
+                if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') =>
+            {
+                res.push(c);
+                res.push(c);
+            }
+            _ => res.push(c),
+        }
+    }
+    res.push('\'');
+    res
+}
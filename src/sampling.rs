@@ -0,0 +1,182 @@
+// How the characters of a password are drawn, written out so a change to the distribution is a
+// decision rather than a side effect of a refactor. `tests/sampling.rs` holds the procedure to it.
+//
+// 1. One interval is picked for each choice, uniformly among the ones that still leave the length
+//    reachable by the choices after it. Only `any_of` choices have more than one.
+// 2. Each choice's minimum is drawn, every character uniformly from its pool, the charset less
+//    whatever `allowed` or the excluded charsets rule out and the characters it shares with
+//    another choice that has a maximum. Weighted choices follow their weights instead.
+// 3. The rest of the length is spread over the choices that can still take characters. Under
+//    `FillMode::Random` each slot goes to one of them picked uniformly, and a choice that reaches
+//    its maximum drops out of the picking, the others keep their order and odds. Under
+//    `FillMode::Balanced` the slots are split evenly, see `fill`. Every character is again
+//    uniform over the pool of the choice it went to.
+// 4. The characters are shuffled together. When a choice's count, every character of its charset
+//    whichever choice drew it, isn't allowed, which overlapping charsets can still do, they're
+//    all drawn again. Then they're moved for position and adjacency rules.
+//
+// So with no maximum in reach the number of extra characters a choice gets is binomial with
+// `1 / active choices` odds, and the characters of a charset are uniform whichever slot they
+// ended up in.
+//
+// That's `SamplingMode::PerChoice`. It gives `:number:` as many of the free slots as `:lower:`
+// though there are far fewer strings with lots of digits in them. `SamplingMode::Uniform` replaces
+// steps 1 to 3: the counts for all the choices are drawn at once, each in proportion to how many
+// strings have them, then the characters for those counts, so every string the counts allow is as
+// likely as any other. Pools are taken as telling the characters apart, with overlapping charsets
+// a string reachable through both is a little more likely. Position and adjacency rules are still
+// applied afterwards, and weights still pick the characters. The fill mode has no say in it.
+// Working out the counts takes time in the square of the length, so uniform sampling only goes up
+// to `MAX_UNIFORM_LENGTH` characters and a longer spec is a `UniformTooLong` error.
+//
+// Indices come from `rand::seq::index`, which draws them without modulo bias, the same way
+// `SliceRandom::choose` does, so seeded output stays what it was.
+use std::collections::HashMap;
+
+use rand::{
+    seq::{index, SliceRandom},
+    Rng,
+};
+
+use crate::{choice::Choice, entropy::LogFactorials};
+
+// longest password `SamplingMode::Uniform` works out the counts for
+pub const MAX_UNIFORM_LENGTH: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SamplingMode {
+    // each choice with room left as likely as the others to get the next character
+    #[default]
+    PerChoice,
+    // every string the spec's counts allow equally likely, for up to `MAX_UNIFORM_LENGTH`
+    // characters
+    Uniform,
+}
+
+// a uniform index below `len`, `None` when there's nothing to pick from
+pub(crate) fn pick<R: Rng + ?Sized>(len: usize, rng: &mut R) -> Option<usize> {
+    if len == 0 {
+        None
+    } else {
+        Some(index::sample(rng, len, 1).index(0))
+    }
+}
+
+// a character of `pool` with every one equally likely
+pub(crate) fn uniform<R: Rng + ?Sized>(pool: &[char], rng: &mut R) -> Option<char> {
+    pick(pool.len(), rng).map(|i| pool[i])
+}
+
+// log2 of the sum of the powers of two
+fn log2_sum(bits: impl Iterator<Item = f64>) -> f64 {
+    let bits: Vec<f64> = bits.collect();
+    let top = bits.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if top == f64::NEG_INFINITY {
+        return top;
+    }
+    top + bits.iter().map(|b| (b - top).exp2()).sum::<f64>().log2()
+}
+
+// how many strings of each length the choices can fill, for drawing counts under
+// `SamplingMode::Uniform`, in bits so long passwords don't overflow
+pub(crate) struct Completions<'a> {
+    drawable: &'a [(Choice, Vec<char>)],
+    length: usize,
+    // `table[i][r]` for `r` characters from the choices from `i` on
+    table: Vec<Vec<f64>>,
+    lf: LogFactorials,
+}
+
+impl<'a> Completions<'a> {
+    // `None` beyond `MAX_UNIFORM_LENGTH`
+    pub(crate) fn new(drawable: &'a [(Choice, Vec<char>)], length: usize) -> Option<Self> {
+        if length > MAX_UNIFORM_LENGTH {
+            return None;
+        }
+        let mut completions = Self {
+            drawable,
+            length,
+            table: vec![vec![f64::NEG_INFINITY; length.checked_add(1)?]; drawable.len() + 1],
+            lf: LogFactorials::new(length)?,
+        };
+        completions.table[drawable.len()][0] = 0.0;
+        for i in (0..drawable.len()).rev() {
+            for r in 0..=length {
+                let ways = log2_sum(completions.counts(i, r).map(|k| completions.ways(i, r, k)));
+                completions.table[i][r] = ways;
+            }
+        }
+        Some(completions)
+    }
+
+    // counts the choice can take out of `r` characters
+    fn counts(&self, i: usize, r: usize) -> impl Iterator<Item = usize> + '_ {
+        let (choice, pool) = &self.drawable[i];
+        (0..=r).filter(move |k| choice.admits(*k) && (*k == 0 || !pool.is_empty()))
+    }
+
+    // the strings of `r` characters with `k` of them from choice `i` and the rest from the ones
+    // after it
+    fn ways(&self, i: usize, r: usize, k: usize) -> f64 {
+        let arrangements = self.lf.get(r) - self.lf.get(k) - self.lf.get(r - k);
+        let drawn = if k == 0 {
+            0.0
+        } else {
+            k as f64 * (self.drawable[i].1.len() as f64).log2()
+        };
+        arrangements + drawn + self.table[i + 1][r - k]
+    }
+
+    // bits of the number of strings, negative infinity when there are none
+    pub(crate) fn total(&self) -> f64 {
+        self.table[0][self.length]
+    }
+
+    // a count for each choice, in proportion to the strings with those counts
+    pub(crate) fn draw<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Vec<usize>> {
+        let mut remaining = self.length;
+        let mut drawn = vec![];
+        for i in 0..self.drawable.len() {
+            let total = self.table[i][remaining];
+            let options: Vec<(usize, f64)> = self
+                .counts(i, remaining)
+                .map(|k| (k, (self.ways(i, remaining, k) - total).exp2()))
+                .collect();
+            let (k, _) = options.choose_weighted(rng, |(_, p)| *p).ok()?;
+            drawn.push(*k);
+            remaining -= k;
+        }
+        Some(drawn)
+    }
+
+    // every way the counts can come out with its probability, `None` beyond `limit` of them
+    pub(crate) fn compositions(&self, limit: usize) -> Option<HashMap<Vec<usize>, f64>> {
+        let total = self.total();
+        let mut partial = vec![(vec![], self.length, 0.0)];
+        for i in 0..self.drawable.len() {
+            let mut next = vec![];
+            for (counts, remaining, bits) in partial {
+                for k in self.counts(i, remaining) {
+                    let rest = self.table[i + 1][remaining - k];
+                    if rest == f64::NEG_INFINITY {
+                        continue;
+                    }
+                    let ways = self.ways(i, remaining, k) - rest;
+                    let mut counts: Vec<usize> = counts.clone();
+                    counts.push(k);
+                    next.push((counts, remaining - k, bits + ways));
+                }
+            }
+            if next.len() > limit {
+                return None;
+            }
+            partial = next;
+        }
+        Some(
+            partial
+                .into_iter()
+                .map(|(counts, _, bits)| (counts, (bits - total).exp2()))
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,59 @@
+// The exact number of passwords a spec can produce, for small policies where entropy in bits is
+// too rough, e.g. to tell 6561 from 10000.
+pub use num_bigint::BigUint;
+
+use crate::password::PasswordSpec;
+
+// longest password `search_space` counts for, the tables it builds grow with the square of the
+// length
+pub const MAX_SEARCH_SPACE_LENGTH: usize = 256;
+
+impl PasswordSpec {
+    // number of distinct character sequences, summed over every composition (how many characters
+    // from each choice, and from the allowed characters no choice has) the intervals and length
+    // allow:
+    //   length! / (k_1! ... k_n!) * |pool_1|^k_1 ... |pool_n|^k_n
+    // pools are treated as labeled, a character in two overlapping charsets is counted once for
+    // each, so with overlaps this is an upper bound on the distinct strings
+    // zero when the spec can't produce anything, affixes don't change the count
+    // `None` for lengths beyond `MAX_SEARCH_SPACE_LENGTH`
+    pub fn search_space(&self) -> Option<BigUint> {
+        let choices = self.drawn_choices();
+        let length = match self.resolve_length() {
+            Ok(length) if length > MAX_SEARCH_SPACE_LENGTH => return None,
+            Ok(length) if !choices.is_empty() => length,
+            _ => return Some(BigUint::from(0u8)),
+        };
+        // binomial[j][k] for j up to the length, built row by row
+        let mut binomial: Vec<Vec<BigUint>> = vec![vec![BigUint::from(1u8)]];
+        for j in 1..=length {
+            let prev = &binomial[j - 1];
+            let mut row = vec![BigUint::from(1u8)];
+            for k in 1..j {
+                row.push(&prev[k - 1] + &prev[k]);
+            }
+            row.push(BigUint::from(1u8));
+            binomial.push(row);
+        }
+        // ways[j]: sequences of length j using the choices so far, with the positions taken by
+        // each choice interleaved in every possible way
+        let mut ways = vec![BigUint::from(0u8); length + 1];
+        ways[0] = BigUint::from(1u8);
+        for choice in &choices {
+            let pool = BigUint::from(self.pool(choice).len());
+            let mut powers = vec![BigUint::from(1u8)];
+            for k in 1..=length {
+                let next = &powers[k - 1] * &pool;
+                powers.push(next);
+            }
+            let mut next = vec![BigUint::from(0u8); length + 1];
+            for (j, slot) in next.iter_mut().enumerate() {
+                for k in (0..=j).filter(|k| choice.admits(*k)) {
+                    *slot += &ways[j - k] * &binomial[j][k] * &powers[k];
+                }
+            }
+            ways = next;
+        }
+        Some(ways.swap_remove(length))
+    }
+}
@@ -0,0 +1,37 @@
+// A value that mustn't end up in logs, panic messages or error reports, like a generated password.
+// `Debug` only shows its length and there's no `Display`, getting at the value takes `expose`.
+use std::fmt::Debug;
+
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+// `Secret([REDACTED], len=32)`, the length in characters
+impl<T: AsRef<str>> Debug for Secret<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Secret([REDACTED], len={})",
+            self.0.as_ref().chars().count()
+        )
+    }
+}
@@ -0,0 +1,130 @@
+// Startup self-test of a random source, for compliance checklists that require one. The
+// statistical checks are the FIPS 140-2 power-up tests (monobit, runs and long run over 20,000
+// bits) followed by a known-answer test of seeded generation.
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use thiserror::Error;
+
+use crate::{charset::Charset, password::PasswordSpec};
+
+// 20,000 bits
+const SAMPLE_BYTES: usize = 2500;
+// ones in the sample have to be strictly between these
+const MONOBIT_BOUNDS: (usize, usize) = (9725, 10275);
+// allowed number of runs of each length 1 to 6+, for runs of zeros and runs of ones separately
+const RUN_BOUNDS: [(usize, usize); 6] = [
+    (2315, 2685),
+    (1114, 1386),
+    (527, 723),
+    (240, 384),
+    (103, 209),
+    (103, 209),
+];
+const LONG_RUN: usize = 26;
+
+const KNOWN_ANSWER_SEED: u64 = 0x7061_6e74_735f_6b61;
+const KNOWN_ANSWER: &str = "251g3eoy1295ygc4";
+
+#[derive(Debug, Error)]
+pub enum SelfTestError {
+    #[error("Monobit test failed, {ones} of 20000 bits were ones")]
+    Monobit { ones: usize },
+    #[error("Runs test failed, {count} runs of {length} {bit}s")]
+    Runs {
+        length: String,
+        bit: u8,
+        count: usize,
+    },
+    #[error("Long run test failed, found a run of {0} identical bits")]
+    LongRun(usize),
+    #[error("Known answer test failed, seeded generation gave `{got}` instead of `{expected}`")]
+    KnownAnswer { expected: String, got: String },
+}
+
+fn bits(bytes: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    bytes
+        .iter()
+        .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1))
+}
+
+fn monobit(bytes: &[u8]) -> Result<(), SelfTestError> {
+    let ones = bits(bytes).filter(|bit| *bit == 1).count();
+    if MONOBIT_BOUNDS.0 < ones && ones < MONOBIT_BOUNDS.1 {
+        Ok(())
+    } else {
+        Err(SelfTestError::Monobit { ones })
+    }
+}
+
+fn runs(bytes: &[u8]) -> Result<(), SelfTestError> {
+    // counts[bit][length - 1], lengths of 6 and more share the last slot
+    let mut counts = [[0usize; 6]; 2];
+    let mut record = |bit: u8, length: usize| -> Result<(), SelfTestError> {
+        if length >= LONG_RUN {
+            return Err(SelfTestError::LongRun(length));
+        }
+        counts[bit as usize][length.min(6) - 1] += 1;
+        Ok(())
+    };
+    let mut current = None;
+    let mut length = 0;
+    for bit in bits(bytes) {
+        match current {
+            Some(c) if c == bit => length += 1,
+            Some(c) => {
+                record(c, length)?;
+                current = Some(bit);
+                length = 1;
+            }
+            None => {
+                current = Some(bit);
+                length = 1;
+            }
+        }
+    }
+    if let Some(c) = current {
+        record(c, length)?;
+    }
+    for (bit, counts) in counts.iter().enumerate() {
+        for (i, (count, (low, high))) in counts.iter().zip(RUN_BOUNDS).enumerate() {
+            if *count < low || *count > high {
+                return Err(SelfTestError::Runs {
+                    length: if i == 5 {
+                        "6+".to_string()
+                    } else {
+                        (i + 1).to_string()
+                    },
+                    bit: bit as u8,
+                    count: *count,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn known_answer() -> Result<(), SelfTestError> {
+    let spec = PasswordSpec::new()
+        .length(16)
+        .include(Charset::Lower.at_least(1))
+        .include(Charset::Number.at_least(1));
+    let got = spec
+        .generate_with_rng(&mut StdRng::seed_from_u64(KNOWN_ANSWER_SEED))
+        .unwrap_or_default();
+    if got == KNOWN_ANSWER {
+        Ok(())
+    } else {
+        Err(SelfTestError::KnownAnswer {
+            expected: KNOWN_ANSWER.to_string(),
+            got,
+        })
+    }
+}
+
+// statistical checks on 20,000 bits from `rng`, then the known-answer test
+pub fn run<R: RngCore + ?Sized>(rng: &mut R) -> Result<(), SelfTestError> {
+    let mut sample = [0u8; SAMPLE_BYTES];
+    rng.fill_bytes(&mut sample);
+    monobit(&sample)?;
+    runs(&sample)?;
+    known_answer()
+}
@@ -0,0 +1,96 @@
+// Checking a password that was read out or typed with separators added, e.g. `ABCD 1234 efgh`.
+// The separators are stripped before checking, but only the ones the spec can't produce itself,
+// stripping a `-` from a spec whose symbols include it could hide a missing or misplaced symbol.
+//
+// This is opt-in, `violations` and `matches` stay strict.
+use std::fmt::{Debug, Display};
+
+use crate::{password::PasswordSpec, secret::Secret, violation::Violation};
+
+// what gets stripped unless other separators are given
+pub const DEFAULT_SEPARATORS: [char; 2] = [' ', '-'];
+
+// the verdict on the password as given and with the separators stripped
+#[derive(Clone, PartialEq, Eq)]
+pub struct SeparatedCheck {
+    pub raw: Vec<Violation>,
+    pub normalized: String,
+    pub normalized_violations: Vec<Violation>,
+    // separators left in because the spec's charsets or affixes have them
+    pub refused: Vec<char>,
+}
+
+impl SeparatedCheck {
+    pub fn raw_ok(&self) -> bool {
+        self.raw.is_empty()
+    }
+
+    pub fn normalized_ok(&self) -> bool {
+        self.normalized_violations.is_empty()
+    }
+}
+
+fn verdict(violations: &[Violation]) -> String {
+    if violations.is_empty() {
+        "ok".to_string()
+    } else {
+        violations
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+// the normalized password redacted
+impl Debug for SeparatedCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SeparatedCheck")
+            .field("raw", &self.raw)
+            .field("normalized", &Secret::new(self.normalized.as_str()))
+            .field("normalized_violations", &self.normalized_violations)
+            .field("refused", &self.refused)
+            .finish()
+    }
+}
+
+impl Display for SeparatedCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "as given: {}", verdict(&self.raw))?;
+        write!(
+            f,
+            "without separators: {}",
+            verdict(&self.normalized_violations)
+        )?;
+        for c in &self.refused {
+            write!(
+                f,
+                "\n`{}` wasn't stripped, the spec can have it in a password",
+                c
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl PasswordSpec {
+    // the separators a spec can't produce itself, the rest are returned as refused
+    fn strippable(&self, separators: &[char]) -> (Vec<char>, Vec<char>) {
+        separators.iter().partition(|c| {
+            !self.is_allowed(**c) && !self.prefix.contains(**c) && !self.suffix.contains(**c)
+        })
+    }
+
+    // check the password both as given and with the separators stripped, see
+    // `DEFAULT_SEPARATORS`
+    pub fn check_separated(&self, password: &str, separators: &[char]) -> SeparatedCheck {
+        let (strip, refused) = self.strippable(separators);
+        let normalized: String = password.chars().filter(|c| !strip.contains(c)).collect();
+        SeparatedCheck {
+            raw: self.violations(password),
+            normalized_violations: self.violations(&normalized),
+            normalized,
+            refused,
+        }
+    }
+}
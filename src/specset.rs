@@ -0,0 +1,113 @@
+// A choice between specs, e.g. either a passphrase-like spec or a strong character spec, or a
+// spread of shapes for honeywords. A member is picked by weight and then generated from.
+use rand::{distributions::WeightedIndex, prelude::Distribution, thread_rng, Rng};
+use thiserror::Error;
+
+use crate::password::{GenerateError, PasswordSpec};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpecSet {
+    members: Vec<(PasswordSpec, f64)>,
+}
+
+#[derive(Debug, Error)]
+pub enum SpecSetError {
+    #[error("A spec set needs at least one spec")]
+    Empty,
+    #[error("Spec {0} has weight {1}, weights have to be positive and finite")]
+    BadWeight(usize, f64),
+    #[error("Every spec in the set failed: {}", failures(.0))]
+    AllFailed(Vec<(usize, GenerateError)>),
+}
+
+fn failures(errors: &[(usize, GenerateError)]) -> String {
+    errors
+        .iter()
+        .map(|(i, e)| format!("spec {}: {}", i, e))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl SpecSet {
+    // every spec equally likely
+    pub fn any_of(specs: Vec<PasswordSpec>) -> Result<Self, SpecSetError> {
+        Self::weighted(specs.into_iter().map(|spec| (spec, 1.0)).collect())
+    }
+
+    // specs picked in proportion to their weights
+    pub fn weighted(members: Vec<(PasswordSpec, f64)>) -> Result<Self, SpecSetError> {
+        if members.is_empty() {
+            return Err(SpecSetError::Empty);
+        }
+        if let Some((i, (_, w))) = members
+            .iter()
+            .enumerate()
+            .find(|(_, (_, w))| !w.is_finite() || *w <= 0.0)
+        {
+            return Err(SpecSetError::BadWeight(i, *w));
+        }
+        Ok(Self { members })
+    }
+
+    pub fn members(&self) -> &[(PasswordSpec, f64)] {
+        &self.members
+    }
+
+    pub fn generate(&self) -> Result<String, SpecSetError> {
+        self.generate_with_rng(&mut thread_rng())
+    }
+
+    // a spec that fails is dropped and another picked by the remaining weights, so only when
+    // every spec fails is it an error, listing each failure
+    pub fn generate_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<String, SpecSetError> {
+        let mut candidates: Vec<usize> = (0..self.members.len()).collect();
+        let mut errors = vec![];
+        while !candidates.is_empty() {
+            let weights = candidates.iter().map(|i| self.members[*i].1);
+            // weights are checked when the set is made
+            let picked = match WeightedIndex::new(weights) {
+                Ok(index) => index.sample(rng),
+                Err(_) => 0,
+            };
+            let member = candidates[picked];
+            match self.members[member].0.generate_once(rng) {
+                Ok(password) => return Ok(password),
+                Err(e) => {
+                    errors.push((member, e));
+                    candidates.remove(picked);
+                }
+            }
+        }
+        errors.sort_by_key(|(i, _)| *i);
+        Err(SpecSetError::AllFailed(errors))
+    }
+
+    // whether a member spec accepts the password
+    pub fn matches(&self, password: &str) -> bool {
+        self.members.iter().any(|(spec, _)| spec.matches(password))
+    }
+
+    // guaranteed entropy of the members weighted by how likely they are, plus the entropy of
+    // picking the member, specs that can't be generated are left out
+    pub fn entropy(&self) -> Result<f64, SpecSetError> {
+        let mut entropies = vec![];
+        let mut errors = vec![];
+        for (i, (spec, w)) in self.members.iter().enumerate() {
+            match spec.entropy() {
+                Ok(h) => entropies.push((h, *w)),
+                Err(e) => errors.push((i, e)),
+            }
+        }
+        if entropies.is_empty() {
+            return Err(SpecSetError::AllFailed(errors));
+        }
+        let total: f64 = entropies.iter().map(|(_, w)| w).sum();
+        Ok(entropies
+            .iter()
+            .map(|(h, w)| {
+                let p = w / total;
+                p * h - p * p.log2()
+            })
+            .sum())
+    }
+}
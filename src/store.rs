@@ -0,0 +1,396 @@
+// Writing a generated password straight into a secret store, e.g. the Windows Credential Manager,
+// rather than printing it. Backends implement `SecretStore`, `put` holds the overwrite rules so
+// they're the same for every backend. `MemoryStore` keeps secrets in memory, for tests and for
+// trying things out.
+//
+// Entries are named by a service and an account, the way the keychain and secret service name
+// them, the credential manager gets `service/account` as its target.
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use crate::secret::Secret;
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("`{0}` already has a secret, overwriting it needs forcing")]
+    Exists(String),
+    #[error("The `{0}` store isn't available in this build")]
+    Unavailable(String),
+    #[error("Unknown store `{0}`")]
+    Unknown(String),
+    #[error("The store is locked and couldn't be unlocked")]
+    Locked,
+    #[error("The `{0}` store isn't running, or there's no session to reach it through")]
+    NoService(String),
+    #[error("Target `{0}` needs to be `service/account`")]
+    BadTarget(String),
+    #[error("The store failed: {0}")]
+    Backend(String),
+}
+
+// where a secret goes, `label` is what stores that show entries to people display
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Entry {
+    pub service: String,
+    pub account: String,
+    pub label: String,
+}
+
+impl Entry {
+    // labelled `service/account` unless given another label
+    pub fn new(service: impl Into<String>, account: impl Into<String>) -> Self {
+        let service = service.into();
+        let account = account.into();
+        Self {
+            label: format!("{}/{}", service, account),
+            service,
+            account,
+        }
+    }
+
+    // `service/account`, split at the first `/` so accounts can have one
+    pub fn parse_target(target: &str) -> Result<Self, StoreError> {
+        match target.split_once('/') {
+            Some((service, account)) if !service.is_empty() && !account.is_empty() => {
+                Ok(Self::new(service, account))
+            }
+            _ => Err(StoreError::BadTarget(target.to_string())),
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    pub fn target(&self) -> String {
+        format!("{}/{}", self.service, self.account)
+    }
+}
+
+pub trait SecretStore {
+    // the name the store is picked by, e.g. `wincred`
+    fn name(&self) -> &'static str;
+    fn exists(&self, entry: &Entry) -> Result<bool, StoreError>;
+    fn write(&mut self, entry: &Entry, secret: &str) -> Result<(), StoreError>;
+}
+
+// the secret under `entry`, an existing one is only replaced when forced
+// returns the confirmation to show in place of the secret
+pub fn put<S: SecretStore + ?Sized>(
+    store: &mut S,
+    entry: &Entry,
+    secret: &str,
+    force: bool,
+) -> Result<String, StoreError> {
+    let existed = store.exists(entry)?;
+    if existed && !force {
+        return Err(StoreError::Exists(entry.target()));
+    }
+    store.write(entry, secret)?;
+    Ok(confirmation(store.name(), entry, existed))
+}
+
+pub fn confirmation(store: &str, entry: &Entry, replaced: bool) -> String {
+    let verb = if replaced { "Replaced" } else { "Stored" };
+    format!(
+        "{} the password for `{}` in {} as `{}`",
+        verb,
+        entry.target(),
+        store,
+        entry.label
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Memory,
+    WinCred,
+    SecretService,
+    Keychain,
+}
+
+impl Backend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backend::Memory => "memory",
+            Backend::WinCred => "wincred",
+            Backend::SecretService => "secret-service",
+            Backend::Keychain => "keychain",
+        }
+    }
+
+    // whether this build can use it, it needs its feature and the right platform
+    pub fn compiled_in(&self) -> bool {
+        match self {
+            Backend::Memory => true,
+            Backend::WinCred => cfg!(all(windows, feature = "wincred")),
+            Backend::SecretService => cfg!(all(target_os = "linux", feature = "secret-service")),
+            Backend::Keychain => cfg!(all(target_os = "macos", feature = "keychain")),
+        }
+    }
+
+    // the backend a name picks, `auto` is the platform's own store
+    pub fn select(name: &str) -> Result<Backend, StoreError> {
+        let backend = match name {
+            "auto" => [Backend::WinCred, Backend::SecretService, Backend::Keychain]
+                .into_iter()
+                .find(Backend::compiled_in)
+                .ok_or_else(|| StoreError::Unavailable(name.to_string()))?,
+            "memory" => Backend::Memory,
+            "wincred" => Backend::WinCred,
+            "secret-service" => Backend::SecretService,
+            "keychain" => Backend::Keychain,
+            _ => return Err(StoreError::Unknown(name.to_string())),
+        };
+        if backend.compiled_in() {
+            Ok(backend)
+        } else {
+            Err(StoreError::Unavailable(name.to_string()))
+        }
+    }
+}
+
+// the store with the given name, as long as it's built in
+pub fn open(name: &str) -> Result<Box<dyn SecretStore>, StoreError> {
+    match Backend::select(name)? {
+        #[cfg(all(windows, feature = "wincred"))]
+        Backend::WinCred => Ok(Box::new(wincred::WinCred)),
+        #[cfg(all(target_os = "linux", feature = "secret-service"))]
+        Backend::SecretService => Ok(Box::new(freedesktop::SecretServiceStore)),
+        #[cfg(all(target_os = "macos", feature = "keychain"))]
+        Backend::Keychain => Ok(Box::new(keychain::Keychain)),
+        Backend::Memory => Ok(Box::new(MemoryStore::new())),
+        // `select` only gives the others when they're compiled in
+        #[allow(unreachable_patterns)]
+        other => Err(StoreError::Unavailable(other.name().to_string())),
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryStore {
+    secrets: BTreeMap<(String, String), (String, Secret<String>)>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, service: &str, account: &str) -> Option<&str> {
+        self.entry(service, account).map(|(_, secret)| secret)
+    }
+
+    // the label and secret stored for the service and account
+    pub fn entry(&self, service: &str, account: &str) -> Option<(&str, &str)> {
+        self.secrets
+            .get(&(service.to_string(), account.to_string()))
+            .map(|(label, secret)| (label.as_str(), secret.expose().as_str()))
+    }
+}
+
+impl SecretStore for MemoryStore {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn exists(&self, entry: &Entry) -> Result<bool, StoreError> {
+        Ok(self.get(&entry.service, &entry.account).is_some())
+    }
+
+    fn write(&mut self, entry: &Entry, secret: &str) -> Result<(), StoreError> {
+        self.secrets.insert(
+            (entry.service.clone(), entry.account.clone()),
+            (entry.label.clone(), Secret::new(secret.to_string())),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(all(windows, feature = "wincred"))]
+pub use wincred::WinCred;
+
+#[cfg(all(windows, feature = "wincred"))]
+mod wincred {
+    use std::{ffi::OsStr, os::windows::ffi::OsStrExt, ptr};
+
+    use windows_sys::Win32::{
+        Foundation::{GetLastError, ERROR_NOT_FOUND},
+        Security::Credentials::{
+            CredFree, CredReadW, CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE,
+            CRED_TYPE_GENERIC,
+        },
+    };
+
+    use super::{Entry, SecretStore, StoreError};
+
+    // generic credentials of the Windows Credential Manager, named `service/account`
+    pub struct WinCred;
+
+    fn wide(s: &str) -> Vec<u16> {
+        OsStr::new(s).encode_wide().chain(Some(0)).collect()
+    }
+
+    impl SecretStore for WinCred {
+        fn name(&self) -> &'static str {
+            "wincred"
+        }
+
+        fn exists(&self, entry: &Entry) -> Result<bool, StoreError> {
+            let target = wide(&entry.target());
+            let mut credential: *mut CREDENTIALW = ptr::null_mut();
+            // SAFETY: the target is nul terminated and a credential read is freed with CredFree
+            unsafe {
+                if CredReadW(target.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) != 0 {
+                    CredFree(credential as *const _);
+                    return Ok(true);
+                }
+                match GetLastError() {
+                    ERROR_NOT_FOUND => Ok(false),
+                    code => Err(StoreError::Backend(format!(
+                        "CredReadW failed with {}",
+                        code
+                    ))),
+                }
+            }
+        }
+
+        fn write(&mut self, entry: &Entry, secret: &str) -> Result<(), StoreError> {
+            let mut target = wide(&entry.target());
+            let mut comment = wide(&entry.label);
+            let mut user = wide(&entry.account);
+            // stored as UTF-16 like credentials written by Windows itself
+            let mut blob: Vec<u8> = secret
+                .encode_utf16()
+                .flat_map(|unit| unit.to_le_bytes())
+                .collect();
+            // SAFETY: an all zero CREDENTIALW is valid, the pointers set on it outlive the call
+            unsafe {
+                let mut credential: CREDENTIALW = std::mem::zeroed();
+                credential.Type = CRED_TYPE_GENERIC;
+                credential.TargetName = target.as_mut_ptr();
+                credential.Comment = comment.as_mut_ptr();
+                credential.UserName = user.as_mut_ptr();
+                credential.CredentialBlobSize = blob.len() as u32;
+                credential.CredentialBlob = blob.as_mut_ptr();
+                credential.Persist = CRED_PERSIST_LOCAL_MACHINE;
+                let written = CredWriteW(&credential, 0);
+                blob.fill(0);
+                if written == 0 {
+                    return Err(StoreError::Backend(format!(
+                        "CredWriteW failed with {}",
+                        GetLastError()
+                    )));
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "secret-service"))]
+pub use freedesktop::SecretServiceStore;
+
+#[cfg(all(target_os = "linux", feature = "secret-service"))]
+mod freedesktop {
+    use std::collections::HashMap;
+
+    use secret_service::{blocking::SecretService, EncryptionType, Error};
+
+    use super::{Entry, SecretStore, StoreError};
+
+    // the default collection of the freedesktop secret service, items carry `service` and
+    // `account` attributes
+    pub struct SecretServiceStore;
+
+    fn map_error(e: Error) -> StoreError {
+        match e {
+            Error::Locked | Error::Prompt => StoreError::Locked,
+            Error::Unavailable => StoreError::NoService("secret-service".to_string()),
+            e => StoreError::Backend(e.to_string()),
+        }
+    }
+
+    fn attributes(entry: &Entry) -> HashMap<&str, &str> {
+        HashMap::from([
+            ("service", entry.service.as_str()),
+            ("account", entry.account.as_str()),
+        ])
+    }
+
+    impl SecretStore for SecretServiceStore {
+        fn name(&self) -> &'static str {
+            "secret-service"
+        }
+
+        fn exists(&self, entry: &Entry) -> Result<bool, StoreError> {
+            let service = SecretService::connect(EncryptionType::Dh).map_err(map_error)?;
+            let found = service.search_items(attributes(entry)).map_err(map_error)?;
+            Ok(!found.unlocked.is_empty() || !found.locked.is_empty())
+        }
+
+        fn write(&mut self, entry: &Entry, secret: &str) -> Result<(), StoreError> {
+            let service = SecretService::connect(EncryptionType::Dh).map_err(map_error)?;
+            let collection = service.get_default_collection().map_err(map_error)?;
+            collection.ensure_unlocked().map_err(map_error)?;
+            collection
+                .create_item(
+                    &entry.label,
+                    attributes(entry),
+                    secret.as_bytes(),
+                    true,
+                    "text/plain",
+                )
+                .map_err(map_error)?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(all(target_os = "macos", feature = "keychain"))]
+pub use keychain::Keychain;
+
+#[cfg(all(target_os = "macos", feature = "keychain"))]
+mod keychain {
+    use security_framework::{
+        base::Error,
+        passwords::{get_generic_password, set_generic_password},
+    };
+
+    use super::{Entry, SecretStore, StoreError};
+
+    const ITEM_NOT_FOUND: i32 = -25300;
+    const INTERACTION_NOT_ALLOWED: i32 = -25308;
+    const AUTH_FAILED: i32 = -25293;
+
+    // generic passwords in the login keychain, which names them by service and account only so
+    // the label isn't kept
+    pub struct Keychain;
+
+    fn map_error(e: Error) -> StoreError {
+        match e.code() {
+            INTERACTION_NOT_ALLOWED | AUTH_FAILED => StoreError::Locked,
+            _ => StoreError::Backend(e.to_string()),
+        }
+    }
+
+    impl SecretStore for Keychain {
+        fn name(&self) -> &'static str {
+            "keychain"
+        }
+
+        fn exists(&self, entry: &Entry) -> Result<bool, StoreError> {
+            match get_generic_password(&entry.service, &entry.account) {
+                Ok(_) => Ok(true),
+                Err(e) if e.code() == ITEM_NOT_FOUND => Ok(false),
+                Err(e) => Err(map_error(e)),
+            }
+        }
+
+        fn write(&mut self, entry: &Entry, secret: &str) -> Result<(), StoreError> {
+            set_generic_password(&entry.service, &entry.account, secret.as_bytes())
+                .map_err(map_error)
+        }
+    }
+}
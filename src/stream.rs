@@ -0,0 +1,251 @@
+// Generating a large batch one password at a time, so callers can write them out as they come and
+// report progress rather than waiting on the whole batch, or as many as needed with `iter`.
+//
+// Nothing is generated until the iterator is advanced. The spec is resolved once up front, a
+// length given in bits or including the affixes isn't worked out again for every password.
+use std::collections::HashSet;
+
+use rand::{rngs::ThreadRng, thread_rng, Rng};
+
+use crate::{
+    choice::Choice,
+    password::{GenerateError, PasswordSpec},
+};
+
+// below this many passwords a progress bar is more noise than help
+pub const PROGRESS_THRESHOLD: usize = 1000;
+// duplicates in a row `generate_unique_n` draws again before giving up
+const UNIQUE_ATTEMPTS: usize = 1000;
+
+pub struct Stream<R> {
+    // the spec with its length resolved, or why it couldn't be
+    spec: Option<PasswordSpec>,
+    error: Option<GenerateError>,
+    rng: R,
+    remaining: usize,
+}
+
+impl PasswordSpec {
+    // `count` passwords, drawn lazily from `rng`
+    // a spec that can't resolve its length yields that error once and ends
+    pub fn stream<R: Rng>(&self, count: usize, rng: R) -> Stream<R> {
+        let (spec, error) = match self.resolved() {
+            Ok(spec) => (Some(spec), None),
+            Err(e) => (None, Some(e)),
+        };
+        Stream {
+            spec,
+            error,
+            rng,
+            remaining: count,
+        }
+    }
+}
+
+impl<R: Rng> Iterator for Stream<R> {
+    type Item = Result<String, GenerateError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        #[cfg(feature = "tracing")]
+        tracing::trace!(remaining = self.remaining, "streaming password");
+        match &self.spec {
+            Some(spec) => Some(spec.generate_once(&mut self.rng)),
+            None => {
+                self.remaining = 0;
+                self.error.take().map(Err)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = match self.spec {
+            Some(_) => self.remaining,
+            None => self.remaining.min(1),
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+// one line of progress, e.g. `[#####               ]  25% (250/1000)`, meant to be redrawn in
+// place on stderr with a leading `\r`
+pub fn progress_bar(done: usize, total: usize, width: usize) -> String {
+    let fraction = if total == 0 {
+        1.0
+    } else {
+        done.min(total) as f64 / total as f64
+    };
+    let filled = (fraction * width as f64).floor() as usize;
+    format!(
+        "[{}{}] {:>3}% ({}/{})",
+        "#".repeat(filled),
+        " ".repeat(width - filled),
+        (fraction * 100.0).floor() as usize,
+        done,
+        total
+    )
+}
+
+// passwords without end, for taking as many as needed, e.g. `spec.iter().take(500)`
+pub struct PasswordIter<R> {
+    // the spec with its length resolved, `None` once it can't generate
+    spec: Option<PasswordSpec>,
+    drawable: Vec<(Choice, Vec<char>)>,
+    length: usize,
+    rng: R,
+}
+
+impl PasswordSpec {
+    pub fn iter(&self) -> PasswordIter<ThreadRng> {
+        self.iter_with_rng(thread_rng())
+    }
+
+    // passwords drawn from `rng`, whether the spec can generate and the characters of its
+    // charsets are worked out once rather than for every password
+    // a spec that can't generate gives no passwords, and they end at the first one that fails,
+    // which for a spec that can only happens when position or adjacency rules run out of attempts
+    pub fn iter_with_rng<R: Rng>(&self, rng: R) -> PasswordIter<R> {
+        let spec = self
+            .resolved()
+            .ok()
+            .filter(|spec| spec.measured() || spec.precheck().is_ok());
+        let (drawable, length) = match &spec {
+            Some(spec) => (spec.drawable(), spec.resolve_length().unwrap_or(0)),
+            None => (vec![], 0),
+        };
+        PasswordIter {
+            spec,
+            drawable,
+            length,
+            rng,
+        }
+    }
+
+    // `n` passwords, `None` when the spec can't generate them
+    pub fn generate_n(&self, n: usize) -> Option<Vec<String>> {
+        self.generate_n_with_rng(n, &mut thread_rng())
+    }
+
+    pub fn generate_n_with_rng<R: Rng + ?Sized>(
+        &self,
+        n: usize,
+        rng: &mut R,
+    ) -> Option<Vec<String>> {
+        self.try_generate_n_with_rng(n, rng).ok()
+    }
+
+    // `n` passwords or why the spec can't generate them, an infeasible spec is found out before
+    // anything is drawn rather than once for each password
+    pub fn try_generate_n(&self, n: usize) -> Result<Vec<String>, GenerateError> {
+        self.try_generate_n_with_rng(n, &mut thread_rng())
+    }
+
+    pub fn try_generate_n_with_rng<R: Rng + ?Sized>(
+        &self,
+        n: usize,
+        rng: &mut R,
+    ) -> Result<Vec<String>, GenerateError> {
+        self.batch_precheck()?;
+        let mut iter = self.iter_with_rng(rng);
+        (0..n).map(|_| iter.try_next()).collect()
+    }
+
+    // `n` passwords with no two the same, `TooFewDistinct` when the charsets and length can't
+    // make that many, which is checked up front for lengths in characters
+    pub fn generate_unique_n(&self, n: usize) -> Result<Vec<String>, GenerateError> {
+        self.generate_unique_n_with_rng(n, &mut thread_rng())
+    }
+
+    // duplicates are drawn again, after `UNIQUE_ATTEMPTS` in a row it gives up, which happens
+    // when the counts leave fewer passwords than the charsets alone would
+    pub fn generate_unique_n_with_rng<R: Rng + ?Sized>(
+        &self,
+        n: usize,
+        rng: &mut R,
+    ) -> Result<Vec<String>, GenerateError> {
+        let spec = self.batch_precheck()?;
+        if !spec.measured() && spec.distinct_bound() < n {
+            return Err(GenerateError::TooFewDistinct {
+                requested: n,
+                possible: spec.distinct_bound(),
+            });
+        }
+        let mut iter = self.iter_with_rng(rng);
+        let mut seen = HashSet::new();
+        let mut passwords = Vec::with_capacity(n);
+        let mut repeats = 0;
+        while passwords.len() < n {
+            let password = iter.try_next()?;
+            if seen.insert(password.clone()) {
+                passwords.push(password);
+                repeats = 0;
+            } else {
+                repeats += 1;
+                if repeats == UNIQUE_ATTEMPTS {
+                    return Err(GenerateError::AttemptsExhausted {
+                        attempts: UNIQUE_ATTEMPTS,
+                    });
+                }
+            }
+        }
+        Ok(passwords)
+    }
+
+    // the resolved spec when it can generate, lengths in other units are only found out drawing
+    fn batch_precheck(&self) -> Result<PasswordSpec, GenerateError> {
+        let spec = self.resolved()?;
+        if !spec.measured() {
+            spec.precheck()?;
+        }
+        Ok(spec)
+    }
+
+    // the passwords the characters drawn from could make at the length, ignoring the counts, so
+    // at least as many as the spec can, saturating at `usize::MAX`
+    fn distinct_bound(&self) -> usize {
+        let mut pool: Vec<char> = self
+            .drawable()
+            .into_iter()
+            .flat_map(|(_, pool)| pool)
+            .collect();
+        pool.sort_unstable();
+        pool.dedup();
+        let length = self.resolve_length().unwrap_or(0);
+        (0..length)
+            .try_fold(1usize, |total, _| total.checked_mul(pool.len()))
+            .unwrap_or(usize::MAX)
+    }
+}
+
+impl<R: Rng> PasswordIter<R> {
+    // the next password or why there isn't one, the iterator ends at the first error
+    fn try_next(&mut self) -> Result<String, GenerateError> {
+        let spec = self.spec.as_ref().ok_or(GenerateError::Infeasible)?;
+        // rules placing characters and other units need more than a draw, those go the long way
+        let password =
+            if spec.measured() || !spec.position_rules.is_empty() || !spec.no_adjacent.is_empty() {
+                spec.generate_once(&mut self.rng)
+            } else {
+                spec.draw_from(&self.drawable, self.length, &mut self.rng)
+                    .map(|drawn| {
+                        let core: String = drawn.into_iter().map(|(c, _)| c).collect();
+                        format!("{}{}{}", spec.prefix, core, spec.suffix)
+                    })
+            };
+        if password.is_err() {
+            self.spec = None;
+        }
+        password
+    }
+}
+
+impl<R: Rng> Iterator for PasswordIter<R> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next().ok()
+    }
+}
@@ -0,0 +1,138 @@
+// Turning bits of entropy into how long guessing would take, which is easier to get a feel for.
+use std::{fmt::Display, time::Duration};
+
+const MINUTE: f64 = 60.0;
+const HOUR: f64 = 60.0 * MINUTE;
+const DAY: f64 = 24.0 * HOUR;
+// julian year, 365.25 days
+const YEAR: f64 = 365.25 * DAY;
+const CENTURY: f64 = 100.0 * YEAR;
+// beyond this many years the count is shown in scientific notation
+const SCIENTIFIC_YEARS: f64 = 10_000.0;
+
+// Rough guess rates for a few kinds of attacker, the same orders of magnitude zxcvbn uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attacker {
+    // guessing against a live login that rate limits, 100 guesses an hour
+    OnlineThrottled,
+    // a stolen database of bcrypt or similarly slow hashes
+    OfflineSlowHash,
+    // a stolen database of MD5 or other fast hashes cracked on GPUs
+    OfflineFastHash,
+}
+
+impl Attacker {
+    pub const ALL: [Attacker; 3] = [
+        Attacker::OnlineThrottled,
+        Attacker::OfflineSlowHash,
+        Attacker::OfflineFastHash,
+    ];
+
+    pub fn guesses_per_second(&self) -> f64 {
+        match self {
+            Attacker::OnlineThrottled => 100.0 / HOUR,
+            Attacker::OfflineSlowHash => 1e4,
+            Attacker::OfflineFastHash => 1e10,
+        }
+    }
+}
+
+impl Display for Attacker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Attacker::OnlineThrottled => write!(f, "online, throttled (100/hour)"),
+            Attacker::OfflineSlowHash => write!(f, "offline, bcrypt (10⁴/s)"),
+            Attacker::OfflineFastHash => write!(f, "offline, MD5 on GPUs (10¹⁰/s)"),
+        }
+    }
+}
+
+// time to try all 2^bits possibilities, on average a password is found in half of it
+// saturates at `Duration::MAX` (around 5×10¹¹ years)
+pub fn crack_time(bits: f64, guesses_per_second: f64) -> Duration {
+    let seconds = bits.exp2() / guesses_per_second;
+    if seconds >= 0.0 && seconds < u64::MAX as f64 {
+        Duration::from_secs_f64(seconds)
+    } else {
+        Duration::MAX
+    }
+}
+
+fn superscript(n: u32) -> String {
+    n.to_string()
+        .chars()
+        .map(|c| match c {
+            '0' => '⁰',
+            '1' => '¹',
+            '2' => '²',
+            '3' => '³',
+            '4' => '⁴',
+            '5' => '⁵',
+            '6' => '⁶',
+            '7' => '⁷',
+            '8' => '⁸',
+            _ => '⁹',
+        })
+        .collect()
+}
+
+// whole units rounded down, pluralized
+fn units(amount: f64, unit: &str) -> String {
+    let amount = amount.floor();
+    if amount == 1.0 {
+        format!("1 {}", unit)
+    } else {
+        format!("{} {}s", amount, unit)
+    }
+}
+
+// `3×10⁵ years`, the mantissa is rounded down like the other units
+fn scientific_years(years: f64) -> String {
+    let mut exponent = years.log10().floor();
+    let mut mantissa = (years / 10f64.powf(exponent)).floor();
+    // log10 can land just short of a power of ten
+    if mantissa >= 10.0 {
+        mantissa /= 10.0;
+        exponent += 1.0;
+    }
+    format!("{}×10{} years", mantissa, superscript(exponent as u32))
+}
+
+// coarse human readable duration, e.g. `instant`, `4 hours`, `3 centuries` or `3×10⁵ years`
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs_f64();
+    if duration == Duration::MAX {
+        format!("more than {}", scientific_years(seconds / YEAR))
+    } else if seconds < 1.0 {
+        "instant".to_string()
+    } else if seconds < MINUTE {
+        units(seconds, "second")
+    } else if seconds < HOUR {
+        units(seconds / MINUTE, "minute")
+    } else if seconds < DAY {
+        units(seconds / HOUR, "hour")
+    } else if seconds < YEAR {
+        units(seconds / DAY, "day")
+    } else if seconds < CENTURY {
+        units(seconds / YEAR, "year")
+    } else if seconds < SCIENTIFIC_YEARS * YEAR {
+        let centuries = (seconds / CENTURY).floor();
+        if centuries == 1.0 {
+            "1 century".to_string()
+        } else {
+            format!("{} centuries", centuries)
+        }
+    } else {
+        scientific_years(seconds / YEAR)
+    }
+}
+
+// one line per attacker profile with how long trying every password would take them
+pub fn crack_table(bits: f64) -> String {
+    let mut res = String::new();
+    for attacker in Attacker::ALL {
+        let time = crack_time(bits, attacker.guesses_per_second());
+        res.push_str(&format!("{}: {}\n", attacker, format_duration(time)));
+    }
+    res
+}
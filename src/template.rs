@@ -0,0 +1,98 @@
+// Spec strings with variables, so one policy file can serve several environments, e.g.
+// `${LEN}//1+|:upper://${SYM_MIN:-1}+|:symbol:` with `LEN=20`.
+//
+// `${NAME}` is replaced by the variable's value and `${NAME:-default}` falls back to the default
+// when the variable isn't set. `$${` is a literal `${`, any other `$` is left as it is so custom
+// charsets can still have one.
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::password::{PasswordParseError, PasswordSpec};
+
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error("Undefined variables: {}", .0.join(", "))]
+    Undefined(Vec<String>),
+    #[error("`${{` at {0} isn't closed by a `}}`")]
+    Unclosed(usize),
+    #[error("`{0}` isn't a variable name, use letters, digits and `_` not starting with a digit")]
+    BadName(String),
+    #[error("Expected NAME=value, got `{0}`")]
+    BadAssignment(String),
+    #[error("{0}")]
+    Parse(PasswordParseError),
+}
+
+fn is_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// the template with every variable replaced, the undefined ones without defaults are all named
+// in the error
+pub fn substitute(template: &str, vars: &HashMap<String, String>) -> Result<String, TemplateError> {
+    let mut out = String::new();
+    let mut undefined: Vec<String> = vec![];
+    let mut rest = template;
+    while let Some(start) = rest.find('$') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        if let Some(escaped) = after.strip_prefix("${") {
+            out.push_str("${");
+            rest = escaped;
+        } else if let Some(body) = after.strip_prefix('{') {
+            let end = body
+                .find('}')
+                .ok_or(TemplateError::Unclosed(template.len() - rest.len() + start))?;
+            let (name, default) = match body[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&body[..end], None),
+            };
+            if !is_name(name) {
+                return Err(TemplateError::BadName(name.to_string()));
+            }
+            match vars.get(name).map(String::as_str).or(default) {
+                Some(value) => out.push_str(value),
+                None if !undefined.iter().any(|u| u == name) => undefined.push(name.to_string()),
+                None => {}
+            }
+            rest = &body[end + 1..];
+        } else {
+            out.push('$');
+            rest = after;
+        }
+    }
+    out.push_str(rest);
+    if undefined.is_empty() {
+        Ok(out)
+    } else {
+        Err(TemplateError::Undefined(undefined))
+    }
+}
+
+// `NAME=value` as given to `--set`
+pub fn parse_assignment(s: &str) -> Result<(String, String), TemplateError> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| TemplateError::BadAssignment(s.to_string()))?;
+    if !is_name(name) {
+        return Err(TemplateError::BadName(name.to_string()));
+    }
+    Ok((name.to_string(), value.to_string()))
+}
+
+impl PasswordSpec {
+    // substitute the variables and parse the result as a spec string
+    pub fn from_template(
+        template: &str,
+        vars: &HashMap<String, String>,
+    ) -> Result<PasswordSpec, TemplateError> {
+        substitute(template, vars)?
+            .parse()
+            .map_err(TemplateError::Parse)
+    }
+}
@@ -0,0 +1,70 @@
+// Numeric tokens shaped like card numbers, for test fixtures only. These aren't real PANs and
+// nothing about the prefix or length is checked against any card scheme.
+use rand::{thread_rng, Rng};
+use thiserror::Error;
+
+use crate::checksum::luhn_check_digit;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenSpec {
+    digits: usize,
+    luhn: bool,
+    prefix: String,
+}
+
+#[derive(Debug, Error)]
+pub enum TokenError {
+    #[error("Prefix `{0}` has to be made of digits")]
+    BadPrefix(String),
+    #[error("Prefix `{prefix}` leaves no room in a {digits} digit token")]
+    PrefixTooLong { prefix: String, digits: usize },
+}
+
+impl TokenSpec {
+    pub fn new(digits: usize) -> Self {
+        Self {
+            digits,
+            luhn: false,
+            prefix: String::new(),
+        }
+    }
+
+    // make the final digit a Luhn check digit over the rest, it counts toward `digits`
+    pub fn luhn(mut self) -> Self {
+        self.luhn = true;
+        self
+    }
+
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    pub fn generate(&self) -> Result<String, TokenError> {
+        self.generate_with_rng(&mut thread_rng())
+    }
+
+    pub fn generate_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<String, TokenError> {
+        if !self.prefix.chars().all(|c| c.is_ascii_digit()) {
+            return Err(TokenError::BadPrefix(self.prefix.clone()));
+        }
+        let check = usize::from(self.luhn);
+        if self.prefix.len() + check > self.digits {
+            return Err(TokenError::PrefixTooLong {
+                prefix: self.prefix.clone(),
+                digits: self.digits,
+            });
+        }
+        let mut token = self.prefix.clone();
+        for _ in self.prefix.len()..self.digits - check {
+            token.push(char::from(b'0' + rng.gen_range(0..10u8)));
+        }
+        if self.luhn {
+            // only digits have been pushed so there's always a check digit
+            if let Some(c) = luhn_check_digit(&token) {
+                token.push(c);
+            }
+        }
+        Ok(token)
+    }
+}
@@ -0,0 +1,278 @@
+// Audit record of a generation, to show afterwards that a password came from this generator with a
+// given spec and rng. The password itself is never kept, only a salted SHA-256 commitment to it
+// that also covers every other field of the record, so changing any of them breaks `verify` with
+// the real password as well. Only with the `transcript` feature.
+use std::{fmt::Display, fs, io, path::Path, str::FromStr};
+
+use rand::{thread_rng, Rng};
+use sha2::{Digest as _, Sha256};
+use thiserror::Error;
+
+use crate::{
+    generated::GeneratedPassword,
+    hex::{self, Digest},
+    metadata::{Clock, Metadata, SystemClock},
+    password::PasswordSpec,
+    violation::Violation,
+};
+
+const HEADER: &str = "pants-gen-transcript v1";
+
+// one check of the password against the spec, named like `length` or `class:upper`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub passed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    pub generator_version: String,
+    pub rng_source: String,
+    // `None` when the spec has settings a spec string can't record, the `spec` line is left out
+    // then, see `PasswordSpec::to_spec_string`
+    pub spec_canonical: Option<String>,
+    // seconds since the unix epoch, when the password was generated and when it was recorded
+    pub generated_at: u64,
+    pub recorded_at: u64,
+    pub checks: Vec<Check>,
+    salt: Digest,
+    commitment: Digest,
+}
+
+#[derive(Debug, Error)]
+pub enum TranscriptError {
+    #[error("{0}")]
+    Io(io::Error),
+    #[error("Not a transcript, expected it to start with `pants-gen-transcript v1`")]
+    BadHeader,
+    #[error("Transcript line {0} isn't a known field")]
+    BadLine(usize),
+    #[error("Transcript field `{0}` is missing")]
+    Missing(&'static str),
+}
+
+impl Transcript {
+    // record the generation of `generated` against `spec`, with what produced it from `metadata`
+    // every check `violations` makes is listed with whether it passed, a password that doesn't
+    // match the spec is still recorded so the record shows the failure
+    pub fn record(generated: &GeneratedPassword, spec: &PasswordSpec, metadata: &Metadata) -> Self {
+        Self::record_with(generated, spec, metadata, &SystemClock, &mut thread_rng())
+    }
+
+    // with the time of recording from `clock` and the salt from `rng`, so tests can pin the
+    // whole transcript
+    pub fn record_with<C: Clock + ?Sized, R: Rng + ?Sized>(
+        generated: &GeneratedPassword,
+        spec: &PasswordSpec,
+        metadata: &Metadata,
+        clock: &C,
+        rng: &mut R,
+    ) -> Self {
+        let password = generated.password();
+        let mut transcript = Transcript {
+            generator_version: metadata.generator_version.to_string(),
+            rng_source: metadata.rng_source.to_string(),
+            spec_canonical: spec.to_spec_string(),
+            generated_at: metadata.timestamp,
+            recorded_at: clock.now(),
+            checks: checks(spec, &password),
+            salt: rng.gen(),
+            commitment: [0; 32],
+        };
+        transcript.commitment = transcript.commit(&password);
+        transcript
+    }
+
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TranscriptError> {
+        fs::read_to_string(path)
+            .map_err(TranscriptError::Io)?
+            .parse()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), TranscriptError> {
+        fs::write(path, self.to_string()).map_err(TranscriptError::Io)
+    }
+
+    // the lines the commitment covers, everything but the commitment itself
+    fn body(&self) -> String {
+        let mut body = format!(
+            "{}\nversion: {}\nrng: {}\n",
+            HEADER,
+            escape(&self.generator_version),
+            escape(&self.rng_source),
+        );
+        if let Some(spec) = &self.spec_canonical {
+            body.push_str(&format!("spec: {}\n", escape(spec)));
+        }
+        body.push_str(&format!(
+            "generated: {}\nrecorded: {}\n",
+            self.generated_at, self.recorded_at
+        ));
+        for check in &self.checks {
+            let result = if check.passed { "pass" } else { "fail" };
+            body.push_str(&format!("check: {} {}\n", result, escape(&check.name)));
+        }
+        body.push_str(&format!("salt: {}\n", hex::encode(&self.salt)));
+        body
+    }
+
+    fn commit(&self, password: &str) -> Digest {
+        let body = self.body();
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt);
+        hasher.update((body.len() as u64).to_be_bytes());
+        hasher.update(body.as_bytes());
+        hasher.update(password.as_bytes());
+        hasher.finalize().into()
+    }
+}
+
+// whether `candidate` is the password the transcript was recorded for, and the transcript is as
+// it was recorded
+pub fn verify(transcript: &Transcript, candidate: &str) -> bool {
+    transcript.commit(candidate) == transcript.commitment
+}
+
+fn checks(spec: &PasswordSpec, password: &str) -> Vec<Check> {
+    let violations = spec.violations(password);
+    let failed = |f: &dyn Fn(&Violation) -> bool| violations.iter().any(f);
+    let mut checks = vec![
+        Check {
+            name: "prefix".to_string(),
+            passed: !failed(&|v| matches!(v, Violation::MissingPrefix(_))),
+        },
+        Check {
+            name: "suffix".to_string(),
+            passed: !failed(&|v| matches!(v, Violation::MissingSuffix(_))),
+        },
+    ];
+    // without the affixes the rest can't be told apart, so they aren't checked
+    if checks.iter().any(|check| !check.passed) {
+        return checks;
+    }
+    checks.push(Check {
+        name: "length".to_string(),
+        passed: !failed(&|v| {
+            matches!(
+                v,
+                Violation::TooShort { .. }
+                    | Violation::TooLong { .. }
+                    | Violation::TooManyBytes { .. }
+                    | Violation::TotalLength { .. }
+            )
+        }),
+    });
+    for choice in spec.choices() {
+        checks.push(Check {
+            name: format!("class:{}", choice.name()),
+            passed: !failed(&|v| match v {
+                Violation::MissingClass { charset, .. }
+                | Violation::ExcessClass { charset, .. }
+                | Violation::CountNotAllowed { charset, .. } => charset == &choice.chars,
+                _ => false,
+            }),
+        });
+    }
+    checks.push(Check {
+        name: "allowed".to_string(),
+        passed: !failed(&|v| matches!(v, Violation::DisallowedChar { .. })),
+    });
+    checks.push(Check {
+        name: "adjacent".to_string(),
+        passed: !failed(&|v| matches!(v, Violation::Adjacent { .. })),
+    });
+    checks.push(Check {
+        name: "position".to_string(),
+        passed: !failed(&|v| matches!(v, Violation::PositionRule { .. })),
+    });
+    checks
+}
+
+impl Display for Transcript {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}commitment: {}",
+            self.body(),
+            hex::encode(&self.commitment)
+        )
+    }
+}
+
+impl FromStr for Transcript {
+    type Err = TranscriptError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().enumerate();
+        if lines.next().map(|(_, line)| line) != Some(HEADER) {
+            return Err(TranscriptError::BadHeader);
+        }
+        let (mut version, mut rng, mut spec, mut generated, mut recorded) =
+            (None, None, None, None, None);
+        let (mut salt, mut commitment) = (None, None);
+        let mut checks = vec![];
+        for (i, line) in lines {
+            let bad = || TranscriptError::BadLine(i + 1);
+            let (field, value) = line.split_once(": ").ok_or_else(bad)?;
+            match field {
+                "version" => version = Some(unescape(value).ok_or_else(bad)?),
+                "rng" => rng = Some(unescape(value).ok_or_else(bad)?),
+                "spec" => spec = Some(unescape(value).ok_or_else(bad)?),
+                "generated" => generated = Some(value.parse().map_err(|_| bad())?),
+                "recorded" => recorded = Some(value.parse().map_err(|_| bad())?),
+                "check" => {
+                    let (result, name) = value.split_once(' ').ok_or_else(bad)?;
+                    let passed = match result {
+                        "pass" => true,
+                        "fail" => false,
+                        _ => return Err(bad()),
+                    };
+                    let name = unescape(name).ok_or_else(bad)?;
+                    checks.push(Check { name, passed });
+                }
+                "salt" => salt = Some(hex::decode(value).ok_or_else(bad)?),
+                "commitment" => commitment = Some(hex::decode(value).ok_or_else(bad)?),
+                _ => return Err(bad()),
+            }
+        }
+        Ok(Transcript {
+            generator_version: version.ok_or(TranscriptError::Missing("version"))?,
+            rng_source: rng.ok_or(TranscriptError::Missing("rng"))?,
+            spec_canonical: spec,
+            generated_at: generated.ok_or(TranscriptError::Missing("generated"))?,
+            recorded_at: recorded.ok_or(TranscriptError::Missing("recorded"))?,
+            checks,
+            salt: salt.ok_or(TranscriptError::Missing("salt"))?,
+            commitment: commitment.ok_or(TranscriptError::Missing("commitment"))?,
+        })
+    }
+}
+
+// values stay on one line, custom charsets in the spec can have any character
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(s: &str) -> Option<String> {
+    let mut res = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '\\' => res.push('\\'),
+            'n' => res.push('\n'),
+            'r' => res.push('\r'),
+            _ => return None,
+        }
+    }
+    Some(res)
+}
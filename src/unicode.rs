@@ -0,0 +1,121 @@
+// Unicode normalization of custom charsets, affixes and the passwords checked against a spec.
+// `é` can be one character or an `e` followed by a combining accent. Without normalizing, a
+// charset written one way doesn't contain the password typed the other way, and the two forms in
+// one charset aren't repeats of each other.
+//
+// Lengths count characters unless given in graphemes, see `units`, so a decomposed `é` is two
+// characters long and a password can get shorter when it's normalized. Under `Nfc` the normalized password is the one
+// whose length and characters are checked.
+use std::borrow::Cow;
+
+use unicode_normalization::{
+    char::canonical_combining_class, is_nfc, is_nfc_quick, IsNormalized, UnicodeNormalization,
+};
+
+use crate::{
+    choice::{Choice, Choices},
+    format,
+    password::{PasswordParseError, PasswordSpec},
+    position::PositionRule,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Normalization {
+    // custom charsets and affixes are put in NFC as they're added, passwords before they're
+    // checked, and generated passwords are in NFC unless the suffix starts with a combining
+    // character
+    #[default]
+    Nfc,
+    // everything is taken as given
+    None,
+}
+
+impl Normalization {
+    pub fn apply<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        match self {
+            Normalization::Nfc if !is_nfc(s) => Cow::Owned(s.nfc().collect()),
+            _ => Cow::Borrowed(s),
+        }
+    }
+
+    // the characters of a custom charset, repeats are kept so they still show up in lints
+    // under NFC characters that could combine with a neighbour, like a lone combining accent, are
+    // left out, drawing them could give a password that isn't in NFC
+    pub(crate) fn chars(&self, chars: &[char]) -> Vec<char> {
+        match self {
+            Normalization::Nfc => chars
+                .iter()
+                .collect::<String>()
+                .nfc()
+                .filter(|c| stable(*c))
+                .collect(),
+            Normalization::None => chars.to_vec(),
+        }
+    }
+}
+
+// a character that stays as it is whatever is next to it, any string of these is in NFC
+fn stable(c: char) -> bool {
+    canonical_combining_class(c) == 0 && is_nfc_quick(std::iter::once(c)) == IsNormalized::Yes
+}
+
+impl PositionRule {
+    pub(crate) fn normalized(self, normalization: Normalization) -> PositionRule {
+        match self {
+            PositionRule::MustBe(charset) => {
+                PositionRule::MustBe(charset.normalized(normalization))
+            }
+            PositionRule::MustNotBe(charset) => {
+                PositionRule::MustNotBe(charset.normalized(normalization))
+            }
+        }
+    }
+}
+
+impl PasswordSpec {
+    // a spec string with its custom charsets normalized this way, parsing normalizes to NFC
+    pub fn parse_with(s: &str, normalization: Normalization) -> Result<Self, PasswordParseError> {
+        format::parse(s, normalization)
+    }
+
+    // how custom charsets, affixes and checked passwords are normalized, NFC unless set otherwise
+    // switching to NFC normalizes what the spec already has, switching it off keeps things as
+    // they are, so it has to come before the charsets to keep them as given
+    pub fn normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        if normalization == Normalization::None {
+            return self;
+        }
+        let choices: Vec<Choice> = self
+            .choices()
+            .cloned()
+            .map(|mut choice| {
+                choice.chars = choice.chars.normalized(normalization);
+                choice
+            })
+            .collect();
+        self.choices = Choices::from(choices);
+        self.allowed = self
+            .allowed
+            .map(|allowed| allowed.normalized(normalization));
+        self.prefix = normalization.apply(&self.prefix).into_owned();
+        self.suffix = normalization.apply(&self.suffix).into_owned();
+        let mut rules: Vec<_> = self
+            .position_rules
+            .into_iter()
+            .map(|(start, end, rule)| (start, end, rule.normalized(normalization)))
+            .collect();
+        rules.sort();
+        rules.dedup();
+        self.position_rules = rules;
+        let mut no_adjacent: Vec<_> = self
+            .no_adjacent
+            .into_iter()
+            .map(|charset| charset.normalized(normalization))
+            .collect();
+        no_adjacent.sort();
+        no_adjacent.dedup();
+        self.no_adjacent = no_adjacent;
+        self
+    }
+}
@@ -0,0 +1,226 @@
+// Lengths in bytes or graphemes rather than characters, for fields limited to a number of bytes
+// and for counting what people see. The unit follows the length in the spec string, `64B//...`
+// for bytes and `24g//...` for graphemes, characters are the default and can be written `32c`.
+// A length in bits is always a number of characters.
+//
+// Generating draws characters as usual and keeps a draw when it measures the length in the unit,
+// the number of characters drawn is picked from those that could. What works in characters,
+// like entropy, lints, chunks and `feasible_lengths`, takes the length as a number of characters.
+// Every number of characters that could measure the length is tried, so a length in bytes or
+// graphemes goes up to `MAX_MEASURED_LENGTH` and a longer one is a `MeasuredTooLong` error. Marks
+// can stack onto a character without end, so graphemes are taken to be at most
+// `MAX_CLUSTER_CHARS` characters and lengths needing longer ones aren't tried.
+//
+// `max_bytes` caps the UTF-8 size of the whole password separately, so a length in characters
+// can still come with a byte limit.
+use std::fmt::Display;
+
+use rand::{seq::SliceRandom, Rng};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    charset::Charset,
+    length_policy::Decorations,
+    password::{GenerateError, Length, PasswordSpec},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LengthUnit {
+    #[default]
+    Chars,
+    Bytes,
+    // extended grapheme clusters
+    Graphemes,
+}
+
+// longest length in bytes or graphemes generating works out the numbers of characters for
+pub const MAX_MEASURED_LENGTH: usize = 4096;
+// most characters in one grapheme a length in graphemes is worked out for, enough for emoji ZWJ
+// sequences like the families of seven
+pub const MAX_CLUSTER_CHARS: usize = 8;
+// draws tried for a length in bytes or graphemes, or under a byte cap, before giving up
+const MEASURE_ATTEMPTS: usize = 1000;
+// characters in a charset beyond which they aren't checked for joining into one grapheme, and
+// graphemes they could make beyond which they aren't followed, larger ones are assumed to join
+// into graphemes of `MAX_CLUSTER_CHARS`
+const JOIN_CHECK_LIMIT: usize = 512;
+
+impl LengthUnit {
+    pub fn measure(&self, s: &str) -> usize {
+        match self {
+            LengthUnit::Chars => s.chars().count(),
+            LengthUnit::Bytes => s.len(),
+            LengthUnit::Graphemes => s.graphemes(true).count(),
+        }
+    }
+
+    // what follows the length in a spec string, nothing for characters
+    pub(crate) fn suffix(&self) -> &'static str {
+        match self {
+            LengthUnit::Chars => "",
+            LengthUnit::Bytes => "B",
+            LengthUnit::Graphemes => "g",
+        }
+    }
+
+    pub(crate) fn from_suffix(c: char) -> Option<Self> {
+        match c {
+            'c' => Some(LengthUnit::Chars),
+            'B' => Some(LengthUnit::Bytes),
+            'g' => Some(LengthUnit::Graphemes),
+            _ => None,
+        }
+    }
+}
+
+impl Display for LengthUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LengthUnit::Chars => write!(f, "characters"),
+            LengthUnit::Bytes => write!(f, "bytes"),
+            LengthUnit::Graphemes => write!(f, "graphemes"),
+        }
+    }
+}
+
+impl PasswordSpec {
+    // what the length counts, characters unless set otherwise
+    pub fn length_unit(mut self, unit: LengthUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    // the most bytes the whole password can take in UTF-8, affixes included, whatever the length
+    // is counted in
+    pub fn max_bytes(mut self, max: usize) -> Self {
+        self.max_bytes = Some(max);
+        self
+    }
+
+    // the unit the length is measured in, a length in bits is in characters whatever is set
+    pub fn unit(&self) -> LengthUnit {
+        match self.length {
+            Length::Bits(_) => LengthUnit::Chars,
+            Length::Chars(_) => self.unit,
+        }
+    }
+
+    pub(crate) fn within_max_bytes(&self, password: &str) -> bool {
+        self.max_bytes.map_or(true, |max| password.len() <= max)
+    }
+
+    // whether a draw of characters has to be measured before it's kept
+    pub(crate) fn measured(&self) -> bool {
+        self.unit() != LengthUnit::Chars || self.max_bytes.is_some()
+    }
+
+    // characters for the random core measuring the length in the unit and keeping the password
+    // under the byte cap, by drawing a number of characters that could and trying again when
+    // they don't
+    pub(crate) fn draw_measured<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<Vec<(char, Charset)>, GenerateError> {
+        self.check_min_entropy()?;
+        self.check_total_length(&Decorations::new())?;
+        let target = self.resolve_length()?;
+        if self.unit() != LengthUnit::Chars && target > MAX_MEASURED_LENGTH {
+            return Err(GenerateError::MeasuredTooLong {
+                unit: self.unit(),
+                length: target,
+                max: MAX_MEASURED_LENGTH,
+            });
+        }
+        let lengths = self.char_lengths(target);
+        if lengths.is_empty() {
+            // no choices or an empty charset are reported as they are for characters
+            let error = self.in_chars(target).generate_drawn(rng).err();
+            return Err(error.unwrap_or(GenerateError::Infeasible));
+        }
+        let affixes = self.prefix.len() + self.suffix.len();
+        for _ in 0..MEASURE_ATTEMPTS {
+            let length = *lengths.choose(rng).unwrap();
+            let drawn = self.in_chars(length).generate_drawn(rng)?;
+            let core: String = drawn.iter().map(|(c, _)| c).collect();
+            if self.unit().measure(&core) == target
+                && self
+                    .max_bytes
+                    .map_or(true, |max| affixes + core.len() <= max)
+            {
+                return Ok(drawn);
+            }
+        }
+        Err(GenerateError::AttemptsExhausted {
+            attempts: MEASURE_ATTEMPTS,
+        })
+    }
+
+    // the spec for a random core of exactly `length` characters
+    fn in_chars(&self, length: usize) -> PasswordSpec {
+        let mut spec = self.clone().length(length).length_unit(LengthUnit::Chars);
+        spec.affixes_in_length = false;
+        spec.max_bytes = None;
+        spec.min_entropy = None;
+        spec.total_length = None;
+        spec
+    }
+
+    // numbers of characters the spec can make that could measure `target` in the unit
+    fn char_lengths(&self, target: usize) -> Vec<usize> {
+        let mut pool: Vec<char> = self
+            .drawn_choices()
+            .iter()
+            .flat_map(|choice| self.pool(choice))
+            .collect();
+        pool.sort_unstable();
+        pool.dedup();
+        let (min, max) = match self.unit() {
+            LengthUnit::Chars => return vec![target],
+            LengthUnit::Bytes => {
+                let widths = pool.iter().map(|c| c.len_utf8());
+                match (widths.clone().min(), widths.max()) {
+                    (Some(narrowest), Some(widest)) => {
+                        ((target + widest - 1) / widest, target / narrowest)
+                    }
+                    _ => return vec![],
+                }
+            }
+            // characters can only join into fewer graphemes, never split into more
+            LengthUnit::Graphemes => (target, target.saturating_mul(longest_cluster(&pool))),
+        };
+        (min..=max)
+            .filter(|length| self.in_chars(*length).check().unwrap_or(false))
+            .collect()
+    }
+}
+
+// the most characters of `pool` that make a single grapheme, up to `MAX_CLUSTER_CHARS`
+// graphemes are grown a character at a time for as long as any of them stays one
+fn longest_cluster(pool: &[char]) -> usize {
+    if pool.len() > JOIN_CHECK_LIMIT {
+        return MAX_CLUSTER_CHARS;
+    }
+    let mut clusters: Vec<String> = pool.iter().map(|c| c.to_string()).collect();
+    let mut longest = 1;
+    while longest < MAX_CLUSTER_CHARS {
+        clusters = clusters
+            .iter()
+            .flat_map(|cluster| {
+                pool.iter().map(move |c| {
+                    let mut longer = cluster.clone();
+                    longer.push(*c);
+                    longer
+                })
+            })
+            .filter(|longer| longer.graphemes(true).count() == 1)
+            .collect();
+        if clusters.is_empty() {
+            break;
+        }
+        if clusters.len() > JOIN_CHECK_LIMIT {
+            return MAX_CLUSTER_CHARS;
+        }
+        longest += 1;
+    }
+    longest
+}
@@ -0,0 +1,164 @@
+// How much goes to stderr beside the passwords, from nothing but errors with `-q` to the details of
+// how the spec was worked out with `-vv`. Messages go through a `Logger` that drops what the
+// verbosity doesn't show, stdout is never written to, so the passwords come out the same at
+// every level.
+//
+// Only things about the spec are logged, never what was generated. The messages take anything
+// `Display`, which `GeneratedPassword` is too, so passwords have to be kept away from it; the
+// notes from `spec` only describe the spec.
+use std::{
+    fmt::Display,
+    io::{self, Write},
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+use crate::password::PasswordSpec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    // shown even with `-q`, what went wrong when there's no output
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Verbosity {
+    // errors only
+    Quiet,
+    // errors and warnings
+    #[default]
+    Normal,
+    // `-v`, what the spec resolved to
+    Verbose,
+    // `-vv`, everything
+    Debug,
+}
+
+#[derive(Debug, Error)]
+#[error("unknown verbosity `{0}`, expected quiet, normal, verbose or debug")]
+pub struct VerbosityParseError(String);
+
+impl FromStr for Verbosity {
+    type Err = VerbosityParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "quiet" => Ok(Verbosity::Quiet),
+            "normal" => Ok(Verbosity::Normal),
+            "verbose" => Ok(Verbosity::Verbose),
+            "debug" => Ok(Verbosity::Debug),
+            _ => Err(VerbosityParseError(s.to_string())),
+        }
+    }
+}
+
+impl Verbosity {
+    // `-q` and the number of `-v`s, quiet wins when both are given
+    pub fn from_flags(quiet: bool, verbose: u8) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::Debug,
+        }
+    }
+
+    pub fn shows(&self, level: Level) -> bool {
+        match self {
+            Verbosity::Quiet => level == Level::Error,
+            Verbosity::Normal => level <= Level::Warn,
+            Verbosity::Verbose => level <= Level::Info,
+            Verbosity::Debug => true,
+        }
+    }
+}
+
+// messages for stderr, or any other writer, at a verbosity
+pub struct Logger<W> {
+    verbosity: Verbosity,
+    sink: W,
+}
+
+impl Logger<io::Stderr> {
+    pub fn stderr(verbosity: Verbosity) -> Self {
+        Self::new(verbosity, io::stderr())
+    }
+}
+
+impl<W: Write> Logger<W> {
+    pub fn new(verbosity: Verbosity, sink: W) -> Self {
+        Self { verbosity, sink }
+    }
+
+    pub fn verbosity(&self) -> Verbosity {
+        self.verbosity
+    }
+
+    // one line, prefixed with the level below info, failing to write to stderr isn't worth
+    // stopping for
+    pub fn log(&mut self, level: Level, message: impl Display) {
+        if !self.verbosity.shows(level) {
+            return;
+        }
+        let _ = match level {
+            Level::Error => writeln!(self.sink, "error: {}", message),
+            Level::Warn => writeln!(self.sink, "warning: {}", message),
+            Level::Info => writeln!(self.sink, "{}", message),
+            Level::Debug => writeln!(self.sink, "debug: {}", message),
+        };
+    }
+
+    pub fn error(&mut self, message: impl Display) {
+        self.log(Level::Error, message)
+    }
+
+    pub fn warn(&mut self, message: impl Display) {
+        self.log(Level::Warn, message)
+    }
+
+    pub fn info(&mut self, message: impl Display) {
+        self.log(Level::Info, message)
+    }
+
+    pub fn debug(&mut self, message: impl Display) {
+        self.log(Level::Debug, message)
+    }
+
+    // what there is to say about a spec before generating from it: lint warnings, then the
+    // resolved spec and its entropy, then the fields behind it
+    pub fn spec(&mut self, spec: &PasswordSpec) {
+        for warning in spec.lint() {
+            self.warn(warning);
+        }
+        if !self.verbosity.shows(Level::Info) {
+            return;
+        }
+        match spec.resolved() {
+            Ok(resolved) => {
+                self.info(format_args!("spec: {}", resolved));
+                self.info(format_args!(
+                    "entropy: {:.1} bits guaranteed, {:.1} on average",
+                    resolved.entropy().unwrap_or(0.0),
+                    resolved.entropy_bits()
+                ));
+            }
+            Err(e) => self.info(format_args!("spec: {} ({})", spec, e)),
+        }
+        self.debug(format_args!("{:?}", spec.raw_debug()));
+    }
+
+    // a candidate a constraint turned down, for counting how many it took
+    pub fn rejected(&mut self, attempt: usize, constraint: &str) {
+        self.debug(format_args!(
+            "attempt {} rejected by `{}`",
+            attempt, constraint
+        ));
+    }
+
+    pub fn into_inner(self) -> W {
+        self.sink
+    }
+}
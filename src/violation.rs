@@ -0,0 +1,309 @@
+// Everything wrong with a password against a spec, for checking passwords people chose themselves
+// or telling a form what to fix. Each violation has a stable code for programs with the message
+// on top for people.
+use std::fmt::Display;
+
+use crate::{
+    charset::Charset, choice::name, interval::Interval, length_policy, password::PasswordSpec,
+    units::LengthUnit,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    MissingPrefix(String),
+    MissingSuffix(String),
+    // lengths in the unit of the spec's length
+    TooShort {
+        expected: usize,
+        actual: usize,
+        unit: LengthUnit,
+    },
+    TooLong {
+        expected: usize,
+        actual: usize,
+        unit: LengthUnit,
+    },
+    // more UTF-8 bytes than `max_bytes`, affixes included
+    TooManyBytes {
+        max: usize,
+        actual: usize,
+    },
+    // the whole password outside the total length, in the unit of the spec's length
+    TotalLength {
+        allowed: Interval,
+        actual: usize,
+        unit: LengthUnit,
+    },
+    // fewer characters from the charset than any interval allows
+    MissingClass {
+        charset: Charset,
+        label: Option<String>,
+        min: usize,
+        count: usize,
+    },
+    // more characters from the charset than any interval allows
+    ExcessClass {
+        charset: Charset,
+        label: Option<String>,
+        max: usize,
+        count: usize,
+    },
+    // a count between the intervals of a union, e.g. 1 for `0,2`
+    CountNotAllowed {
+        charset: Charset,
+        label: Option<String>,
+        count: usize,
+    },
+    // a character in none of the spec's charsets, or not allowed, the position counts from 1
+    DisallowedChar {
+        c: char,
+        position: usize,
+    },
+    // a character one of the position rules doesn't allow where it is, counting from 1 as well
+    PositionRule {
+        c: char,
+        position: usize,
+    },
+    // a character right after another from a charset kept apart, at the position of the second
+    Adjacent {
+        charset: Charset,
+        position: usize,
+    },
+}
+
+// charset part of the codes, custom charsets all share one name so codes stay a fixed set
+fn class_name(charset: &Charset) -> &'static str {
+    match charset {
+        Charset::Upper => "upper",
+        Charset::Lower => "lower",
+        Charset::Number => "number",
+        Charset::NumberNoZero => "number_no_zero",
+        Charset::Symbol => "symbol",
+        Charset::Base58 => "base58",
+        Charset::UpperLatin1 => "upper_latin1",
+        Charset::LowerLatin1 => "lower_latin1",
+        Charset::Custom(_) => "custom",
+    }
+}
+
+impl Violation {
+    // stable identifier, e.g. `too_short` or `missing_class:upper`, these don't change between
+    // versions
+    pub fn code(&self) -> String {
+        match self {
+            Violation::MissingPrefix(_) => "missing_prefix".to_string(),
+            Violation::MissingSuffix(_) => "missing_suffix".to_string(),
+            Violation::TooShort { .. } => "too_short".to_string(),
+            Violation::TooLong { .. } => "too_long".to_string(),
+            Violation::TooManyBytes { .. } => "too_many_bytes".to_string(),
+            Violation::TotalLength { .. } => "total_length".to_string(),
+            Violation::MissingClass { charset, .. } => {
+                format!("missing_class:{}", class_name(charset))
+            }
+            Violation::ExcessClass { charset, .. } => {
+                format!("excess_class:{}", class_name(charset))
+            }
+            Violation::CountNotAllowed { charset, .. } => {
+                format!("count_not_allowed:{}", class_name(charset))
+            }
+            Violation::DisallowedChar { .. } => "disallowed_char".to_string(),
+            Violation::PositionRule { .. } => "position_rule".to_string(),
+            Violation::Adjacent { charset, .. } => format!("adjacent:{}", class_name(charset)),
+        }
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::MissingPrefix(prefix) => write!(f, "Doesn't start with `{}`", prefix),
+            Violation::MissingSuffix(suffix) => write!(f, "Doesn't end with `{}`", suffix),
+            Violation::TooShort {
+                expected,
+                actual,
+                unit,
+            } => {
+                write!(f, "Has {} {}, needs {}", actual, unit, expected)
+            }
+            Violation::TooLong {
+                expected,
+                actual,
+                unit,
+            } => {
+                write!(f, "Has {} {}, allows only {}", actual, unit, expected)
+            }
+            Violation::TooManyBytes { max, actual } => {
+                write!(f, "Takes {} bytes, allows at most {}", actual, max)
+            }
+            Violation::TotalLength {
+                allowed,
+                actual,
+                unit,
+            } => write!(
+                f,
+                "Has {} {} in all, needs {}",
+                actual,
+                unit,
+                length_policy::bounds(allowed)
+            ),
+            Violation::MissingClass {
+                charset,
+                label,
+                min,
+                count,
+            } => write!(
+                f,
+                "Has {} characters from {}, needs at least {}",
+                count,
+                name(charset, label),
+                min
+            ),
+            Violation::ExcessClass {
+                charset,
+                label,
+                max,
+                count,
+            } => write!(
+                f,
+                "Has {} characters from {}, allows at most {}",
+                count,
+                name(charset, label),
+                max
+            ),
+            Violation::CountNotAllowed {
+                charset,
+                label,
+                count,
+            } => {
+                write!(
+                    f,
+                    "Has {} characters from {}, which isn't allowed",
+                    count,
+                    name(charset, label)
+                )
+            }
+            Violation::DisallowedChar { c, position } => {
+                write!(f, "Character {} `{}` isn't allowed", position, c)
+            }
+            Violation::PositionRule { c, position } => {
+                write!(
+                    f,
+                    "Character {} `{}` isn't allowed at that position",
+                    position, c
+                )
+            }
+            Violation::Adjacent { charset, position } => write!(
+                f,
+                "Characters {} and {} are both from {}, which can't be next to each other",
+                position - 1,
+                position,
+                charset
+            ),
+        }
+    }
+}
+
+impl PasswordSpec {
+    // empty exactly when `matches` accepts the password, unless the spec itself can't resolve
+    // its length, then the length isn't checked
+    // a missing prefix or suffix is the only violation reported, the rest can't be told apart
+    // without knowing where the random part is
+    // the password is normalized first like the spec's charsets, see `Normalization`, positions
+    // are in the normalized password
+    pub fn violations(&self, password: &str) -> Vec<Violation> {
+        let password = self.normalization.apply(password);
+        let password = password.as_ref();
+        let mut violations = vec![];
+        if !password.starts_with(self.prefix.as_str()) {
+            violations.push(Violation::MissingPrefix(self.prefix.clone()));
+        }
+        let core = password
+            .strip_prefix(self.prefix.as_str())
+            .unwrap_or(password);
+        if !core.ends_with(self.suffix.as_str()) {
+            violations.push(Violation::MissingSuffix(self.suffix.clone()));
+        }
+        if !violations.is_empty() {
+            return violations;
+        }
+        let core = core.strip_suffix(self.suffix.as_str()).unwrap_or(core);
+        let unit = self.unit();
+        let actual = unit.measure(core);
+        match self.resolve_length() {
+            Ok(expected) if actual < expected => violations.push(Violation::TooShort {
+                expected,
+                actual,
+                unit,
+            }),
+            Ok(expected) if actual > expected => violations.push(Violation::TooLong {
+                expected,
+                actual,
+                unit,
+            }),
+            _ => {}
+        }
+        if let Some(max) = self.max_bytes.filter(|max| password.len() > *max) {
+            violations.push(Violation::TooManyBytes {
+                max,
+                actual: password.len(),
+            });
+        }
+        if let Some(allowed) = self.total_length.as_ref() {
+            if !self.within_total_length(password) {
+                violations.push(Violation::TotalLength {
+                    allowed: allowed.clone(),
+                    actual: unit.measure(password),
+                    unit,
+                });
+            }
+        }
+        for (choice, (charset, count)) in self.choices().zip(self.count_by_charset(core)) {
+            if choice.admits(count) {
+                continue;
+            }
+            let label = choice.label.clone();
+            violations.push(if count < choice.min {
+                Violation::MissingClass {
+                    charset,
+                    label,
+                    min: choice.min,
+                    count,
+                }
+            } else if count > choice.max {
+                Violation::ExcessClass {
+                    charset,
+                    label,
+                    max: choice.max,
+                    count,
+                }
+            } else {
+                Violation::CountNotAllowed {
+                    charset,
+                    label,
+                    count,
+                }
+            });
+        }
+        for (i, c) in core.chars().enumerate() {
+            if !self.is_allowed(c) {
+                violations.push(Violation::DisallowedChar {
+                    c,
+                    position: self.prefix.chars().count() + i + 1,
+                });
+            }
+        }
+        for (i, charset) in self.adjacency_breaks(core) {
+            violations.push(Violation::Adjacent {
+                charset,
+                position: self.prefix.chars().count() + i + 1,
+            });
+        }
+        for (i, c) in self.position_rule_breaks(core) {
+            violations.push(Violation::PositionRule {
+                c,
+                position: self.prefix.chars().count() + i + 1,
+            });
+        }
+        violations
+    }
+}
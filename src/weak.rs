@@ -0,0 +1,201 @@
+// Deliberately weak passwords for testing validators, each wrong in one named way and otherwise
+// matching the spec they're based on as far as that allows.
+use std::{fmt::Display, str::FromStr};
+
+use rand::{seq::SliceRandom, Rng};
+use thiserror::Error;
+
+use crate::{
+    charset::Charset,
+    password::{GenerateError, PasswordSpec},
+    units::LengthUnit,
+};
+
+// words any password checker should refuse, all lowercase
+pub const DICTIONARY_WORDS: &[&str] = &["password", "letmein", "qwerty", "welcome", "dragon"];
+// how many characters in a row make a sequential run, like `abcd` or `6789`
+pub const SEQUENCE_LENGTH: usize = 4;
+
+const SEQUENCES: &[&str] = &[
+    "abcdefghijklmnopqrstuvwxyz",
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+    "0123456789",
+];
+// passwords tried when placing a word or run, looking for one that still matches the spec
+const MAX_ATTEMPTS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WeaknessKind {
+    // a character shorter than the spec's length
+    TooShort,
+    // none of a charset the spec requires at least one of
+    MissingClass(Charset),
+    // one of `DICTIONARY_WORDS` somewhere in the password
+    ContainsDictionaryWord,
+    // a run of `SEQUENCE_LENGTH` consecutive letters or digits
+    Sequential,
+}
+
+#[derive(Debug, Error)]
+pub enum WeakError {
+    #[error("Unknown weakness `{0}`, expect too-short, missing-class:<charset>, dictionary-word or sequential")]
+    UnknownKind(String),
+    #[error(
+        "The spec doesn't require any characters from `{0}`, so leaving them out isn't a weakness"
+    )]
+    NotRequired(Charset),
+    #[error("The spec has no length to go below")]
+    NoLength,
+    #[error("The password is too short to hold `{0}`")]
+    TooShortFor(String),
+    #[error("{0}")]
+    Generate(GenerateError),
+}
+
+impl Display for WeaknessKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WeaknessKind::TooShort => write!(f, "too-short"),
+            WeaknessKind::MissingClass(charset) => write!(f, "missing-class:{}", charset),
+            WeaknessKind::ContainsDictionaryWord => write!(f, "dictionary-word"),
+            WeaknessKind::Sequential => write!(f, "sequential"),
+        }
+    }
+}
+
+// the forms `pants-gen weak --kind` takes, the charset of `missing-class:` is a name like
+// `symbol` or a charset in the spec syntax
+impl FromStr for WeaknessKind {
+    type Err = WeakError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "too-short" => Ok(WeaknessKind::TooShort),
+            "dictionary-word" => Ok(WeaknessKind::ContainsDictionaryWord),
+            "sequential" => Ok(WeaknessKind::Sequential),
+            _ => {
+                let charset = s
+                    .strip_prefix("missing-class:")
+                    .and_then(|name| format!(":{}:", name).parse().or_else(|_| name.parse()).ok())
+                    .ok_or_else(|| WeakError::UnknownKind(s.to_string()))?;
+                Ok(WeaknessKind::MissingClass(charset))
+            }
+        }
+    }
+}
+
+// a password that violates `base_spec` in the way `kind` names
+// too short lowers the minimums when they don't fit the shorter length, so the password can also
+// be missing characters the spec requires, and a word or run that can't be placed anywhere the
+// spec allows is placed anyway, every other kind matches the spec apart from its weakness
+pub fn generate<R: Rng + ?Sized>(
+    kind: &WeaknessKind,
+    base_spec: &PasswordSpec,
+    rng: &mut R,
+) -> Result<String, WeakError> {
+    match kind {
+        WeaknessKind::TooShort => {
+            let core = base_spec.resolve_length().map_err(WeakError::Generate)?;
+            if core == 0 {
+                return Err(WeakError::NoLength);
+            }
+            let affixes = if base_spec.affixes_in_length {
+                base_spec.unit().measure(&base_spec.prefix)
+                    + base_spec.unit().measure(&base_spec.suffix)
+            } else {
+                0
+            };
+            let (short, _) = base_spec.relax_to_length(core - 1 + affixes);
+            generate_from(&short, rng)
+        }
+        WeaknessKind::MissingClass(charset) => {
+            let required = base_spec
+                .choices()
+                .any(|choice| &choice.chars == charset && choice.min > 0);
+            if !required {
+                return Err(WeakError::NotRequired(charset.clone()));
+            }
+            let missing = base_spec.clone().include(charset.clone().exactly(0));
+            generate_from(&missing, rng)
+        }
+        WeaknessKind::ContainsDictionaryWord => {
+            let fragments = DICTIONARY_WORDS
+                .iter()
+                .flat_map(|word| [word.to_string(), word.to_uppercase()])
+                .collect();
+            embed(base_spec, fragments, rng)
+        }
+        WeaknessKind::Sequential => {
+            let fragments = SEQUENCES
+                .iter()
+                .flat_map(|sequence| {
+                    let chars: Vec<char> = sequence.chars().collect();
+                    chars
+                        .windows(SEQUENCE_LENGTH)
+                        .map(|run| run.iter().collect())
+                        .collect::<Vec<String>>()
+                })
+                .collect();
+            embed(base_spec, fragments, rng)
+        }
+    }
+}
+
+fn generate_from<R: Rng + ?Sized>(spec: &PasswordSpec, rng: &mut R) -> Result<String, WeakError> {
+    spec.generate_detailed_with_rng(rng)
+        .map(|generated| generated.password())
+        .map_err(WeakError::Generate)
+}
+
+// a password of the spec with one of the fragments written over part of its random core,
+// fragments made of allowed characters are preferred
+// the core is counted in characters of each generated password, a length in bytes or graphemes
+// can give a different number of them every time
+fn embed<R: Rng + ?Sized>(
+    spec: &PasswordSpec,
+    fragments: Vec<String>,
+    rng: &mut R,
+) -> Result<String, WeakError> {
+    let too_short = || {
+        let shortest = fragments
+            .iter()
+            .min_by_key(|fragment| fragment.chars().count());
+        WeakError::TooShortFor(shortest.cloned().unwrap_or_default())
+    };
+    let affixes = spec.prefix.chars().count() + spec.suffix.chars().count();
+    let prefix = spec.prefix.chars().count();
+    let mut candidate = None;
+    for _ in 0..MAX_ATTEMPTS {
+        let password = generate_from(spec, rng)?;
+        let mut chars: Vec<char> = password.chars().collect();
+        let core = chars.len().saturating_sub(affixes);
+        let fitting: Vec<&String> = fragments
+            .iter()
+            .filter(|fragment| fragment.chars().count() <= core)
+            .collect();
+        if fitting.is_empty() {
+            // in characters every password has the same core, no other draw will fit either
+            if spec.unit() == LengthUnit::Chars {
+                return Err(too_short());
+            }
+            continue;
+        }
+        let allowed: Vec<&String> = fitting
+            .iter()
+            .copied()
+            .filter(|fragment| fragment.chars().all(|c| spec.is_allowed(c)))
+            .collect();
+        let fragments = if allowed.is_empty() { fitting } else { allowed };
+        let fragment = fragments.choose(rng).expect("there is always a fragment");
+        let length = fragment.chars().count();
+        let start = prefix + rng.gen_range(0..=core - length);
+        for (i, c) in fragment.chars().enumerate() {
+            chars[start + i] = c;
+        }
+        let password: String = chars.into_iter().collect();
+        if spec.matches(&password) {
+            return Ok(password);
+        }
+        candidate = Some(password);
+    }
+    candidate.ok_or_else(too_short)
+}
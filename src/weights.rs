@@ -0,0 +1,111 @@
+use std::{
+    collections::HashMap,
+    fs,
+    hash::{Hash, Hasher},
+    path::Path,
+};
+
+use rand::{seq::SliceRandom, Rng};
+use thiserror::Error;
+
+use crate::comments;
+
+// per character weights used when sampling from a charset
+// characters of the charset that aren't in the table get a weight of 1.0 and entries for
+// characters that aren't in the charset are ignored, so one frequency table can be shared across
+// several charsets
+#[derive(Debug, Clone, PartialEq)]
+pub struct Weights {
+    weights: HashMap<char, f64>,
+}
+
+// the weights are finite and positive, so comparing them is total
+impl Eq for Weights {}
+
+impl Hash for Weights {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut entries: Vec<(char, u64)> = self
+            .weights
+            .iter()
+            .map(|(c, w)| (*c, w.to_bits()))
+            .collect();
+        entries.sort_unstable();
+        entries.hash(state);
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum WeightsError {
+    #[error("Weight for `{0}` must be a finite number greater than zero, got {1}")]
+    BadWeight(char, f64),
+    #[error("The weights add up to more than a finite number, got {0}")]
+    TotalTooLarge(f64),
+    #[error("`{0}` was given a weight more than once")]
+    Duplicate(char),
+    #[error("Line {0} is not of the form char,weight: `{1}`")]
+    BadLine(usize, String),
+    #[error("{0}")]
+    Io(std::io::Error),
+}
+
+impl Weights {
+    pub fn new(weights: Vec<(char, f64)>) -> Result<Self, WeightsError> {
+        let mut table = HashMap::new();
+        for (c, w) in weights {
+            if !w.is_finite() || w <= 0.0 {
+                return Err(WeightsError::BadWeight(c, w));
+            }
+            if table.insert(c, w).is_some() {
+                return Err(WeightsError::Duplicate(c));
+            }
+        }
+        // sampling and entropy divide by the total of the weights drawn from, which has to stay
+        // finite however many of them a pool takes
+        let total: f64 = table.values().sum();
+        if !total.is_finite() {
+            return Err(WeightsError::TotalTooLarge(total));
+        }
+        Ok(Self { weights: table })
+    }
+
+    // file of `char,weight` lines, with comments and blank lines as in `comments`
+    // the character is always the first character of the line so `,,0.5` weights the comma and
+    // `\#,0.5` the hash, lines are trimmed so whitespace can't be weighted
+    pub fn from_csv(path: impl AsRef<Path>) -> Result<Self, WeightsError> {
+        let contents = fs::read_to_string(path).map_err(WeightsError::Io)?;
+        let mut weights = vec![];
+        for (i, line) in comments::lines(&contents) {
+            let bad_line = || WeightsError::BadLine(i, line.clone());
+            let mut chars = line.chars();
+            let c = chars.next().ok_or_else(bad_line)?;
+            if chars.next() != Some(',') {
+                return Err(bad_line());
+            }
+            let w = chars.as_str().trim().parse().map_err(|_| bad_line())?;
+            weights.push((c, w));
+        }
+        Self::new(weights)
+    }
+
+    pub fn weight(&self, c: char) -> f64 {
+        self.weights.get(&c).copied().unwrap_or(1.0)
+    }
+
+    pub fn choose<R: Rng + ?Sized>(&self, pool: &[char], rng: &mut R) -> Option<char> {
+        pool.choose_weighted(rng, |c| self.weight(*c)).ok().copied()
+    }
+
+    // shannon entropy in bits of a single draw from the pool
+    pub fn entropy(&self, pool: &[char]) -> f64 {
+        if pool.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = pool.iter().map(|c| self.weight(*c)).sum();
+        pool.iter()
+            .map(|c| {
+                let p = self.weight(*c) / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+}
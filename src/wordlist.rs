@@ -0,0 +1,117 @@
+// Words for passphrases, read from a file or any reader such as stdin.
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use thiserror::Error;
+
+// lists with fewer distinct words than this give noticeably weak passphrases
+pub const RECOMMENDED_MIN_WORDS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wordlist {
+    words: Vec<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum WordlistError {
+    #[error("Wordlist has no words")]
+    Empty,
+    #[error("Words can come from a wordlist file or stdin, not both")]
+    ConflictingSources,
+    #[error("No wordlist given, expect a wordlist file or words on stdin")]
+    NoSource,
+    #[error("{0}")]
+    Io(std::io::Error),
+}
+
+impl Wordlist {
+    // one word per line, surrounding whitespace and blank lines are ignored and repeated words
+    // are only kept once so they don't get picked more often
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self, WordlistError> {
+        let mut seen = HashSet::new();
+        let mut words = vec![];
+        for line in reader.lines() {
+            let line = line.map_err(WordlistError::Io)?;
+            let word = line.trim();
+            if !word.is_empty() && seen.insert(word.to_string()) {
+                words.push(word.to_string());
+            }
+        }
+        if words.is_empty() {
+            Err(WordlistError::Empty)
+        } else {
+            Ok(Self { words })
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, WordlistError> {
+        let file = File::open(path).map_err(WordlistError::Io)?;
+        Self::from_reader(BufReader::new(file))
+    }
+
+    // words from exactly one of a file or stdin, stdin is passed in so callers that already use
+    // it for something else can refuse to hand it over
+    pub fn from_sources<R: BufRead>(
+        file: Option<&Path>,
+        stdin: Option<R>,
+    ) -> Result<Self, WordlistError> {
+        match (file, stdin) {
+            (Some(_), Some(_)) => Err(WordlistError::ConflictingSources),
+            (Some(path), None) => Self::from_file(path),
+            (None, Some(stdin)) => Self::from_reader(stdin),
+            (None, None) => Err(WordlistError::NoSource),
+        }
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    // a warning to show when the list is short enough to weaken passphrases
+    pub fn size_warning(&self) -> Option<String> {
+        if self.len() < RECOMMENDED_MIN_WORDS {
+            Some(format!(
+                "Wordlist only has {} distinct words ({:.1} bits per word), at least {} are recommended",
+                self.len(),
+                self.entropy_per_word(),
+                RECOMMENDED_MIN_WORDS
+            ))
+        } else {
+            None
+        }
+    }
+
+    pub fn entropy_per_word(&self) -> f64 {
+        (self.len() as f64).log2()
+    }
+
+    pub fn passphrase(&self, words: usize, separator: &str) -> String {
+        self.passphrase_with_rng(words, separator, &mut thread_rng())
+    }
+
+    pub fn passphrase_with_rng<R: Rng + ?Sized>(
+        &self,
+        words: usize,
+        separator: &str,
+        rng: &mut R,
+    ) -> String {
+        (0..words)
+            .filter_map(|_| self.words.choose(rng))
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+}
@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        charset::Charset,
+        password::{GenerateError, PasswordSpec},
+        position::PositionRule,
+        violation::Violation,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn symbols(n: usize) -> PasswordSpec {
+        format!("10//{}|:symbol://0+|:lower:", n)
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .no_adjacent_from(Charset::Symbol)
+    }
+
+    fn apart(password: &str) -> bool {
+        let chars: Vec<char> = password.chars().collect();
+        chars
+            .windows(2)
+            .all(|pair| !(Charset::Symbol.contains(pair[0]) && Charset::Symbol.contains(pair[1])))
+    }
+
+    #[test]
+    fn half_symbols() {
+        let spec = symbols(5);
+        let mut rng = StdRng::seed_from_u64(469);
+        for _ in 0..200 {
+            let password = spec.generate_with_rng(&mut rng).unwrap();
+            assert!(apart(&password), "{}", password);
+            assert!(spec.matches(&password));
+        }
+    }
+
+    #[test]
+    fn too_many_symbols() {
+        assert!(matches!(
+            symbols(6).generate_detailed(),
+            Err(GenerateError::AdjacencyInfeasible {
+                count: 6,
+                length: 10,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn unbounded_counts() {
+        // draws can have too many symbols to keep apart, those get drawn again
+        let spec = "10//1+|:symbol://1+|:lower:"
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .no_adjacent_from(Charset::Symbol);
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            assert!(apart(&spec.generate_with_rng(&mut rng).unwrap()));
+        }
+    }
+
+    #[test]
+    fn with_position_rules() {
+        let spec = symbols(4)
+            .position_rule(0, PositionRule::MustBe(Charset::Symbol))
+            .position_rule(1, PositionRule::MustBe(Charset::Lower));
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..100 {
+            let password = spec.generate_with_rng(&mut rng).unwrap();
+            assert!(apart(&password), "{}", password);
+            assert!(Charset::Symbol.contains(password.chars().next().unwrap()));
+        }
+    }
+
+    #[test]
+    fn validation() {
+        let spec = symbols(2);
+        assert!(spec.matches("a!aaaaaa!a"));
+        assert!(!spec.matches("a!!aaaaaaa"));
+        let violations = spec.violations("a!!aaaaaaa");
+        assert_eq!(
+            violations,
+            vec![Violation::Adjacent {
+                charset: Charset::Symbol,
+                position: 3
+            }]
+        );
+        assert_eq!(violations[0].code(), "adjacent:symbol");
+    }
+}
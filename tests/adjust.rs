@@ -0,0 +1,193 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        adjust::{Adjustment, Relaxation},
+        charset::Charset,
+        password::PasswordSpec,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn spec(s: &str) -> PasswordSpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn relax_largest_first() {
+        let original = spec("16//6+|:upper://3+|:lower://1+|:number:");
+        // 10 required, 8 fits after taking 2 off the largest
+        let (relaxed, changes) = original.relax_to_length(8);
+        assert_eq!(
+            relaxed.to_string(),
+            "8//4+|:upper://3+|:lower://1+|:number:"
+        );
+        assert_eq!(
+            changes,
+            vec![Adjustment::LoweredMin {
+                charset: Charset::Upper,
+                from: 6,
+                to: 4
+            }]
+        );
+        // once level the shrinking alternates, first choice first
+        let (relaxed, changes) = original.relax_to_length(5);
+        assert_eq!(
+            relaxed.to_string(),
+            "5//2+|:upper://2+|:lower://1+|:number:"
+        );
+        assert_eq!(changes.len(), 2);
+        let (relaxed, _) = original.relax_to_length(2);
+        assert_eq!(
+            relaxed.to_string(),
+            "2//0+|:upper://1+|:lower://1+|:number:"
+        );
+        let (relaxed, changes) = original.relax_to_length(0);
+        assert_eq!(
+            relaxed.to_string(),
+            "0//0+|:upper://0+|:lower://0+|:number:"
+        );
+        assert_eq!(changes.len(), 3);
+        assert!(relaxed.generate().is_some());
+    }
+
+    #[test]
+    fn relax_when_it_fits() {
+        let original = spec("16//1+|:upper://1+|:lower:");
+        let (relaxed, changes) = original.relax_to_length(10);
+        assert_eq!(relaxed, original.clone().length(10));
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn relax_is_feasible() {
+        let original = PasswordSpec::default();
+        for length in 0..8 {
+            let (relaxed, _) = original.relax_to_length(length);
+            let password = relaxed.generate().unwrap();
+            assert_eq!(password.chars().count(), length);
+        }
+    }
+
+    #[test]
+    fn tighten_raises_widest() {
+        let original = spec("16//2-|:upper://1-3|:number://0-1|:symbol:");
+        let (tightened, changes) = original.tighten_to_length(10);
+        // upper and symbol share the most characters, upper comes first
+        assert_eq!(
+            tightened.to_string(),
+            "10//6-|:upper://1-3|:number://1-|:symbol:"
+        );
+        assert_eq!(
+            changes,
+            vec![Adjustment::RaisedMax {
+                charset: Charset::Upper,
+                from: 2,
+                to: 6
+            }]
+        );
+        assert_eq!(
+            changes[0].to_string(),
+            "raised the maximum of :upper: from 2 to 6"
+        );
+        assert!(tightened.generate().is_some());
+    }
+
+    #[test]
+    fn tighten_adds_filler() {
+        let (tightened, changes) = PasswordSpec::new().tighten_to_length(6);
+        assert_eq!(changes, vec![Adjustment::AddedFiller(Charset::Lower)]);
+        assert_eq!(tightened.generate().unwrap().chars().count(), 6);
+    }
+
+    #[test]
+    fn tighten_when_reachable() {
+        let original = PasswordSpec::default();
+        let (tightened, changes) = original.tighten_to_length(64);
+        assert_eq!(tightened, original.length(64));
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn best_effort_feasible() {
+        let original = spec("16//2+|:upper://1+|:lower://1-3|:number:");
+        for seed in 0..10 {
+            let (password, relaxations) =
+                original.generate_best_effort(&mut StdRng::seed_from_u64(seed));
+            assert!(relaxations.is_empty());
+            assert_eq!(
+                Some(password),
+                original.generate_with_rng(&mut StdRng::seed_from_u64(seed))
+            );
+        }
+    }
+
+    #[test]
+    fn best_effort_raises_length() {
+        let original = spec("12//6+|:upper://6+|:lower://3+|:number:");
+        let (password, relaxations) = original.generate_best_effort(&mut StdRng::seed_from_u64(3));
+        assert_eq!(
+            relaxations,
+            vec![Relaxation::RaisedLength { from: 12, to: 15 }]
+        );
+        assert_eq!(
+            relaxations[0].to_string(),
+            "increased the length from 12 to 15"
+        );
+        assert!(original.length(15).matches(&password));
+    }
+
+    #[test]
+    fn best_effort_lowers_minimums() {
+        // 9 required is more than twice the length, so the minimums give instead
+        let original = spec("4//6+|:upper://3+|:number:");
+        let (password, relaxations) = original.generate_best_effort(&mut StdRng::seed_from_u64(3));
+        assert_eq!(
+            relaxations,
+            vec![
+                Relaxation::Adjusted(Adjustment::LoweredMin {
+                    charset: Charset::Upper,
+                    from: 6,
+                    to: 2
+                }),
+                Relaxation::Adjusted(Adjustment::LoweredMin {
+                    charset: Charset::Number,
+                    from: 3,
+                    to: 2
+                }),
+            ]
+        );
+        assert!(spec("4//2+|:upper://2+|:number:").matches(&password));
+    }
+
+    #[test]
+    fn best_effort_raises_maximums() {
+        let original = spec("12//0-2|:upper://0-3|:number:");
+        let (password, relaxations) = original.generate_best_effort(&mut StdRng::seed_from_u64(3));
+        assert_eq!(
+            relaxations,
+            vec![Relaxation::Adjusted(Adjustment::RaisedMax {
+                charset: Charset::Upper,
+                from: 2,
+                to: 9
+            })]
+        );
+        assert!(spec("12//9-|:upper://3-|:number:").matches(&password));
+        let (password, relaxations) =
+            PasswordSpec::new().generate_best_effort(&mut StdRng::seed_from_u64(3));
+        assert_eq!(
+            relaxations,
+            vec![Relaxation::Adjusted(Adjustment::AddedFiller(
+                Charset::Lower
+            ))]
+        );
+        assert_eq!(password.chars().count(), 32);
+    }
+
+    #[test]
+    fn best_effort_falls_back_to_default() {
+        let original = PasswordSpec::new().include(Charset::Custom(vec![]).at_least(1));
+        assert!(original.generate().is_none());
+        let (password, relaxations) = original.generate_best_effort(&mut StdRng::seed_from_u64(3));
+        assert_eq!(relaxations, vec![Relaxation::UsedDefault]);
+        assert!(PasswordSpec::default().matches(&password));
+    }
+}
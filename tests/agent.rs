@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use pants_gen::{
+        agent::{handle, respond},
+        password::PasswordSpec,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn responses() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let password = respond("default", &mut rng);
+        assert!(PasswordSpec::default().matches(&password));
+        let password = respond("  6//6|:number:\r", &mut rng);
+        assert_eq!(password.len(), 6);
+        assert!(password.chars().all(|c| c.is_ascii_digit()));
+        assert!(respond("sixteen//1+|:upper:", &mut rng).starts_with("error: "));
+        assert!(respond("4//5|:upper:", &mut rng).starts_with("error: "));
+        // the reason generation failed is passed on rather than a generic message
+        assert_eq!(
+            respond("4//5|:upper:", &mut rng),
+            "error: The choices need at least 5 characters but the length is only 4"
+        );
+        assert!(respond("", &mut rng).starts_with("error: "));
+    }
+
+    #[test]
+    fn line_protocol() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let input = Cursor::new("default\nbad\n8//8|:lower:\n");
+        let mut output = vec![];
+        assert_eq!(handle(input, &mut output, &mut rng).unwrap(), 3);
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(PasswordSpec::default().matches(lines[0]));
+        assert!(lines[1].starts_with("error: "));
+        assert_eq!(lines[2].len(), 8);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn over_socketpair() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::UnixStream;
+
+        let (server, mut client) = UnixStream::pair().unwrap();
+        let agent = std::thread::spawn(move || {
+            let mut rng = StdRng::seed_from_u64(2);
+            handle(BufReader::new(&server), &server, &mut rng).unwrap()
+        });
+        let mut responses = BufReader::new(client.try_clone().unwrap()).lines();
+        writeln!(client, "default").unwrap();
+        let password = responses.next().unwrap().unwrap();
+        assert!(PasswordSpec::default().matches(&password));
+        writeln!(client, "4//4|:number:").unwrap();
+        assert_eq!(responses.next().unwrap().unwrap().len(), 4);
+        client.shutdown(std::net::Shutdown::Write).unwrap();
+        assert_eq!(agent.join().unwrap(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn socket_agent() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::{fs::PermissionsExt, net::UnixStream};
+        use std::time::Duration;
+
+        use pants_gen::agent::{Agent, AgentError};
+
+        let path = std::env::temp_dir().join("pants_gen_agent_socket");
+        let _ = std::fs::remove_file(&path);
+        let agent = Agent::new(&path)
+            .once(true)
+            .idle_timeout(Duration::from_secs(10));
+        let server = std::thread::spawn(move || agent.serve(&mut StdRng::seed_from_u64(3)));
+        let mut client = loop {
+            match UnixStream::connect(&path) {
+                Ok(client) => break client,
+                Err(_) => std::thread::sleep(Duration::from_millis(10)),
+            }
+        };
+        writeln!(client, "default").unwrap();
+        let mut password = String::new();
+        BufReader::new(&client).read_line(&mut password).unwrap();
+        assert!(PasswordSpec::default().matches(password.trim_end()));
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        client.shutdown(std::net::Shutdown::Write).unwrap();
+        server.join().unwrap().unwrap();
+        assert!(!path.exists());
+
+        // idle timeout with nobody connecting
+        Agent::new(&path)
+            .idle_timeout(Duration::from_millis(50))
+            .serve(&mut StdRng::seed_from_u64(3))
+            .unwrap();
+
+        let taken = std::env::temp_dir().join("pants_gen_agent_taken");
+        std::fs::write(&taken, "not a socket").unwrap();
+        assert!(matches!(
+            Agent::new(&taken).serve(&mut StdRng::seed_from_u64(3)),
+            Err(AgentError::BadPath(..))
+        ));
+        std::fs::remove_file(&taken).unwrap();
+        let missing_dir = std::env::temp_dir().join("pants_gen_agent_missing/agent.sock");
+        assert!(matches!(
+            Agent::new(missing_dir).serve(&mut StdRng::seed_from_u64(3)),
+            Err(AgentError::BadPath(..))
+        ));
+    }
+}
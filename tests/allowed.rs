@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{charset::Charset, password::PasswordSpec, violation::Violation};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn universe() -> Charset {
+        Charset::Custom("abcdefXYZ123!?".chars().collect())
+    }
+
+    fn spec() -> PasswordSpec {
+        "12//1+|:number://1-2|:symbol:"
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .allowed(universe())
+    }
+
+    #[test]
+    fn generation() {
+        let spec = spec();
+        let mut rng = StdRng::seed_from_u64(472);
+        let mut letters = false;
+        for _ in 0..200 {
+            let password = spec.generate_with_rng(&mut rng).unwrap();
+            assert_eq!(password.chars().count(), 12);
+            assert!(
+                password.chars().all(|c| universe().contains(c)),
+                "{}",
+                password
+            );
+            assert!(password.chars().any(|c| Charset::Number.contains(c)));
+            let symbols = password
+                .chars()
+                .filter(|c| Charset::Symbol.contains(*c))
+                .count();
+            assert!((1..=2).contains(&symbols), "{}", password);
+            letters |= password.chars().any(|c| c.is_ascii_alphabetic());
+            assert!(spec.matches(&password));
+        }
+        // the filler comes from the allowed characters the choices don't cover
+        assert!(letters);
+    }
+
+    #[test]
+    fn outside_flagged() {
+        let spec = spec();
+        assert!(spec.matches("abc1!XYZdefa"));
+        // 9 is a number but not allowed
+        assert!(!spec.matches("abc9!XYZdefa"));
+        assert_eq!(
+            spec.violations("abc1!XYZdefq"),
+            vec![Violation::DisallowedChar {
+                c: 'q',
+                position: 12
+            }]
+        );
+        assert_eq!(spec.count_unmatched("q9a1"), 2);
+    }
+
+    #[test]
+    fn not_counted() {
+        // the allowed characters don't add a choice, so the counts are as before
+        let spec = spec();
+        assert_eq!(spec.choices().count(), 2);
+        assert_eq!(
+            spec.count_by_charset("abc1!XYZdefa"),
+            vec![(Charset::Number, 1), (Charset::Symbol, 1)]
+        );
+    }
+
+    #[test]
+    fn spec_string() {
+        let s = "12//1+|:number://1-2|:symbol://*|abcdefXYZ123!?";
+        assert_eq!(s.parse::<PasswordSpec>().unwrap(), spec());
+        assert_eq!(spec().to_string(), s);
+
+        let named = "8//1+|:upper://*|:base58:".parse::<PasswordSpec>().unwrap();
+        assert_eq!(named.to_string(), "8//1+|:upper://*|:base58:");
+
+        // escapes need v2
+        let escaped = PasswordSpec::new()
+            .length(8)
+            .include(Charset::Lower.at_least(1))
+            .allowed(Charset::Custom("ab//|".chars().collect()));
+        let written = escaped.to_string();
+        assert!(written.starts_with("v2:"), "{}", written);
+        assert_eq!(written.parse::<PasswordSpec>().unwrap(), escaped);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json() {
+        let json = spec().to_json();
+        assert!(json.contains("\"allowed\""));
+        assert_eq!(PasswordSpec::from_json(&json).unwrap(), spec());
+    }
+}
@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use pants_gen::{
+        api::{self, CharClass, ClassRequirement, Error, Policy, WordlistOptions, POLICY_VERSION},
+        password::PasswordSpec,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn policy() -> Policy {
+        let mut policy = Policy::new(16);
+        // in the order a spec lists its choices, so it survives a round trip
+        policy.classes = vec![
+            ClassRequirement::at_least(CharClass::Upper, 2),
+            ClassRequirement::at_least(CharClass::Lower, 0),
+            ClassRequirement::new(CharClass::Number, 1, Some(3)),
+            ClassRequirement::exactly(CharClass::Custom("!@#".to_string()), 1),
+        ];
+        policy.exclude = "OI0".to_string();
+        policy
+    }
+
+    #[test]
+    fn generates_within_policy() {
+        let policy = policy();
+        let spec = policy.to_spec().unwrap();
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..50 {
+            let password = api::generate_with_rng(&policy, &mut rng).unwrap();
+            let password = password.expose();
+            assert!(spec.matches(password));
+            assert_eq!(password.chars().count(), 16);
+            assert!(!password.contains(['O', 'I', '0']));
+        }
+    }
+
+    #[test]
+    fn spec_string_round_trip() {
+        for s in [
+            "16//2+|:upper://1-3|:number://1|!@#//0|OI",
+            "12//1+|:lower://0|:symbol:",
+            "v2:10//1+|:base58://1+|a\\|b",
+        ] {
+            let policy = Policy::from_spec_string(s).unwrap();
+            let spec: PasswordSpec = s.parse().unwrap();
+            assert_eq!(policy.to_spec().unwrap(), spec, "{}", s);
+            assert_eq!(Policy::from_spec_string(&spec.to_string()).unwrap(), policy);
+        }
+    }
+
+    #[test]
+    fn policy_round_trip() {
+        let policy = policy();
+        let spec = policy.to_spec().unwrap();
+        assert_eq!(Policy::from_spec_string(&spec.to_string()).unwrap(), policy);
+    }
+
+    #[test]
+    fn from_spec_string() {
+        let policy = Policy::from_spec_string("12//2+|:upper://0-3|:number://0|xy").unwrap();
+        assert_eq!(policy.version, POLICY_VERSION);
+        assert_eq!(policy.length, 12);
+        assert_eq!(
+            policy.classes,
+            vec![
+                ClassRequirement::at_least(CharClass::Upper, 2),
+                ClassRequirement::new(CharClass::Number, 0, Some(3)),
+            ]
+        );
+        assert_eq!(policy.exclude, "xy");
+        assert!(policy.wordlist.is_none());
+    }
+
+    #[test]
+    fn bits_resolve_and_labels_drop() {
+        let bits = Policy::from_spec_string("64b//1+|:upper://1+|:lower:").unwrap();
+        let spec: PasswordSpec = "64b//1+|:upper://1+|:lower:".parse().unwrap();
+        assert_eq!(bits.length, spec.resolve_length().unwrap());
+        let labeled = Policy::from_spec_string("v2:8//1+|safe=!@#").unwrap();
+        assert_eq!(
+            labeled.classes,
+            vec![ClassRequirement::at_least(
+                CharClass::Custom("!@#".to_string()),
+                1
+            )]
+        );
+    }
+
+    #[test]
+    fn unsupported_specs() {
+        assert!(matches!(
+            Policy::from_spec_string("12//0,2|:number://1+|:lower:"),
+            Err(Error::Unsupported(_))
+        ));
+        assert!(matches!(
+            Policy::from_spec_string("12//1+|:lower://*|:base58:"),
+            Err(Error::Unsupported(_))
+        ));
+        assert!(matches!(
+            Policy::from_spec_string("12//1+|"),
+            Err(Error::Parse(_))
+        ));
+        // 16 bytes of `é` would come back as 16 characters, twice the size
+        for spec in ["16B//1+|\u{e9}\u{e8}", "16g//1+|:lower:"] {
+            assert!(matches!(
+                Policy::from_spec_string(spec),
+                Err(Error::Unsupported(_))
+            ));
+        }
+        assert!(Policy::from_spec_string("16c//1+|:lower:").is_ok());
+    }
+
+    #[test]
+    fn bad_policies() {
+        let mut policy = Policy::new(8);
+        policy.classes = vec![ClassRequirement::new(CharClass::Upper, 3, Some(2))];
+        assert!(matches!(policy.to_spec(), Err(Error::BadBounds(_, 3, 2))));
+        policy.classes = vec![ClassRequirement::at_least(
+            CharClass::Custom(String::new()),
+            1,
+        )];
+        assert!(matches!(policy.to_spec(), Err(Error::EmptyCustom)));
+        policy.classes = vec![ClassRequirement::at_least(CharClass::Upper, 9)];
+        assert!(matches!(api::generate(&policy), Err(Error::Generate(_))));
+        policy.version = POLICY_VERSION + 1;
+        assert!(matches!(
+            api::generate(&policy),
+            Err(Error::UnknownVersion(v)) if v == POLICY_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn passphrase() {
+        let dir = std::env::temp_dir().join(format!("pants-gen-api-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("words.txt");
+        let mut file = std::fs::File::create(&path).unwrap();
+        writeln!(file, "apple\nbanana\ncherry").unwrap();
+        let mut policy = Policy::new(0);
+        policy.wordlist = Some(WordlistOptions {
+            path: path.clone(),
+            words: 4,
+            separator: "-".to_string(),
+        });
+        let passphrase = api::generate_with_rng(&policy, &mut StdRng::seed_from_u64(3)).unwrap();
+        let words: Vec<&str> = passphrase.expose().split('-').collect();
+        assert_eq!(words.len(), 4);
+        assert!(words
+            .iter()
+            .all(|word| ["apple", "banana", "cherry"].contains(word)));
+        assert!(matches!(policy.to_spec(), Err(Error::Passphrase)));
+        policy.wordlist.as_mut().unwrap().words = 0;
+        assert!(matches!(api::generate(&policy), Err(Error::NoWords)));
+        policy.wordlist.as_mut().unwrap().words = 2;
+        policy.wordlist.as_mut().unwrap().path = dir.join("missing.txt");
+        assert!(matches!(api::generate(&policy), Err(Error::Wordlist(_))));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
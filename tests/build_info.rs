@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{build_info::build_info, password::PasswordSpec};
+
+    // the features declared in Cargo.toml
+    fn declared() -> Vec<String> {
+        let manifest = include_str!("../Cargo.toml");
+        let section = manifest.split("[features]").nth(1).unwrap();
+        let section = section.split("\n[").next().unwrap();
+        let mut features: Vec<String> = section
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(name, _)| name.trim().to_string())
+            .collect();
+        features.sort();
+        features
+    }
+
+    #[test]
+    fn table_covers_every_feature() {
+        let info = build_info();
+        let names: Vec<String> = info
+            .features
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect();
+        assert_eq!(names, declared());
+    }
+
+    #[test]
+    fn report_follows_the_build() {
+        let info = build_info();
+        let report = info.to_string();
+        let features_line = report
+            .lines()
+            .find_map(|line| line.strip_prefix("features: "))
+            .unwrap();
+        let listed: Vec<&str> = features_line.split(", ").collect();
+        let enabled = [
+            ("hashing", cfg!(feature = "hashing")),
+            ("history", cfg!(feature = "history")),
+            ("json", cfg!(feature = "json")),
+            ("metadata", cfg!(feature = "metadata")),
+            ("num-bigint", cfg!(feature = "num-bigint")),
+            ("schema", cfg!(feature = "schema")),
+            ("tracing", cfg!(feature = "tracing")),
+            ("transcript", cfg!(feature = "transcript")),
+        ];
+        for (name, on) in enabled {
+            assert_eq!(listed.contains(&name), on, "{}", name);
+        }
+        if info.enabled_features().is_empty() {
+            assert_eq!(features_line, "none");
+        }
+        assert!(report.starts_with(&format!("pants-gen {}\n", env!("CARGO_PKG_VERSION"))));
+        assert!(report.contains("wordlists: none"));
+        assert!(report.contains("stores: memory"));
+        assert!(report.ends_with(&format!("default spec: {}", PasswordSpec::default())));
+    }
+}
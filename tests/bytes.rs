@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pants_gen::bytes::{check_raw_output, generate_bytes, write_secret_file, BytesError};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("pants_gen_bytes_{}", name))
+    }
+
+    #[test]
+    fn byte_count() {
+        let mut rng = StdRng::seed_from_u64(9);
+        assert_eq!(generate_bytes(64, &mut rng).len(), 64);
+        assert!(generate_bytes(0, &mut rng).is_empty());
+        // not all the same, the buffer is actually filled
+        let bytes = generate_bytes(32, &mut rng);
+        assert!(bytes.iter().any(|b| *b != bytes[0]));
+    }
+
+    #[test]
+    fn terminal_refusal() {
+        assert!(matches!(
+            check_raw_output(true, false),
+            Err(BytesError::Terminal)
+        ));
+        assert!(check_raw_output(true, true).is_ok());
+        assert!(check_raw_output(false, false).is_ok());
+    }
+
+    #[test]
+    fn secret_file() {
+        let path = temp_path("keyfile");
+        let bytes = generate_bytes(64, &mut StdRng::seed_from_u64(1));
+        write_secret_file(&path, &bytes).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), bytes);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn existing_file_is_tightened() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = temp_path("existing");
+        std::fs::write(&path, b"a much longer old secret").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        write_secret_file(&path, b"new").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"new");
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
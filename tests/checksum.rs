@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        charset::Charset,
+        checksum::{
+            append_check_character, check_character, check_character_valid, luhn_check_digit,
+            luhn_valid,
+        },
+        password::{PasswordSpec, TranscriptionError},
+        token::{TokenError, TokenSpec},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn luhn_vectors() {
+        assert_eq!(luhn_check_digit("7992739871"), Some('3'));
+        assert_eq!(luhn_check_digit("411111111111111"), Some('1'));
+        assert_eq!(luhn_check_digit("37828224631000"), Some('5'));
+        assert_eq!(luhn_check_digit(""), Some('0'));
+        assert_eq!(luhn_check_digit("12a4"), None);
+
+        assert!(luhn_valid("79927398713"));
+        assert!(luhn_valid("4111111111111111"));
+        assert!(luhn_valid("378282246310005"));
+        assert!(!luhn_valid("79927398710"));
+        assert!(!luhn_valid("4111111111111112"));
+        assert!(!luhn_valid(""));
+        assert!(!luhn_valid("4111-1111"));
+    }
+
+    #[test]
+    fn luhn_tokens_validate() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let spec = TokenSpec::new(16).luhn().prefix("4111");
+        for _ in 0..200 {
+            let token = spec.generate_with_rng(&mut rng).unwrap();
+            assert_eq!(token.len(), 16);
+            assert!(token.starts_with("4111"));
+            assert!(luhn_valid(&token));
+        }
+        let plain = TokenSpec::new(8).generate_with_rng(&mut rng).unwrap();
+        assert_eq!(plain.len(), 8);
+        assert!(plain.chars().all(|c| c.is_ascii_digit()));
+    }
+
+    #[test]
+    fn bad_prefix() {
+        assert!(matches!(
+            TokenSpec::new(16).prefix("41x").generate(),
+            Err(TokenError::BadPrefix(_))
+        ));
+        assert!(matches!(
+            TokenSpec::new(4).luhn().prefix("1234").generate(),
+            Err(TokenError::PrefixTooLong { .. })
+        ));
+        assert_eq!(TokenSpec::new(4).prefix("1234").generate().unwrap(), "1234");
+    }
+
+    #[test]
+    fn check_character_vectors() {
+        assert_eq!(check_character(""), '1');
+        assert_eq!(check_character("A"), 'G');
+        assert_eq!(check_character("hunter2"), 'R');
+        assert_eq!(check_character("Tr0ub4dor&3"), 'J');
+        assert_eq!(check_character("correct horse"), '6');
+
+        assert!(check_character_valid("hunter2R"));
+        assert!(check_character_valid("hunter2r"));
+        assert!(!check_character_valid("hunter3R"));
+        assert!(!check_character_valid("huntre2R"));
+        assert!(!check_character_valid(""));
+    }
+
+    #[test]
+    fn check_character_catches_single_typos() {
+        let password = "Xk4#pL9q";
+        let alphabet: Vec<char> = ('!'..='~').collect();
+        for i in 0..password.len() {
+            for c in &alphabet {
+                let mut typo: Vec<char> = password.chars().collect();
+                if typo[i] == *c || (typo[i] as u32).abs_diff(*c as u32) % 36 == 0 {
+                    continue;
+                }
+                typo[i] = *c;
+                let typo: String = typo.into_iter().collect();
+                assert_ne!(check_character(&typo), check_character(password));
+            }
+        }
+    }
+
+    #[test]
+    fn generated_check_character() {
+        let spec = PasswordSpec::new()
+            .length(10)
+            .include(Charset::Number.exactly(10));
+        for _ in 0..50 {
+            let password = spec.generate_with_check_character().unwrap();
+            assert_eq!(password.chars().count(), 11);
+            assert!(check_character_valid(&password));
+            // the check character is often a letter but isn't held against the spec
+            assert!(spec.check_transcribed(&password).is_ok());
+        }
+    }
+
+    #[test]
+    fn transcription_errors() {
+        let spec = PasswordSpec::new()
+            .length(6)
+            .include(Charset::Lower.exactly(6));
+        let password = append_check_character("abcdef");
+        assert!(spec.check_transcribed(&password).is_ok());
+
+        let typo = password.replacen('d', "D", 1);
+        let err = spec.check_transcribed(&typo).unwrap_err();
+        assert!(matches!(err, TranscriptionError::Mistyped(4)));
+        assert!(err.to_string().contains("character 4 looks mistyped"));
+
+        let swapped = password.replacen("cd", "dc", 1);
+        assert!(matches!(
+            spec.check_transcribed(&swapped),
+            Err(TranscriptionError::ChecksumMismatch)
+        ));
+
+        let short = append_check_character("abcde");
+        assert!(matches!(
+            spec.check_transcribed(&short),
+            Err(TranscriptionError::Mismatch)
+        ));
+    }
+}
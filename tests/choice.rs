@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use pants_gen::{password::GenerateError, Charset, Choice, Interval, PasswordSpec};
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    // counts how much randomness has been asked for
+    struct Counting {
+        inner: StdRng,
+        calls: Rc<Cell<usize>>,
+    }
+
+    impl RngCore for Counting {
+        fn next_u32(&mut self) -> u32 {
+            self.calls.set(self.calls.get() + 1);
+            self.inner.next_u32()
+        }
+        fn next_u64(&mut self) -> u64 {
+            self.calls.set(self.calls.get() + 1);
+            self.inner.next_u64()
+        }
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            self.calls.set(self.calls.get() + 1);
+            self.inner.fill_bytes(dest)
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.calls.set(self.calls.get() + 1);
+            self.inner.try_fill_bytes(dest)
+        }
+    }
+
+    fn counting(seed: u64) -> (Counting, Rc<Cell<usize>>) {
+        let calls = Rc::new(Cell::new(0));
+        let rng = Counting {
+            inner: StdRng::seed_from_u64(seed),
+            calls: calls.clone(),
+        };
+        (rng, calls)
+    }
+
+    #[test]
+    fn zero_min_draws_nothing() {
+        let (mut rng, calls) = counting(1);
+        let choice = Charset::Lower.at_most(5);
+        assert_eq!(choice.get_required(&mut rng).unwrap(), vec![]);
+        assert_eq!(calls.get(), 0);
+    }
+
+    #[test]
+    fn exact_draws_once_each() {
+        let (mut rng, calls) = counting(2);
+        let choice = Charset::Number.exactly(3);
+        let required = choice.get_required(&mut rng).unwrap();
+        assert_eq!(required.len(), 3);
+        assert!(required.iter().all(|c| c.is_ascii_digit()));
+        // every character is one draw, which takes a couple of numbers from the rng and rarely
+        // more when a number is rejected for being out of range
+        assert!((3..=9).contains(&calls.get()));
+        // the same rng state gives the same characters
+        let again = choice.get_required(&mut StdRng::seed_from_u64(2)).unwrap();
+        assert_eq!(again, required);
+    }
+
+    #[test]
+    fn large_min() {
+        let (mut rng, calls) = counting(3);
+        let choice = Charset::Custom(vec!['x', 'y']).at_least(10_000);
+        let required = choice.get_required(&mut rng).unwrap();
+        assert_eq!(required.len(), 10_000);
+        assert!(required.iter().all(|c| ['x', 'y'].contains(c)));
+        assert!((10_000..=30_000).contains(&calls.get()));
+    }
+
+    #[test]
+    fn fails_loudly() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let backwards = Choice::from_interval(Interval { min: 5, max: 2 }, Charset::Lower);
+        assert!(matches!(
+            backwards.get_required(&mut rng),
+            Err(GenerateError::BadBounds { min: 5, max: 2, .. })
+        ));
+        let empty = Choice::from_interval(Interval::exactly(2), Charset::Custom(vec![]));
+        assert!(matches!(
+            empty.get_required(&mut rng),
+            Err(GenerateError::EmptyCharset(_))
+        ));
+        // and a spec with such a choice doesn't generate
+        let spec = PasswordSpec::new().length(8).include(backwards);
+        let error = spec.try_generate_with_rng(&mut rng).unwrap_err();
+        assert!(matches!(error, GenerateError::BadBounds { .. }));
+        assert_eq!(error.code(), "bad_bounds");
+    }
+
+    // the deprecated iterator still uses up the counts as 0.3 did
+    #[test]
+    fn iterator_still_counts_down() {
+        let mut choice = Charset::Number.exactly(2);
+        let drawn: Vec<char> = choice.by_ref().collect();
+        assert_eq!(drawn.len(), 2);
+        assert!(drawn.iter().all(|c| c.is_ascii_digit()));
+        assert_eq!(choice.interval(), Interval::exactly(0));
+        assert_eq!(choice.next(), None);
+    }
+}
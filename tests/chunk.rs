@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        chunk::{ChunkError, ChunkSpec},
+        password::PasswordSpec,
+        Charset,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn letters() -> Charset {
+        Charset::Custom(('A'..='Z').chain('a'..='z').collect())
+    }
+
+    fn rule() -> ChunkSpec {
+        ChunkSpec::new(4).chunk_rule(vec![letters().at_least(1), Charset::Number.at_least(1)])
+    }
+
+    #[test]
+    fn plain_chunks() {
+        let spec: PasswordSpec = "10//10|:upper:".parse().unwrap();
+        let chunks = ChunkSpec::new(4);
+        assert_eq!(chunks.chunk(&spec, "ABCDEFGHIJ"), "ABCD-EFGH-IJ");
+        assert_eq!(
+            chunks
+                .separator(" ")
+                .chunk(&spec.clone().prefix("K:"), "K:ABCDEFGHIJ"),
+            "K:ABCD EFGH IJ"
+        );
+        let mut rng = StdRng::seed_from_u64(1);
+        let password = ChunkSpec::new(5)
+            .generate_with_rng(&spec, &mut rng)
+            .unwrap();
+        assert_eq!(password.len(), 11);
+        assert_eq!(password.chars().nth(5), Some('-'));
+    }
+
+    #[test]
+    fn every_chunk_meets_the_rule() {
+        let spec: PasswordSpec = "16//4+|:upper://0+|:lower://4+|:number:".parse().unwrap();
+        let mut rng = StdRng::seed_from_u64(496);
+        for _ in 0..200 {
+            let password = rule().generate_with_rng(&spec, &mut rng).unwrap();
+            let chunks: Vec<&str> = password.split('-').collect();
+            assert_eq!(chunks.len(), 4);
+            for chunk in &chunks {
+                assert_eq!(chunk.len(), 4);
+                assert!(
+                    chunk.chars().any(|c| c.is_ascii_alphabetic()),
+                    "{}",
+                    password
+                );
+                assert!(chunk.chars().any(|c| c.is_ascii_digit()), "{}", password);
+            }
+            assert!(spec.matches(&password.replace('-', "")));
+        }
+    }
+
+    #[test]
+    fn keeps_the_spec_rules() {
+        let spec: PasswordSpec = "12//3+|:upper://3+|:number://0+|:lower:".parse().unwrap();
+        let spec = spec.no_adjacent_from(Charset::Number).prefix("ID-");
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..50 {
+            let password = rule().generate_with_rng(&spec, &mut rng).unwrap();
+            let core = password.strip_prefix("ID-").unwrap();
+            for chunk in core.split('-') {
+                assert!(chunk.chars().any(|c| c.is_ascii_digit()));
+            }
+            assert!(spec.matches(&format!("ID-{}", core.replace('-', ""))));
+        }
+    }
+
+    #[test]
+    fn too_few_digits_fails_up_front() {
+        let spec: PasswordSpec = "16//4+|:upper://2|:number://0+|:lower:".parse().unwrap();
+        let err = rule().check(&spec).unwrap_err();
+        match &err {
+            ChunkError::Shortfall {
+                charset,
+                needed,
+                guaranteed,
+                chunks,
+            } => {
+                assert_eq!(*charset, Charset::Number);
+                assert_eq!((*needed, *guaranteed, *chunks), (4, 2, 4));
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+        assert!(err.to_string().contains("2 short"));
+        let mut rng = StdRng::seed_from_u64(2);
+        assert!(matches!(
+            rule().generate_with_rng(&spec, &mut rng),
+            Err(ChunkError::Shortfall { .. })
+        ));
+    }
+
+    #[test]
+    fn chunks_too_small_for_the_rule() {
+        let spec: PasswordSpec = "9//4+|:upper://5+|:number:".parse().unwrap();
+        // the last chunk has a single character
+        assert!(matches!(
+            rule().check(&spec),
+            Err(ChunkError::ChunkTooSmall { needed: 2, size: 1 })
+        ));
+        assert!(matches!(
+            ChunkSpec::new(0).check(&spec),
+            Err(ChunkError::ZeroSize)
+        ));
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pants_gen::{
+        comments::{lines, strip_comment, SpecFileError},
+        password::PasswordSpec,
+    };
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("pants_gen_comments_{}", name))
+    }
+
+    #[test]
+    fn stripping() {
+        assert_eq!(strip_comment("# a whole line"), "");
+        assert_eq!(strip_comment("   # indented"), "");
+        assert_eq!(
+            strip_comment("  16//1+|:upper:  # policy X  "),
+            "16//1+|:upper:"
+        );
+        assert_eq!(strip_comment("16//1+|:upper:\t# tab"), "16//1+|:upper:");
+        // no whitespace before it, part of the charset
+        assert_eq!(strip_comment("16//1+|a#b"), "16//1+|a#b");
+        assert_eq!(strip_comment("16//1+|ab#"), "16//1+|ab#");
+    }
+
+    #[test]
+    fn escaped_hash() {
+        assert_eq!(strip_comment("\\#,0.5"), "#,0.5");
+        assert_eq!(strip_comment("16//1+|a \\#b # comment"), "16//1+|a #b");
+        assert_eq!(strip_comment("16//1+|\\#\\#"), "16//1+|##");
+        // other escapes are left for the spec parser
+        assert_eq!(strip_comment("v2:16//1+|a\\/b"), "v2:16//1+|a\\/b");
+    }
+
+    #[test]
+    fn line_numbers() {
+        let s = "# header\n\nfirst # one\n   \nsecond\n";
+        assert_eq!(
+            lines(s),
+            vec![(3, "first".to_string()), (5, "second".to_string())]
+        );
+    }
+
+    #[test]
+    fn spec_file() {
+        let path = temp_path("spec");
+        std::fs::write(
+            &path,
+            "# rationale: the vault rejects symbols\n\n  16//1+|:upper://1+|:lower:  # no symbols\n",
+        )
+        .unwrap();
+        let spec = PasswordSpec::from_file(&path).unwrap();
+        assert_eq!(spec, "16//1+|:upper://1+|:lower:".parse().unwrap());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn spec_file_errors() {
+        assert!(matches!(
+            PasswordSpec::from_file_contents("# nothing\n\n"),
+            Err(SpecFileError::Empty)
+        ));
+        assert!(matches!(
+            PasswordSpec::from_file_contents("16//1+|:upper:\n# another\n8//1+|:lower:\n"),
+            Err(SpecFileError::MultipleSpecs(3))
+        ));
+        assert!(matches!(
+            PasswordSpec::from_file_contents("\nnot a spec\n"),
+            Err(SpecFileError::BadSpec(2, _))
+        ));
+    }
+}
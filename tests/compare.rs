@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{charset::Charset, password::PasswordSpec, position::PositionRule};
+
+    fn spec(s: &str) -> PasswordSpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn equal_specs() {
+        let default = PasswordSpec::default();
+        assert_eq!(default.is_at_least_as_strict_as(&default), Some(true));
+    }
+
+    #[test]
+    fn tighter_and_looser() {
+        let old = PasswordSpec::default();
+        let tighter = spec("32//2+|:upper://2+|:lower://1-4|:number://1+|:symbol:");
+        assert_eq!(tighter.is_at_least_as_strict_as(&old), Some(true));
+        assert_eq!(old.is_at_least_as_strict_as(&tighter), Some(false));
+
+        // excluding symbols breaks the old requirement of one, and the old spec uses them
+        let no_symbols = spec("32//1+|:upper://1+|:lower://1+|:number://0|:symbol:");
+        assert_eq!(no_symbols.is_at_least_as_strict_as(&old), None);
+        let optional_symbols = spec("32//1+|:upper://1+|:lower://1+|:number://0+|:symbol:");
+        assert_eq!(
+            no_symbols.is_at_least_as_strict_as(&optional_symbols),
+            Some(true)
+        );
+        assert_eq!(
+            optional_symbols.is_at_least_as_strict_as(&no_symbols),
+            Some(false)
+        );
+
+        // a charset the looser spec doesn't mention at all
+        let lower_only = spec("12//1+|:lower:");
+        let with_upper = spec("12//1+|:lower://0+|:upper:");
+        assert_eq!(lower_only.is_at_least_as_strict_as(&with_upper), Some(true));
+        assert_eq!(
+            with_upper.is_at_least_as_strict_as(&lower_only),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn incomparable() {
+        // different lengths never contain each other
+        let old = PasswordSpec::default();
+        assert_eq!(old.clone().length(16).is_at_least_as_strict_as(&old), None);
+        // each rules out something the other allows
+        let a = spec("12//2+|:upper://0+|:lower:");
+        let b = spec("12//0+|:upper://2+|:lower:");
+        assert_eq!(a.is_at_least_as_strict_as(&b), None);
+        // new characters
+        let a = spec("12//1+|:lower:");
+        let b = spec("12//1+|:upper:");
+        assert_eq!(a.is_at_least_as_strict_as(&b), None);
+        // affixes
+        assert_eq!(old.clone().prefix("x").is_at_least_as_strict_as(&old), None);
+    }
+
+    #[test]
+    fn unions_and_overlaps() {
+        let a = spec("8//2|:number://0+|:lower:");
+        let b = spec("8//0,2|:number://0+|:lower:");
+        assert_eq!(a.is_at_least_as_strict_as(&b), Some(true));
+        assert_eq!(b.is_at_least_as_strict_as(&a), Some(false));
+        // base58 overlaps :number-no-zero:, counts of digits are still checked by charset
+        let c = spec("8//0+|:base58:");
+        let d = spec("8//0+|:base58://1+|:number-no-zero:");
+        assert_eq!(d.is_at_least_as_strict_as(&c), Some(true));
+        assert_eq!(c.is_at_least_as_strict_as(&d), Some(false));
+        // `b` is shared by two capped choices so neither draws it, but `bb` is still accepted
+        let shared = spec("2//0-2|ab//0-2|bc");
+        let without_b = spec("2//0-2|a//0-2|c");
+        assert!(shared.matches("bb"));
+        assert!(!without_b.matches("bb"));
+        assert_eq!(shared.is_at_least_as_strict_as(&without_b), Some(false));
+        assert_eq!(without_b.is_at_least_as_strict_as(&shared), Some(true));
+    }
+
+    #[test]
+    fn units_and_other_limits() {
+        // 8 bytes of latin-1 can be fewer than 8 characters, neither contains the other
+        let bytes = spec("8B//0+|:lower-latin1:");
+        let chars = spec("8//0+|:lower-latin1:");
+        assert_eq!(bytes.is_at_least_as_strict_as(&chars), None);
+        assert_eq!(chars.is_at_least_as_strict_as(&bytes), None);
+        assert_eq!(bytes.is_at_least_as_strict_as(&bytes), Some(true));
+
+        let old = PasswordSpec::default();
+        assert_eq!(
+            old.clone().max_bytes(32).is_at_least_as_strict_as(&old),
+            None
+        );
+        assert_eq!(
+            old.clone().min_entropy(64.0).is_at_least_as_strict_as(&old),
+            None
+        );
+        let capped = old.clone().max_bytes(40);
+        assert_eq!(capped.is_at_least_as_strict_as(&capped), Some(true));
+    }
+
+    #[test]
+    fn position_rules() {
+        let a = spec("4//4|AB");
+        let ruled = a
+            .clone()
+            .position_rule(0, PositionRule::MustNotBe(Charset::Custom(vec!['A'])));
+        // `a` accepts `ABAB`, `ruled` doesn't
+        assert_eq!(a.is_at_least_as_strict_as(&ruled), None);
+        assert_eq!(ruled.is_at_least_as_strict_as(&a), None);
+        assert_eq!(ruled.is_at_least_as_strict_as(&ruled), Some(true));
+    }
+
+    #[test]
+    fn no_adjacent() {
+        let a = spec("16//0+|:upper://0+|:lower:");
+        let apart = a.clone().no_adjacent_from(Charset::Upper);
+        // `a` accepts `AAAAAAAAaaaaaaaa`, `apart` doesn't
+        assert!(a.matches("AAAAAAAAaaaaaaaa") && !apart.matches("AAAAAAAAaaaaaaaa"));
+        assert_eq!(a.is_at_least_as_strict_as(&apart), None);
+        assert_eq!(apart.is_at_least_as_strict_as(&a), None);
+        assert_eq!(apart.is_at_least_as_strict_as(&apart), Some(true));
+    }
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pants_gen::{
+        charset::Charset,
+        confusables::Confusables,
+        pwgen::{PwgenOptions, AMBIGUOUS},
+    };
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("pants_gen_confusables_{}", name))
+    }
+
+    #[test]
+    fn builtin_matches_pwgen() {
+        let builtin = Confusables::builtin();
+        assert!(AMBIGUOUS.chars().all(|c| builtin.contains(c)));
+        assert_eq!(
+            builtin.groups().iter().map(Vec::len).sum::<usize>(),
+            AMBIGUOUS.len()
+        );
+    }
+
+    #[test]
+    fn overlapping_groups_merge() {
+        let confusables = Confusables::parse("0Oo\n\n1lI|\no0Q\n");
+        assert_eq!(confusables.groups().len(), 2);
+        assert!(confusables
+            .groups()
+            .iter()
+            .any(|g| g.len() == 4 && g.contains(&'Q') && g.contains(&'O')));
+        let extended = Confusables::builtin().extend(Confusables::parse("{(\n"));
+        assert!(extended.contains('{') && extended.contains('B'));
+    }
+
+    #[test]
+    fn comments_skipped() {
+        let confusables = Confusables::parse("# lookalikes\n0O # zero and oh\n\\#H\n");
+        assert_eq!(confusables.groups(), &[vec!['0', 'O'], vec!['#', 'H']]);
+    }
+
+    #[test]
+    fn custom_file_filters() {
+        let path = temp_path("custom");
+        std::fs::write(&path, "ab\nxyz\n").unwrap();
+        let confusables = Confusables::from_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let filtered = confusables.filter(&Charset::Lower).to_charset().to_vec();
+        assert_eq!(filtered.len(), 21);
+        assert!(!filtered.contains(&'a') && !filtered.contains(&'z'));
+        // the built in list isn't used, `l` stays
+        assert!(filtered.contains(&'l'));
+
+        let mut options = PwgenOptions::from_args(["-B", "12"], false).unwrap();
+        options.confusables = confusables;
+        for _ in 0..50 {
+            let password = options.spec().generate().unwrap();
+            assert!(!password.contains(['a', 'b', 'x', 'y', 'z']));
+        }
+    }
+
+    #[test]
+    fn empty_file_filters_nothing() {
+        let path = temp_path("empty");
+        std::fs::write(&path, "").unwrap();
+        let confusables = Confusables::from_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(confusables.groups().is_empty());
+        assert_eq!(confusables.filter(&Charset::Upper), Charset::Upper);
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{units::LengthUnit, Charset, Interval, PasswordSpec};
+
+    #[test]
+    fn default_spec_in_the_grammar() {
+        let spec = PasswordSpec::builtin_default();
+        assert_eq!(
+            format!("{:?}", spec),
+            "PasswordSpec(32//1+|:upper://1+|:lower://1+|:number://1+|:symbol:)"
+        );
+        assert_eq!(
+            format!("{:?}", spec.choices().next().unwrap()),
+            "Choice(1+|:upper:)"
+        );
+        assert!(!format!("{:?}", spec).contains(&usize::MAX.to_string()));
+        assert!(!format!("{:#?}", spec).contains(&usize::MAX.to_string()));
+    }
+
+    #[test]
+    fn intervals() {
+        assert_eq!(format!("{:?}", Interval::at_least(2)), "Interval(2+)");
+        assert_eq!(format!("{:?}", Interval::at_most(3)), "Interval(3-)");
+        assert_eq!(
+            format!("{:?}", Interval::new(1, 4).unwrap()),
+            "Interval(1-4)"
+        );
+        assert_eq!(
+            format!("{:?}", Some(Interval::exactly(5))),
+            "Some(Interval(5))"
+        );
+    }
+
+    #[test]
+    fn beyond_the_spec_string() {
+        let spec = PasswordSpec::new()
+            .length(10)
+            .include(Charset::Lower.at_least(1))
+            .prefix("ab")
+            .length_unit(LengthUnit::Bytes)
+            .max_bytes(12);
+        assert_eq!(
+            format!("{:?}", spec),
+            "PasswordSpec(10B//1+|:lower:, prefix: \"ab\", max bytes: 12)"
+        );
+        // specs that differ outside the string don't look the same
+        assert_ne!(
+            format!("{:?}", spec),
+            format!("{:?}", spec.clone().prefix("cd"))
+        );
+    }
+
+    #[test]
+    fn raw_fields() {
+        let interval = Interval::at_least(1);
+        assert_eq!(
+            format!("{:?}", interval.raw_debug()),
+            format!("Interval {{ min: 1, max: {} }}", usize::MAX)
+        );
+        let spec = PasswordSpec::new()
+            .length(8)
+            .include(Charset::Number.at_least(1));
+        let raw = format!("{:?}", spec.raw_debug());
+        assert!(
+            raw.starts_with("PasswordSpec { length: Chars(8), choices: [Choice { min: 1, max: ")
+        );
+        assert!(raw.contains("chars: Number"));
+        assert!(raw.contains("max_bytes: None"));
+    }
+}
@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor, Read};
+
+    use pants_gen::dedup::find_duplicates;
+
+    #[test]
+    fn no_duplicates() {
+        let input = "aB3$xyz\nQw9!rty\n\nZx8&uio\n";
+        assert!(find_duplicates(Cursor::new(input)).unwrap().is_empty());
+        assert!(find_duplicates(Cursor::new("")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn duplicates() {
+        let input = "aB3$xyz\nQw9!rty\r\naB3$xyz\nZx8&uio\nQw9!rty\naB3$xyz";
+        let reports = find_duplicates(Cursor::new(input)).unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].lines, vec![1, 3, 6]);
+        assert_eq!(reports[0].count(), 3);
+        assert_eq!(reports[0].password(), "aB3$xyz");
+        // the line ending isn't part of the password
+        assert_eq!(reports[1].lines, vec![2, 5]);
+        assert_eq!(reports[1].password(), "Qw9!rty");
+    }
+
+    #[test]
+    fn hidden_by_default() {
+        let reports = find_duplicates(Cursor::new("hunter2\nhunter2\n")).unwrap();
+        let shown = reports[0].to_string();
+        assert!(!shown.contains("hunter2"));
+        assert!(shown.ends_with(" appears 2 times, lines 1, 2"));
+        assert_eq!(shown.split(' ').next().unwrap().len(), 32);
+        assert_eq!(
+            reports[0].revealed(),
+            "`hunter2` appears 2 times, lines 1, 2"
+        );
+        // the same password always gets the same fingerprint
+        let again = find_duplicates(Cursor::new("x\nhunter2\nhunter2\n")).unwrap();
+        assert_eq!(again[0].fingerprint, reports[0].fingerprint);
+    }
+
+    // lines made up as they're read, the input is never in memory as a whole
+    struct Synthetic {
+        next: usize,
+        total: usize,
+        pending: Vec<u8>,
+    }
+
+    impl Read for Synthetic {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.pending.is_empty() {
+                if self.next == self.total {
+                    return Ok(0);
+                }
+                // every 1000th line repeats the first one
+                let line = if self.next % 1000 == 999 {
+                    0
+                } else {
+                    self.next
+                };
+                self.pending = format!("password-{:08}\n", line).into_bytes();
+                self.next += 1;
+            }
+            let n = buf.len().min(self.pending.len());
+            buf[..n].copy_from_slice(&self.pending[..n]);
+            self.pending.drain(..n);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn streams_large_input() {
+        let input = Synthetic {
+            next: 0,
+            total: 200_000,
+            pending: vec![],
+        };
+        let reports = find_duplicates(BufReader::new(input)).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].password(), "password-00000000");
+        assert_eq!(reports[0].count(), 201);
+        assert_eq!(reports[0].lines[1], 1000);
+    }
+}
@@ -0,0 +1,236 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pants_gen::{
+        fill::FillMode, password::GenerateError, sampling::SamplingMode, Charset, Interval,
+        PasswordSpec,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn spec(s: &str) -> PasswordSpec {
+        s.parse().unwrap()
+    }
+
+    // every string of `length` characters from `alphabet`
+    fn strings(alphabet: &[char], length: usize) -> Vec<String> {
+        let mut all = vec![String::new()];
+        for _ in 0..length {
+            all = all
+                .iter()
+                .flat_map(|s| alphabet.iter().map(move |c| format!("{}{}", s, c)))
+                .collect();
+        }
+        all
+    }
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    #[test]
+    fn simple_cases() {
+        assert!(close(spec("4//0+|ab").entropy_bits(), 4.0, 1e-9));
+        // one from each in either order
+        assert!(close(spec("2//1|ab//1|cd").entropy_bits(), 3.0, 1e-9));
+        let default = PasswordSpec::builtin_default();
+        let bits = default.entropy_bits();
+        assert!(bits > default.entropy().unwrap());
+        assert!(bits < 32.0 * (94f64).log2());
+    }
+
+    // the chances of every password the spec can make add up to one, and averaged they give the
+    // entropy of the spec
+    #[test]
+    fn password_bits_agree_with_spec_bits() {
+        for (spec, fill) in [
+            (spec("4//1+|ab//0+|cde"), FillMode::Random),
+            (spec("5//1-2|ab//0+|cde"), FillMode::Random),
+            (spec("4//0,2|ab//1+|cd//0+|e"), FillMode::Random),
+            (spec("5//1+|ab//1+|cd//0+|e"), FillMode::Balanced),
+            (spec("4//0+|a//1-3|bcd"), FillMode::Balanced),
+        ] {
+            let spec = spec.fill_mode(fill);
+            let mut total = 0.0;
+            let mut entropy = 0.0;
+            for password in strings(&['a', 'b', 'c', 'd', 'e'], spec.resolve_length().unwrap()) {
+                // passwords that match but the spec couldn't have generated have no estimate
+                if let Some(bits) = spec.password_entropy_bits(&password) {
+                    let p = (-bits).exp2();
+                    total += p;
+                    entropy += p * bits;
+                }
+            }
+            assert!(close(total, 1.0, 1e-9), "{} adds up to {}", spec, total);
+            assert!(close(entropy, spec.entropy_bits(), 1e-9), "{}", spec);
+        }
+    }
+
+    // uniform sampling makes every matching password as likely, so each is worth the log of how
+    // many there are
+    #[test]
+    fn uniform_sampling_bits() {
+        for spec in [spec("4//1+|ab//1-2|cde"), spec("4//0,2|ab//1+|cd//0+|e")] {
+            let spec = spec.sampling_mode(SamplingMode::Uniform);
+            let matching: Vec<String> = strings(&['a', 'b', 'c', 'd', 'e'], 4)
+                .into_iter()
+                .filter(|password| spec.matches(password))
+                .collect();
+            let bits = (matching.len() as f64).log2();
+            assert!(close(spec.entropy_bits(), bits, 1e-9), "{}", spec);
+            for password in &matching {
+                let found = spec.password_entropy_bits(password).unwrap();
+                assert!(close(found, bits, 1e-9), "{}: {}", password, found);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_sampled_frequencies() {
+        let spec = spec("3//1+|ab//0-1|cd");
+        let mut rng = StdRng::seed_from_u64(507);
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        let samples = 100_000;
+        for _ in 0..samples {
+            *seen
+                .entry(spec.generate_with_rng(&mut rng).unwrap())
+                .or_default() += 1;
+        }
+        let sampled: f64 = seen
+            .values()
+            .map(|n| {
+                let p = *n as f64 / samples as f64;
+                -p * p.log2()
+            })
+            .sum();
+        assert!(close(sampled, spec.entropy_bits(), 0.01));
+        for (password, n) in seen {
+            let expected = (-spec.password_entropy_bits(&password).unwrap()).exp2();
+            assert!(
+                close(n as f64 / samples as f64, expected, 0.01),
+                "{}",
+                password
+            );
+        }
+    }
+
+    #[test]
+    fn edge_cases() {
+        // an unbounded minimum that takes the whole length
+        let spec = PasswordSpec::new()
+            .length(8)
+            .include(Charset::Number.at_least(8));
+        assert!(close(spec.entropy_bits(), 8.0 * 10f64.log2(), 1e-9));
+        assert!(close(
+            spec.password_entropy_bits("12345678").unwrap(),
+            8.0 * 10f64.log2(),
+            1e-9
+        ));
+        // overlapping charsets
+        let overlapping = PasswordSpec::new()
+            .length(12)
+            .include(Charset::Lower.at_least(1))
+            .include(Charset::Custom("abc123".chars().collect()).at_least(1));
+        assert!(overlapping.entropy_bits() > 0.0);
+        let password = overlapping.generate().unwrap();
+        assert!(overlapping.password_entropy_bits(&password).is_some());
+        // empty and infeasible specs have nothing to generate
+        assert_eq!(PasswordSpec::new().entropy_bits(), 0.0);
+        let empty = PasswordSpec::new()
+            .length(4)
+            .custom(vec![], Interval::at_least(1));
+        assert_eq!(empty.entropy_bits(), 0.0);
+        assert_eq!(empty.password_entropy_bits("abcd"), None);
+        let infeasible = PasswordSpec::new().length(2).upper_at_least(3);
+        assert_eq!(infeasible.entropy_bits(), 0.0);
+        // passwords that don't match aren't estimated
+        assert_eq!(spec.password_entropy_bits("1234567"), None);
+        // affixes are fixed and add nothing
+        let affixed = PasswordSpec::new()
+            .length(4)
+            .include(Charset::Number.at_least(1))
+            .prefix("id-");
+        assert!(close(affixed.entropy_bits(), 4.0 * 10f64.log2(), 1e-9));
+        assert!(affixed.password_entropy_bits("id-1234").is_some());
+    }
+
+    // lengths too long to model give the guaranteed entropy without building tables for them
+    #[test]
+    fn huge_lengths_fall_back() {
+        for length in [(1 << 20) + 1, 1 << 40, usize::MAX] {
+            let spec = PasswordSpec::default().length(length);
+            assert_eq!(spec.entropy_bits(), spec.entropy().unwrap());
+            assert!(spec.entropy_bits() > 0.0);
+            assert_eq!(spec.password_entropy_bits("aA1!"), None);
+            // uniform sampling can't generate passwords that long at all
+            let uniform = spec.sampling_mode(SamplingMode::Uniform);
+            assert_eq!(uniform.entropy_bits(), 0.0);
+            assert_eq!(uniform.password_entropy_bits("aA1!"), None);
+        }
+    }
+
+    #[test]
+    fn long_capped_specs_fall_back() {
+        let spec: PasswordSpec =
+            "400//0-150|:upper://0-150|:lower://0-150|:number://0-150|:symbol:"
+                .parse()
+                .unwrap();
+        let bits = spec.entropy_bits();
+        assert!(bits >= spec.entropy().unwrap());
+        assert!(bits.is_finite());
+    }
+
+    #[test]
+    fn min_entropy_boundaries() {
+        // 8 digits guarantee exactly 8 * log2(10) bits
+        let digits = PasswordSpec::new()
+            .length(8)
+            .include(Charset::Number.at_least(1));
+        let exact = 8.0 * 10f64.log2();
+        assert!(digits.clone().min_entropy(exact).try_generate().is_ok());
+        assert!(digits.clone().min_entropy(0.0).try_generate().is_ok());
+        assert!(digits.clone().min_entropy(f64::NAN).try_generate().is_ok());
+        assert_eq!(
+            digits.clone().min_entropy(f64::NAN).required_entropy(),
+            None
+        );
+        match digits.clone().min_entropy(exact + 0.001).try_generate() {
+            Err(GenerateError::EntropyBelowMinimum { required, achieved }) => {
+                assert_eq!(required, exact + 0.001);
+                assert!((achieved - exact).abs() < 1e-9);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        let error = digits.clone().min_entropy(80.0).try_generate().unwrap_err();
+        assert_eq!(error.code(), "entropy_below_minimum");
+        assert_eq!(
+            error.to_string(),
+            "The spec guarantees 26.6 bits of entropy, short of the minimum of 80.0 bits"
+        );
+        assert!(digits
+            .clone()
+            .min_entropy(f64::INFINITY)
+            .generate()
+            .is_none());
+
+        // the guaranteed entropy counts rather than the average, the worst case is all digits
+        let mixed = PasswordSpec::new()
+            .length(10)
+            .include(Charset::Number.at_least(1))
+            .include(Charset::Lower.at_least(1));
+        let guaranteed = mixed.entropy().unwrap();
+        assert!(mixed.entropy_bits() > guaranteed + 1.0);
+        let between = mixed.clone().min_entropy(guaranteed + 1.0);
+        assert!(between.try_generate().is_err());
+        assert!(between.generate_n(3).is_none());
+        assert!(between.iter().next().is_none());
+
+        // a length in bits that reaches the minimum passes
+        let policy = PasswordSpec::default().length_bits(80).min_entropy(80.0);
+        let password = policy.try_generate().unwrap();
+        assert!(policy.matches(&password));
+        assert!(policy
+            .describe()
+            .contains("at least 80.0 bits of entropy guaranteed"));
+    }
+}
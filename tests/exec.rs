@@ -0,0 +1,69 @@
+#[cfg(all(test, unix))]
+mod tests {
+    use std::process::Command;
+
+    use pants_gen::exec::{command, run, Delivery, ExecError, PASSWORD_VAR};
+
+    fn sh(script: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(script);
+        command
+    }
+
+    #[test]
+    fn env_delivery() {
+        let status = run(
+            &mut sh(&format!("test \"${}\" = 'hunter2!'", PASSWORD_VAR)),
+            "hunter2!",
+            Delivery::Env,
+        )
+        .unwrap();
+        assert!(status.success());
+        // only the child saw it
+        assert!(std::env::var(PASSWORD_VAR).is_err());
+    }
+
+    #[test]
+    fn stdin_delivery() {
+        let script = format!(
+            "read -r p; test \"$p\" = 'hunter2!' && test -z \"${}\"",
+            PASSWORD_VAR
+        );
+        let status = run(&mut sh(&script), "hunter2!", Delivery::Stdin).unwrap();
+        assert!(status.success());
+        // children that don't read their input are fine
+        let status = run(&mut sh("true"), "hunter2!", Delivery::Stdin).unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn exit_status_passed_on() {
+        let status = run(&mut sh("exit 3"), "hunter2!", Delivery::Env).unwrap();
+        assert_eq!(status.code(), Some(3));
+    }
+
+    #[test]
+    fn spawn_failure() {
+        let err = run(
+            &mut command("pants-gen-no-such-program --flag").unwrap(),
+            "hunter2!",
+            Delivery::Stdin,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, ExecError::Spawn(program, _) if program == "pants-gen-no-such-program")
+        );
+        assert!(!err.to_string().contains("hunter2"));
+        assert!(matches!(command("   "), Err(ExecError::NoCommand)));
+    }
+
+    #[test]
+    fn command_line() {
+        let command = command("smbpasswd -s  newuser").unwrap();
+        assert_eq!(command.get_program(), "smbpasswd");
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            vec!["-s", "newuser"]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        fallible::TryRng,
+        password::{GenerateError, PasswordSpec},
+    };
+    use rand::{rngs::StdRng, Error, RngCore, SeedableRng};
+
+    // hands out `budget` bytes and then fails
+    struct Exhaustible {
+        inner: StdRng,
+        budget: usize,
+        used: usize,
+    }
+
+    impl Exhaustible {
+        fn new(budget: usize) -> Self {
+            Self {
+                inner: StdRng::seed_from_u64(476),
+                budget,
+                used: 0,
+            }
+        }
+    }
+
+    impl RngCore for Exhaustible {
+        fn next_u32(&mut self) -> u32 {
+            panic!("generation has to go through try_fill_bytes")
+        }
+        fn next_u64(&mut self) -> u64 {
+            panic!("generation has to go through try_fill_bytes")
+        }
+        fn fill_bytes(&mut self, _: &mut [u8]) {
+            panic!("generation has to go through try_fill_bytes")
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+            if self.used + dest.len() > self.budget {
+                return Err(Error::new("entropy exhausted"));
+            }
+            self.used += dest.len();
+            self.inner.try_fill_bytes(dest)
+        }
+    }
+
+    fn spec() -> PasswordSpec {
+        "12//1+|:upper://1+|:lower://1+|:number://1+|:symbol:"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn enough_entropy() {
+        let mut rng = Exhaustible::new(usize::MAX);
+        let password = spec().generate_with_try_rng(&mut rng).unwrap();
+        assert!(spec().matches(&password));
+        assert!(rng.used > 0);
+    }
+
+    #[test]
+    fn fails_anywhere() {
+        // running out at every point of a generation, from the first choice's draws to the last
+        // swap of the shuffle, is an error and never a password
+        let needed = {
+            let mut rng = Exhaustible::new(usize::MAX);
+            spec().generate_with_try_rng(&mut rng).unwrap();
+            rng.used
+        };
+        for budget in 0..needed {
+            let result = spec().generate_with_try_rng(&mut Exhaustible::new(budget));
+            assert!(
+                matches!(result, Err(GenerateError::RngFailure(_))),
+                "{} {:?}",
+                budget,
+                result
+            );
+        }
+        assert!(spec()
+            .generate_with_try_rng(&mut Exhaustible::new(needed))
+            .is_ok());
+    }
+
+    #[test]
+    fn batch() {
+        let needed = {
+            let mut rng = Exhaustible::new(usize::MAX);
+            spec()
+                .generate_batch_with_try_rng(3, &mut rng)
+                .unwrap()
+                .len();
+            rng.used
+        };
+        let result = spec().generate_batch_with_try_rng(3, &mut Exhaustible::new(needed - 1));
+        assert!(matches!(result, Err(GenerateError::RngFailure(_))));
+        let passwords = spec()
+            .generate_batch_with_try_rng(3, &mut Exhaustible::new(needed))
+            .unwrap();
+        assert_eq!(passwords.len(), 3);
+    }
+
+    #[test]
+    fn error_details() {
+        let error = spec()
+            .generate_with_try_rng(&mut Exhaustible::new(0))
+            .unwrap_err();
+        assert_eq!(error.code(), "rng_failure");
+        assert!(error.to_string().contains("entropy exhausted"));
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    #[test]
+    fn run() {
+        let mut inner = Exhaustible::new(4);
+        let result = TryRng::run(&mut inner, |rng| {
+            let first = rng.next_u32();
+            assert!(!rng.failed());
+            rng.next_u32();
+            assert!(rng.failed());
+            Ok(first)
+        });
+        assert!(matches!(result, Err(GenerateError::RngFailure(_))));
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{charset::Charset, password::PasswordSpec, position::PositionRule};
+
+    fn parse(s: &str) -> PasswordSpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn contiguous() {
+        let spec = parse("16//1+|:upper://1+|:lower://1-3|:number:");
+        assert_eq!(spec.feasible_lengths(0..=6), vec![3, 4, 5, 6]);
+        let bounded = parse("16//1-2|:upper://2|:lower:");
+        assert_eq!(bounded.feasible_lengths(0..=10), vec![3, 4]);
+    }
+
+    #[test]
+    fn gaps() {
+        let spec = parse("8//0,4|a//0,4|b");
+        assert_eq!(spec.feasible_lengths(0..=10), vec![0, 4, 8]);
+        let spec = parse("8//0,4|a//0,4|b//1-2|c");
+        assert_eq!(spec.feasible_lengths(0..=12), vec![1, 2, 5, 6, 9, 10]);
+    }
+
+    #[test]
+    fn agrees_with_generation() {
+        let spec = parse("8//0,3|a//2,5-6|b//0-1|c");
+        for length in 0..=12 {
+            let generated = spec.clone().length(length).generate().is_some();
+            assert_eq!(
+                spec.feasible_lengths(length..=length) == vec![length],
+                generated,
+                "{}",
+                length
+            );
+        }
+    }
+
+    #[test]
+    fn affixes_and_rules() {
+        let spec = parse("8//1+|:lower:")
+            .prefix("ab")
+            .length_includes_affixes(true);
+        assert_eq!(spec.feasible_lengths(0..=4), vec![3, 4]);
+
+        let spec = parse("8//2|:symbol://0+|:lower:")
+            .no_adjacent_from(Charset::Symbol)
+            .position_rule(-4, PositionRule::MustBe(Charset::Lower));
+        assert_eq!(spec.feasible_lengths(0..=5), vec![4, 5]);
+    }
+
+    #[test]
+    fn affixes_in_bytes() {
+        // the prefix is one character but two bytes
+        let spec = parse("8B//1+|:lower:")
+            .prefix("\u{e9}")
+            .length_includes_affixes(true);
+        assert_eq!(spec.feasible_lengths(0..=5), vec![3, 4, 5]);
+        for length in 0..=5 {
+            let generated = spec.clone().length(length).generate().is_some();
+            assert_eq!(
+                spec.feasible_lengths(length..=length) == vec![length],
+                generated,
+                "{}",
+                length
+            );
+        }
+    }
+}
@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{fill::FillMode, password::PasswordSpec};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn counts(spec: &PasswordSpec, password: &str) -> Vec<usize> {
+        spec.count_by_charset(password)
+            .into_iter()
+            .map(|(_, count)| count)
+            .collect()
+    }
+
+    #[test]
+    fn even() {
+        let spec = PasswordSpec::default().fill_mode(FillMode::Balanced);
+        let mut rng = StdRng::seed_from_u64(473);
+        for _ in 0..200 {
+            let password = spec.generate_with_rng(&mut rng).unwrap();
+            let counts = counts(&spec, &password);
+            let (min, max) = (counts.iter().min().unwrap(), counts.iter().max().unwrap());
+            assert!(max - min <= 1, "{:?}", counts);
+            assert!(spec.matches(&password));
+        }
+    }
+
+    #[test]
+    fn minima_and_maxima() {
+        // 3 upper and 1 lower up front, then the other 8 split as evenly as 2 numbers at most allow
+        let spec: PasswordSpec = "14//3+|:upper://1+|:lower://0-2|:number:"
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .fill_mode(FillMode::Balanced);
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..200 {
+            let password = spec.generate_with_rng(&mut rng).unwrap();
+            let counts = counts(&spec, &password);
+            assert_eq!(counts[2], 2, "{:?}", counts);
+            let extra = [counts[0] - 3, counts[1] - 1];
+            assert!(extra[0].abs_diff(extra[1]) <= 1, "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn default_unchanged() {
+        assert_eq!(
+            PasswordSpec::new().fill_mode(FillMode::Random),
+            PasswordSpec::new()
+        );
+        let spec = PasswordSpec::default();
+        let mut rng = StdRng::seed_from_u64(2);
+        // plain random filling lets a class run well ahead of the others now and then
+        let lopsided = (0..200).any(|_| {
+            let password = spec.generate_with_rng(&mut rng).unwrap();
+            let counts = counts(&spec, &password);
+            counts.iter().max().unwrap() - counts.iter().min().unwrap() > 1
+        });
+        assert!(lopsided);
+    }
+}
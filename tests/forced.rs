@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        charset::Charset,
+        lint::LintWarning,
+        password::{GenerateError, PasswordSpec},
+        position::PositionRule,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn spec(s: &str) -> PasswordSpec {
+        s.parse().unwrap()
+    }
+
+    fn counts(spec: &PasswordSpec) -> Option<Vec<(Charset, usize)>> {
+        spec.forced_composition().map(|composition| {
+            composition
+                .into_iter()
+                .map(|(choice, n)| (choice.chars, n))
+                .collect()
+        })
+    }
+
+    #[test]
+    fn sum_of_minimums() {
+        let forced = spec("8//2+|:upper://6+|:lower://0-2|:symbol:");
+        assert_eq!(
+            counts(&forced),
+            Some(vec![
+                (Charset::Upper, 2),
+                (Charset::Lower, 6),
+                (Charset::Symbol, 0)
+            ])
+        );
+        let maxed = spec("6//0-2|:upper://1-4|:lower:");
+        assert_eq!(
+            counts(&maxed),
+            Some(vec![(Charset::Upper, 2), (Charset::Lower, 4)])
+        );
+        assert_eq!(
+            counts(&spec("9//2+|:upper://6+|:lower://0-2|:symbol:")),
+            None
+        );
+        assert_eq!(counts(&PasswordSpec::default()), None);
+    }
+
+    #[test]
+    fn lint_flags_unused_ranges() {
+        let warnings = spec("8//2+|:upper://6+|:lower://0-2|:symbol:").lint();
+        let forced: Vec<_> = warnings
+            .iter()
+            .filter(|w| matches!(w, LintWarning::ForcedComposition { .. }))
+            .collect();
+        assert_eq!(forced.len(), 1);
+        assert_eq!(
+            forced[0].to_string(),
+            "The length leaves no room for the ranges, every password has 2 from `:upper:`, 6 from `:lower:`, 0 from `:symbol:`"
+        );
+        // exact counts adding up to the length are what the spec asks for
+        let exact = spec("16//4|:upper://8|:lower://4|:number:");
+        assert!(exact.forced_composition().is_some());
+        assert!(exact.lint().is_empty());
+    }
+
+    #[test]
+    fn explained() {
+        let described = spec("8//2+|:upper://6+|:lower://0-2|:symbol:").describe();
+        assert!(described.ends_with(
+            "the length leaves no room, always 2 from :upper:, 6 from :lower:, 0 from :symbol:"
+        ));
+        let exact = spec("16//4|:upper://8|:lower://4|:number:").describe();
+        assert!(!exact.contains("leaves no room"));
+    }
+
+    #[test]
+    fn rules_checked_up_front() {
+        // there's no room for a symbol so the first position can never be one
+        let forced = spec("8//2+|:upper://6+|:lower://0-2|:symbol:")
+            .position_rule(0, PositionRule::MustBe(Charset::Symbol));
+        let mut rng = StdRng::seed_from_u64(3);
+        assert!(matches!(
+            forced.generate_detailed_with_rng(&mut rng),
+            Err(GenerateError::PositionConflict(0))
+        ));
+        // with room it's a matter of drawing a symbol
+        let roomy = spec("9//2+|:upper://6+|:lower://0-2|:symbol:")
+            .position_rule(0, PositionRule::MustBe(Charset::Symbol));
+        let password = roomy.generate_detailed_with_rng(&mut rng).unwrap();
+        assert!(Charset::Symbol.contains(password.password().chars().next().unwrap()));
+
+        // every count at its maximum keeps too many numbers to keep apart
+        let maxed = spec("4//0-3|:number://0-1|:upper:").no_adjacent_from(Charset::Number);
+        assert!(matches!(
+            maxed.generate_detailed_with_rng(&mut rng),
+            Err(GenerateError::AdjacencyInfeasible { count: 3, .. })
+        ));
+    }
+}
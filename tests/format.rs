@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        charset::Charset,
+        password::{PasswordParseError, PasswordSpec},
+    };
+
+    #[test]
+    fn legacy_strings_parse_identically() {
+        let spec: PasswordSpec = "16//3+|:upper://1-2|:lower://3-|:number://1|:symbol:"
+            .parse()
+            .unwrap();
+        let expected = PasswordSpec::new()
+            .length(16)
+            .upper_at_least(3)
+            .lower(pants_gen::interval::Interval::new(1, 2).unwrap())
+            .number_at_most(3)
+            .symbol_exactly(1);
+        assert_eq!(spec, expected);
+
+        let spec: PasswordSpec = "12//2|a/b//1+|\\x".parse().unwrap();
+        assert_eq!(
+            spec,
+            PasswordSpec::new()
+                .length(12)
+                .custom_exactly(vec!['a', '/', 'b'], 2)
+                .custom_at_least(vec!['\\', 'x'], 1)
+        );
+        assert!(!spec.to_string().starts_with("v2:"));
+        assert!(!PasswordSpec::default().to_string().starts_with("v2:"));
+    }
+
+    #[test]
+    fn v2_round_trip() {
+        let spec = PasswordSpec::new()
+            .length(12)
+            .custom_exactly(":upper:".chars().collect(), 2)
+            .custom_at_least(vec!['a', '/', '/', 'b', '\\', '|'], 1)
+            .lower_at_least(0);
+        let s = spec.to_string();
+        assert!(s.starts_with("v2:12//"));
+        assert!(s.contains(r"2|\:upper\:"));
+        assert!(s.contains(r"1+|a\/\/b\\\|"));
+        assert!(s.contains("0+|:lower:"));
+        let parsed: PasswordSpec = s.parse().unwrap();
+        assert_eq!(parsed, spec);
+        assert!(parsed
+            .choices()
+            .any(|c| c.chars == Charset::Custom(":upper:".chars().collect())));
+    }
+
+    #[test]
+    fn v2_accepts_unescaped_named_sets() {
+        let spec: PasswordSpec = "v2:8//1+|:upper://1+|x\\/y".parse().unwrap();
+        assert_eq!(
+            spec,
+            PasswordSpec::new()
+                .length(8)
+                .upper_at_least(1)
+                .custom_at_least(vec!['x', '/', 'y'], 1)
+        );
+        assert!(matches!(
+            "v2:8//1+|x\\".parse::<PasswordSpec>(),
+            Err(PasswordParseError::DanglingEscape)
+        ));
+        assert!(matches!(
+            "v2:8//1+|x////1|y".parse::<PasswordSpec>(),
+            Err(PasswordParseError::EmptySegment)
+        ));
+    }
+
+    #[test]
+    fn missing_prefix_is_suggested() {
+        let err = r"8//2|a\//b".parse::<PasswordSpec>().unwrap_err();
+        assert!(matches!(err, PasswordParseError::MissingVersion(_)));
+        assert!(err.to_string().contains("v2:"));
+        assert!(r"v2:8//2|a\//b".parse::<PasswordSpec>().is_ok());
+        // plain errors aren't affected
+        assert!(matches!(
+            "eight//1|a".parse::<PasswordSpec>(),
+            Err(PasswordParseError::InvalidLength(_))
+        ));
+        // v2 reads a bare length too, but there's nothing to escape
+        let err = "8".parse::<PasswordSpec>().unwrap_err();
+        assert!(!matches!(err, PasswordParseError::MissingVersion(_)));
+        assert!(!err.to_string().contains("v2:"));
+    }
+
+    #[test]
+    fn legacy_brackets() {
+        let cases = [
+            (
+                "16[3+|:upper:][1-2|:lower:][3-|:number:][1|:symbol:]",
+                "16//3+|:upper://1-2|:lower://3-|:number://1|:symbol:",
+            ),
+            ("8[8|:number:]", "8//8|:number:"),
+            ("12[1+|abc][2|:upper:]", "12//2|:upper://1+|abc"),
+        ];
+        for (legacy, current) in cases {
+            assert!(PasswordSpec::is_legacy_format(legacy));
+            let spec = legacy.parse::<PasswordSpec>().unwrap();
+            assert_eq!(spec, current.parse::<PasswordSpec>().unwrap());
+            assert_eq!(spec.to_string(), current);
+        }
+        assert!("8[8|:number:".parse::<PasswordSpec>().is_err());
+        assert!("8[8|:number:][]".parse::<PasswordSpec>().is_err());
+    }
+
+    #[test]
+    fn brackets_in_current_format() {
+        // custom charsets with brackets in the current format aren't mistaken for the old one
+        for current in ["8//8|[]", "8//4|[//4|]", "v2:8//8|\\[]", "8//8|][x"] {
+            assert!(!PasswordSpec::is_legacy_format(current));
+            let spec = current.parse::<PasswordSpec>().unwrap();
+            assert_eq!(
+                spec.to_string()
+                    .parse::<PasswordSpec>()
+                    .unwrap()
+                    .to_string(),
+                spec.to_string()
+            );
+        }
+        // lone brackets in a legacy custom charset are part of it, `][` always splits
+        let spec = "8[8|[]]".parse::<PasswordSpec>().unwrap();
+        assert_eq!(spec.to_string(), "8//8|[]");
+        let spec = "8[4|a]b][4|c]".parse::<PasswordSpec>().unwrap();
+        assert_eq!(spec.to_string(), "8//4|a]b//4|c");
+    }
+}
@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        charset::Charset, generated::RerollError, password::PasswordSpec, position::PositionRule,
+        violation::Violation,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn same_as_generate() {
+        let spec = PasswordSpec::default();
+        let plain = spec
+            .generate_with_rng(&mut StdRng::seed_from_u64(7))
+            .unwrap();
+        let detailed = spec
+            .generate_detailed_with_rng(&mut StdRng::seed_from_u64(7))
+            .unwrap();
+        assert_eq!(detailed.password(), plain);
+        for (c, charset) in plain.chars().zip(detailed.sources()) {
+            assert!(charset.contains(c));
+        }
+    }
+
+    #[test]
+    fn reroll_keeps_classes() {
+        let spec = PasswordSpec::default().prefix("id-");
+        let mut rng = StdRng::seed_from_u64(8);
+        let mut generated = spec.generate_detailed_with_rng(&mut rng).unwrap();
+        let before: Vec<char> = generated.password().chars().collect();
+        generated.reroll_positions(7..=10, &mut rng).unwrap();
+        let after: Vec<char> = generated.password().chars().collect();
+        assert!(spec.matches(&generated.password()));
+        for (i, (old, new)) in before.iter().zip(&after).enumerate() {
+            if (6..10).contains(&i) {
+                assert_ne!(old, new);
+                assert!(generated.sources()[i - 3].contains(*new));
+            } else {
+                assert_eq!(old, new);
+            }
+        }
+    }
+
+    #[test]
+    fn bad_positions() {
+        let spec = PasswordSpec::new().length(4).lower_at_least(1).prefix("x");
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut generated = spec.generate_detailed_with_rng(&mut rng).unwrap();
+        assert!(matches!(
+            generated.reroll_positions([1], &mut rng),
+            Err(RerollError::Fixed(1))
+        ));
+        assert!(matches!(
+            generated.reroll_positions([6], &mut rng),
+            Err(RerollError::OutOfRange(6))
+        ));
+        assert!(matches!(
+            generated.reroll_positions([0], &mut rng),
+            Err(RerollError::OutOfRange(0))
+        ));
+        let mut single = PasswordSpec::new()
+            .length(2)
+            .custom_exactly(vec!['a'], 2)
+            .generate_detailed_with_rng(&mut rng)
+            .unwrap();
+        assert!(matches!(
+            single.reroll_positions([2], &mut rng),
+            Err(RerollError::NoAlternative { position: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn reroll_breaking_the_spec() {
+        // at most one `a` and one `c`, so a length of two is one of each, `ac` has nothing of its
+        // own to draw and whichever it drew, the other in its place makes two
+        let spec: PasswordSpec = "2//0-1|a//0-1|c//1+|ac".parse().unwrap();
+        let mut rng = StdRng::seed_from_u64(10);
+        let mut generated = spec.generate_detailed_with_rng(&mut rng).unwrap();
+        assert!(spec.matches(&generated.password()));
+        let position = generated
+            .sources()
+            .iter()
+            .position(|charset| *charset == Charset::Custom(vec!['a', 'c']))
+            .unwrap()
+            + 1;
+        let before = generated.password();
+        let err = generated
+            .reroll_positions([position], &mut rng)
+            .unwrap_err();
+        assert!(matches!(&err, RerollError::Violates { count: 2, .. }));
+        assert!(err.to_string().contains("2 characters from"));
+        assert_eq!(generated.password(), before);
+    }
+
+    #[test]
+    fn reroll_keeps_position_rules() {
+        let spec: PasswordSpec = "4//4|AB"
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .position_rule(0, PositionRule::MustNotBe(Charset::Custom(vec!['A'])));
+        let mut rng = StdRng::seed_from_u64(467);
+        for _ in 0..10 {
+            let mut generated = spec.generate_detailed_with_rng(&mut rng).unwrap();
+            // the first `B` rerolled to `A` is the only way to get there, which the rule refuses
+            let before = generated.password();
+            match generated.reroll_positions([1], &mut rng) {
+                Err(RerollError::Breaks(_)) => assert_eq!(generated.password(), before),
+                result => panic!("{:?} for {}", result, before),
+            }
+            generated.reroll_positions([2], &mut rng).unwrap();
+            assert!(spec.matches(&generated.password()));
+        }
+    }
+
+    #[test]
+    fn reroll_keeps_symbols_apart() {
+        let bang = Charset::Custom(vec!['!']);
+        let spec: PasswordSpec = "8//4|:number://4|!a"
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .no_adjacent_from(bang.clone());
+        let mut rng = StdRng::seed_from_u64(469);
+        let mut refused = 0;
+        for _ in 0..20 {
+            let mut generated = spec.generate_detailed_with_rng(&mut rng).unwrap();
+            for position in 1..=8 {
+                let before = generated.password();
+                // an `a` can only become a `!`, which can land next to another
+                match generated.reroll_positions([position], &mut rng) {
+                    Ok(()) => {}
+                    Err(RerollError::Breaks(Violation::Adjacent { charset, .. })) => {
+                        assert_eq!(charset, bang);
+                        assert_eq!(generated.password(), before);
+                        refused += 1;
+                    }
+                    Err(e) => panic!("{}", e),
+                }
+                assert!(spec.matches(&generated.password()));
+            }
+        }
+        assert!(refused > 0);
+    }
+}
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{default_spec, password::PasswordSpec, set_default_spec, AlreadySet};
+
+    // the default is global to the test binary, so everything about it is in one test
+    #[test]
+    fn default_override() {
+        let builtin = PasswordSpec::builtin_default();
+        assert_eq!(PasswordSpec::default(), builtin);
+        assert_eq!(default_spec(), builtin);
+
+        let policy: PasswordSpec = "24//2+|:upper://2+|:lower://2+|:number:".parse().unwrap();
+        set_default_spec(policy.clone()).unwrap();
+        assert_eq!(PasswordSpec::default(), policy);
+        assert_eq!(default_spec(), policy);
+        assert!(policy.matches(&PasswordSpec::default().generate().unwrap()));
+
+        let other: PasswordSpec = "8//8|:number:".parse().unwrap();
+        assert!(matches!(set_default_spec(other), Err(AlreadySet)));
+        assert_eq!(PasswordSpec::default(), policy);
+
+        assert_eq!(PasswordSpec::builtin_default(), builtin);
+        assert_eq!(
+            builtin.to_string(),
+            "32//1+|:upper://1+|:lower://1+|:number://1+|:symbol:"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{hash_map::DefaultHasher, HashMap},
+        hash::{Hash, Hasher},
+    };
+
+    use pants_gen::{
+        charset::Charset, choice::Choice, interval::Interval, password::PasswordSpec,
+        position::PositionRule, weights::Weights,
+    };
+
+    fn hash<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn order_doesnt_matter() {
+        let a = PasswordSpec::new()
+            .length(16)
+            .upper_at_least(1)
+            .symbol_exactly(2)
+            .custom(vec!['x', 'y'], Interval::new(1, 3).unwrap())
+            .position_rule(0, PositionRule::MustBe(Charset::Upper))
+            .position_rule(-1, PositionRule::MustNotBe(Charset::Symbol))
+            .no_adjacent_from(Charset::Symbol)
+            .no_adjacent_from(Charset::Upper);
+        let b = PasswordSpec::new()
+            .no_adjacent_from(Charset::Upper)
+            .no_adjacent_from(Charset::Symbol)
+            .position_rule(-1, PositionRule::MustNotBe(Charset::Symbol))
+            .position_rule(0, PositionRule::MustBe(Charset::Upper))
+            .custom(vec!['x', 'y'], Interval::new(1, 3).unwrap())
+            .symbol_exactly(2)
+            .upper_at_least(1)
+            .length(16);
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+        let parsed: PasswordSpec = "16//1-3|xy//2|:symbol://1+|:upper:".parse().unwrap();
+        let reordered: PasswordSpec = "16//1+|:upper://2|:symbol://1-3|xy".parse().unwrap();
+        assert_eq!(parsed, reordered);
+        assert_eq!(hash(&parsed), hash(&reordered));
+    }
+
+    #[test]
+    fn differences_count() {
+        let base: PasswordSpec = "16//1+|:upper://2|:symbol:".parse().unwrap();
+        let different = [
+            "16//2+|:upper://2|:symbol:",
+            "17//1+|:upper://2|:symbol:",
+            "16//1+|:upper://0,2|:symbol:",
+            "16//1+|caps=:upper://2|:symbol:",
+        ];
+        for s in different {
+            let spec: PasswordSpec = s.parse().unwrap();
+            assert_ne!(spec, base, "{}", s);
+        }
+        assert_ne!(base.clone().prefix("id-"), base);
+
+        // choices are compared on everything, not just the charset
+        assert_ne!(Charset::Upper.at_least(1), Charset::Upper.at_least(2));
+        let weighted = Charset::Upper
+            .at_least(1)
+            .weighted(Weights::new(vec![('A', 2.0)]).unwrap());
+        assert_ne!(weighted, Charset::Upper.at_least(1));
+        let same = Charset::Upper
+            .at_least(1)
+            .weighted(Weights::new(vec![('A', 2.0)]).unwrap());
+        assert_eq!(weighted, same);
+        assert_eq!(hash(&weighted), hash(&same));
+        assert_eq!(
+            Choice::from_interval(Interval::new(1, 2).unwrap(), Charset::Number),
+            Charset::Number.between(1, 2).unwrap()
+        );
+    }
+
+    #[test]
+    fn later_choice_replaces_earlier() {
+        let spec = PasswordSpec::new().upper_at_least(1).upper_exactly(3);
+        let choices: Vec<_> = spec.choices().collect();
+        assert_eq!(choices, vec![&Charset::Upper.exactly(3)]);
+    }
+
+    struct Compiled {
+        length: usize,
+    }
+
+    #[test]
+    fn cache() {
+        let mut cache: HashMap<PasswordSpec, Compiled> = HashMap::new();
+        let mut compile = |spec: PasswordSpec| {
+            let length = spec.resolve_length().unwrap();
+            cache.entry(spec).or_insert(Compiled { length }).length
+        };
+        compile("16//1+|:upper://1+|:number:".parse().unwrap());
+        compile("16//1+|:number://1+|:upper:".parse().unwrap());
+        compile(
+            PasswordSpec::new()
+                .length(16)
+                .number_at_least(1)
+                .upper_at_least(1),
+        );
+        assert_eq!(compile("80b//1+|:upper:".parse().unwrap()), 18);
+        assert_eq!(cache.len(), 2);
+        let key: PasswordSpec = "16//1+|:number://1+|:upper:".parse().unwrap();
+        assert_eq!(cache[&key].length, 16);
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(all(test, feature = "hashing"))]
+mod tests {
+    use argon2::{password_hash::PasswordVerifier, Argon2, PasswordHash};
+    use pants_gen::{
+        hashing::{HashAlgorithm, HashError},
+        password::PasswordSpec,
+    };
+
+    // cheap parameters so the tests stay fast
+    fn algorithm(name: &str, params: &str) -> HashAlgorithm {
+        name.parse::<HashAlgorithm>()
+            .unwrap()
+            .with_params(params)
+            .unwrap()
+    }
+
+    #[test]
+    fn argon2_verifies() {
+        let password = PasswordSpec::default().generate().unwrap();
+        let hash = algorithm("argon2", "m=1024,t=1,p=1")
+            .hash(&password)
+            .unwrap();
+        assert!(hash.starts_with("$argon2id$v=19$m=1024,t=1,p=1$"));
+        let parsed = PasswordHash::new(&hash).unwrap();
+        assert!(Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok());
+        assert!(Argon2::default()
+            .verify_password(b"something else", &parsed)
+            .is_err());
+    }
+
+    #[test]
+    fn bcrypt_verifies() {
+        let password = PasswordSpec::default().length(20).generate().unwrap();
+        let hash = algorithm("bcrypt", "cost=4").hash(&password).unwrap();
+        assert!(hash.starts_with("$2b$04$"));
+        assert!(bcrypt::verify(&password, &hash).unwrap());
+        assert!(!bcrypt::verify("something else", &hash).unwrap());
+    }
+
+    #[test]
+    fn sha512_crypt_verifies() {
+        let password = PasswordSpec::default().generate().unwrap();
+        let hash = algorithm("sha512-crypt", "rounds=1000")
+            .hash(&password)
+            .unwrap();
+        assert!(hash.starts_with("$6$rounds=1000$"));
+        assert!(pwhash::sha512_crypt::verify(&password, &hash));
+        assert!(!pwhash::sha512_crypt::verify("something else", &hash));
+    }
+
+    #[test]
+    fn param_errors() {
+        let err = "scrypt".parse::<HashAlgorithm>().unwrap_err();
+        assert!(err.to_string().contains("argon2, bcrypt or sha512-crypt"));
+
+        let argon2 = "argon2".parse::<HashAlgorithm>().unwrap();
+        let err = argon2.with_params("m=1024,x=3").unwrap_err();
+        assert!(matches!(err, HashError::UnknownParam { .. }));
+        assert_eq!(
+            err.to_string(),
+            "argon2 has no parameter `x`, expect m, t or p"
+        );
+        let err = argon2.with_params("m=lots").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Hash parameter `m` expects a whole number, got `lots`"
+        );
+        assert!(matches!(
+            argon2.with_params("m1024"),
+            Err(HashError::BadParam(_))
+        ));
+        assert!(matches!(
+            "bcrypt"
+                .parse::<HashAlgorithm>()
+                .unwrap()
+                .with_params("rounds=5"),
+            Err(HashError::UnknownParam { .. })
+        ));
+
+        // out of range costs are caught by the hashing crates
+        let err = algorithm("argon2", "m=1").hash("password").unwrap_err();
+        assert!(err.to_string().starts_with("Hashing with argon2 failed"));
+        assert!(algorithm("bcrypt", "cost=40").hash("password").is_err());
+    }
+}
@@ -0,0 +1,140 @@
+#[cfg(all(test, feature = "history"))]
+mod tests {
+    use std::path::PathBuf;
+
+    use pants_gen::{
+        history::History,
+        password::{GenerateError, PasswordSpec},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("pants_gen_history_{}", name))
+    }
+
+    #[test]
+    fn hit_and_miss() {
+        let mut history = History::new(10);
+        history.record("hunter2");
+        assert!(history.contains("hunter2"));
+        assert!(!history.contains("hunter3"));
+    }
+
+    #[test]
+    fn debug_hides_key() {
+        let path = temp_path("debug_hides_key");
+        let mut history = History::new(10);
+        history.record("hunter2");
+        history.save(&path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let key = contents.lines().next().unwrap().rsplit(' ').next().unwrap();
+        let debug = format!("{:?}", history);
+        assert!(!debug.contains(key), "{}", debug);
+        assert!(debug.contains("REDACTED"));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = temp_path("save_and_load");
+        let mut history = History::new(10);
+        history.record("hunter2");
+        history.save(&path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(!contents.contains("hunter2"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let loaded = History::load(&path, 10).unwrap();
+        assert!(loaded.contains("hunter2"));
+        assert!(!loaded.recovered());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rotation_trims_oldest() {
+        let mut history = History::new(2);
+        history.record("one");
+        history.record("two");
+        history.record("three");
+        assert_eq!(history.len(), 2);
+        assert!(!history.contains("one"));
+        assert!(history.contains("two"));
+        assert!(history.contains("three"));
+
+        // a smaller limit on load trims the file's entries too
+        let path = temp_path("rotation");
+        history.save(&path).unwrap();
+        let loaded = History::load(&path, 1).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert!(loaded.contains("three"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn corrupt_file_recovery() {
+        let path = temp_path("corrupt");
+        let mut history = History::new(10);
+        history.record("hunter2");
+        history.save(&path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, format!("{}not an entry\n", contents)).unwrap();
+        let loaded = History::load(&path, 10).unwrap();
+        assert!(loaded.recovered());
+        assert!(loaded.contains("hunter2"));
+
+        std::fs::write(&path, "garbage").unwrap();
+        let loaded = History::load(&path, 10).unwrap();
+        assert!(loaded.recovered());
+        assert!(loaded.is_empty());
+
+        // a sign is not a hex digit
+        let signed = format!("+f{}", "0".repeat(62));
+        std::fs::write(&path, format!("{}{}\n", contents, signed)).unwrap();
+        let loaded = History::load(&path, 10).unwrap();
+        assert!(loaded.recovered());
+        assert_eq!(loaded.len(), 1);
+        let (header, key) = contents.lines().next().unwrap().rsplit_once(' ').unwrap();
+        std::fs::write(&path, format!("{} +{}\n", header, &key[1..])).unwrap();
+        assert!(History::load(&path, 10).unwrap().is_empty());
+        std::fs::remove_file(&path).unwrap();
+
+        let missing = History::load(temp_path("missing"), 10).unwrap();
+        assert!(!missing.recovered());
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn rerolls_on_hit() {
+        let spec = PasswordSpec::new()
+            .length(1)
+            .custom_exactly(vec!['a', 'b'], 1);
+        let mut history = History::new(10);
+        history.record("a");
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(
+            history.generate_with_rng(&spec, &mut rng),
+            Some("b".to_string())
+        );
+        assert!(history.contains("b"));
+        // everything the spec can produce has been issued
+        assert_eq!(history.generate_with_rng(&spec, &mut rng), None);
+        assert!(matches!(
+            history.try_generate_with_rng(&spec, &mut rng),
+            Err(GenerateError::AttemptsExhausted { .. })
+        ));
+        // a spec that can't be met says why rather than re-rolling
+        let infeasible = PasswordSpec::new().length(1).upper_exactly(2);
+        assert_eq!(
+            history
+                .try_generate_with_rng(&infeasible, &mut rng)
+                .unwrap_err()
+                .code(),
+            "length_below_minimum"
+        );
+    }
+}
@@ -0,0 +1,223 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        charset::Charset, intersect::IntersectError, interval::Interval,
+        length_policy::LengthPolicy, password::PasswordSpec, unicode::Normalization,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn spec(s: &str) -> PasswordSpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn compatible_policies() {
+        // a directory wanting upper, lower and digits, and a vault allowing only a few symbols
+        let directory = spec("20//1+|:upper://1+|:lower://2+|:number://0+|:symbol:");
+        let vault = spec("20//0+|:upper://1+|:lower://1-4|:number://1+|!@#");
+        let both = directory.intersect(&vault).unwrap();
+        let mut rng = StdRng::seed_from_u64(4);
+        for _ in 0..50 {
+            let password = both.generate_with_rng(&mut rng).unwrap();
+            assert!(directory.matches(&password), "{}", password);
+            assert!(vault.matches(&password), "{}", password);
+        }
+        assert_eq!(both, vault.intersect(&directory).unwrap());
+    }
+
+    #[test]
+    fn lengths_in_bits() {
+        let a = spec("24//1+|:lower://1+|:upper:");
+        let b = PasswordSpec::new()
+            .length_bits(100)
+            .lower_at_least(0)
+            .upper_at_least(2);
+        assert_eq!(b.resolve_length().unwrap(), 22);
+        assert!(matches!(
+            a.intersect(&b),
+            Err(IntersectError::Length(24, 22))
+        ));
+        let both = a.length(22).intersect(&b).unwrap();
+        assert_eq!(both.to_string(), "22//2+|:upper://1+|:lower:");
+    }
+
+    #[test]
+    fn conflicts() {
+        let a = spec("12//0-2|:number://0+|:lower:");
+        let b = spec("12//3+|:number://0+|:lower:");
+        let err = a.intersect(&b).unwrap_err();
+        assert!(matches!(&err, IntersectError::Counts(Charset::Number)));
+        assert!(err.to_string().contains(":number:"));
+
+        let a = spec("12//1+|:lower:");
+        let b = spec("12//1+|:upper:");
+        assert!(matches!(a.intersect(&b), Err(IntersectError::NoCharacters)));
+
+        // every count is fine on its own, but not together
+        let a = spec("4//3+|:lower://0+|:upper:");
+        let b = spec("4//0+|:lower://3+|:upper:");
+        assert!(matches!(a.intersect(&b), Err(IntersectError::Infeasible)));
+
+        let a = PasswordSpec::default().prefix("a-");
+        assert!(matches!(
+            a.intersect(&PasswordSpec::default()),
+            Err(IntersectError::Affixes)
+        ));
+    }
+
+    #[test]
+    fn units_and_limits() {
+        let bytes = spec("8B//0+|:lower-latin1:");
+        let chars = spec("8//0+|:lower-latin1:");
+        assert!(matches!(
+            bytes.intersect(&chars),
+            Err(IntersectError::Units(_, _))
+        ));
+        let same = bytes.intersect(&bytes).unwrap();
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..20 {
+            let password = same.generate_with_rng(&mut rng).unwrap();
+            assert!(bytes.matches(&password), "{}", password);
+        }
+        let raw = chars.clone().normalization(Normalization::None);
+        assert!(matches!(
+            chars.intersect(&raw),
+            Err(IntersectError::Normalization)
+        ));
+
+        let a = chars
+            .clone()
+            .max_bytes(12)
+            .min_entropy(30.0)
+            .length_policy(LengthPolicy::new().total_length(Interval::new(6, 10).unwrap()));
+        let b = chars
+            .clone()
+            .max_bytes(10)
+            .min_entropy(35.0)
+            .length_policy(LengthPolicy::new().total_length(Interval::new(8, 12).unwrap()));
+        let both = a.intersect(&b).unwrap();
+        assert_eq!(both.required_entropy(), Some(35.0));
+        assert_eq!(both.total_length(), Interval::new(8, 10).as_ref());
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..50 {
+            let password = both.generate_with_rng(&mut rng).unwrap();
+            assert!(password.len() <= 10, "{}", password);
+            assert!(a.matches(&password), "{}", password);
+            assert!(b.matches(&password), "{}", password);
+        }
+
+        let c =
+            chars.length_policy(LengthPolicy::new().total_length(Interval::new(11, 12).unwrap()));
+        assert!(matches!(a.intersect(&c), Err(IntersectError::TotalLength)));
+    }
+
+    #[test]
+    fn duplicate_customs_merge() {
+        let merged = PasswordSpec::new()
+            .length(16)
+            .custom(vec!['a', 'b', 'c'], Interval::new(1, 3).unwrap())
+            .custom(vec!['c', 'b', 'a'], Interval::new(2, 5).unwrap())
+            .upper_at_least(1)
+            .merge_duplicate_customs()
+            .unwrap();
+        let choices: Vec<_> = merged.choices().collect();
+        assert_eq!(choices.len(), 2);
+        let custom = choices
+            .iter()
+            .find(|c| matches!(c.chars, Charset::Custom(_)))
+            .unwrap();
+        assert_eq!(custom.chars, Charset::Custom(vec!['a', 'b', 'c']));
+        assert_eq!(custom.interval(), Interval::new(2, 3).unwrap());
+        assert!(merged.lint().is_empty());
+
+        let conflicting = PasswordSpec::new()
+            .length(16)
+            .custom_exactly(vec!['a', 'b', 'c'], 2)
+            .custom_exactly(vec!['c', 'b', 'a'], 3);
+        assert!(matches!(
+            conflicting.merge_duplicate_customs(),
+            Err(IntersectError::Counts(Charset::Custom(_)))
+        ));
+    }
+
+    #[test]
+    fn distinct_customs_stay() {
+        let spec = PasswordSpec::new()
+            .length(16)
+            .custom_exactly(vec!['a', 'b', 'c'], 2)
+            .custom_exactly(vec!['a', 'b', 'x'], 2)
+            .custom_exactly(vec!['y', 'z'], 1)
+            .upper_at_least(1);
+        let merged = spec.merge_duplicate_customs().unwrap();
+        assert_eq!(merged.to_string(), spec.to_string());
+    }
+
+    #[test]
+    fn custom_lowercase_normalizes() {
+        let lowercase: Vec<char> = ('a'..='z').collect();
+        let both = PasswordSpec::new()
+            .length(16)
+            .lower(Interval::new(2, 10).unwrap())
+            .custom(lowercase, Interval::at_least(1))
+            .upper_at_least(1);
+        assert_eq!(both.choices().count(), 3);
+        let normalized = both.normalize().unwrap();
+        assert_eq!(normalized.to_string(), "16//1+|:upper://2-10|:lower:");
+        assert!(normalized.lint().is_empty());
+
+        // the custom set on its own, written in any order and with repeats
+        let reversed: Vec<char> = ('a'..='z').rev().chain(['q']).collect();
+        let single = PasswordSpec::new()
+            .length(16)
+            .custom(reversed, Interval::new(1, 4).unwrap());
+        assert_eq!(single.normalize().unwrap().to_string(), "16//1-4|:lower:");
+        assert_eq!(
+            spec("8//3|0123456789").normalize().unwrap().to_string(),
+            "8//3|:number:"
+        );
+        assert_eq!(
+            spec("8//3|123456789").normalize().unwrap().to_string(),
+            "8//3|:number-no-zero:"
+        );
+        assert_eq!(
+            spec("8//3|0123456789x").normalize().unwrap().to_string(),
+            "8//3|0123456789x"
+        );
+    }
+
+    #[test]
+    fn normalizing_keeps_the_named_label() {
+        let spec = spec("v2:16//1+|letters=:lower://0-3|abcdefghijklmnopqrstuvwxyz");
+        let normalized = spec.normalize().unwrap();
+        let choices: Vec<_> = normalized.choices().collect();
+        assert_eq!(choices.len(), 1);
+        assert_eq!(choices[0].chars, Charset::Lower);
+        assert_eq!(choices[0].label(), Some("letters"));
+        assert_eq!(choices[0].interval(), Interval::new(1, 3).unwrap());
+
+        let conflicting = PasswordSpec::new()
+            .length(16)
+            .lower(Interval::exactly(2))
+            .custom(('a'..='z').collect(), Interval::exactly(3));
+        assert!(matches!(
+            conflicting.normalize(),
+            Err(IntersectError::Counts(Charset::Lower))
+        ));
+    }
+
+    #[test]
+    fn near_misses_are_left_alone() {
+        let spec = PasswordSpec::new()
+            .length(16)
+            .custom(('a'..='y').collect(), Interval::at_least(1))
+            .upper_at_least(1)
+            .allowed(Charset::Custom(('A'..='Z').chain('a'..='z').collect()));
+        let normalized = spec.normalize().unwrap();
+        assert_eq!(normalized, spec);
+        let upper = spec.clone().allowed(Charset::Custom(('A'..='Z').collect()));
+        assert_eq!(
+            upper.normalize().unwrap(),
+            spec.clone().allowed(Charset::Upper)
+        );
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::interval::Interval;
+
+    const MAX: usize = usize::MAX;
+
+    fn interval(min: usize, max: usize) -> Interval {
+        Interval::new(min, max).unwrap()
+    }
+
+    #[test]
+    fn add() {
+        assert_eq!(interval(1, 3).add(&interval(2, 5)), interval(3, 8));
+        assert_eq!(Interval::exactly(0).add(&interval(2, 5)), interval(2, 5));
+        // unbounded stays unbounded
+        assert_eq!(
+            Interval::at_least(2).add(&interval(1, 4)),
+            Interval::at_least(3)
+        );
+        assert_eq!(
+            interval(1, 4).add(&Interval::at_least(2)),
+            Interval::at_least(3)
+        );
+        // saturating rather than overflowing
+        assert_eq!(
+            interval(MAX - 1, MAX - 1).add(&interval(5, 5)),
+            interval(MAX, MAX)
+        );
+        assert!(interval(0, MAX - 1).add(&interval(0, 1)).is_unbounded());
+    }
+
+    #[test]
+    fn sub_min() {
+        assert_eq!(interval(3, 8).sub_min(2), interval(1, 6));
+        assert_eq!(interval(3, 8).sub_min(5), interval(0, 3));
+        assert_eq!(interval(3, 8).sub_min(10), interval(0, 0));
+        assert_eq!(Interval::at_least(3).sub_min(5), Interval::at_least(0));
+        assert_eq!(interval(3, MAX - 1).sub_min(1), interval(2, MAX - 2));
+    }
+
+    #[test]
+    fn scale() {
+        assert_eq!(interval(2, 4).scale(1.5), Some(interval(3, 6)));
+        // the min rounds down and the max up
+        assert_eq!(interval(3, 5).scale(0.5), Some(interval(1, 3)));
+        assert_eq!(interval(1, 1).scale(0.5), Some(interval(0, 1)));
+        assert_eq!(interval(2, 4).scale(0.0), Some(interval(0, 0)));
+        assert_eq!(
+            Interval::at_least(4).scale(0.5),
+            Some(Interval::at_least(2))
+        );
+        assert_eq!(interval(2, MAX / 2).scale(4.0).unwrap().max, MAX);
+        assert_eq!(interval(2, 4).scale(-1.0), None);
+        assert_eq!(interval(2, 4).scale(f64::NAN), None);
+        assert_eq!(interval(2, 4).scale(f64::INFINITY), None);
+    }
+
+    fn samples() -> Vec<Interval> {
+        let bounds = [0, 1, 2, 7, 100, MAX / 2, MAX - 1, MAX];
+        let mut samples = vec![];
+        for min in bounds {
+            for max in bounds {
+                if let Some(interval) = Interval::new(min, max) {
+                    samples.push(interval);
+                }
+            }
+        }
+        samples
+    }
+
+    #[test]
+    fn add_is_commutative() {
+        for a in samples() {
+            for b in samples() {
+                assert_eq!(a.add(&b), b.add(&a), "{:?} {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn add_is_monotone() {
+        let samples = samples();
+        for a in &samples {
+            for larger in samples.iter().filter(|x| x.min >= a.min && x.max >= a.max) {
+                for b in &samples {
+                    let (small, big) = (a.add(b), larger.add(b));
+                    assert!(
+                        small.min <= big.min && small.max <= big.max,
+                        "{:?} {:?} {:?}",
+                        a,
+                        larger,
+                        b
+                    );
+                }
+            }
+            // the sum never drops below either part
+            for b in &samples {
+                let sum = a.add(b);
+                assert!(sum.min >= a.min && sum.max >= a.max);
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use pants_gen::{
+        length_policy::LengthPolicy,
+        options::{Constraint, GenerateOptions},
+        password::{PasswordParseError, PasswordSpec},
+        sampling::{SamplingMode, MAX_UNIFORM_LENGTH},
+        units::{LengthUnit, MAX_MEASURED_LENGTH},
+        Interval,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn examples() -> Vec<PasswordSpec> {
+        vec![
+            PasswordSpec::default(),
+            "16//3+|:upper://1-2|:lower://3-|:number://1|:symbol:"
+                .parse()
+                .unwrap(),
+            "128b//0,2|:number://1+|:base58://2|a\"b".parse().unwrap(),
+            "12//2+|safe-symbols=!@#//1+|:upper:".parse().unwrap(),
+            "10//2+|:number-no-zero://1+|:lower:".parse().unwrap(),
+            "64B//1+|:lower-latin1:".parse().unwrap(),
+            "24g//1+|:upper://0+|:lower:".parse().unwrap(),
+            PasswordSpec::default().max_bytes(40),
+            PasswordSpec::default().min_entropy(120.5),
+            PasswordSpec::default()
+                .length_policy(LengthPolicy::new().total_length(Interval::new(30, 40).unwrap())),
+            PasswordSpec::default()
+                .prefix("AC-")
+                .suffix("!")
+                .length_includes_affixes(true),
+        ]
+    }
+
+    #[test]
+    fn json_round_trip() {
+        for spec in examples() {
+            let json = spec.to_json();
+            let parsed = PasswordSpec::from_json(&json).unwrap();
+            assert_eq!(parsed.to_string(), spec.to_string());
+            assert_eq!(parsed.to_json(), json);
+        }
+    }
+
+    #[test]
+    fn length_units_json() {
+        let spec = "64B//1+|:lower:"
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .max_bytes(70);
+        let value: serde_json::Value = serde_json::from_str(&spec.to_json()).unwrap();
+        assert_eq!(value["length"], serde_json::json!({"bytes": 64}));
+        assert_eq!(value["max_bytes"], 70);
+        let parsed = PasswordSpec::from_json(&spec.to_json()).unwrap();
+        assert_eq!(parsed, spec);
+    }
+
+    #[test]
+    fn min_entropy_json() {
+        let spec = PasswordSpec::default().min_entropy(80.0);
+        let value: serde_json::Value = serde_json::from_str(&spec.to_json()).unwrap();
+        assert_eq!(value["min_entropy"], 80.0);
+        assert_eq!(PasswordSpec::from_json(&spec.to_json()).unwrap(), spec);
+        let err = spec.clone().min_entropy(500.0).try_generate().unwrap_err();
+        let json = err.to_json();
+        assert_eq!(json["error"], "entropy_below_minimum");
+        assert_eq!(json["detail"]["required"], 500.0);
+        assert!(json["detail"]["achieved"].is_f64());
+    }
+
+    #[test]
+    fn uniform_too_long_json() {
+        let err = PasswordSpec::default()
+            .length(5000)
+            .sampling_mode(SamplingMode::Uniform)
+            .try_generate()
+            .unwrap_err();
+        let json = err.to_json();
+        assert_eq!(json["error"], "uniform_too_long");
+        assert_eq!(json["detail"]["length"], 5000);
+        assert_eq!(json["detail"]["max"], MAX_UNIFORM_LENGTH);
+    }
+
+    #[test]
+    fn measured_too_long_json() {
+        let err = PasswordSpec::default()
+            .length(5000)
+            .length_unit(LengthUnit::Bytes)
+            .try_generate()
+            .unwrap_err();
+        let json = err.to_json();
+        assert_eq!(json["error"], "measured_too_long");
+        assert_eq!(json["detail"]["unit"], "bytes");
+        assert_eq!(json["detail"]["length"], 5000);
+        assert_eq!(json["detail"]["max"], MAX_MEASURED_LENGTH);
+    }
+
+    #[test]
+    fn json_shape() {
+        let spec = "8//0,2|:number://1+|xyz".parse::<PasswordSpec>().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&spec.to_json()).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "length": 8,
+                "choices": [
+                    {"index": 0, "charset": "number", "intervals": [{"min": 0, "max": 0}, {"min": 2, "max": 2}]},
+                    {"index": 1, "charset": {"custom": "xyz"}, "intervals": [{"min": 1, "max": null}]}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn bad_json() {
+        let bad = [
+            r#"{"length": 8, "choices": [{"charset": "upper", "intervals": [{"min": 3, "max": 1}]}]}"#,
+            r#"{"length": 8, "choices": [{"charset": "upper", "intervals": []}]}"#,
+            r#"{"length": 8, "choices": [{"charset": {"custom": ""}, "intervals": [{"max": 1}]}]}"#,
+            r#"{"length": 8, "choices": [{"charset": "emoji", "intervals": [{"max": 1}]}]}"#,
+            r#"{"length": 8, "choices": [], "colour": "red"}"#,
+            r#"{"length": 8, "choices": [{"charset": "upper", "intervals": [{"max": 1}], "label": ":x:"}]}"#,
+        ];
+        for json in bad {
+            assert!(PasswordSpec::from_json(json).is_err(), "{}", json);
+        }
+        let err = PasswordSpec::from_json(bad[0]).unwrap_err();
+        assert!(err.to_string().contains("min of 3 above its max of 1"));
+    }
+
+    #[test]
+    fn parse_error_json() {
+        let err = "sixteen//1+|:upper:".parse::<PasswordSpec>().unwrap_err();
+        let json = err.to_json();
+        assert_eq!(json["error"], "invalid_length");
+        assert_eq!(json["detail"]["segment"], "sixteen");
+        assert!(json["message"].as_str().unwrap().contains("sixteen"));
+
+        let err = "16//1+|:uper:".parse::<PasswordSpec>().unwrap_err();
+        assert!(matches!(err, PasswordParseError::BadChoice(_)));
+        assert_eq!(err.to_json()["error"], "bad_choice");
+    }
+
+    #[test]
+    fn generate_error_json() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let infeasible = PasswordSpec::new().length(4).upper_at_least(5);
+        let err = infeasible
+            .generate_with_options(&GenerateOptions::new(), &mut rng)
+            .unwrap_err();
+        let json = err.to_json();
+        assert_eq!(json["error"], "length_below_minimum");
+        assert_eq!(json["detail"]["required"], 5);
+        assert_eq!(json["detail"]["length"], 4);
+
+        let never = GenerateOptions::new()
+            .max_attempts(3)
+            .constraint(Constraint::new("never", |_| false));
+        let err = PasswordSpec::default()
+            .generate_with_options(&never, &mut rng)
+            .unwrap_err();
+        let json = err.to_json();
+        assert_eq!(json["error"], "attempts_exhausted");
+        assert_eq!(json["detail"]["attempts"], 3);
+
+        let err = PasswordSpec::new()
+            .length_bits(20)
+            .number_exactly(2)
+            .resolve_length()
+            .unwrap_err();
+        let json = err.to_json();
+        assert_eq!(json["error"], "entropy_unreachable");
+        assert_eq!(json["detail"]["bits"], 20);
+        assert!(json["detail"]["achievable"].is_f64());
+
+        let err = "1//1|ab"
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .generate_unique_n(3)
+            .unwrap_err();
+        let json = err.to_json();
+        assert_eq!(json["error"], "too_few_distinct");
+        assert_eq!(json["detail"]["requested"], 3);
+        assert_eq!(json["detail"]["possible"], 2);
+
+        let err = PasswordSpec::default()
+            .length(4)
+            .prefix("ACME-")
+            .length_includes_affixes(true)
+            .resolve_length()
+            .unwrap_err();
+        let json = err.to_json();
+        assert_eq!(json["error"], "affixes_too_long");
+        assert_eq!(json["detail"]["affixes"], 5);
+        assert_eq!(json["detail"]["length"], 4);
+
+        let err = PasswordSpec::default()
+            .checked_length("short".to_string())
+            .unwrap_err();
+        assert_eq!(err.to_json()["error"], "wrong_length");
+        assert_eq!(err.to_json()["detail"]["generated"], 5);
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn examples_pass_schema() {
+        let schema = PasswordSpec::json_schema();
+        let compiled = jsonschema::JSONSchema::compile(&schema).unwrap();
+        for spec in examples() {
+            let value: serde_json::Value = serde_json::from_str(&spec.to_json()).unwrap();
+            assert!(compiled.is_valid(&value), "{}", spec.to_json());
+        }
+        let invalid = [
+            serde_json::json!({"length": 8, "choices": [{"charset": "emoji", "intervals": [{"max": 1}]}]}),
+            serde_json::json!({"length": 8, "choices": [{"charset": "upper", "intervals": []}]}),
+            serde_json::json!({"length": 8, "choices": [{"charset": {"custom": ""}, "intervals": [{"max": null}]}]}),
+            serde_json::json!({"length": "8", "choices": []}),
+            serde_json::json!({"choices": []}),
+        ];
+        for value in invalid {
+            assert!(!compiled.is_valid(&value), "{}", value);
+        }
+    }
+}
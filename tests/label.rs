@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{charset::Charset, choice::Choice, password::PasswordSpec};
+
+    fn spec() -> PasswordSpec {
+        "v2:16//2+|safe-symbols=!@#$%//1+|:upper://1+|digits=:number:"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_labels() {
+        let spec = spec();
+        let labels: Vec<_> = spec.choices().map(|c| c.label()).collect();
+        assert_eq!(labels, vec![None, Some("digits"), Some("safe-symbols")]);
+        let custom = spec.choices().last().unwrap();
+        assert_eq!(custom.chars, Charset::Custom("!@#$%".chars().collect()));
+        assert_eq!(custom.name(), "safe-symbols");
+    }
+
+    #[test]
+    fn round_trip() {
+        let spec = spec();
+        let written = spec.to_string();
+        assert_eq!(
+            written,
+            "v2:16//1+|:upper://1+|digits=:number://2+|safe-symbols=!@#$%"
+        );
+        assert_eq!(
+            written.parse::<PasswordSpec>().unwrap().to_string(),
+            written
+        );
+
+        // a label on a charset that needs v2 survives as well
+        let spec = PasswordSpec::new().length(8).include(
+            Charset::Custom(vec!['/', '/', 'x'])
+                .at_least(1)
+                .labeled("slashes")
+                .unwrap(),
+        );
+        let written = spec.to_string();
+        assert!(written.starts_with("v2:"));
+        let parsed: PasswordSpec = written.parse().unwrap();
+        assert_eq!(parsed.choices().next().unwrap().label(), Some("slashes"));
+        assert_eq!(parsed.to_string(), written);
+    }
+
+    #[test]
+    fn label_like_charsets_stay_charsets() {
+        // not a label, so the `=` is one of the characters
+        for s in ["8//2|!a=b", "8//2|a b=c", "8//2|abc="] {
+            let spec: PasswordSpec = s.parse().unwrap();
+            assert_eq!(spec.choices().next().unwrap().label(), None, "{}", s);
+        }
+        // v1 has no labels, `=` there is a character as it was before labels
+        let spec: PasswordSpec = "8//2|ab=cd//1+|:lower:".parse().unwrap();
+        let choice = spec.choices().find(|c| c.chars != Charset::Lower).unwrap();
+        assert_eq!(choice.label(), None);
+        assert_eq!(choice.chars, Charset::Custom("ab=cd".chars().collect()));
+        assert_eq!(choice.chars.len(), 5);
+        assert_eq!(spec.to_string(), "8//1+|:lower://2|ab=cd");
+        let choice: Choice = "2|ab=cd".parse().unwrap();
+        assert_eq!(choice.label(), None);
+        assert_eq!(choice.chars.len(), 5);
+        // in v2 it's a label unless the `=` is escaped
+        let labeled: PasswordSpec = "v2:8//2|ab=cd".parse().unwrap();
+        assert_eq!(labeled.choices().next().unwrap().label(), Some("ab"));
+        let spec = PasswordSpec::new()
+            .length(8)
+            .custom_at_least("ab=c".chars().collect(), 1);
+        let written = spec.to_string();
+        assert_eq!(written, "8//1+|ab=c");
+        let parsed: PasswordSpec = written.parse().unwrap();
+        assert_eq!(parsed.choices().next().unwrap().label(), None);
+        assert_eq!(
+            parsed.choices().next().unwrap().chars,
+            Charset::Custom("ab=c".chars().collect())
+        );
+    }
+
+    #[test]
+    fn bad_labels() {
+        assert!(Charset::Upper.at_least(1).labeled(":upper:").is_none());
+        assert!(Charset::Upper.at_least(1).labeled("1st").is_none());
+        assert!(Charset::Upper.at_least(1).labeled("").is_none());
+        assert!(Charset::Upper.at_least(1).labeled("caps_2-x").is_some());
+    }
+
+    #[test]
+    fn diagnostics_use_the_label() {
+        let spec = spec();
+        let violations = spec.violations("ABCDEFGHIJKLMN1!");
+        let messages: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        assert!(
+            messages
+                .iter()
+                .any(|m| m == "Has 1 characters from safe-symbols, needs at least 2"),
+            "{:?}",
+            messages
+        );
+        assert_eq!(violations[0].code(), "missing_class:custom");
+
+        let described = spec.describe();
+        assert!(described.contains("at least 2 from safe-symbols (5 characters"));
+        assert!(described.contains("at least 1 from digits"));
+        assert!(!described.contains("!@#$%"));
+
+        let tiny = PasswordSpec::new().length(8).include(
+            Choice::exactly(8, Charset::Custom(vec!['a', 'b']))
+                .labeled("ab")
+                .unwrap(),
+        );
+        assert!(tiny.lint()[0].to_string().starts_with("`ab` has only 2"));
+    }
+
+    #[test]
+    fn unlabeled_is_unchanged() {
+        let spec: PasswordSpec = "16//2+|!@#$%//1+|:upper:".parse().unwrap();
+        assert_eq!(spec.to_string(), "16//1+|:upper://2+|!@#$%");
+        assert!(spec.choices().all(|c| c.label().is_none()));
+        let violations = spec.violations("ABCDEFGHIJKLMN1!");
+        assert!(violations
+            .iter()
+            .any(|v| v.to_string().contains("from !@#$%, needs at least 2")));
+        assert!(spec.describe().contains("at least 2 from !@#$%"));
+    }
+}
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{charset::Charset, password::PasswordSpec};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn sets() {
+        let upper = Charset::UpperLatin1.to_charset();
+        let lower = Charset::LowerLatin1.to_charset();
+        assert_eq!(upper.len(), 26 + 30);
+        assert_eq!(lower.len(), 26 + 32);
+        assert!(upper.contains(&'É') && upper.contains(&'Þ') && upper.contains(&'Z'));
+        assert!(lower.contains(&'é') && lower.contains(&'ß') && lower.contains(&'ÿ'));
+        assert!(!upper.contains(&'×') && !lower.contains(&'÷'));
+        assert!(upper.iter().all(|c| c.is_uppercase()));
+        assert!(lower.iter().all(|c| c.is_lowercase()));
+    }
+
+    #[test]
+    fn contains_agrees() {
+        for charset in [Charset::UpperLatin1, Charset::LowerLatin1] {
+            let chars = charset.to_charset();
+            for c in '\0'..='\u{17f}' {
+                assert_eq!(charset.contains(c), chars.contains(&c), "{:?}", c);
+            }
+        }
+    }
+
+    #[test]
+    fn ascii_untouched() {
+        assert_eq!(Charset::Upper.to_charset(), ('A'..='Z').collect::<Vec<_>>());
+        assert_eq!(Charset::Lower.to_charset(), ('a'..='z').collect::<Vec<_>>());
+        assert!(!Charset::Upper.contains('É'));
+        assert!(!Charset::Lower.contains('é'));
+    }
+
+    #[test]
+    fn round_trip() {
+        for (s, charset) in [
+            (":upper-latin1:", Charset::UpperLatin1),
+            (":lower-latin1:", Charset::LowerLatin1),
+        ] {
+            assert_eq!(s.parse::<Charset>().unwrap(), charset);
+            assert_eq!(charset.to_string(), s);
+        }
+        let spec = "12//2+|:upper-latin1://0+|:lower-latin1:";
+        assert_eq!(spec.parse::<PasswordSpec>().unwrap().to_string(), spec);
+    }
+
+    #[test]
+    fn generates_accented() {
+        let spec: PasswordSpec = "64//64|:upper-latin1:".parse().unwrap();
+        let password = spec
+            .generate_with_rng(&mut StdRng::seed_from_u64(470))
+            .unwrap();
+        assert!(password.chars().all(|c| Charset::UpperLatin1.contains(c)));
+        assert!(!password.is_ascii());
+        assert!(spec.matches(&password));
+        // accented letters take two bytes
+        assert!(password.len() > password.chars().count());
+    }
+}
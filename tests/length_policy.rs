@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        chunk::{ChunkError, ChunkSpec},
+        length_policy::{Decorations, LengthPolicy},
+        options::GenerateOptions,
+        password::{GenerateError, PasswordSpec},
+        violation::Violation,
+        Interval,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // 12 random characters and a suffix, chunked in fours it's `XXXX-XXXX-XXXX!`
+    fn spec() -> PasswordSpec {
+        "12//1+|:upper://1+|:number:"
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .suffix("!")
+    }
+
+    fn capped(max: usize) -> PasswordSpec {
+        spec().length_policy(LengthPolicy::new().total_length(Interval::at_most(max)))
+    }
+
+    #[test]
+    fn decorated_lengths() {
+        let spec = spec();
+        assert_eq!(spec.decorated_length(&Decorations::new()).unwrap(), 13);
+        let chunks = ChunkSpec::new(4);
+        assert_eq!(spec.decorated_length(&chunks.decorations()).unwrap(), 15);
+        assert_eq!(
+            spec.decorated_length(&chunks.separator(" - ").decorations().check_character())
+                .unwrap(),
+            20
+        );
+        // a core length is the random part whatever the spec said about affixes
+        let spec = spec
+            .length_includes_affixes(true)
+            .length_policy(LengthPolicy::new().core_length(8));
+        assert_eq!(spec.resolve_length().unwrap(), 8);
+        assert_eq!(spec.decorated_length(&Decorations::new()).unwrap(), 9);
+    }
+
+    // lengths too long to add the decorations to are an error rather than an overflow
+    #[test]
+    fn decorated_length_overflow() {
+        let longest = "8//1+|:upper:".parse::<PasswordSpec>().unwrap();
+        let longest = longest.length(usize::MAX);
+        assert_eq!(
+            longest.decorated_length(&Decorations::new()).unwrap(),
+            usize::MAX
+        );
+        let chunked = ChunkSpec::new(4).decorations();
+        assert!(matches!(
+            longest.decorated_length(&chunked),
+            Err(GenerateError::LengthOverflow)
+        ));
+        let prefixed = longest
+            .clone()
+            .prefix("id-")
+            .length_policy(LengthPolicy::new().total_length(Interval::at_least(1)));
+        let error = prefixed.try_generate().unwrap_err();
+        assert!(matches!(error, GenerateError::LengthOverflow));
+        assert_eq!(error.code(), "length_overflow");
+        // a chunk as long as the core has no separators to overflow with
+        let whole = ChunkSpec::new(usize::MAX).decorations();
+        assert_eq!(longest.decorated_length(&whole).unwrap(), usize::MAX);
+    }
+
+    #[test]
+    fn chunks_and_suffix_within_total() {
+        let mut rng = StdRng::seed_from_u64(510);
+        let password = ChunkSpec::new(4)
+            .generate_with_rng(&capped(15), &mut rng)
+            .unwrap();
+        assert_eq!(password.chars().count(), 15);
+        assert_eq!(password.matches('-').count(), 2);
+        assert!(password.ends_with('!'));
+    }
+
+    #[test]
+    fn chunks_past_total() {
+        let mut rng = StdRng::seed_from_u64(510);
+        match ChunkSpec::new(4).generate_with_rng(&capped(14), &mut rng) {
+            Err(ChunkError::Generate(GenerateError::TotalLengthUnmet { allowed, length })) => {
+                assert_eq!(allowed, Interval::at_most(14));
+                assert_eq!(length, 15);
+            }
+            other => panic!("{:?}", other),
+        }
+        // unchunked the same spec fits
+        assert_eq!(capped(14).generate_with_rng(&mut rng).unwrap().len(), 13);
+    }
+
+    #[test]
+    fn total_below_minimum() {
+        let long = spec().length_policy(LengthPolicy::new().total_length(Interval::at_least(20)));
+        let err = long.try_generate().unwrap_err();
+        assert!(matches!(
+            err,
+            GenerateError::TotalLengthUnmet { length: 13, .. }
+        ));
+        assert_eq!(err.code(), "total_length_unmet");
+        assert_eq!(
+            err.to_string(),
+            "With its decorations the password is 13 long, the total length has to be at least 20"
+        );
+        // generating through options applies the policy the same way
+        let options = GenerateOptions::new()
+            .length_policy(LengthPolicy::new().total_length(Interval::at_least(20)));
+        assert!(matches!(
+            spec().generate_with_options(&options, &mut StdRng::seed_from_u64(1)),
+            Err(GenerateError::TotalLengthUnmet { .. })
+        ));
+        let options = options.length_policy(LengthPolicy::new().core_length(19));
+        let password = spec()
+            .generate_with_options(&options, &mut StdRng::seed_from_u64(1))
+            .unwrap();
+        assert_eq!(password.len(), 20);
+    }
+
+    #[test]
+    fn check_character_counts_toward_total() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let exact = spec().length_policy(LengthPolicy::new().total_length(Interval::exactly(14)));
+        let password = exact
+            .generate_with_check_character_with_rng(&mut rng)
+            .unwrap();
+        assert_eq!(password.len(), 14);
+        assert!(exact.check_transcribed(&password).is_ok());
+        assert!(capped(13)
+            .generate_with_check_character_with_rng(&mut rng)
+            .is_none());
+    }
+
+    #[test]
+    fn validation_checks_total() {
+        let password = spec().generate().unwrap();
+        assert!(capped(13).matches(&password));
+        assert!(capped(13).violations(&password).is_empty());
+        let tight = spec().length_policy(LengthPolicy::new().total_length(Interval::at_least(14)));
+        assert!(!tight.matches(&password));
+        let violations = tight.violations(&password);
+        assert!(matches!(
+            violations.as_slice(),
+            [Violation::TotalLength { actual: 13, .. }]
+        ));
+        assert_eq!(
+            violations[0].to_string(),
+            "Has 13 characters in all, needs at least 14"
+        );
+    }
+}
@@ -0,0 +1,163 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        charset::Charset, interval::Interval, lint::Lint, lint::LintWarning, password::PasswordSpec,
+    };
+
+    #[test]
+    fn all_digits_warns() {
+        let spec = "8//8|:number:".parse::<PasswordSpec>().unwrap();
+        let warnings = spec.lint();
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            LintWarning::TinyCharset {
+                charset,
+                pool,
+                forced,
+                length,
+                ..
+            } => {
+                assert_eq!(*charset, Charset::Number);
+                assert_eq!(*pool, Charset::Number.to_charset().len());
+                assert_eq!((*forced, *length), (8, 8));
+            }
+            other => panic!("unexpected warning {:?}", other),
+        }
+        let message = warnings[0].to_string();
+        assert!(message.contains("`:number:`"));
+        assert!(message.contains("controls 8 of 8 characters (100%)"));
+    }
+
+    #[test]
+    fn default_spec_is_fine() {
+        assert!(PasswordSpec::default().lint().is_empty());
+    }
+
+    #[test]
+    fn forced_by_the_other_choices() {
+        // nothing says the numbers have a minimum but the letters can't fill more than 4
+        let spec = "20//4-|:lower://0+|:number:"
+            .parse::<PasswordSpec>()
+            .unwrap();
+        let warnings = spec.lint();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].to_string().contains("16 of 20"));
+
+        let spec = PasswordSpec::new()
+            .length(24)
+            .custom_exactly(vec!['a', 'b', 'c'], 20)
+            .upper_at_least(0);
+        assert_eq!(spec.lint().len(), 1);
+        assert!(Lint::new().max_share(0.9).check(&spec).is_empty());
+        assert!(Lint::new().min_bits_per_char(1.0).check(&spec).is_empty());
+
+        // the allowed upper case letters can fill the rest just as well
+        let spec = "20//4-|:lower://0+|:number://*|abcdefghijklmnopqrstuvwxyz0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ"
+            .parse::<PasswordSpec>()
+            .unwrap();
+        assert!(spec.lint().is_empty());
+    }
+
+    #[test]
+    fn overlapping_customs_warn() {
+        let spec = PasswordSpec::new()
+            .length(16)
+            .custom(vec!['a', 'b', 'c', 'd'], Interval::exactly(2))
+            .custom(vec!['x', 'c', 'y', 'a'], Interval::exactly(2))
+            .upper_at_least(1);
+        let warnings = spec.lint();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0],
+            LintWarning::OverlappingCustom {
+                first: Charset::Custom(vec!['a', 'b', 'c', 'd']),
+                first_label: None,
+                second: Charset::Custom(vec!['x', 'c', 'y', 'a']),
+                second_label: None,
+                shared: vec!['a', 'c'],
+            }
+        );
+        assert!(warnings[0].to_string().contains("share `ac`"));
+    }
+
+    #[test]
+    fn disjoint_customs_are_fine() {
+        let spec = PasswordSpec::new()
+            .length(16)
+            .custom(vec!['a', 'b', 'c', 'd'], Interval::exactly(2))
+            .custom(vec!['w', 'x', 'y', 'z'], Interval::exactly(2))
+            .upper_at_least(1);
+        assert!(spec.lint().is_empty());
+    }
+
+    #[test]
+    fn customs_like_named_charsets() {
+        let spec = PasswordSpec::new()
+            .length(16)
+            .custom(('a'..='z').collect(), Interval::at_least(1))
+            .upper_at_least(1);
+        let warnings = spec.lint();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0],
+            LintWarning::CustomLikeNamed {
+                charset: Charset::Custom(('a'..='z').collect()),
+                label: None,
+                named: Charset::Lower,
+                missing: vec![],
+                extra: vec![],
+            }
+        );
+        assert!(warnings[0]
+            .to_string()
+            .contains("same characters as `:lower:`"));
+        assert!(spec.normalize().unwrap().lint().is_empty());
+
+        // 25 letters are left alone by normalize but still warned about
+        let spec = PasswordSpec::new()
+            .length(16)
+            .custom(('a'..='y').collect(), Interval::at_least(1))
+            .upper_at_least(1);
+        let warnings = spec.normalize().unwrap().lint();
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            LintWarning::CustomLikeNamed {
+                named,
+                missing,
+                extra,
+                ..
+            } => {
+                assert_eq!(*named, Charset::Lower);
+                assert_eq!(*missing, vec!['z']);
+                assert!(extra.is_empty());
+            }
+            other => panic!("unexpected warning {:?}", other),
+        }
+        assert!(warnings[0].to_string().contains("is `:lower:` without `z`"));
+
+        let digits = "8//3+|0123456789x//1+|:upper:"
+            .parse::<PasswordSpec>()
+            .unwrap();
+        let warnings = digits.lint();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].to_string().contains("with `x` as well"));
+        let no_zero = "8//3+|987654321//1+|:upper:"
+            .parse::<PasswordSpec>()
+            .unwrap();
+        let warnings = no_zero.lint();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0]
+            .to_string()
+            .contains("same characters as `:number-no-zero:`"));
+    }
+
+    #[test]
+    fn distant_customs_arent_like_named() {
+        let spec = PasswordSpec::new()
+            .length(16)
+            .custom(('a'..='w').collect(), Interval::at_least(1))
+            .custom(vec!['!', '@', '#'], Interval::at_least(1))
+            .upper_at_least(1);
+        assert!(spec.lint().is_empty());
+    }
+}
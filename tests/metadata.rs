@@ -0,0 +1,73 @@
+#[cfg(all(test, feature = "metadata"))]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use pants_gen::{
+        metadata::{RngSource, GENERATOR_VERSION},
+        password::PasswordSpec,
+    };
+
+    fn spec() -> PasswordSpec {
+        "16//1+|:upper://1+|:lower://1+|:number:".parse().unwrap()
+    }
+
+    #[test]
+    fn fields() {
+        let before = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let (generated, metadata) = spec().generate_with_metadata(RngSource::Os).unwrap();
+        assert!(spec().matches(&generated.password()));
+        assert_eq!(metadata.generator_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(metadata.generator_version, GENERATOR_VERSION);
+        assert_eq!(metadata.rng_source, "os");
+        assert_eq!(metadata.spec_canonical, Some(spec().to_string()));
+        assert!(metadata.timestamp >= before);
+    }
+
+    #[test]
+    fn labels() {
+        assert_eq!(RngSource::Thread.label(), "thread");
+        assert_eq!(RngSource::Os.to_string(), "os");
+        assert_eq!(RngSource::Seeded(1).label(), "insecure-seeded");
+    }
+
+    #[test]
+    fn seeded_repeats() {
+        let (a, metadata) = spec()
+            .generate_with_metadata(RngSource::Seeded(468))
+            .unwrap();
+        let (b, _) = spec()
+            .generate_with_metadata(RngSource::Seeded(468))
+            .unwrap();
+        assert_eq!(a, b);
+        assert_eq!(metadata.rng_source, "insecure-seeded");
+    }
+
+    #[test]
+    fn unrecordable_spec() {
+        // the prefix isn't part of the spec string, so the string isn't the spec
+        let (_, metadata) = spec()
+            .prefix("id-")
+            .generate_with_metadata(RngSource::Seeded(468))
+            .unwrap();
+        assert_eq!(metadata.spec_canonical, None);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json() {
+        let (generated, metadata) = spec().generate_with_metadata(RngSource::Seeded(7)).unwrap();
+        let value = generated.to_json_with_metadata(&metadata);
+        assert_eq!(value["password"], generated.password());
+        assert_eq!(value["metadata"]["generator_version"], GENERATOR_VERSION);
+        assert_eq!(value["metadata"]["rng_source"], "insecure-seeded");
+        assert_eq!(value["metadata"]["spec_canonical"], spec().to_string());
+        assert_eq!(value["metadata"]["timestamp"], metadata.timestamp);
+
+        // the plain forms never carry it
+        assert!(generated.to_json().get("metadata").is_none());
+        assert_eq!(generated.to_string(), generated.password());
+    }
+}
@@ -0,0 +1,205 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pants_gen::{
+        multi::{MultiSpec, MultiSpecError, ProfileError},
+        password::PasswordSpec,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // a fresh directory for a test's profile files
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pants_gen_multi_{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    #[test]
+    fn three_field_profile() {
+        let profile = format!(
+            "username: 12//12|:lower:\npassword: {}\n\npin: 6//6|:number:\n",
+            PasswordSpec::default()
+        );
+        let multi: MultiSpec = profile.parse().unwrap();
+        assert_eq!(multi.fields().len(), 3);
+        let generated = multi.generate_all(&mut StdRng::seed_from_u64(1)).unwrap();
+        assert_eq!(
+            generated.keys().collect::<Vec<_>>(),
+            vec!["password", "pin", "username"]
+        );
+        assert!(multi.fields()["username"].matches(&generated["username"]));
+        assert!(multi.fields()["password"].matches(&generated["password"]));
+        assert!(multi.fields()["pin"].matches(&generated["pin"]));
+
+        let reparsed: MultiSpec = multi.to_string().parse().unwrap();
+        assert_eq!(multi, reparsed);
+    }
+
+    #[test]
+    fn profile_with_comments() {
+        let profile = "# onboarding secrets
+username: 12//12|:lower:  # login name
+
+pin: 6//6|:number:
+tag: 4//4|a#b
+";
+        let multi: MultiSpec = profile.parse().unwrap();
+        assert_eq!(multi.fields().len(), 3);
+        assert_eq!(
+            multi.fields()["username"],
+            "12//12|:lower:".parse().unwrap()
+        );
+        assert_eq!(multi.fields()["tag"], "4//4|a#b".parse().unwrap());
+        // line numbers still count the comment lines
+        assert!(matches!(
+            "# header\nno colon here\n".parse::<MultiSpec>(),
+            Err(MultiSpecError::BadLine(2))
+        ));
+    }
+
+    #[test]
+    fn infeasible_field_is_named() {
+        let multi = MultiSpec::new()
+            .field("password", PasswordSpec::default())
+            .field("pin", PasswordSpec::new().length(6).number_exactly(4));
+        let err = multi
+            .generate_all(&mut StdRng::seed_from_u64(1))
+            .unwrap_err();
+        assert!(matches!(&err, MultiSpecError::Infeasible { name, .. } if name == "pin"));
+        assert!(err.to_string().contains("`pin`"));
+        // the reason the spec failed comes along
+        let direct = PasswordSpec::new()
+            .length(6)
+            .number_exactly(4)
+            .try_generate()
+            .unwrap_err();
+        assert!(
+            matches!(&err, MultiSpecError::Infeasible { source, .. } if source.code() == direct.code())
+        );
+        assert!(err.to_string().contains(&direct.to_string()));
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn bad_profiles() {
+        assert!(matches!(
+            "no separator".parse::<MultiSpec>(),
+            Err(MultiSpecError::BadLine(1))
+        ));
+        assert!(matches!(
+            "pin: 6//6|:number:\npin: 4//4|:number:".parse::<MultiSpec>(),
+            Err(MultiSpecError::Duplicate(_))
+        ));
+        assert!(matches!(
+            "pin: six//6|:number:".parse::<MultiSpec>(),
+            Err(MultiSpecError::BadSpec(name, _)) if name == "pin"
+        ));
+    }
+
+    #[test]
+    fn two_level_include() {
+        let dir = temp_dir("two_level");
+        std::fs::create_dir_all(dir.join("teams")).unwrap();
+        std::fs::write(
+            dir.join("base.profile"),
+            "username: 12//12|:lower:\npassword: 32//1+|:upper://1+|:lower:\npin: 4//4|:number:\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("teams/middle.profile"),
+            "include = \"../base.profile\"\npin: 6//6|:number:\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("teams/ops.profile"),
+            "# ops needs longer passwords\nextends = middle.profile\npassword: 64//1+|:upper://1+|:lower:\n",
+        )
+        .unwrap();
+
+        let loaded = MultiSpec::load(dir.join("teams/ops.profile")).unwrap();
+        let fields = loaded.spec.fields();
+        assert_eq!(fields.len(), 3);
+        assert_eq!(fields["username"], "12//12|:lower:".parse().unwrap());
+        assert_eq!(fields["pin"], "6//6|:number:".parse().unwrap());
+        assert_eq!(
+            fields["password"],
+            "64//1+|:upper://1+|:lower:".parse().unwrap()
+        );
+        assert_eq!(loaded.sources["username"], dir.join("base.profile"));
+        assert_eq!(loaded.sources["pin"], dir.join("teams/middle.profile"));
+        assert_eq!(loaded.sources["password"], dir.join("teams/ops.profile"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn later_includes_override_earlier() {
+        let dir = temp_dir("precedence");
+        std::fs::write(dir.join("a.profile"), "pin: 4//4|:number:\n").unwrap();
+        std::fs::write(dir.join("b.profile"), "pin: 8//8|:number:\n").unwrap();
+        std::fs::write(
+            dir.join("main.profile"),
+            "include = a.profile\ninclude = b.profile\n",
+        )
+        .unwrap();
+        let loaded = MultiSpec::load(dir.join("main.profile")).unwrap();
+        assert_eq!(
+            loaded.spec.fields()["pin"],
+            "8//8|:number:".parse().unwrap()
+        );
+        assert_eq!(loaded.sources["pin"], dir.join("b.profile"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn include_cycle() {
+        let dir = temp_dir("cycle");
+        std::fs::write(dir.join("a.profile"), "include = b.profile\n").unwrap();
+        std::fs::write(
+            dir.join("b.profile"),
+            "include = a.profile\npin: 4//4|:number:\n",
+        )
+        .unwrap();
+        let err = MultiSpec::load(dir.join("a.profile")).unwrap_err();
+        match &err {
+            ProfileError::Cycle(chain) => assert_eq!(
+                chain,
+                &[
+                    dir.join("a.profile"),
+                    dir.join("b.profile"),
+                    dir.join("a.profile")
+                ]
+            ),
+            _ => panic!("expected a cycle, got {}", err),
+        }
+        assert!(err.to_string().contains("a.profile -> "));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn include_errors() {
+        let dir = temp_dir("errors");
+        std::fs::write(dir.join("main.profile"), "include = missing.profile\n").unwrap();
+        assert!(matches!(
+            MultiSpec::load(dir.join("main.profile")),
+            Err(ProfileError::Io(path, _)) if path == dir.join("missing.profile")
+        ));
+        std::fs::write(dir.join("self.profile"), "include = self.profile\n").unwrap();
+        assert!(matches!(
+            MultiSpec::load(dir.join("self.profile")),
+            Err(ProfileError::Cycle(chain)) if chain.len() == 2
+        ));
+        std::fs::write(dir.join("bad.profile"), "no separator\n").unwrap();
+        assert!(matches!(
+            MultiSpec::load(dir.join("bad.profile")),
+            Err(ProfileError::Parse(_, MultiSpecError::BadLine(1)))
+        ));
+        // strings have nowhere to resolve includes from
+        assert!(matches!(
+            "pin: 4//4|:number:\ninclude = base.profile".parse::<MultiSpec>(),
+            Err(MultiSpecError::Include(2))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
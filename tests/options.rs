@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pants_gen::{
+        options::{Constraint, GenerateOptions},
+        password::{GenerateError, PasswordSpec},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn times_out_with_attempt_count() {
+        let options = GenerateOptions::new()
+            .max_attempts(usize::MAX)
+            .timeout(Duration::from_millis(5))
+            .constraint(Constraint::new("never", |_| false));
+        let err = PasswordSpec::default()
+            .generate_with_options(&options, &mut StdRng::seed_from_u64(3))
+            .unwrap_err();
+        match err {
+            GenerateError::TimedOut { attempts, elapsed } => {
+                assert!(attempts >= 1);
+                assert!(elapsed >= Duration::from_millis(5));
+            }
+            e => panic!("expected a timeout, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn attempts_exhausted() {
+        let options = GenerateOptions::new()
+            .max_attempts(5)
+            .constraint(Constraint::new("never", |_| false));
+        let err = PasswordSpec::default()
+            .generate_with_options(&options, &mut StdRng::seed_from_u64(3))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            GenerateError::AttemptsExhausted { attempts: 5 }
+        ));
+    }
+
+    #[test]
+    fn constraints_filter_candidates() {
+        let options = GenerateOptions::new().constraint(Constraint::new("starts upper", |p| {
+            p.starts_with(|c: char| c.is_ascii_uppercase())
+        }));
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..20 {
+            let password = PasswordSpec::default()
+                .generate_with_options(&options, &mut rng)
+                .unwrap();
+            assert!(password.starts_with(|c: char| c.is_ascii_uppercase()));
+        }
+
+        let infeasible = PasswordSpec::new().length(2).upper_exactly(3);
+        assert!(matches!(
+            infeasible.generate_with_options(&options, &mut rng),
+            Err(GenerateError::LengthBelowMinimum {
+                required: 3,
+                length: 2
+            })
+        ));
+    }
+}
@@ -0,0 +1,121 @@
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use pants_gen::{choice::Choice, password::PasswordSpec, Charset};
+
+    // built in an order different from the one choices are listed in
+    fn examples() -> Vec<PasswordSpec> {
+        vec![
+            PasswordSpec::default(),
+            PasswordSpec::new()
+                .length(20)
+                .include(Charset::Custom(vec!['x', 'y', 'z']).at_least(1))
+                .include(Charset::Symbol.exactly(2))
+                .include(Charset::Custom(vec!['a', 'b']).at_most(3))
+                .include(Charset::Upper.at_least(1))
+                .include(Charset::Number.exactly(0)),
+            "12//2+|safe=!@#//1+|:upper://0,2|:number:".parse().unwrap(),
+            PasswordSpec::new()
+                .length(10)
+                .include(Charset::Lower.at_least(2))
+                .allowed(Charset::Base58),
+        ]
+    }
+
+    fn names(spec: &PasswordSpec) -> Vec<String> {
+        spec.choices().map(|choice| choice.name()).collect()
+    }
+
+    #[test]
+    fn default_is_in_canonical_order() {
+        assert_eq!(
+            names(&PasswordSpec::default()),
+            [":upper:", ":lower:", ":number:", ":symbol:"]
+        );
+    }
+
+    #[test]
+    fn insertion_order_does_not_matter() {
+        let forwards = PasswordSpec::new()
+            .include(Charset::Upper.at_least(1))
+            .include(Charset::Custom(vec!['q']).at_least(1))
+            .include(Charset::Lower.at_least(1));
+        let backwards = PasswordSpec::new()
+            .include(Charset::Lower.at_least(1))
+            .include(Charset::Custom(vec!['q']).at_least(1))
+            .include(Charset::Upper.at_least(1));
+        assert_eq!(names(&forwards), names(&backwards));
+        assert_eq!(forwards.to_string(), backwards.to_string());
+        assert_eq!(forwards.to_json(), backwards.to_json());
+    }
+
+    #[test]
+    fn index_access() {
+        let spec = &examples()[1];
+        let all: Vec<&Choice> = spec.choices().collect();
+        for (i, choice) in all.iter().enumerate() {
+            assert_eq!(spec.choice(i), Some(*choice));
+        }
+        assert_eq!(spec.choice(all.len()), None);
+        assert_eq!(spec.choice(0).unwrap().chars, Charset::Upper);
+    }
+
+    #[test]
+    fn every_output_agrees() {
+        for spec in examples() {
+            let names = names(&spec);
+
+            let written = spec.to_string();
+            let mut from = 0;
+            for choice in spec.choices() {
+                let at = written[from..]
+                    .find(&format!("//{}", choice))
+                    .unwrap_or_else(|| panic!("{} out of order in {}", choice, written));
+                from += at + 1;
+            }
+
+            let described = spec.describe();
+            let lines: Vec<&str> = described.lines().skip(1).take(names.len()).collect();
+            assert_eq!(lines.len(), names.len());
+            for (line, name) in lines.iter().zip(&names) {
+                assert!(
+                    line.ends_with(name.as_str()) || line.contains(&format!("from {} (", name)),
+                    "{} {}",
+                    line,
+                    name
+                );
+            }
+
+            let value: serde_json::Value = serde_json::from_str(&spec.to_json()).unwrap();
+            let choices = value["choices"].as_array().unwrap();
+            assert_eq!(choices.len(), names.len());
+            for (i, choice) in choices.iter().enumerate() {
+                assert_eq!(choice["index"], i);
+                let single = serde_json::json!({"length": 1, "choices": [choice]});
+                let parsed = PasswordSpec::from_json(&single.to_string()).unwrap();
+                assert_eq!(parsed.choice(0), spec.choice(i));
+            }
+
+            let report = spec.entropy_report().unwrap();
+            let listed: Vec<&String> = report
+                .iter()
+                .filter(|line| line.index.is_some())
+                .map(|line| &line.name)
+                .collect();
+            assert_eq!(listed, names.iter().collect::<Vec<_>>());
+            for (i, line) in report.iter().enumerate() {
+                assert_eq!(line.index, Some(i).filter(|i| *i < names.len()));
+            }
+            let total: f64 = report.iter().map(|line| line.bits()).sum();
+            assert!((total - spec.entropy().unwrap()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn allowed_only_characters_come_last() {
+        let report = examples()[3].entropy_report().unwrap();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].index, Some(0));
+        assert_eq!(report[1].index, None);
+        assert_eq!(report.iter().map(|line| line.count).sum::<usize>(), 10);
+    }
+}
@@ -0,0 +1,208 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        output::{columnize, layout, spec_comment, with_spec, ColorChoice, Columns, OutputContext},
+        password::PasswordSpec,
+        position::PositionRule,
+        Charset,
+    };
+
+    #[test]
+    fn recorded_spec_reparses() {
+        let specs = [
+            PasswordSpec::default(),
+            "16//3+|:upper://1-2|:lower://3-|:number://1|:symbol:"
+                .parse()
+                .unwrap(),
+            "v2:12//2|a\\/b//0,2|:number://1+|xyz".parse().unwrap(),
+        ];
+        for spec in specs {
+            let comment = spec_comment(&spec).unwrap();
+            let recorded = comment.strip_prefix("# spec: ").unwrap();
+            let parsed = recorded.parse::<PasswordSpec>().unwrap();
+            assert_eq!(parsed, spec);
+            assert_eq!(parsed.to_string(), recorded);
+        }
+    }
+
+    #[test]
+    fn unrecordable_spec_refused() {
+        let base = PasswordSpec::default();
+        let specs = [
+            base.clone().max_bytes(40),
+            base.clone().min_entropy(64.0),
+            base.clone().prefix("id-"),
+            base.clone().suffix("!"),
+            base.clone()
+                .position_rule(0, PositionRule::MustBe(Charset::Upper)),
+            base.clone().no_adjacent_from(Charset::Symbol),
+        ];
+        for spec in specs {
+            // the string would parse back to a different spec
+            assert_ne!(spec.to_string().parse::<PasswordSpec>().unwrap(), spec);
+            assert_eq!(spec.to_spec_string(), None);
+            assert!(spec_comment(&spec).is_err());
+            assert!(with_spec(&spec, &["abc".to_string()]).is_err());
+        }
+        assert_eq!(base.to_spec_string(), Some(base.to_string()));
+    }
+
+    #[test]
+    fn canonical_order() {
+        // the same spec written in any order comes out the same
+        let a = "8//1+|:symbol://1+|:upper://2|xyz"
+            .parse::<PasswordSpec>()
+            .unwrap();
+        let b = "8//2|xyz//1+|:upper://1+|:symbol:"
+            .parse::<PasswordSpec>()
+            .unwrap();
+        assert_eq!(a.to_string(), b.to_string());
+        assert_eq!(a.to_string(), "8//1+|:upper://1+|:symbol://2|xyz");
+    }
+
+    #[test]
+    fn spec_line_placement() {
+        let spec = PasswordSpec::default();
+        let comment = spec_comment(&spec).unwrap();
+
+        let single = with_spec(&spec, &["abc".to_string()]).unwrap();
+        assert_eq!(single, format!("abc\n{}\n", comment));
+
+        let batch = with_spec(&spec, &["abc".to_string(), "def".to_string()]).unwrap();
+        assert_eq!(batch, format!("{}\nabc\ndef\n", comment));
+        assert_eq!(batch.matches("# spec:").count(), 1);
+    }
+
+    fn items(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn columns_fill_the_width_exactly() {
+        let passwords = items(&["aaaaaaaa", "bbbbbbbb", "cccccccc", "dddddddd"]);
+        // three of 8 with two spaces between is 26
+        assert_eq!(
+            columnize(&passwords, 26),
+            "aaaaaaaa bbbbbbbb cccccccc\ndddddddd\n"
+        );
+        assert_eq!(
+            columnize(&passwords, 25),
+            "aaaaaaaa bbbbbbbb\ncccccccc dddddddd\n"
+        );
+        assert_eq!(
+            columnize(&passwords, 35),
+            "aaaaaaaa bbbbbbbb cccccccc dddddddd\n"
+        );
+        for line in columnize(&passwords, 80).lines() {
+            assert!(line.chars().count() <= 80);
+        }
+    }
+
+    #[test]
+    fn columns_align() {
+        let passwords = items(&["abc", "a", "abcde", "ab", "x y "]);
+        assert_eq!(columnize(&passwords, 12), "abc   a\nabcde ab\nx y \n");
+        assert_eq!(columnize(&items(&["é", "ab"]), 5), "é  ab\n");
+    }
+
+    #[test]
+    fn single_column_fallback() {
+        let passwords = items(&["aaaaaaaa", "bbbbbbbb"]);
+        assert_eq!(columnize(&passwords, 17), "aaaaaaaa bbbbbbbb\n");
+        assert_eq!(columnize(&passwords, 16), "aaaaaaaa\nbbbbbbbb\n");
+        assert_eq!(columnize(&passwords, 8), "aaaaaaaa\nbbbbbbbb\n");
+        assert_eq!(columnize(&passwords, 4), "aaaaaaaa\nbbbbbbbb\n");
+        assert_eq!(columnize(&[], 80), "");
+    }
+
+    #[test]
+    fn layout_for_terminals_only() {
+        let passwords = items(&["aaaa", "bbbb", "cccc", "dddd", "eeee"]);
+        assert_eq!(
+            layout(&passwords, Columns::Auto, 80, false),
+            "aaaa\nbbbb\ncccc\ndddd\neeee\n"
+        );
+        assert_eq!(
+            layout(&passwords, Columns::Auto, 80, true),
+            "aaaa bbbb cccc dddd eeee\n"
+        );
+        assert_eq!(
+            layout(&passwords, Columns::Fixed(2), 80, true),
+            "aaaa bbbb\ncccc dddd\neeee\n"
+        );
+        // never more than fit
+        assert_eq!(
+            layout(&passwords, Columns::Fixed(4), 10, true),
+            "aaaa bbbb\ncccc dddd\neeee\n"
+        );
+    }
+
+    #[test]
+    fn columns_parse() {
+        assert_eq!("auto".parse::<Columns>().unwrap(), Columns::Auto);
+        assert_eq!("3".parse::<Columns>().unwrap(), Columns::Fixed(3));
+        assert!("0".parse::<Columns>().is_err());
+        assert!("wide".parse::<Columns>().is_err());
+    }
+
+    fn decorations(context: &OutputContext) -> Vec<bool> {
+        let passwords = items(&["aaaa", "bbbb", "cccc"]);
+        let options = context.pwgen_options();
+        vec![
+            context.layout(&passwords, Columns::Auto) != "aaaa\nbbbb\ncccc\n",
+            context.color_stdout(),
+            context.color_stderr(),
+            context.progress(10_000),
+            context.prompts(),
+            context.check_raw_output(false).is_err(),
+            options.columns,
+            options.capitalize,
+        ]
+    }
+
+    #[test]
+    fn pipes_get_no_decoration() {
+        let piped = OutputContext::new(false, false);
+        assert!(decorations(&piped).iter().all(|on| !on));
+        assert!(piped.check_raw_output(false).is_ok());
+        assert!(decorations(&piped.width(200)).iter().all(|on| !on));
+        let terminal = OutputContext::new(true, true);
+        assert!(decorations(&terminal).iter().all(|on| *on));
+        // a small batch isn't worth a progress bar
+        assert!(!terminal.progress(10));
+    }
+
+    #[test]
+    fn streams_decided_separately() {
+        // stdout piped to a file with stderr still on the terminal
+        let context = OutputContext::new(false, true);
+        assert!(!context.color_stdout());
+        assert!(context.color_stderr());
+        assert!(context.progress(10_000));
+        assert_eq!(context.layout(&items(&["a", "b"]), Columns::Auto), "a\nb\n");
+    }
+
+    #[test]
+    fn overrides_flip() {
+        let detected = OutputContext::new(false, false);
+        assert_eq!(detected.assume_tty(), OutputContext::new(true, true));
+        assert!(decorations(&detected.assume_tty()).iter().all(|on| *on));
+        let detected = OutputContext::new(true, true);
+        assert_eq!(detected.assume_pipe(), OutputContext::new(false, false));
+        assert!(decorations(&detected.assume_pipe()).iter().all(|on| !on));
+    }
+
+    #[test]
+    fn color_choice() {
+        let piped = OutputContext::new(false, false);
+        assert!(piped.color(ColorChoice::Always).color_stdout());
+        let terminal = OutputContext::new(true, true).color(ColorChoice::Never);
+        assert!(!terminal.color_stdout() && !terminal.color_stderr());
+        assert_eq!(
+            "always".parse::<ColorChoice>().unwrap(),
+            ColorChoice::Always
+        );
+        assert_eq!("auto".parse::<ColorChoice>().unwrap(), ColorChoice::Auto);
+        assert!("sometimes".parse::<ColorChoice>().is_err());
+    }
+}
@@ -0,0 +1,164 @@
+// Inputs for the parsers kept as files under `tests/corpus`, so a fuzzer finding or a bug report
+// becomes a regression test by saving the input, see `tests/corpus/README.md`.
+//
+// Every file has to parse or fail without panicking. Files under `valid/` have to parse and come
+// back the same when what they parse to is written and parsed again. Files under `invalid/` have to
+// fail with the error their name starts with.
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeSet,
+        fmt::{Debug, Display},
+        fs,
+        panic::{self, AssertUnwindSafe},
+        path::{Path, PathBuf},
+        str::FromStr,
+    };
+
+    use pants_gen::{Charset, Choice, Interval, PasswordSpec};
+
+    fn corpus() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("corpus")
+    }
+
+    // the files of `valid/<kind>` or `invalid/<kind>`, sorted so failures come in a stable order
+    fn inputs(validity: &str, kind: &str) -> Vec<(PathBuf, String)> {
+        let dir = corpus().join(validity).join(kind);
+        let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+            .unwrap_or_else(|e| panic!("can't read {}: {}", dir.display(), e))
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        paths.sort();
+        paths
+            .into_iter()
+            .map(|path| {
+                // inputs are taken byte for byte, fuzzers can find ones that aren't UTF-8 which
+                // can't reach the parsers as they are
+                let input = String::from_utf8_lossy(&fs::read(&path).unwrap()).into_owned();
+                (path, input)
+            })
+            .collect()
+    }
+
+    fn name(path: &Path) -> String {
+        path.file_stem().unwrap().to_string_lossy().into_owned()
+    }
+
+    // the error variant in snake case, `UnrecognizedPattern(..)` is `unrecognized_pattern`
+    fn variant<E: Debug>(error: &E) -> String {
+        let debug = format!("{:?}", error);
+        let mut variant = String::new();
+        for c in debug.chars().take_while(|c| c.is_alphanumeric()) {
+            if c.is_uppercase() && !variant.is_empty() {
+                variant.push('_');
+            }
+            variant.extend(c.to_lowercase());
+        }
+        variant
+    }
+
+    // parses every input of a kind, returning the variants the invalid ones failed with
+    fn check<T>(kind: &str) -> BTreeSet<String>
+    where
+        T: FromStr + Display + PartialEq + Debug,
+        T::Err: Debug,
+    {
+        let mut failures = vec![];
+        for (path, input) in inputs("valid", kind) {
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                let parsed = input.parse::<T>().map_err(|e| format!("{:?}", e))?;
+                let written = parsed.to_string();
+                match written.parse::<T>() {
+                    Ok(reparsed) if reparsed == parsed => Ok(()),
+                    Ok(reparsed) => Err(format!(
+                        "written as `{}` which parses to {:?}, not {:?}",
+                        written, reparsed, parsed
+                    )),
+                    Err(e) => Err(format!("written as `{}` which fails with {:?}", written, e)),
+                }
+            }));
+            match outcome {
+                Ok(Ok(())) => {}
+                Ok(Err(message)) => failures.push(format!("{}: {}", path.display(), message)),
+                Err(_) => failures.push(format!("{}: panicked", path.display())),
+            }
+        }
+        let mut variants = BTreeSet::new();
+        for (path, input) in inputs("invalid", kind) {
+            let expected = name(&path);
+            let expected = expected.split('-').next().unwrap();
+            match panic::catch_unwind(AssertUnwindSafe(|| input.parse::<T>())) {
+                Ok(Ok(parsed)) => {
+                    failures.push(format!("{}: parsed to {:?}", path.display(), parsed))
+                }
+                Ok(Err(e)) if variant(&e) == expected => {
+                    variants.insert(variant(&e));
+                }
+                Ok(Err(e)) => failures.push(format!(
+                    "{}: failed with {:?}, expected {}",
+                    path.display(),
+                    e,
+                    expected
+                )),
+                Err(_) => failures.push(format!("{}: panicked", path.display())),
+            }
+        }
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+        variants
+    }
+
+    fn covers(variants: BTreeSet<String>, expected: &[&str]) {
+        let expected: BTreeSet<String> = expected.iter().map(|v| v.to_string()).collect();
+        assert_eq!(variants, expected, "every error variant needs an input");
+    }
+
+    #[test]
+    fn specs() {
+        covers(
+            check::<PasswordSpec>("spec"),
+            // `BadInterval` and `BadCharset` aren't returned by the parser, a bad interval or
+            // charset in a spec is a `BadChoice`
+            &[
+                "bad_choice",
+                "dangling_escape",
+                "empty_segment",
+                "improper_format",
+                "invalid_length",
+                "length_overflow",
+                "missing_version",
+                "non_ascii_length",
+            ],
+        );
+    }
+
+    #[test]
+    fn choices() {
+        covers(
+            check::<Choice>("choice"),
+            &["bad_format", "bad_interval", "charset"],
+        );
+    }
+
+    #[test]
+    fn intervals() {
+        covers(
+            check::<Interval>("interval"),
+            &[
+                "bad_bounds",
+                "improper_format",
+                "non_ascii_digit",
+                "overflow",
+            ],
+        );
+    }
+
+    #[test]
+    fn charsets() {
+        covers(
+            check::<Charset>("charset"),
+            &["no_charset", "unrecognized_pattern"],
+        );
+    }
+}
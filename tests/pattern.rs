@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        charset::Charset,
+        pattern::{KeePassParseError, PatternSpec, MAX_REPEAT},
+    };
+
+    #[test]
+    fn hex_groups() {
+        let spec = PatternSpec::from_keepass("HHHH-HHHH").unwrap();
+        assert_eq!(spec.len(), 9);
+        assert_eq!(spec.positions()[4], Charset::Custom(vec!['-']));
+        for _ in 0..20 {
+            let gen: Vec<char> = spec.generate().chars().collect();
+            assert_eq!(gen.len(), 9);
+            assert_eq!(gen[4], '-');
+            assert!(gen
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != 4)
+                .all(|(_, c)| c.is_ascii_digit() || ('A'..='F').contains(c)));
+        }
+    }
+
+    #[test]
+    fn repeat_counts() {
+        let spec = PatternSpec::from_keepass("uullA{6}d{2}").unwrap();
+        assert_eq!(spec.len(), 12);
+        for _ in 0..20 {
+            let gen: Vec<char> = spec.generate().chars().collect();
+            assert_eq!(gen.len(), 12);
+            assert!(gen[..2].iter().all(|c| c.is_ascii_uppercase()));
+            assert!(gen[2..4].iter().all(|c| c.is_ascii_lowercase()));
+            assert!(gen[4..10].iter().all(|c| c.is_ascii_alphanumeric()));
+            assert!(gen[10..].iter().all(|c| c.is_ascii_digit()));
+        }
+    }
+
+    #[test]
+    fn custom_class_and_escapes() {
+        let spec = PatternSpec::from_keepass(r"[dp\]]{5}\d").unwrap();
+        assert_eq!(spec.len(), 6);
+        assert_eq!(spec.positions()[5], Charset::Custom(vec!['d']));
+        for _ in 0..20 {
+            let gen: Vec<char> = spec.generate().chars().collect();
+            assert!(gen[..5]
+                .iter()
+                .all(|c| c.is_ascii_digit() || ",.;:]".contains(*c)));
+            assert_eq!(gen[5], 'd');
+        }
+    }
+
+    #[test]
+    fn literal_digits_and_letters() {
+        let spec = PatternSpec::from_keepass("ddd-2024é").unwrap();
+        assert_eq!(spec.len(), 9);
+        for _ in 0..20 {
+            let gen = spec.generate();
+            assert!(gen.ends_with("-2024é"), "{}", gen);
+            assert!(gen.chars().take(3).all(|c| c.is_ascii_digit()));
+        }
+    }
+
+    #[test]
+    fn unsupported_constructs() {
+        assert!(matches!(
+            PatternSpec::from_keepass("ddx"),
+            Err(KeePassParseError::Unsupported(_))
+        ));
+        assert!(matches!(
+            PatternSpec::from_keepass("ddq"),
+            Err(KeePassParseError::UnknownPlaceholder('q'))
+        ));
+        assert!(matches!(
+            PatternSpec::from_keepass("[dd"),
+            Err(KeePassParseError::UnclosedClass(0))
+        ));
+        assert!(matches!(
+            PatternSpec::from_keepass("{3}"),
+            Err(KeePassParseError::NothingToRepeat(0))
+        ));
+        assert!(matches!(
+            PatternSpec::from_keepass("d{x}"),
+            Err(KeePassParseError::BadRepeat(_))
+        ));
+        assert!(matches!(
+            PatternSpec::from_keepass("d\\"),
+            Err(KeePassParseError::DanglingEscape)
+        ));
+    }
+
+    #[test]
+    fn repeat_capped() {
+        let spec = PatternSpec::from_keepass(&format!("d{{{}}}", MAX_REPEAT)).unwrap();
+        assert_eq!(spec.len(), MAX_REPEAT);
+        assert!(matches!(
+            PatternSpec::from_keepass("d{99999999999}"),
+            Err(KeePassParseError::RepeatTooLarge(99999999999))
+        ));
+    }
+
+    // a position with nothing to draw from would make a password shorter than the pattern
+    #[test]
+    fn empty_positions_rejected() {
+        assert_eq!(
+            PatternSpec::new(vec![Charset::Number, Charset::Custom(vec![])]),
+            None
+        );
+        let spec = PatternSpec::new(vec![Charset::Number, Charset::Custom(vec!['-'])]).unwrap();
+        assert_eq!(spec.generate().chars().count(), 2);
+    }
+}
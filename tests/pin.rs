@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{pin::PinSpec, preset};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn brute_force(spec: &PinSpec) -> u128 {
+        (0..10u32.pow(spec.digits as u32))
+            .filter(|n| spec.allowed(&format!("{:0width$}", n, width = spec.digits)))
+            .count() as u128
+    }
+
+    #[test]
+    fn no_leading_zero() {
+        let spec = PinSpec {
+            allow_leading_zero: false,
+            ..PinSpec::new(6)
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..500 {
+            let pin = spec.generate_with_rng(&mut rng).unwrap();
+            assert_eq!(pin.len(), 6);
+            assert!(!pin.starts_with('0'));
+        }
+    }
+
+    #[test]
+    fn repeats_and_sequences_excluded() {
+        let spec = PinSpec {
+            max_repeat_run: 5,
+            forbid_sequential: true,
+            ..PinSpec::new(6)
+        };
+        assert!(!spec.allowed("111111"));
+        assert!(!spec.allowed("123456"));
+        assert!(!spec.allowed("654321"));
+        assert!(spec.allowed("111112"));
+        assert!(spec.allowed("123457"));
+
+        // small enough that every pin turns up, so the excluded ones would too
+        let spec = PinSpec {
+            max_repeat_run: 1,
+            forbid_sequential: true,
+            ..PinSpec::new(3)
+        };
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..5000 {
+            let pin = spec.generate_with_rng(&mut rng).unwrap();
+            assert!(pin != "111" && pin != "123" && pin != "321");
+            assert!(spec.allowed(&pin));
+        }
+    }
+
+    #[test]
+    fn four_digit_entropy() {
+        // 10000 pins less 10 of one repeated digit and 14 sequences up or down
+        let spec = preset::pin();
+        assert_eq!(spec.count(), 9976);
+        assert!((spec.entropy() - 9976f64.log2()).abs() < 1e-9);
+
+        // without a leading zero 0123 and 0000 are already gone
+        let spec = PinSpec {
+            allow_leading_zero: false,
+            ..preset::pin()
+        };
+        assert_eq!(spec.count(), 9000 - 9 - 13);
+        assert_eq!(PinSpec::new(4).count(), 10000);
+    }
+
+    #[test]
+    fn count_matches_brute_force() {
+        for digits in 1..=5 {
+            for max_repeat_run in [1, 2, 3, usize::MAX] {
+                for allow_leading_zero in [true, false] {
+                    for forbid_sequential in [true, false] {
+                        let spec = PinSpec {
+                            digits,
+                            allow_leading_zero,
+                            max_repeat_run,
+                            forbid_sequential,
+                        };
+                        assert_eq!(spec.count(), brute_force(&spec), "{:?}", spec);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn impossible_pin() {
+        let spec = PinSpec {
+            max_repeat_run: 0,
+            ..PinSpec::new(4)
+        };
+        assert_eq!(spec.count(), 0);
+        assert!(spec.generate().is_none());
+    }
+
+    #[test]
+    fn zero_entropy() {
+        let spec = PinSpec {
+            max_repeat_run: 0,
+            ..PinSpec::new(4)
+        };
+        assert_eq!(spec.entropy(), 0.0);
+        assert_eq!(PinSpec::new(0).entropy(), 0.0);
+    }
+
+    #[test]
+    fn long_pin() {
+        // 10^40 is past what a u128 holds
+        let spec = PinSpec::new(40);
+        assert_eq!(spec.count(), u128::MAX);
+        assert!((spec.entropy() - 40.0 * 10f64.log2()).abs() < 1e-9);
+        let pin = spec.generate().unwrap();
+        assert_eq!(pin.len(), 40);
+        assert!(spec.allowed(&pin));
+        assert_eq!(PinSpec::new(38).count(), 10u128.pow(38));
+    }
+}
@@ -0,0 +1,112 @@
+// Policies as the vault stores them, saved in the current format. They have to keep loading and
+// giving the same specs whatever happens to the types behind `PasswordSpec`.
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use std::path::PathBuf;
+
+    use pants_gen::api::{self, CharClass, ClassRequirement, Error, Policy, POLICY_VERSION};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn load(saved: &str) -> Policy {
+        serde_json::from_str(saved).unwrap()
+    }
+
+    #[test]
+    fn saved_policies() {
+        for (saved, spec) in [
+            (
+                include_str!("policies/v1_classes.json"),
+                // `number` was 1-9 before version 2
+                "20//2+|:upper://1+|:lower://3-|:number-no-zero://1|!@#//0|Il",
+            ),
+            (
+                include_str!("policies/v1_named.json"),
+                "12//0|:symbol://4+|:base58://1+|:upper-latin1://1+|:lower-latin1:",
+            ),
+            (include_str!("policies/unversioned.json"), "8//1+|:lower:"),
+        ] {
+            let policy = load(saved);
+            assert_eq!(policy.version, 1);
+            assert_eq!(policy.to_spec().unwrap().to_string(), spec);
+            let password = api::generate_with_rng(&policy, &mut StdRng::seed_from_u64(3)).unwrap();
+            assert!(policy.to_spec().unwrap().matches(password.expose()));
+        }
+    }
+
+    #[test]
+    fn number_by_version() {
+        let mut policy = Policy::new(8);
+        policy.classes = vec![ClassRequirement::exactly(CharClass::Number, 8)];
+        assert_eq!(policy.to_spec().unwrap().to_string(), "8//8|:number:");
+        policy.version = 1;
+        assert_eq!(
+            policy.to_spec().unwrap().to_string(),
+            "8//8|:number-no-zero:"
+        );
+        let mut rng = StdRng::seed_from_u64(501);
+        for _ in 0..50 {
+            let password = api::generate_with_rng(&policy, &mut rng).unwrap();
+            assert!(!password.expose().contains('0'));
+        }
+    }
+
+    #[test]
+    fn saved_fields() {
+        let policy = load(include_str!("policies/v1_classes.json"));
+        assert_eq!(policy.length, 20);
+        assert_eq!(
+            policy.classes,
+            vec![
+                ClassRequirement::at_least(CharClass::Upper, 2),
+                ClassRequirement::at_least(CharClass::Lower, 1),
+                ClassRequirement::new(CharClass::Number, 0, Some(3)),
+                ClassRequirement::exactly(CharClass::Custom("!@#".to_string()), 1),
+            ]
+        );
+        assert_eq!(policy.exclude, "Il");
+        let passphrase = load(include_str!("policies/v1_passphrase.json"));
+        let wordlist = passphrase.wordlist.unwrap();
+        assert_eq!(wordlist.path, PathBuf::from("words.txt"));
+        assert_eq!(wordlist.words, 5);
+        assert_eq!(wordlist.separator, " ");
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut policy = Policy::new(16);
+        policy.classes = vec![
+            ClassRequirement::at_least(CharClass::UpperLatin1, 1),
+            ClassRequirement::exactly(CharClass::NumberNoZero, 2),
+            ClassRequirement::exactly(CharClass::Custom("-_".to_string()), 2),
+        ];
+        policy.exclude = "ÀÁ".to_string();
+        let saved = serde_json::to_string(&policy).unwrap();
+        assert_eq!(
+            saved,
+            r#"{"version":2,"length":16,"classes":[{"class":"upper-latin1","min":1,"max":null},{"class":"number-no-zero","min":2,"max":2},{"class":{"custom":"-_"},"min":2,"max":2}],"exclude":"ÀÁ"}"#
+        );
+        assert_eq!(load(&saved), policy);
+    }
+
+    // fields written by a later version are ignored, a later version number is refused when
+    // generating rather than on load so the vault can still show the policy
+    #[test]
+    fn later_versions() {
+        let policy = load(r#"{"version": 1, "length": 8, "classes": [], "rotation": "90d"}"#);
+        assert_eq!(
+            policy,
+            Policy {
+                version: 1,
+                ..Policy::new(8)
+            }
+        );
+        let later = load(&format!(
+            r#"{{"version": {}, "length": 8}}"#,
+            POLICY_VERSION + 1
+        ));
+        assert!(matches!(
+            api::generate(&later),
+            Err(Error::UnknownVersion(_))
+        ));
+    }
+}
@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        charset::Charset,
+        password::{GenerateError, PasswordSpec},
+        position::PositionRule,
+        violation::Violation,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn spec() -> PasswordSpec {
+        "12//1+|:upper://1+|:lower://1+|:number://1+|:symbol:"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn first_and_last() {
+        let spec = spec()
+            .position_rule(
+                0,
+                PositionRule::MustBe(Charset::Custom(('a'..='z').chain('A'..='Z').collect())),
+            )
+            .position_rule(-1, PositionRule::MustNotBe(Charset::Symbol));
+        let mut rng = StdRng::seed_from_u64(467);
+        for _ in 0..200 {
+            let password = spec.generate_with_rng(&mut rng).unwrap();
+            let chars: Vec<char> = password.chars().collect();
+            assert!(chars[0].is_ascii_alphabetic(), "{}", password);
+            assert!(!Charset::Symbol.contains(chars[11]), "{}", password);
+            assert!(spec.matches(&password));
+        }
+    }
+
+    #[test]
+    fn middle_range() {
+        let spec = spec().position_range_rule(1, -2, PositionRule::MustNotBe(Charset::Symbol));
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..200 {
+            let password = spec.generate_with_rng(&mut rng).unwrap();
+            let chars: Vec<char> = password.chars().collect();
+            assert!(chars[1..11].iter().all(|c| !Charset::Symbol.contains(*c)));
+            // the one symbol has to be at an end
+            assert!(Charset::Symbol.contains(chars[0]) || Charset::Symbol.contains(chars[11]));
+        }
+    }
+
+    #[test]
+    fn negative_indices() {
+        let spec = "4//0+|:upper://0+|:number:"
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .position_rule(-2, PositionRule::MustBe(Charset::Number))
+            .position_rule(-4, PositionRule::MustBe(Charset::Upper));
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..50 {
+            let password = spec.generate_with_rng(&mut rng).unwrap();
+            let chars: Vec<char> = password.chars().collect();
+            assert!(chars[0].is_ascii_uppercase());
+            assert!(chars[2].is_ascii_digit());
+        }
+        assert!(spec.matches("AB21"));
+        assert!(!spec.matches("A2B1"));
+    }
+
+    #[test]
+    fn affixes_not_covered() {
+        let spec = "4//0+|:number:"
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .prefix("id-")
+            .position_rule(0, PositionRule::MustNotBe(Charset::Custom(vec!['1'])));
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..50 {
+            let password = spec.generate_with_rng(&mut rng).unwrap();
+            assert!(password.starts_with("id-"));
+            assert_ne!(password.chars().nth(3), Some('1'));
+        }
+    }
+
+    #[test]
+    fn unsatisfiable() {
+        // only one symbol but both ends need one
+        let spec = "8//1|:symbol://0+|:lower:"
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .position_rule(0, PositionRule::MustBe(Charset::Symbol))
+            .position_rule(-1, PositionRule::MustBe(Charset::Symbol));
+        assert!(matches!(
+            spec.generate_detailed(),
+            Err(GenerateError::PositionConflict(_))
+        ));
+
+        // nothing is left for the position
+        let spec = spec_with(PositionRule::MustBe(Charset::Number));
+        assert!(matches!(
+            spec.generate_detailed(),
+            Err(GenerateError::PositionConflict(0))
+        ));
+    }
+
+    fn spec_with(rule: PositionRule) -> PasswordSpec {
+        "8//0+|:lower:"
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .position_rule(0, rule)
+    }
+
+    #[test]
+    fn out_of_range() {
+        let spec = spec_with(PositionRule::MustBe(Charset::Lower))
+            .position_rule(8, PositionRule::MustBe(Charset::Lower));
+        assert!(matches!(
+            spec.generate_detailed(),
+            Err(GenerateError::PositionOutOfRange {
+                index: 8,
+                length: 8
+            })
+        ));
+        let spec = spec_with(PositionRule::MustBe(Charset::Lower))
+            .position_rule(-9, PositionRule::MustBe(Charset::Lower));
+        assert_eq!(
+            spec.generate_detailed().unwrap_err().code(),
+            "position_out_of_range"
+        );
+    }
+
+    #[test]
+    fn violations_agree() {
+        let spec = spec_with(PositionRule::MustNotBe(Charset::Custom(vec!['a'])));
+        assert!(spec.matches("baaaaaaa"));
+        assert!(!spec.matches("abbbbbbb"));
+        assert_eq!(
+            spec.violations("abbbbbbb"),
+            vec![Violation::PositionRule {
+                c: 'a',
+                position: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn no_rules_unchanged() {
+        let spec = spec();
+        let a = spec
+            .generate_with_rng(&mut StdRng::seed_from_u64(9))
+            .unwrap();
+        let b = spec
+            .clone()
+            .position_range_rule(0, -1, PositionRule::MustNotBe(Charset::Custom(vec![])))
+            .generate_with_rng(&mut StdRng::seed_from_u64(9))
+            .unwrap();
+        assert_eq!(a.chars().count(), b.chars().count());
+    }
+}
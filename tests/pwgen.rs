@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        charset::Charset,
+        password::PasswordSpec,
+        pwgen::{PwgenError, PwgenOptions, AMBIGUOUS},
+    };
+
+    #[test]
+    fn defaults_follow_tty() {
+        let tty = PwgenOptions::from_args(Vec::<String>::new(), true).unwrap();
+        assert_eq!(
+            tty.spec(),
+            PasswordSpec::new()
+                .length(8)
+                .lower_at_least(0)
+                .upper_at_least(1)
+                .number_at_least(1)
+        );
+        assert_eq!(tty.count(), 8 * 20);
+
+        let piped = PwgenOptions::from_args(Vec::<String>::new(), false).unwrap();
+        assert_eq!(
+            piped.spec(),
+            PasswordSpec::new().length(8).lower_at_least(0)
+        );
+        assert_eq!(piped.count(), 1);
+    }
+
+    #[test]
+    fn flags_map_to_spec() {
+        let options = PwgenOptions::from_args(["-cny1", "12", "3"], false).unwrap();
+        assert_eq!(
+            options.spec(),
+            PasswordSpec::new()
+                .length(12)
+                .lower_at_least(0)
+                .upper_at_least(1)
+                .number_at_least(1)
+                .symbol_at_least(1)
+        );
+        assert_eq!(options.count(), 3);
+        assert!(!options.columns);
+
+        let options = PwgenOptions::from_args(["-A0", "--symbols", "-s"], true).unwrap();
+        assert_eq!(
+            options.spec(),
+            PasswordSpec::new()
+                .length(8)
+                .lower_at_least(0)
+                .symbol_at_least(1)
+        );
+    }
+
+    #[test]
+    fn avoid_ambiguous() {
+        let options = PwgenOptions::from_args(["-cnB", "20", "1"], false).unwrap();
+        let spec = options.spec();
+        for choice in spec.choices() {
+            assert!(matches!(choice.chars, Charset::Custom(_)));
+            assert!(!choice
+                .chars
+                .to_charset()
+                .iter()
+                .any(|c| AMBIGUOUS.contains(*c)));
+        }
+        let password = spec.generate().unwrap();
+        assert!(!password.chars().any(|c| AMBIGUOUS.contains(c)));
+    }
+
+    #[test]
+    fn output_shape() {
+        let passwords: Vec<String> = (0..20).map(|i| format!("pass{:04}", i)).collect();
+        let columns = PwgenOptions::from_args(["8", "20"], true).unwrap();
+        let output = columns.format(&passwords);
+        let lines: Vec<&str> = output.lines().collect();
+        // 80 / (8 + 1) = 8 passwords per row
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].split(' ').count(), 8);
+        assert_eq!(lines[2].split(' ').count(), 4);
+        assert!(output.ends_with('\n'));
+
+        let single = PwgenOptions::from_args(["-1", "8", "20"], true).unwrap();
+        assert_eq!(single.format(&passwords).lines().count(), 20);
+
+        // too long for a row still gets one per row
+        let mut long = PwgenOptions::new(true);
+        long.length = usize::MAX;
+        assert_eq!(long.count(), 20);
+        assert_eq!(long.format(&passwords).lines().count(), 20);
+    }
+
+    #[test]
+    fn bad_arguments() {
+        assert!(matches!(
+            PwgenOptions::from_args(["-q"], true),
+            Err(PwgenError::UnknownOption(_))
+        ));
+        assert!(matches!(
+            PwgenOptions::from_args(["eight"], true),
+            Err(PwgenError::BadNumber("length", _))
+        ));
+        assert!(matches!(
+            PwgenOptions::from_args(["8", "1", "2"], true),
+            Err(PwgenError::ExtraArgument(_))
+        ));
+    }
+}
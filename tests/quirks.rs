@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        charset::Charset,
+        password::PasswordSpec,
+        quirks::{QuirkError, Registry},
+    };
+
+    fn spec(s: &str) -> PasswordSpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn detection() {
+        let registry = Registry::builtin();
+        assert_eq!(
+            registry.names(),
+            vec!["csv", "sh-heredoc", "url-query", "xml"]
+        );
+        let symbols = spec("16//1+|:upper://1+|:symbol:");
+        match symbols.compatible_with(&registry, "url-query") {
+            Err(QuirkError::Rejected { target, chars }) => {
+                assert_eq!(target, "url-query");
+                assert_eq!(chars, vec!['%', '&', '+', '=', '?']);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(
+            symbols
+                .compatible_with(&registry, "xml")
+                .unwrap_err()
+                .to_string(),
+            "`xml` rejects `&` which the spec could use"
+        );
+        assert!(symbols.compatible_with(&registry, "sh-heredoc").is_ok());
+        // excluded characters and the affixes count too
+        let excluded = spec("16//1+|:upper://1+|:symbol://0|&");
+        assert!(excluded.compatible_with(&registry, "xml").is_ok());
+        assert!(excluded
+            .clone()
+            .prefix("a&b-")
+            .compatible_with(&registry, "xml")
+            .is_err());
+    }
+
+    #[test]
+    fn filtering() {
+        let registry = Registry::builtin();
+        let filtered = spec("16//1+|:upper://1+|:symbol:")
+            .for_target(&registry, "url-query")
+            .unwrap();
+        assert!(filtered.compatible_with(&registry, "url-query").is_ok());
+        for _ in 0..20 {
+            let password = filtered.generate().unwrap();
+            assert!(
+                !password.contains(['%', '&', '+', '=', '?']),
+                "{}",
+                password
+            );
+        }
+        // already compatible is left alone
+        let letters = spec("16//1+|:upper://1+|:lower:");
+        assert_eq!(letters.for_target(&registry, "xml").unwrap(), letters);
+    }
+
+    #[test]
+    fn filtering_empties_a_charset() {
+        let registry = Registry::builtin().target("no-symbols", "!@%^&*-_=+:;,.?~");
+        let err = spec("16//1+|:upper://1+|:symbol:")
+            .for_target(&registry, "no-symbols")
+            .unwrap_err();
+        assert_eq!(
+            err,
+            QuirkError::EmptiedCharset {
+                target: "no-symbols".to_string(),
+                charset: Charset::Symbol
+            }
+        );
+        // a choice that doesn't need any is fine
+        assert!(spec("16//1+|:upper://0+|:symbol:")
+            .for_target(&registry, "no-symbols")
+            .is_ok());
+        let err = spec("16//1+|:upper:")
+            .prefix("a&")
+            .for_target(&registry, "xml")
+            .unwrap_err();
+        assert!(matches!(err, QuirkError::InAffix { c: '&', .. }));
+    }
+
+    #[test]
+    fn unknown_target() {
+        let err = PasswordSpec::default()
+            .compatible_with(&Registry::builtin(), "yaml")
+            .unwrap_err();
+        assert!(matches!(&err, QuirkError::UnknownTarget(name, _) if name == "yaml"));
+        assert_eq!(
+            err.to_string(),
+            "No target named `yaml`, known targets are csv, sh-heredoc, url-query, xml"
+        );
+        assert!(PasswordSpec::default()
+            .for_target(&Registry::builtin(), "yaml")
+            .is_err());
+    }
+}
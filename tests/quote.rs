@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use pants_gen::quote::{shell_quote, ShellDialect};
+
+    const NASTY: [&str; 8] = [
+        "plain",
+        "it's",
+        "''",
+        r"back\slash\",
+        "$HOME${x}$(id)",
+        "`id`",
+        "!!$!",
+        "\"double\" ;&|<>*?~#",
+    ];
+
+    #[test]
+    fn posix_quoting() {
+        assert_eq!(shell_quote("abc", ShellDialect::Posix), "'abc'");
+        assert_eq!(shell_quote("it's", ShellDialect::Posix), r"'it'\''s'");
+        assert_eq!(shell_quote("$`!\\", ShellDialect::Posix), "'$`!\\'");
+    }
+
+    #[test]
+    fn powershell_quoting() {
+        assert_eq!(shell_quote("it's", ShellDialect::PowerShell), "'it''s'");
+        assert_eq!(
+            shell_quote("a\u{2019}b$x", ShellDialect::PowerShell),
+            "'a\u{2019}\u{2019}b$x'"
+        );
+    }
+
+    // round trip through a real shell when there is one
+    #[test]
+    fn posix_round_trip() {
+        for input in NASTY {
+            let quoted = shell_quote(input, ShellDialect::Posix);
+            let output = match Command::new("sh")
+                .arg("-c")
+                .arg(format!("printf %s {}", quoted))
+                .output()
+            {
+                Ok(output) => output,
+                Err(_) => return,
+            };
+            assert_eq!(String::from_utf8(output.stdout).unwrap(), input);
+        }
+    }
+}
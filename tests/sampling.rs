@@ -0,0 +1,226 @@
+// The distribution generation is documented to have in `src/sampling.rs`, checked with chi-square
+// tests. The rng is seeded so the tests don't flake, the thresholds are generous so they only trip
+// on a change to the distribution, not on the seed.
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pants_gen::{
+        charset::Charset,
+        fill::FillMode,
+        password::{GenerateError, PasswordSpec},
+        sampling::{SamplingMode, MAX_UNIFORM_LENGTH},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const SAMPLES: usize = 10_000;
+
+    // chi-square of the observed counts against the expected probabilities
+    fn chi_square(observed: &[usize], expected: &[f64]) -> f64 {
+        let total: usize = observed.iter().sum();
+        observed
+            .iter()
+            .zip(expected)
+            .map(|(o, p)| {
+                let e = p * total as f64;
+                (*o as f64 - e).powi(2) / e
+            })
+            .sum()
+    }
+
+    // far beyond what chance gives for the degrees of freedom, a broken distribution over this
+    // many samples lands well above it
+    fn limit(bins: usize) -> f64 {
+        let df = (bins - 1) as f64;
+        df + 6.0 * (2.0 * df).sqrt() + 6.0
+    }
+
+    fn fits(observed: &[usize], expected: &[f64]) {
+        let limit = limit(observed.len());
+        let chi = chi_square(observed, expected);
+        assert!(
+            chi < limit,
+            "chi-square {chi:.1} over {limit:.1}\nobserved {observed:?}\nexpected {expected:?}"
+        );
+    }
+
+    fn passwords(spec: &PasswordSpec, seed: u64) -> Vec<String> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..SAMPLES)
+            .map(|_| spec.try_generate_with_rng(&mut rng).unwrap())
+            .collect()
+    }
+
+    fn binomial(n: usize, p: f64) -> Vec<f64> {
+        let mut pmf = vec![];
+        let mut coefficient = 1.0;
+        for k in 0..=n {
+            pmf.push(coefficient * p.powi(k as i32) * (1.0 - p).powi((n - k) as i32));
+            coefficient *= (n - k) as f64 / (k + 1) as f64;
+        }
+        pmf
+    }
+
+    // how many passwords had each count of characters from `charset`
+    fn counts(
+        spec: &PasswordSpec,
+        passwords: &[String],
+        charset: &Charset,
+        bins: usize,
+    ) -> Vec<usize> {
+        let mut counts = vec![0; bins];
+        for password in passwords {
+            let (_, count) = spec
+                .count_by_charset(password)
+                .into_iter()
+                .find(|(chars, _)| chars == charset)
+                .unwrap();
+            counts[count] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn characters_uniform_within_pool() {
+        let base: PasswordSpec = "12//1+|:upper://1+|:number://1+|:symbol://0-2|xyz"
+            .parse()
+            .unwrap();
+        for spec in [
+            base.clone(),
+            base.clone().fill_mode(FillMode::Balanced),
+            base.clone().sampling_mode(SamplingMode::Uniform),
+        ] {
+            let mut seen: HashMap<char, usize> = HashMap::new();
+            for password in passwords(&spec, 7) {
+                for c in password.chars() {
+                    *seen.entry(c).or_default() += 1;
+                }
+            }
+            for choice in spec.choices() {
+                let pool = choice.chars.to_charset();
+                let observed: Vec<usize> = pool
+                    .iter()
+                    .map(|c| seen.get(c).copied().unwrap_or(0))
+                    .collect();
+                fits(&observed, &vec![1.0 / pool.len() as f64; pool.len()]);
+            }
+        }
+    }
+
+    // the pool is the charset less what isn't allowed, the rest stays uniform
+    #[test]
+    fn allowed_pool_uniform() {
+        let spec: PasswordSpec = "8//1+|:lower:".parse().unwrap();
+        let spec = spec.allowed(Charset::Custom("abcdefg".chars().collect()));
+        let mut seen: HashMap<char, usize> = HashMap::new();
+        for password in passwords(&spec, 11) {
+            for c in password.chars() {
+                *seen.entry(c).or_default() += 1;
+            }
+        }
+        assert_eq!(seen.len(), 7);
+        let observed: Vec<usize> = "abcdefg".chars().map(|c| seen[&c]).collect();
+        fits(&observed, &[1.0 / 7.0; 7]);
+    }
+
+    // beyond the minimums each slot goes to one of the choices picked uniformly, so with no maximum
+    // in reach a choice's extra count is binomial
+    #[test]
+    fn random_fill_binomial() {
+        let spec: PasswordSpec = "7//1+|:upper://1+|:lower://1+|:number:".parse().unwrap();
+        let passwords = passwords(&spec, 3);
+        for charset in [Charset::Upper, Charset::Lower, Charset::Number] {
+            let observed = counts(&spec, &passwords, &charset, 6);
+            // the count is at least the minimum of one
+            assert_eq!(observed[0], 0);
+            fits(&observed[1..], &binomial(4, 1.0 / 3.0));
+        }
+    }
+
+    // a choice at its maximum drops out and the slots left go to the others, so the capped count
+    // takes all the odds of going over it
+    #[test]
+    fn random_fill_capped() {
+        let spec: PasswordSpec = "8//0-2|:upper://0+|:number:".parse().unwrap();
+        let passwords = passwords(&spec, 5);
+        let observed = counts(&spec, &passwords, &Charset::Upper, 3);
+        let pmf = binomial(8, 0.5);
+        fits(&observed, &[pmf[0], pmf[1], 1.0 - pmf[0] - pmf[1]]);
+    }
+
+    // balanced fill splits the slots evenly, only the leftover goes to distinct choices at random
+    #[test]
+    fn balanced_fill_split() {
+        let spec: PasswordSpec = "8//0+|:upper://0+|:lower://0+|:number:".parse().unwrap();
+        let spec = spec.fill_mode(FillMode::Balanced);
+        let passwords = passwords(&spec, 9);
+        for charset in [Charset::Upper, Charset::Lower, Charset::Number] {
+            let observed = counts(&spec, &passwords, &charset, 4);
+            assert_eq!(observed[..2], [0, 0]);
+            fits(&observed[2..], &[1.0 / 3.0, 2.0 / 3.0]);
+        }
+    }
+
+    // under uniform sampling a choice's count follows how many strings have it, a charset of two
+    // beside one of six gets a quarter of the characters rather than half
+    #[test]
+    fn uniform_counts_follow_charset_sizes() {
+        let spec: PasswordSpec = "6//0+|ab//0+|cdefgh".parse().unwrap();
+        let ab = Charset::Custom(vec!['a', 'b']);
+        let expected = binomial(6, 0.25);
+        let uniform = spec.clone().sampling_mode(SamplingMode::Uniform);
+        fits(
+            &counts(&uniform, &passwords(&uniform, 13), &ab, 7),
+            &expected,
+        );
+        // per choice is the half and half the uniform mode fixes
+        let per_choice = counts(&spec, &passwords(&spec, 13), &ab, 7);
+        assert!(chi_square(&per_choice, &expected) > 100.0 * limit(7));
+    }
+
+    // with bounds on the counts the strings that break them drop out and the rest stay alike,
+    // `4//1+|ab//1-2|cdefgh` has 864 strings with two of `ab` and 192 with three
+    #[test]
+    fn uniform_counts_bounded() {
+        let spec: PasswordSpec = "4//1+|ab//1-2|cdefgh".parse().unwrap();
+        let spec = spec.sampling_mode(SamplingMode::Uniform);
+        let observed = counts(
+            &spec,
+            &passwords(&spec, 17),
+            &Charset::Custom(vec!['a', 'b']),
+            5,
+        );
+        assert_eq!(observed[..2], [0, 0]);
+        assert_eq!(observed[4], 0);
+        fits(&observed[2..4], &[864.0 / 1056.0, 192.0 / 1056.0]);
+    }
+
+    // working out the counts grows with the square of the length, longer specs are refused
+    // before any of it is done
+    #[test]
+    fn uniform_length_limited() {
+        let spec: PasswordSpec = "8//1+|:lower://1+|:number:".parse().unwrap();
+        let spec = spec.sampling_mode(SamplingMode::Uniform);
+        let mut rng = StdRng::seed_from_u64(19);
+        let longest = spec.clone().length(MAX_UNIFORM_LENGTH);
+        assert_eq!(
+            longest.try_generate_with_rng(&mut rng).unwrap().len(),
+            MAX_UNIFORM_LENGTH
+        );
+        for length in [MAX_UNIFORM_LENGTH + 1, usize::MAX] {
+            let error = spec
+                .clone()
+                .length(length)
+                .try_generate_with_rng(&mut rng)
+                .unwrap_err();
+            assert!(matches!(
+                error,
+                GenerateError::UniformTooLong { length: l, max: MAX_UNIFORM_LENGTH } if l == length
+            ));
+            assert_eq!(error.code(), "uniform_too_long");
+        }
+        // the default mode has no such limit
+        let long = PasswordSpec::default().length(MAX_UNIFORM_LENGTH + 1);
+        assert!(long.try_generate_with_rng(&mut rng).is_ok());
+    }
+}
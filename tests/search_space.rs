@@ -0,0 +1,80 @@
+#[cfg(all(test, feature = "num-bigint"))]
+mod tests {
+    use pants_gen::{
+        password::PasswordSpec,
+        search_space::{BigUint, MAX_SEARCH_SPACE_LENGTH},
+    };
+
+    fn space(spec: &str) -> BigUint {
+        spec.parse::<PasswordSpec>()
+            .unwrap()
+            .search_space()
+            .unwrap()
+    }
+
+    #[test]
+    fn pin() {
+        assert_eq!(space("4//4|:number:"), BigUint::from(10000u32));
+        assert_eq!(space("4//4|0123456789"), BigUint::from(10000u32));
+        // without the 0 a 4 digit pin has 9^4 possibilities rather than 10^4
+        assert_eq!(space("4//4|:number-no-zero:"), BigUint::from(6561u32));
+    }
+
+    #[test]
+    fn small_cases() {
+        // ac, ca, bc, cb
+        assert_eq!(space("2//1+|ab//1+|c"), BigUint::from(4u32));
+        assert_eq!(space("3//3-|ab"), BigUint::from(8u32));
+        // cc, or two of a and b
+        assert_eq!(space("2//0,2|ab//2-|c"), BigUint::from(5u32));
+        // 3 positions for the x, 2^2 for the rest
+        assert_eq!(space("3//1|x//2|ab"), BigUint::from(12u32));
+        // a is excluded from ab as well
+        assert_eq!(space("2//0|a//2-|ab"), BigUint::from(1u32));
+        assert_eq!(space("0//0+|ab"), BigUint::from(1u32));
+    }
+
+    #[test]
+    fn allowed_characters() {
+        // a digit, and one of a or b that only the allowed characters add
+        assert_eq!(space("2//1|:number://*|0123456789ab"), BigUint::from(40u32));
+        assert_eq!(
+            space("2//0+|:number://*|0123456789ab"),
+            BigUint::from(144u32)
+        );
+    }
+
+    #[test]
+    fn nothing_possible() {
+        assert_eq!(space("4//5+|ab"), BigUint::from(0u32));
+        assert_eq!(
+            PasswordSpec::new().search_space(),
+            Some(BigUint::from(0u32))
+        );
+    }
+
+    #[test]
+    fn long_lengths_refused() {
+        let spec = "0//0+|ab".parse::<PasswordSpec>().unwrap();
+        let longest = spec.clone().length(MAX_SEARCH_SPACE_LENGTH).search_space();
+        assert_eq!(longest, Some(BigUint::from(1u8) << MAX_SEARCH_SPACE_LENGTH));
+        for length in [MAX_SEARCH_SPACE_LENGTH + 1, usize::MAX] {
+            assert_eq!(spec.clone().length(length).search_space(), None);
+        }
+    }
+
+    #[test]
+    fn default_spec() {
+        let space = PasswordSpec::default().search_space().unwrap();
+        // every composition is a subset of all 32 character strings from the 78 characters
+        assert!(space < BigUint::from(78u32).pow(32));
+        // and at least those with one of each class fixed in the first four positions
+        let lower = BigUint::from(26u32 * 26 * 10 * 16) * BigUint::from(78u32).pow(28);
+        assert!(space > lower);
+        // affixes add nothing
+        assert_eq!(
+            PasswordSpec::default().prefix("id-").search_space(),
+            Some(space)
+        );
+    }
+}
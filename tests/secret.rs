@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Cursor,
+        panic,
+        sync::{Arc, Mutex},
+    };
+
+    use pants_gen::{
+        dedup::find_duplicates,
+        options::{Constraint, GenerateOptions},
+        password::PasswordSpec,
+        secret::Secret,
+        store::{Entry, MemoryStore, SecretStore},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // a password that can't turn up by accident in a message, with no symbol so the spec refuses it
+    const SENTINEL: &str = "Zq8SentinelXv3";
+
+    fn spec() -> PasswordSpec {
+        "14//1+|:upper://1+|:lower://1+|:number://1+|:symbol:"
+            .parse()
+            .unwrap()
+    }
+
+    fn assert_hidden(shown: &str, password: &str) {
+        assert!(!shown.contains(password), "`{}` shows the password", shown);
+    }
+
+    #[test]
+    fn debug_is_redacted() {
+        let secret = Secret::new(SENTINEL.to_string());
+        assert_eq!(format!("{:?}", secret), "Secret([REDACTED], len=14)");
+        assert_eq!(
+            format!("{:?}", Secret::new("né")),
+            "Secret([REDACTED], len=2)"
+        );
+        assert_eq!(secret.expose(), SENTINEL);
+        assert_eq!(secret.into_inner(), SENTINEL);
+    }
+
+    #[test]
+    fn generated_debug() {
+        let generated = spec()
+            .generate_detailed_with_rng(&mut StdRng::seed_from_u64(3))
+            .unwrap();
+        let password = generated.password();
+        let shown = format!("{:?}", generated);
+        assert_hidden(&shown, &password);
+        assert!(shown.contains("Secret([REDACTED], len=14)"));
+        assert_hidden(&format!("{:#?}", generated), &password);
+        assert_eq!(generated.secret().expose(), &password);
+        // the password is still there for Display
+        assert_eq!(generated.to_string(), password);
+    }
+
+    #[test]
+    fn violations_and_separated_check() {
+        let spec = spec();
+        let violations = spec.violations(SENTINEL);
+        assert!(!violations.is_empty());
+        for violation in &violations {
+            assert_hidden(&violation.to_string(), SENTINEL);
+            assert_hidden(&format!("{:?}", violation), SENTINEL);
+        }
+        let check = spec.check_separated("Zq8S enti nelX v3", &[' ']);
+        assert_eq!(check.normalized, SENTINEL);
+        assert_hidden(&format!("{:?}", check), SENTINEL);
+        assert_hidden(&check.to_string(), SENTINEL);
+    }
+
+    #[test]
+    fn duplicates() {
+        let input = format!("{}\nother\n{}\n", SENTINEL, SENTINEL);
+        let reports = find_duplicates(Cursor::new(input)).unwrap();
+        assert_eq!(reports[0].password(), SENTINEL);
+        assert_hidden(&format!("{:?}", reports), SENTINEL);
+        assert_hidden(&reports[0].to_string(), SENTINEL);
+    }
+
+    #[test]
+    fn memory_store() {
+        let mut store = MemoryStore::new();
+        let entry = Entry::parse_target("MyApp/alice").unwrap();
+        store.write(&entry, SENTINEL).unwrap();
+        assert_eq!(store.get("MyApp", "alice"), Some(SENTINEL));
+        assert_hidden(&format!("{:?}", store), SENTINEL);
+    }
+
+    #[test]
+    fn rejected_candidates_stay_out_of_errors() {
+        let seen = Arc::new(Mutex::new(vec![]));
+        let recorded = seen.clone();
+        let options = GenerateOptions::new()
+            .max_attempts(20)
+            .constraint(Constraint::new("never", move |password| {
+                recorded.lock().unwrap().push(password.to_string());
+                false
+            }));
+        let err = spec()
+            .generate_with_options(&options, &mut StdRng::seed_from_u64(3))
+            .unwrap_err();
+        let (shown, debug) = (err.to_string(), format!("{:?}", err));
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 20);
+        for candidate in seen.iter() {
+            assert_hidden(&shown, candidate);
+            assert_hidden(&debug, candidate);
+        }
+    }
+
+    #[test]
+    fn panic_message() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let first = spec().generate_detailed_with_rng(&mut rng).unwrap();
+        let second = spec().generate_detailed_with_rng(&mut rng).unwrap();
+        let passwords = [first.password(), second.password()];
+        // keep the expected panic out of the test output
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let payload = panic::catch_unwind(|| assert_eq!(first, second)).unwrap_err();
+        panic::set_hook(hook);
+        let message = payload.downcast_ref::<String>().unwrap();
+        assert!(message.contains("GeneratedPassword"));
+        for password in &passwords {
+            assert_hidden(message, password);
+        }
+    }
+}
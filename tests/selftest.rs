@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::selftest::{run, SelfTestError};
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    // hands out the same byte forever
+    struct Constant(u8);
+
+    impl RngCore for Constant {
+        fn next_u32(&mut self) -> u32 {
+            u32::from_ne_bytes([self.0; 4])
+        }
+        fn next_u64(&mut self) -> u64 {
+            u64::from_ne_bytes([self.0; 8])
+        }
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            dest.fill(self.0);
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    // a good rng that drops a stretch of zeros into its output
+    struct Stuck(StdRng);
+
+    impl RngCore for Stuck {
+        fn next_u32(&mut self) -> u32 {
+            self.0.next_u32()
+        }
+        fn next_u64(&mut self) -> u64 {
+            self.0.next_u64()
+        }
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            self.0.fill_bytes(dest);
+            let middle = dest.len() / 2;
+            dest[middle..middle + 4].fill(0);
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn healthy_rng_passes() {
+        assert!(run(&mut rand::thread_rng()).is_ok());
+        for seed in 0..10 {
+            assert!(run(&mut StdRng::seed_from_u64(seed)).is_ok());
+        }
+    }
+
+    #[test]
+    fn all_zero_rng_fails() {
+        let err = run(&mut Constant(0)).unwrap_err();
+        assert!(matches!(err, SelfTestError::Monobit { ones: 0 }));
+        assert_eq!(
+            err.to_string(),
+            "Monobit test failed, 0 of 20000 bits were ones"
+        );
+    }
+
+    #[test]
+    fn alternating_rng_fails_runs() {
+        // exactly half ones, but every run has length 1
+        let err = run(&mut Constant(0b0101_0101)).unwrap_err();
+        assert!(matches!(err, SelfTestError::Runs { count: 10000, .. }));
+    }
+
+    #[test]
+    fn long_run_fails() {
+        let err = run(&mut Stuck(StdRng::seed_from_u64(1))).unwrap_err();
+        assert!(matches!(err, SelfTestError::LongRun(length) if length >= 32));
+    }
+}
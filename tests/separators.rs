@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        password::PasswordSpec,
+        separators::{SeparatedCheck, DEFAULT_SEPARATORS},
+    };
+
+    fn spec() -> PasswordSpec {
+        "12//4|:upper://4|:number://4|:lower:".parse().unwrap()
+    }
+
+    #[test]
+    fn strip_then_pass() {
+        let check = spec().check_separated("ABCD 1234-efgh", &DEFAULT_SEPARATORS);
+        assert!(!check.raw_ok());
+        assert!(check.normalized_ok());
+        assert_eq!(check.normalized, "ABCD1234efgh");
+        assert!(check.refused.is_empty());
+        // other separators only when asked for
+        let check = spec().check_separated("ABCD.1234.efgh", &DEFAULT_SEPARATORS);
+        assert!(!check.normalized_ok());
+        let check = spec().check_separated("ABCD.1234.efgh", &['.']);
+        assert!(check.normalized_ok());
+    }
+
+    #[test]
+    fn refuses_separators_the_spec_uses() {
+        // the symbols include `-`, stripping it would hide that there's no symbol left
+        let symbols: PasswordSpec = "9//4|:upper://4|:number://1|:symbol:".parse().unwrap();
+        let check = symbols.check_separated("ABCD 1234-", &DEFAULT_SEPARATORS);
+        assert_eq!(check.refused, vec!['-']);
+        assert_eq!(check.normalized, "ABCD1234-");
+        assert!(check.normalized_ok());
+        let check = symbols.check_separated("ABCD-1234", &DEFAULT_SEPARATORS);
+        assert_eq!(check.normalized, "ABCD-1234");
+        assert!(check.normalized_ok());
+        let check = symbols.check_separated("AB-CD-1234", &DEFAULT_SEPARATORS);
+        assert!(!check.normalized_ok());
+
+        // or in the affixes
+        let prefixed = spec().prefix("id-");
+        let check = prefixed.check_separated("id-ABCD 1234 efgh", &DEFAULT_SEPARATORS);
+        assert_eq!(check.refused, vec!['-']);
+        assert!(check.normalized_ok());
+    }
+
+    #[test]
+    fn dual_verdict() {
+        let check = spec().check_separated("ABCD 1234 efgh", &DEFAULT_SEPARATORS);
+        assert_eq!(
+            check.to_string(),
+            "as given: Has 14 characters, allows only 12; Character 5 ` ` isn't allowed; \
+             Character 10 ` ` isn't allowed\nwithout separators: ok"
+        );
+        let spec: PasswordSpec = "9//4|:upper://4|:number://1|:symbol:".parse().unwrap();
+        let check: SeparatedCheck = spec.check_separated("ABCD-1234", &DEFAULT_SEPARATORS);
+        assert_eq!(
+            check.to_string(),
+            "as given: ok\nwithout separators: ok\n`-` wasn't stripped, the spec can have it in a password"
+        );
+    }
+}
@@ -0,0 +1,183 @@
+// Pinned output of every structured format, so a change to any of them shows up as a diff to
+// review. Run with `UPDATE_SNAPSHOTS=1` to rewrite the files in `tests/snapshots` after a
+// deliberate change.
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use pants_gen::{output, password::PasswordSpec, position::PositionRule, Charset};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const SEED: u64 = 494;
+
+    fn assert_snapshot(name: &str, actual: &str) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("snapshots")
+            .join(format!("{}.txt", name));
+        if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+            panic!(
+                "no snapshot at {}, run with UPDATE_SNAPSHOTS=1 to create it",
+                path.display()
+            )
+        });
+        assert!(
+            expected == actual,
+            "{} changed, run with UPDATE_SNAPSHOTS=1 if that's intended\n--- expected\n{}\n--- actual\n{}",
+            name,
+            expected,
+            actual
+        );
+    }
+
+    // what a spec string can record
+    fn plain_spec() -> PasswordSpec {
+        "v2:16//2+|:upper://1-3|:lower://2|:number://1+|safe=!@#"
+            .parse::<PasswordSpec>()
+            .unwrap()
+    }
+
+    fn spec() -> PasswordSpec {
+        plain_spec()
+            .prefix("ID-")
+            .position_rule(0, PositionRule::MustBe(Charset::Upper))
+            .no_adjacent_from(Charset::Number)
+    }
+
+    fn passwords(spec: &PasswordSpec, n: usize) -> Vec<String> {
+        let mut rng = StdRng::seed_from_u64(SEED);
+        (0..n)
+            .map(|_| spec.generate_with_rng(&mut rng).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn plain_output() {
+        let spec = plain_spec();
+        let single = output::with_spec(&spec, &passwords(&spec, 1)).unwrap();
+        assert_snapshot("plain_single", &single);
+        let batch = output::with_spec(&spec, &passwords(&spec, 5)).unwrap();
+        assert_snapshot("plain_batch", &batch);
+    }
+
+    #[test]
+    fn describe() {
+        assert_snapshot("describe", &spec().describe());
+    }
+
+    #[test]
+    fn entropy_report() {
+        let lines: Vec<String> = spec()
+            .entropy_report()
+            .unwrap()
+            .iter()
+            .map(|line| {
+                format!(
+                    "{:?} {} {} characters {:.3} bits each x{} = {:.3}",
+                    line.index,
+                    line.name,
+                    line.characters,
+                    line.bits_each,
+                    line.count,
+                    line.bits()
+                )
+            })
+            .collect();
+        assert_snapshot("entropy_report", &lines.join("\n"));
+    }
+
+    #[test]
+    fn lint() {
+        let spec: PasswordSpec = "8//6+|abc//0+|abcdefghijklmnopqrstuvwxy//0+|:upper:"
+            .parse()
+            .unwrap();
+        let lines: Vec<String> = spec.lint().iter().map(|w| w.to_string()).collect();
+        assert_snapshot("lint", &lines.join("\n"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn spec_json() {
+        let value: serde_json::Value = serde_json::from_str(&spec().to_json()).unwrap();
+        assert_snapshot("spec_json", &serde_json::to_string_pretty(&value).unwrap());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn violations_json() {
+        let violations: Vec<serde_json::Value> = spec()
+            .violations("ID-ab12cdefgh0ijk")
+            .iter()
+            .map(|violation| violation.to_json())
+            .collect();
+        assert_snapshot(
+            "violations_json",
+            &serde_json::to_string_pretty(&violations).unwrap(),
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn errors_json() {
+        let parse = "16//1+|:nope:".parse::<PasswordSpec>().unwrap_err();
+        let generate = PasswordSpec::new()
+            .length(2)
+            .upper_at_least(3)
+            .generate_detailed()
+            .unwrap_err();
+        let errors = serde_json::json!([parse.to_json(), generate.to_json()]);
+        assert_snapshot(
+            "errors_json",
+            &serde_json::to_string_pretty(&errors).unwrap(),
+        );
+    }
+
+    #[cfg(all(feature = "json", feature = "metadata"))]
+    #[test]
+    fn metadata_json() {
+        use pants_gen::metadata::{FixedClock, RngSource};
+
+        let (generated, metadata) = spec()
+            .generate_with_metadata_and_clock(RngSource::Seeded(SEED), &FixedClock(1_700_000_000))
+            .unwrap();
+        let value = generated.to_json_with_metadata(&metadata);
+        let pretty = serde_json::to_string_pretty(&value).unwrap();
+        assert_snapshot(
+            "metadata_json",
+            &pretty.replace(env!("CARGO_PKG_VERSION"), "<version>"),
+        );
+    }
+
+    #[cfg(feature = "transcript")]
+    #[test]
+    fn transcript() {
+        use pants_gen::{
+            metadata::{FixedClock, RngSource},
+            transcript::Transcript,
+        };
+
+        let (generated, metadata) = spec()
+            .generate_with_metadata_and_clock(RngSource::Seeded(SEED), &FixedClock(1_700_000_000))
+            .unwrap();
+        let transcript = Transcript::record_with(
+            &generated,
+            &spec(),
+            &metadata,
+            &FixedClock(1_700_000_060),
+            &mut StdRng::seed_from_u64(SEED),
+        );
+        // the commitment covers the version, so it's pinned with the version it was made with
+        let serialized = transcript.to_string();
+        let version = format!("version: {}\n", env!("CARGO_PKG_VERSION"));
+        assert!(serialized.contains(&version));
+        let lines: Vec<&str> = serialized
+            .lines()
+            .filter(|line| !line.starts_with("version:") && !line.starts_with("commitment:"))
+            .collect();
+        assert_snapshot("transcript", &lines.join("\n"));
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        password::{GenerateError, PasswordSpec},
+        specset::{SpecSet, SpecSetError},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn spec(s: &str) -> PasswordSpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn weighted_selection() {
+        let short = spec("8//8|:lower:");
+        let long = spec("12//12|:upper:");
+        let set = SpecSet::weighted(vec![(short, 3.0), (long, 1.0)]).unwrap();
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut shorts = 0;
+        for _ in 0..1000 {
+            let password = set.generate_with_rng(&mut rng).unwrap();
+            assert!(set.matches(&password), "{}", password);
+            if password.len() == 8 {
+                shorts += 1;
+            }
+        }
+        // 750 expected, the standard deviation is about 14
+        assert!((680..=820).contains(&shorts), "{}", shorts);
+    }
+
+    #[test]
+    fn infeasible_members() {
+        let broken = spec("4//5+|:lower:");
+        let fine = spec("6//6|:number:");
+        let set = SpecSet::any_of(vec![broken.clone(), fine.clone()]).unwrap();
+        let mut rng = StdRng::seed_from_u64(6);
+        for _ in 0..20 {
+            let password = set.generate_with_rng(&mut rng).unwrap();
+            assert!(fine.matches(&password));
+        }
+
+        let set = SpecSet::any_of(vec![broken, PasswordSpec::new().length(4)]).unwrap();
+        let err = set.generate_with_rng(&mut rng).unwrap_err();
+        match &err {
+            SpecSetError::AllFailed(errors) => {
+                assert!(matches!(
+                    errors[0],
+                    (0, GenerateError::LengthBelowMinimum { .. })
+                ));
+                assert!(matches!(errors[1], (1, GenerateError::NoChoices)));
+            }
+            _ => panic!("{}", err),
+        }
+        assert!(err.to_string().contains("spec 0: ") && err.to_string().contains("spec 1: "));
+    }
+
+    #[test]
+    fn entropy() {
+        // 4 and 8 bits, half the time each, plus a bit for which
+        let set = SpecSet::any_of(vec![spec("4//4|ab"), spec("4//4|abcd")]).unwrap();
+        assert!((set.entropy().unwrap() - 7.0).abs() < 1e-9);
+        let set = SpecSet::weighted(vec![(spec("4//4|ab"), 1.0)]).unwrap();
+        assert!((set.entropy().unwrap() - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bad_sets() {
+        assert!(matches!(SpecSet::any_of(vec![]), Err(SpecSetError::Empty)));
+        assert!(matches!(
+            SpecSet::weighted(vec![(PasswordSpec::default(), 0.0)]),
+            Err(SpecSetError::BadWeight(0, _))
+        ));
+    }
+}
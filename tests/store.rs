@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::store::{open, put, Backend, Entry, MemoryStore, SecretStore, StoreError};
+
+    fn alice() -> Entry {
+        Entry::parse_target("MyApp/alice").unwrap()
+    }
+
+    #[test]
+    fn write() {
+        let mut store = MemoryStore::new();
+        let confirmation = put(&mut store, &alice(), "hunter2", false).unwrap();
+        assert_eq!(store.get("MyApp", "alice"), Some("hunter2"));
+        assert!(confirmation.contains("MyApp/alice"));
+        assert!(!confirmation.contains("hunter2"));
+    }
+
+    #[test]
+    fn overwrite_needs_force() {
+        let mut store = MemoryStore::new();
+        put(&mut store, &alice(), "first", false).unwrap();
+        assert!(matches!(
+            put(&mut store, &alice(), "second", false),
+            Err(StoreError::Exists(target)) if target == "MyApp/alice"
+        ));
+        assert_eq!(store.get("MyApp", "alice"), Some("first"));
+
+        let confirmation = put(&mut store, &alice(), "second", true).unwrap();
+        assert_eq!(store.get("MyApp", "alice"), Some("second"));
+        assert!(confirmation.starts_with("Replaced"));
+        // forcing a new target is just a write
+        let bob = Entry::new("MyApp", "bob");
+        assert!(put(&mut store, &bob, "third", true)
+            .unwrap()
+            .starts_with("Stored"));
+    }
+
+    #[test]
+    fn attributes() {
+        let entry = Entry::parse_target("mail/alice/work").unwrap();
+        assert_eq!(entry.service, "mail");
+        assert_eq!(entry.account, "alice/work");
+        assert_eq!(entry.label, "mail/alice/work");
+        assert_eq!(entry.target(), "mail/alice/work");
+        for bad in ["alice", "/alice", "mail/"] {
+            assert!(matches!(
+                Entry::parse_target(bad),
+                Err(StoreError::BadTarget(_))
+            ));
+        }
+
+        let mut store = MemoryStore::new();
+        let entry = Entry::new("mail", "alice").label("Work mail");
+        let confirmation = put(&mut store, &entry, "secret", false).unwrap();
+        assert_eq!(store.entry("mail", "alice"), Some(("Work mail", "secret")));
+        assert!(confirmation.contains("Work mail"));
+        assert_eq!(store.get("mail", "bob"), None);
+    }
+
+    struct Broken(StoreError);
+
+    impl SecretStore for Broken {
+        fn name(&self) -> &'static str {
+            "broken"
+        }
+        fn exists(&self, _: &Entry) -> Result<bool, StoreError> {
+            Ok(false)
+        }
+        fn write(&mut self, _: &Entry, _: &str) -> Result<(), StoreError> {
+            Err(match &self.0 {
+                StoreError::Locked => StoreError::Locked,
+                _ => StoreError::NoService("broken".to_string()),
+            })
+        }
+    }
+
+    #[test]
+    fn backend_failure() {
+        assert!(matches!(
+            put(&mut Broken(StoreError::Locked), &alice(), "secret", false),
+            Err(StoreError::Locked)
+        ));
+        assert!(matches!(
+            put(
+                &mut Broken(StoreError::NoService(String::new())),
+                &alice(),
+                "secret",
+                false
+            ),
+            Err(StoreError::NoService(_))
+        ));
+    }
+
+    #[test]
+    fn selection() {
+        let mut store = open("memory").unwrap();
+        assert_eq!(store.name(), "memory");
+        put(store.as_mut(), &alice(), "secret", false).unwrap();
+        assert!(store.exists(&alice()).unwrap());
+
+        assert!(matches!(open("keepass"), Err(StoreError::Unknown(_))));
+        for backend in [Backend::WinCred, Backend::SecretService, Backend::Keychain] {
+            match Backend::select(backend.name()) {
+                Ok(selected) => {
+                    assert!(backend.compiled_in());
+                    assert_eq!(selected, backend);
+                }
+                // not compiled in is its own error, apart from a store that's locked or down
+                Err(e) => {
+                    assert!(!backend.compiled_in());
+                    assert!(matches!(e, StoreError::Unavailable(name) if name == backend.name()));
+                }
+            }
+        }
+        match Backend::select("auto") {
+            Ok(backend) => assert!(backend.compiled_in() && backend != Backend::Memory),
+            Err(e) => assert!(matches!(e, StoreError::Unavailable(_))),
+        }
+        if cfg!(all(target_os = "linux", feature = "secret-service")) {
+            assert_eq!(Backend::select("auto").unwrap(), Backend::SecretService);
+        }
+    }
+
+    // needs a running secret service, keychain or credential manager
+    #[test]
+    #[ignore]
+    fn smoke() {
+        let mut store = open("auto").unwrap();
+        let entry = Entry::new("pants-gen-test", "smoke").label("pants-gen smoke test");
+        put(store.as_mut(), &entry, "not a real secret", true).unwrap();
+        assert!(store.exists(&entry).unwrap());
+    }
+}
@@ -0,0 +1,213 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use pants_gen::{
+        password::{GenerateError, PasswordSpec},
+        stream::progress_bar,
+        units::LengthUnit,
+        Charset,
+    };
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    // counts how much randomness has been asked for
+    struct Counting {
+        inner: StdRng,
+        calls: Rc<Cell<usize>>,
+    }
+
+    impl RngCore for Counting {
+        fn next_u32(&mut self) -> u32 {
+            self.calls.set(self.calls.get() + 1);
+            self.inner.next_u32()
+        }
+        fn next_u64(&mut self) -> u64 {
+            self.calls.set(self.calls.get() + 1);
+            self.inner.next_u64()
+        }
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            self.calls.set(self.calls.get() + 1);
+            self.inner.fill_bytes(dest)
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.calls.set(self.calls.get() + 1);
+            self.inner.try_fill_bytes(dest)
+        }
+    }
+
+    #[test]
+    fn lazy() {
+        let calls = Rc::new(Cell::new(0));
+        let rng = Counting {
+            inner: StdRng::seed_from_u64(475),
+            calls: calls.clone(),
+        };
+        let spec = PasswordSpec::default();
+        let mut stream = spec.stream(5_000_000, rng);
+        assert_eq!(calls.get(), 0);
+        assert_eq!(stream.size_hint(), (5_000_000, Some(5_000_000)));
+
+        let first = stream.next().unwrap().unwrap();
+        let after_one = calls.get();
+        assert!(after_one > 0);
+        assert!(spec.matches(&first));
+        let rest: Vec<String> = stream.by_ref().take(9).map(Result::unwrap).collect();
+        assert_eq!(rest.len(), 9);
+        // ten passwords worth of randomness, nowhere near five million
+        assert!(calls.get() < after_one * 100);
+        assert_eq!(stream.size_hint().0, 5_000_000 - 10);
+    }
+
+    #[test]
+    fn same_as_generating() {
+        let spec = PasswordSpec::default();
+        let streamed: Vec<String> = spec
+            .stream(3, StdRng::seed_from_u64(1))
+            .map(Result::unwrap)
+            .collect();
+        let mut rng = StdRng::seed_from_u64(1);
+        let generated: Vec<String> = (0..3)
+            .map(|_| spec.generate_with_rng(&mut rng).unwrap())
+            .collect();
+        assert_eq!(streamed, generated);
+    }
+
+    #[test]
+    fn unresolvable() {
+        let spec = "4//1+|a"
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .prefix("abcde")
+            .length_includes_affixes(true);
+        let results: Vec<_> = spec.stream(10, StdRng::seed_from_u64(2)).collect();
+        assert_eq!(results.len(), 1);
+        assert!(matches!(
+            results[0],
+            Err(GenerateError::AffixesTooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn iter_same_as_generating() {
+        let specs = [
+            PasswordSpec::default(),
+            "12//1+|:upper://0,2|:number://0+|:lower:".parse().unwrap(),
+            PasswordSpec::default().prefix("id-").suffix("!"),
+        ];
+        for spec in specs {
+            let iterated: Vec<String> = spec
+                .iter_with_rng(StdRng::seed_from_u64(505))
+                .take(20)
+                .collect();
+            let mut rng = StdRng::seed_from_u64(505);
+            let generated: Vec<String> = (0..20)
+                .map(|_| spec.generate_with_rng(&mut rng).unwrap())
+                .collect();
+            assert_eq!(iterated, generated);
+        }
+        let passwords: Vec<String> = PasswordSpec::default().iter().take(500).collect();
+        assert_eq!(passwords.len(), 500);
+        assert!(passwords.iter().all(|p| PasswordSpec::default().matches(p)));
+    }
+
+    #[test]
+    fn iter_with_rules() {
+        let spaced = PasswordSpec::default().no_adjacent_from(Charset::Number);
+        let bytes = PasswordSpec::new()
+            .length(24)
+            .length_unit(LengthUnit::Bytes)
+            .include(Charset::LowerLatin1.at_least(1));
+        for spec in [spaced, bytes] {
+            let passwords: Vec<String> = spec
+                .iter_with_rng(StdRng::seed_from_u64(3))
+                .take(20)
+                .collect();
+            assert_eq!(passwords.len(), 20);
+            assert!(passwords.iter().all(|p| spec.matches(p)));
+        }
+    }
+
+    #[test]
+    fn generate_n() {
+        let spec = PasswordSpec::default();
+        let mut rng = StdRng::seed_from_u64(6);
+        let passwords = spec.generate_n_with_rng(100, &mut rng).unwrap();
+        assert_eq!(passwords.len(), 100);
+        assert!(passwords.iter().all(|p| spec.matches(p)));
+        assert_eq!(spec.generate_n(0), Some(vec![]));
+
+        let infeasible = PasswordSpec::new().length(2).upper_at_least(3);
+        assert_eq!(infeasible.generate_n(5), None);
+        assert_eq!(infeasible.generate_n(0), None);
+        assert_eq!(infeasible.iter().next(), None);
+    }
+
+    #[test]
+    fn try_generate_n() {
+        let spec = PasswordSpec::new()
+            .length(12)
+            .lower_at_least(1)
+            .prefix("id-");
+        let mut rng = StdRng::seed_from_u64(506);
+        let passwords = spec.try_generate_n_with_rng(10, &mut rng).unwrap();
+        assert_eq!(passwords.len(), 10);
+        assert!(passwords.iter().all(|p| spec.matches(p)));
+
+        let infeasible = PasswordSpec::new().length(2).upper_at_least(3);
+        assert!(matches!(
+            infeasible.try_generate_n(10),
+            Err(GenerateError::LengthBelowMinimum {
+                required: 3,
+                length: 2
+            })
+        ));
+        assert!(matches!(
+            PasswordSpec::new().try_generate_n(0),
+            Err(GenerateError::NoChoices)
+        ));
+    }
+
+    #[test]
+    fn generate_unique_n() {
+        let spec: PasswordSpec = "2//1+|abc".parse().unwrap();
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut passwords = spec.generate_unique_n_with_rng(9, &mut rng).unwrap();
+        passwords.sort();
+        passwords.dedup();
+        assert_eq!(passwords.len(), 9);
+        let error = spec.generate_unique_n(10).unwrap_err();
+        assert!(matches!(
+            error,
+            GenerateError::TooFewDistinct {
+                requested: 10,
+                possible: 9
+            }
+        ));
+        assert_eq!(error.code(), "too_few_distinct");
+
+        // the counts allow fewer than the charsets alone, found out by drawing
+        let counted: PasswordSpec = "2//1|a//1|b".parse().unwrap();
+        assert_eq!(counted.generate_unique_n(2).unwrap().len(), 2);
+        assert!(matches!(
+            counted.generate_unique_n(3),
+            Err(GenerateError::AttemptsExhausted { .. })
+        ));
+
+        let default = PasswordSpec::default();
+        let passwords = default.generate_unique_n(50).unwrap();
+        assert!(passwords.iter().all(|p| default.matches(p)));
+    }
+
+    #[test]
+    fn progress() {
+        assert_eq!(progress_bar(0, 1000, 10), "[          ]   0% (0/1000)");
+        assert_eq!(progress_bar(250, 1000, 10), "[##        ]  25% (250/1000)");
+        assert_eq!(progress_bar(500, 1000, 10), "[#####     ]  50% (500/1000)");
+        assert_eq!(progress_bar(999, 1000, 10), "[######### ]  99% (999/1000)");
+        assert_eq!(
+            progress_bar(1000, 1000, 10),
+            "[##########] 100% (1000/1000)"
+        );
+        assert_eq!(progress_bar(0, 0, 4), "[####] 100% (0/0)");
+    }
+}
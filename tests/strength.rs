@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pants_gen::strength::{crack_table, crack_time, format_duration, Attacker};
+
+    fn formatted(bits: f64, attacker: Attacker) -> String {
+        format_duration(crack_time(bits, attacker.guesses_per_second()))
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(format_duration(Duration::from_millis(999)), "instant");
+        assert_eq!(format_duration(Duration::from_secs(1)), "1 second");
+        assert_eq!(format_duration(Duration::from_secs(59)), "59 seconds");
+        assert_eq!(format_duration(Duration::from_secs(90)), "1 minute");
+        assert_eq!(
+            format_duration(Duration::from_secs(4 * 3600 + 10)),
+            "4 hours"
+        );
+        assert_eq!(format_duration(Duration::from_secs(3 * 86400)), "3 days");
+        assert_eq!(
+            format_duration(Duration::from_secs(101 * 31_557_600)),
+            "1 century"
+        );
+    }
+
+    #[test]
+    fn known_crack_times() {
+        assert_eq!(crack_time(10.0, 1024.0), Duration::from_secs(1));
+        assert_eq!(formatted(0.0, Attacker::OfflineSlowHash), "instant");
+        assert_eq!(formatted(20.0, Attacker::OnlineThrottled), "1 year");
+        assert_eq!(formatted(40.0, Attacker::OfflineSlowHash), "3 years");
+        assert_eq!(formatted(64.0, Attacker::OfflineFastHash), "58 years");
+        assert_eq!(formatted(68.0, Attacker::OfflineFastHash), "9 centuries");
+        assert_eq!(formatted(72.0, Attacker::OfflineFastHash), "1×10⁴ years");
+        assert_eq!(formatted(80.0, Attacker::OfflineFastHash), "3×10⁶ years");
+    }
+
+    #[test]
+    fn saturation() {
+        assert_eq!(crack_time(128.0, 1e10), Duration::MAX);
+        assert_eq!(crack_time(10_000.0, 1.0), Duration::MAX);
+        assert_eq!(crack_time(10.0, 0.0), Duration::MAX);
+        assert_eq!(format_duration(Duration::MAX), "more than 5×10¹¹ years");
+    }
+
+    #[test]
+    fn table() {
+        assert_eq!(
+            crack_table(40.0),
+            "online, throttled (100/hour): 1×10⁶ years\n\
+             offline, bcrypt (10⁴/s): 3 years\n\
+             offline, MD5 on GPUs (10¹⁰/s): 1 minute\n"
+        );
+    }
+}
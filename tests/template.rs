@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pants_gen::{
+        password::PasswordSpec,
+        template::{parse_assignment, substitute, TemplateError},
+    };
+
+    const TEMPLATE: &str = "${LEN}//1+|:upper://${SYM_MIN:-1}+|:symbol:";
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn substitution() {
+        let spec = PasswordSpec::from_template(TEMPLATE, &vars(&[("LEN", "20"), ("SYM_MIN", "2")]))
+            .unwrap();
+        assert_eq!(spec.to_string(), "20//1+|:upper://2+|:symbol:");
+        assert_eq!(spec.resolve_length().unwrap(), 20);
+    }
+
+    #[test]
+    fn defaults() {
+        let spec = PasswordSpec::from_template(TEMPLATE, &vars(&[("LEN", "12")])).unwrap();
+        assert_eq!(spec.to_string(), "12//1+|:upper://1+|:symbol:");
+        // an empty default is allowed, set values win over it
+        assert_eq!(substitute("a${X:-}b", &vars(&[])).unwrap(), "ab");
+        assert_eq!(substitute("a${X:-}b", &vars(&[("X", "-")])).unwrap(), "a-b");
+    }
+
+    #[test]
+    fn undefined_variables_are_named() {
+        let err = PasswordSpec::from_template("${LEN}//${MIN}+|:upper://${LEN}|x", &vars(&[]))
+            .unwrap_err();
+        match &err {
+            TemplateError::Undefined(names) => assert_eq!(names, &vec!["LEN", "MIN"]),
+            other => panic!("unexpected error {:?}", other),
+        }
+        assert_eq!(err.to_string(), "Undefined variables: LEN, MIN");
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(
+            substitute("16//1|$${A}//2|$%", &vars(&[("A", "x")])).unwrap(),
+            "16//1|${A}//2|$%"
+        );
+        let spec = PasswordSpec::from_template("8//2+|$a${N}", &vars(&[("N", "q")])).unwrap();
+        assert_eq!(spec.to_string(), "8//2+|$aq");
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(matches!(
+            substitute("16//${LEN", &vars(&[])),
+            Err(TemplateError::Unclosed(4))
+        ));
+        assert!(matches!(
+            substitute("${1X}", &vars(&[])),
+            Err(TemplateError::BadName(_))
+        ));
+        assert!(matches!(
+            PasswordSpec::from_template("${LEN}//1+|:uper:", &vars(&[("LEN", "8")])),
+            Err(TemplateError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn assignments() {
+        assert_eq!(
+            parse_assignment("LEN=20").unwrap(),
+            ("LEN".to_string(), "20".to_string())
+        );
+        assert_eq!(
+            parse_assignment("S=a=b").unwrap(),
+            ("S".to_string(), "a=b".to_string())
+        );
+        assert!(matches!(
+            parse_assignment("LEN"),
+            Err(TemplateError::BadAssignment(_))
+        ));
+        assert!(matches!(
+            parse_assignment("2=x"),
+            Err(TemplateError::BadName(_))
+        ));
+    }
+}
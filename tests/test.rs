@@ -1,6 +1,14 @@
 #[cfg(test)]
 mod tests {
-    use pants_gen::{charset::Charset, choice::Choice, password::PasswordSpec};
+    use pants_gen::{
+        charset::{self, Charset},
+        choice::{Choice, ChoiceParseError},
+        interval::{Interval, IntervalParseError},
+        options::GenerateOptions,
+        password::{GenerateError, PasswordParseError, PasswordSpec, VerifyError, DEFAULT_SPEC},
+        preset,
+    };
+    use rand::{rngs::StdRng, thread_rng, SeedableRng};
 
     #[test]
     fn default_spec_parses() {
@@ -9,6 +17,140 @@ mod tests {
         assert!(spec.is_ok());
     }
 
+    #[test]
+    fn default_spec_string() {
+        let spec = DEFAULT_SPEC.parse::<PasswordSpec>().unwrap();
+        assert_eq!(spec, PasswordSpec::default());
+        assert_eq!(PasswordSpec::default().to_string(), DEFAULT_SPEC);
+    }
+
+    #[test]
+    fn excluded_charsets() {
+        let spec: PasswordSpec = "16//1+|:upper://0|:symbol://1+|:lower:".parse().unwrap();
+        assert_eq!(
+            spec,
+            PasswordSpec::new()
+                .length(16)
+                .upper_at_least(1)
+                .lower_at_least(1)
+                .symbol_exactly(0)
+        );
+        assert_eq!(spec.to_string(), "16//1+|:upper://1+|:lower://0|:symbol:");
+        assert_eq!(spec.excluded(), vec![&Charset::Symbol]);
+        assert_eq!(
+            spec.describe(),
+            "16 characters\n\
+             at least 1 from :upper: (26 characters, 4.7 bits each)\n\
+             at least 1 from :lower: (26 characters, 4.7 bits each)\n\
+             excluded :symbol:"
+        );
+        assert!(spec.matches("ABCDEFGHabcdefgh"));
+        assert!(!spec.matches("ABCDEFGHabcdefg!"));
+    }
+
+    #[test]
+    fn excluded_overlapping_charsets() {
+        // digits can't sneak in through base58 either
+        let spec: PasswordSpec = "24//0|:number://1+|:base58:".parse().unwrap();
+        for _ in 0..20 {
+            let password = spec.generate().unwrap();
+            assert!(
+                !password.chars().any(|c| c.is_ascii_digit()),
+                "{}",
+                password
+            );
+            assert!(spec.matches(&password));
+        }
+        assert!(spec
+            .describe()
+            .contains("at least 1 from :base58: (49 characters, 5.6 bits each)"));
+        // everything left out makes a required choice impossible
+        let spec: PasswordSpec = "4//0|abc//1+|ab".parse().unwrap();
+        assert!(matches!(
+            spec.generate_with_options(&GenerateOptions::new(), &mut thread_rng()),
+            Err(GenerateError::EmptyCharset(_))
+        ));
+    }
+
+    #[test]
+    fn capped_overlapping_charsets() {
+        // counts are of every character in the charset, `:lower:` can't add more of `abc`
+        let spec = PasswordSpec::new()
+            .length(12)
+            .custom(vec!['a', 'b', 'c'], Interval::at_most(1))
+            .lower(Interval::at_least(0));
+        let mut rng = StdRng::seed_from_u64(510);
+        for _ in 0..500 {
+            let password = spec.generate_with_rng(&mut rng).unwrap();
+            let count = password.chars().filter(|c| "abc".contains(*c)).count();
+            assert!(count <= 1, "{}", password);
+            assert!(spec.matches(&password), "{}", password);
+        }
+        // `abcd` has nothing of its own to draw so its characters are checked once drawn
+        let spec: PasswordSpec = "6//0-1|ab//0-1|cd//1+|abcd//0+|:upper:".parse().unwrap();
+        for _ in 0..200 {
+            let password = spec.generate_with_rng(&mut rng).unwrap();
+            assert!(spec.matches(&password), "{}", password);
+        }
+        // `a` always makes a second character from `ab`
+        let spec: PasswordSpec = "2//1|ab//1+|a".parse().unwrap();
+        assert!(matches!(
+            spec.try_generate_with_rng(&mut rng),
+            Err(GenerateError::AttemptsExhausted { .. })
+        ));
+    }
+
+    #[test]
+    fn describe_spec() {
+        let spec: PasswordSpec = "v2:60b//0,2|:number://1-3|:symbol://2+|:lower:"
+            .parse::<PasswordSpec>()
+            .unwrap()
+            .prefix("id-");
+        assert_eq!(
+            spec.describe(),
+            "14 characters for at least 60 bits\n\
+             at least 2 from :lower: (26 characters, 4.7 bits each)\n\
+             exactly 0 or exactly 2 from :number: (10 characters, 3.3 bits each)\n\
+             1 to 3 from :symbol: (16 characters, 4.0 bits each)\n\
+             prefix `id-`"
+        );
+    }
+
+    #[test]
+    fn remove_charsets() {
+        let spec = PasswordSpec::default().remove(Charset::Symbol);
+        assert_eq!(spec.to_string(), "32//1+|:upper://1+|:lower://1+|:number:");
+        assert!(spec.excluded().is_empty());
+        let password = spec.generate().unwrap();
+        assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+        // removing what isn't there changes nothing
+        assert_eq!(spec.clone().remove(Charset::Base58), spec);
+        // a custom charset is found in another order, with repeats or in another normal form
+        let custom = spec.clone().custom_exactly(vec!['x', 'y', 'z'], 2);
+        assert_eq!(
+            custom
+                .clone()
+                .remove(Charset::Custom(vec!['z', 'x', 'y', 'x'])),
+            spec
+        );
+        let accented = spec.clone().custom_exactly(vec!['\u{e9}', 'x'], 1);
+        assert_eq!(
+            accented.remove(Charset::Custom(vec!['x', 'e', '\u{301}'])),
+            spec
+        );
+
+        let empty = spec
+            .remove(Charset::Upper)
+            .remove(Charset::Lower)
+            .remove(Charset::Number);
+        assert!(empty.generate().is_none());
+        let err = empty
+            .generate_with_options(&GenerateOptions::new(), &mut thread_rng())
+            .unwrap_err();
+        assert!(matches!(err, GenerateError::NoChoices));
+        assert!(err.to_string().contains("No choices remain"));
+    }
+
     #[test]
     fn default_spec_works() {
         let spec = PasswordSpec::default();
@@ -87,4 +229,550 @@ mod tests {
         let spec = spec_string.parse::<PasswordSpec>();
         assert!(spec.is_err())
     }
+
+    #[test]
+    fn length_errors() {
+        let too_big = "99999999999999999999999";
+        for length in [too_big.to_string(), format!("{}b", too_big)] {
+            let err = format!("{}//1+|:upper:", length)
+                .parse::<PasswordSpec>()
+                .unwrap_err();
+            assert!(
+                matches!(&err, PasswordParseError::LengthOverflow { text, max } if *text == length && *max == usize::MAX),
+                "{:?}",
+                err
+            );
+            assert!(err.to_string().contains(&usize::MAX.to_string()));
+        }
+        assert!(matches!(
+            "abc//1+|:upper:".parse::<PasswordSpec>(),
+            Err(PasswordParseError::InvalidLength(_))
+        ));
+        assert!(matches!(
+            "١٦//1+|:upper:".parse::<PasswordSpec>(),
+            Err(PasswordParseError::NonAsciiLength(_))
+        ));
+    }
+
+    #[test]
+    fn interval_errors() {
+        let too_big = "99999999999999999999999";
+        for interval in [
+            too_big.to_string(),
+            format!("{}+", too_big),
+            format!("{}-", too_big),
+            format!("1-{}", too_big),
+        ] {
+            let err = interval.parse::<Interval>().unwrap_err();
+            assert!(
+                matches!(&err, IntervalParseError::Overflow { text, .. } if text == too_big),
+                "{}: {:?}",
+                interval,
+                err
+            );
+        }
+        for interval in ["abc", "1-x", "", "-"] {
+            assert!(
+                matches!(
+                    interval.parse::<Interval>(),
+                    Err(IntervalParseError::ImproperFormat(_))
+                ),
+                "{}",
+                interval
+            );
+        }
+        for interval in ["٣", "١-٣", "2-٣", "٣+"] {
+            let err = interval.parse::<Interval>().unwrap_err();
+            assert!(
+                matches!(err, IntervalParseError::NonAsciiDigit(_)),
+                "{}",
+                interval
+            );
+            assert!(err.to_string().contains("ASCII"));
+        }
+        // and through a spec
+        assert!(matches!(
+            format!("16//{}|:upper:", too_big).parse::<PasswordSpec>(),
+            Err(PasswordParseError::BadChoice(
+                ChoiceParseError::BadInterval(IntervalParseError::Overflow { .. })
+            ))
+        ));
+    }
+
+    #[test]
+    fn generate_leaves_spec_unchanged() {
+        let spec = PasswordSpec::new()
+            .length(12)
+            .upper_exactly(4)
+            .lower_at_least(2)
+            .number_at_most(3);
+        let before = spec.clone();
+        for _ in 0..10 {
+            assert!(spec.generate().is_some());
+        }
+        assert_eq!(before, spec);
+        // equality between choices only looks at the charset, so compare the counts too
+        let counts = |spec: &PasswordSpec| {
+            let mut counts = spec
+                .choices()
+                .map(|c| (c.chars.to_string(), c.interval()))
+                .collect::<Vec<_>>();
+            counts.sort_by(|a, b| a.0.cmp(&b.0));
+            counts
+        };
+        assert_eq!(counts(&before), counts(&spec));
+    }
+
+    #[test]
+    fn verified_generation_passes() {
+        let spec = PasswordSpec::default().upper_exactly(3).number_at_most(2);
+        for _ in 0..100 {
+            let password = spec.generate_verified().unwrap();
+            assert!(spec.matches(&password));
+        }
+    }
+
+    #[test]
+    fn verification_catches_mismatch() {
+        let spec = PasswordSpec::new().length(4).upper_exactly(4);
+        let broken = Some("ABCd".to_string());
+        assert!(matches!(spec.verified(broken), Err(VerifyError::Mismatch)));
+        assert!(matches!(spec.verified(None), Err(VerifyError::Infeasible)));
+        assert!(spec.verified(Some("ABCD".to_string())).is_ok());
+        // wrong length
+        assert!(!spec.matches("ABC"));
+    }
+
+    #[test]
+    fn count_by_charset_overlaps() {
+        let spec = PasswordSpec::new()
+            .length(6)
+            .lower_at_least(1)
+            .custom_at_most(vec!['a', 'b', 'c'], 2);
+        let counts = spec.count_by_charset("abcxY!");
+        // the overlapping characters count towards both charsets
+        assert!(counts.contains(&(Charset::Lower, 4)));
+        assert!(counts.contains(&(Charset::Custom(vec!['a', 'b', 'c']), 3)));
+        assert_eq!(counts.len(), 2);
+        assert_eq!(spec.count_unmatched("abcxY!"), 2);
+        assert_eq!(spec.count_unmatched(""), 0);
+    }
+
+    #[test]
+    fn empty_custom_charset_is_an_error() {
+        let spec = PasswordSpec::new()
+            .length(8)
+            .lower_at_least(1)
+            .custom_exactly(vec![], 3);
+        assert_eq!(spec.generate(), None);
+        let err = spec
+            .generate_with_options(&GenerateOptions::new(), &mut thread_rng())
+            .unwrap_err();
+        assert!(matches!(err, GenerateError::EmptyCharset(Charset::Custom(v)) if v.is_empty()));
+        assert!(Choice::new(1, 2, Charset::Custom(vec![])).is_none());
+        assert!("8//3|".parse::<PasswordSpec>().is_err());
+    }
+
+    // the checked builders refuse an empty charset when the spec is built
+    #[test]
+    fn empty_custom_charset_refused_when_built() {
+        let empty = Choice::exactly(1, Charset::Custom(vec![]));
+        assert!(matches!(
+            PasswordSpec::new().length(8).try_include(empty),
+            Err(GenerateError::EmptyCharset(Charset::Custom(v))) if v.is_empty()
+        ));
+        assert!(matches!(
+            PasswordSpec::new().try_custom(vec![], Interval::at_least(1)),
+            Err(GenerateError::EmptyCharset(_))
+        ));
+        let spec = PasswordSpec::new()
+            .length(8)
+            .try_custom(vec!['a', 'b'], Interval::at_least(1))
+            .unwrap();
+        assert_eq!(spec.generate().unwrap().len(), 8);
+    }
+
+    #[test]
+    fn wrong_length_is_an_error() {
+        let spec = PasswordSpec::new().length(4).upper_exactly(4);
+        assert!(matches!(
+            spec.checked_length("ABC".to_string()),
+            Err(GenerateError::WrongLength {
+                expected: 4,
+                generated: 3
+            })
+        ));
+        assert!(spec.checked_length("ABCD".to_string()).is_ok());
+    }
+
+    #[test]
+    fn length_in_bits() {
+        let spec: PasswordSpec = "128b//1+|:number:".parse().unwrap();
+        // log2(10) bits per digit, 128 / 3.32 = 38.5
+        assert_eq!(spec.resolve_length().unwrap(), 39);
+        assert_eq!(spec.generate().map(|p| p.len()), Some(39));
+        assert_eq!(spec.to_string(), "128b//1+|:number:");
+        assert_eq!(spec.to_string().parse::<PasswordSpec>().unwrap(), spec);
+        assert_eq!(spec.resolved().unwrap().to_string(), "39//1+|:number:");
+
+        // exact multiples don't round up
+        let spec = PasswordSpec::new()
+            .length_bits(32)
+            .custom_at_least(('a'..='p').collect(), 0);
+        assert_eq!(spec.resolve_length().unwrap(), 8);
+
+        // the weakest charset is assumed to take the characters beyond the minima
+        let spec = PasswordSpec::new()
+            .length_bits(32)
+            .custom_at_most(vec!['a', 'b'], 4)
+            .custom_at_least(('A'..='P').collect(), 1);
+        assert_eq!(spec.resolve_length().unwrap(), 4 + 7);
+    }
+
+    #[test]
+    fn unreachable_bits() {
+        let spec: PasswordSpec = "64b//1-4|:number:".parse().unwrap();
+        let err = spec.resolve_length().unwrap_err();
+        assert!(matches!(
+            err,
+            GenerateError::EntropyUnreachable { bits: 64, .. }
+        ));
+        assert!(err.to_string().contains("13.3 bits"));
+        assert_eq!(spec.generate(), None);
+    }
+
+    #[test]
+    fn base58_charset() {
+        let expected = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+        let chars: String = Charset::Base58.to_charset().iter().copied().collect();
+        assert_eq!(chars, expected);
+        assert_eq!(chars.len(), 58);
+        assert!(expected.chars().all(|c| Charset::Base58.contains(c)));
+        assert!("0OIl".chars().all(|c| !Charset::Base58.contains(c)));
+        assert_eq!(":base58:".parse::<Charset>().unwrap(), Charset::Base58);
+        assert_eq!(Charset::Base58.to_string(), ":base58:");
+
+        let spec = preset::by_name("base58-key").unwrap();
+        for _ in 0..20 {
+            let key = spec.generate().unwrap();
+            assert_eq!(key.len(), 44);
+            assert!(!key.contains(['0', 'O', 'I', 'l']));
+        }
+    }
+
+    #[test]
+    fn seeded_generation_repeats() {
+        let spec = PasswordSpec::default();
+        let seeded = |seed| spec.generate_with_rng(&mut StdRng::seed_from_u64(seed));
+        assert_eq!(seeded(42), seeded(42));
+        assert!(seeded(42).is_some());
+        assert_ne!(seeded(42), seeded(43));
+        let checked =
+            |seed| spec.generate_with_check_character_with_rng(&mut StdRng::seed_from_u64(seed));
+        assert_eq!(checked(42), checked(42));
+
+        let choice = Charset::Lower.at_least(1);
+        let sampled = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..16)
+                .map(|_| choice.sample(&mut rng).unwrap())
+                .collect::<String>()
+        };
+        assert_eq!(sampled(42), sampled(42));
+        assert_ne!(sampled(42), sampled(43));
+        assert!(sampled(7).chars().all(|c| c.is_ascii_lowercase()));
+        assert_eq!(
+            Charset::Custom(vec![])
+                .at_least(0)
+                .sample(&mut StdRng::seed_from_u64(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn try_generate_reports_why() {
+        let short = PasswordSpec::new()
+            .length(4)
+            .upper_at_least(3)
+            .lower_at_least(2);
+        assert!(matches!(
+            short.try_generate(),
+            Err(GenerateError::LengthBelowMinimum {
+                required: 5,
+                length: 4
+            })
+        ));
+        assert_eq!(short.generate(), None);
+        let long: PasswordSpec = "10//1-3|:upper://2|:number:".parse().unwrap();
+        let err = long.try_generate().unwrap_err();
+        assert!(matches!(
+            err,
+            GenerateError::LengthAboveMaximum {
+                allowed: 5,
+                length: 10
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "The choices allow at most 5 characters but the length is 10"
+        );
+        let empty = PasswordSpec::new()
+            .length(4)
+            .include(Charset::Custom(vec![]).at_least(1));
+        assert!(matches!(
+            empty.try_generate(),
+            Err(GenerateError::EmptyCharset(Charset::Custom(_)))
+        ));
+        // in range but between what a union of intervals can make
+        let gap: PasswordSpec = "5//0,4|:upper://0,4|:lower:".parse().unwrap();
+        assert!(matches!(gap.try_generate(), Err(GenerateError::Infeasible)));
+        let fine = PasswordSpec::default().try_generate().unwrap();
+        assert!(PasswordSpec::default().matches(&fine));
+    }
+
+    #[test]
+    fn charset_constants() {
+        for (charset, chars) in [
+            (Charset::Upper, charset::UPPER),
+            (Charset::Lower, charset::LOWER),
+            (Charset::Number, charset::NUMBER),
+            (Charset::NumberNoZero, charset::NUMBER_NO_ZERO),
+            (Charset::Symbol, charset::SYMBOL),
+            (Charset::Base58, charset::BASE58),
+            (Charset::UpperLatin1, charset::UPPER_LATIN1),
+            (Charset::LowerLatin1, charset::LOWER_LATIN1),
+        ] {
+            assert_eq!(charset.to_charset(), chars);
+            assert_eq!(charset.len(), chars.len());
+            for c in '\0'..='\u{17f}' {
+                assert_eq!(
+                    charset.contains(c),
+                    chars.contains(&c),
+                    "{:?} {:?}",
+                    charset,
+                    c
+                );
+            }
+        }
+        assert_eq!(charset::SYMBOL.len(), 16);
+        assert_eq!(Charset::Custom(vec!['a', 'b', 'a']).len(), 3);
+        assert!(Charset::Custom(vec![]).is_empty());
+    }
+
+    #[test]
+    fn number_charsets() {
+        let digits: String = Charset::Number.to_charset().iter().copied().collect();
+        assert_eq!(digits, "0123456789");
+        assert!(('0'..='9').all(|c| Charset::Number.contains(c)));
+        let no_zero: String = Charset::NumberNoZero.to_charset().iter().copied().collect();
+        assert_eq!(no_zero, "123456789");
+        assert!(!Charset::NumberNoZero.contains('0'));
+        assert!(('1'..='9').all(|c| Charset::NumberNoZero.contains(c)));
+        assert_eq!(
+            ":number-no-zero:".parse::<Charset>().unwrap(),
+            Charset::NumberNoZero
+        );
+        assert_eq!(Charset::NumberNoZero.to_string(), ":number-no-zero:");
+
+        let spec: PasswordSpec = "12//4+|:number://1+|:number-no-zero://0+|:lower:"
+            .parse()
+            .unwrap();
+        assert_eq!(spec.to_string().parse::<PasswordSpec>().unwrap(), spec);
+        let spec: PasswordSpec = "40//40|:number:".parse().unwrap();
+        let zeros = (0..20)
+            .filter_map(|_| spec.generate())
+            .filter(|password| password.contains('0'))
+            .count();
+        assert!(zeros > 0);
+        let spec: PasswordSpec = "40//40|:number-no-zero:".parse().unwrap();
+        for _ in 0..20 {
+            assert!(!spec.generate().unwrap().contains('0'));
+        }
+    }
+
+    #[test]
+    fn charset_aliases() {
+        let cases = [
+            (":Upper:", Charset::Upper),
+            (":UPPER:", Charset::Upper),
+            (":lower:", Charset::Lower),
+            (":LoWeR:", Charset::Lower),
+            (":num:", Charset::Number),
+            (":digit:", Charset::Number),
+            (":Digits:", Charset::Number),
+            (":special:", Charset::Symbol),
+            (":punct:", Charset::Symbol),
+            (":BASE58:", Charset::Base58),
+        ];
+        for (name, charset) in cases {
+            let parsed = name.parse::<Charset>().unwrap();
+            assert_eq!(parsed, charset);
+            // display always uses the canonical lowercase name
+            assert_eq!(parsed.to_string().parse::<Charset>().unwrap(), charset);
+            assert_eq!(parsed.to_string(), parsed.to_string().to_lowercase());
+        }
+        let spec = "8//1+|:Upper://1+|:digits:"
+            .parse::<PasswordSpec>()
+            .unwrap();
+        assert!(spec.to_string().contains(":upper:"));
+        assert!(spec.to_string().contains(":number:"));
+    }
+
+    #[test]
+    fn unknown_charset_suggestion() {
+        let err = ":uper:".parse::<Charset>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Specified a :pattern:, but `:uper:` isn't recognized, did you mean `:upper:`?"
+        );
+        let err = ":digts:".parse::<Charset>().unwrap_err();
+        assert!(err.to_string().ends_with("did you mean `:number:`?"));
+        let err = ":emoji:".parse::<Charset>().unwrap_err();
+        assert!(!err.to_string().contains("did you mean"));
+        assert!(":".parse::<Charset>().is_err());
+    }
+
+    #[test]
+    fn interval_union() {
+        let spec = PasswordSpec::new()
+            .length(8)
+            .include(
+                Choice::any_of(
+                    vec![Interval::exactly(0), Interval::exactly(2)],
+                    Charset::Number,
+                )
+                .unwrap(),
+            )
+            .include(Charset::Lower.at_least(0));
+        let mut seen = [false; 3];
+        for _ in 0..200 {
+            let password = spec.generate().unwrap();
+            let digits = password.chars().filter(|c| c.is_ascii_digit()).count();
+            assert!(digits == 0 || digits == 2, "{}", password);
+            seen[digits] = true;
+            assert!(spec.matches(&password));
+        }
+        assert!(seen[0] && seen[2]);
+        assert!(!spec.matches("abcdefg1"));
+        assert!(spec.matches("abcdef12"));
+    }
+
+    #[test]
+    fn interval_union_parsing() {
+        let spec = "8//0,2|:number://1+|:lower:"
+            .parse::<PasswordSpec>()
+            .unwrap();
+        let round_trip = spec.to_string().parse::<PasswordSpec>().unwrap();
+        let number = round_trip
+            .choices()
+            .find(|choice| choice.chars == Charset::Number)
+            .unwrap();
+        assert_eq!(
+            number.intervals(),
+            vec![Interval::exactly(0), Interval::exactly(2)]
+        );
+        assert!(spec.to_string().contains("0,2|:number:"));
+
+        let choice = "3-5,0,4-8,10+|:upper:".parse::<Choice>().unwrap();
+        assert_eq!(choice.to_string(), "0,3-8,10+|:upper:");
+        let choice = "0-2,3|:upper:".parse::<Choice>().unwrap();
+        assert_eq!(choice.to_string(), "3-|:upper:");
+        assert!("0,|:upper:".parse::<Choice>().is_err());
+
+        let v2 = "v2:8//0,2|\\x".parse::<PasswordSpec>().unwrap();
+        assert!(v2.to_string().contains("0,2|"));
+    }
+
+    #[test]
+    fn infeasible_interval_union() {
+        // one character can't hold 0 or 2 digits and a lowercase letter at the same time
+        let spec = "2//0,3|:number://1|:lower:"
+            .parse::<PasswordSpec>()
+            .unwrap();
+        assert!(spec.generate().is_none());
+        let spec = "3//0,3|:number://1|:lower:"
+            .parse::<PasswordSpec>()
+            .unwrap();
+        assert!(spec.generate().is_none());
+        let spec = "4//0,3|:number://1|:lower:"
+            .parse::<PasswordSpec>()
+            .unwrap();
+        assert_eq!(spec.generate().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn many_interval_unions() {
+        // every combination of intervals is 6^40, the reachable totals are only the even numbers
+        let choices: Vec<String> = ('a'..='z')
+            .chain('A'..='N')
+            .map(|c| format!("0,2,4,6,8,10|{}", c))
+            .collect();
+        let spec = format!("1000//{}", choices.join("//"))
+            .parse::<PasswordSpec>()
+            .unwrap();
+        assert!(matches!(
+            spec.try_generate(),
+            Err(GenerateError::LengthAboveMaximum { .. })
+        ));
+        let spec = format!("61//{}", choices.join("//"))
+            .parse::<PasswordSpec>()
+            .unwrap();
+        assert!(matches!(
+            spec.try_generate(),
+            Err(GenerateError::Infeasible)
+        ));
+        let spec = format!("60//{}", choices.join("//"))
+            .parse::<PasswordSpec>()
+            .unwrap();
+        let password = spec.generate().unwrap();
+        assert_eq!(password.len(), 60);
+        assert!(spec.matches(&password));
+    }
+
+    #[test]
+    fn affixes() {
+        let core = PasswordSpec::new()
+            .length(10)
+            .include(Charset::Lower.at_least(1));
+        let spec = core.clone().prefix("AC-").suffix("!x");
+        for _ in 0..50 {
+            let password = spec.generate().unwrap();
+            assert_eq!(password.chars().count(), 15);
+            let middle = &password[3..13];
+            assert_eq!(password, format!("AC-{}!x", middle));
+            assert!(core.matches(middle));
+            assert!(spec.matches(&password));
+            assert!(!spec.matches(middle));
+        }
+        assert_eq!(spec.resolve_length().unwrap(), 10);
+    }
+
+    #[test]
+    fn length_includes_affixes() {
+        let spec = PasswordSpec::new()
+            .length(10)
+            .include(Charset::Lower.at_least(1))
+            .prefix("AC-")
+            .suffix("!x")
+            .length_includes_affixes(true);
+        let password = spec.generate().unwrap();
+        assert_eq!(password.chars().count(), 10);
+        assert_eq!(spec.resolve_length().unwrap(), 5);
+        assert!(spec.matches(&password));
+
+        // affixes give no entropy so bits only size the core
+        let bits = spec.clone().length_bits(48);
+        assert_eq!(bits.resolve_length().unwrap(), 11);
+        assert_eq!(bits.generate().unwrap().chars().count(), 16);
+
+        let too_long = spec.length(4);
+        assert!(matches!(
+            too_long.resolve_length(),
+            Err(GenerateError::AffixesTooLong {
+                affixes: 5,
+                length: 4
+            })
+        ));
+        assert!(too_long.generate().is_none());
+    }
 }
@@ -0,0 +1,114 @@
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::{
+        io::Write,
+        sync::{Arc, Mutex},
+    };
+
+    use pants_gen::{
+        options::{Constraint, GenerateOptions},
+        password::PasswordSpec,
+        position::PositionRule,
+        Charset,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // everything logged while running `f` at every level
+    fn capture(f: impl FnOnce()) -> String {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, f);
+        let bytes = captured.0.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn parsing_logs_the_spec() {
+        let output = capture(|| {
+            let _: PasswordSpec = "16//1+|:upper://1+|:lower:".parse().unwrap();
+        });
+        assert!(output.contains("parsed spec"));
+        assert!(output.contains("16//1+|:upper://1+|:lower:"));
+        let output = capture(|| {
+            assert!("16//1+|:nope:".parse::<PasswordSpec>().is_err());
+        });
+        assert!(output.contains("spec didn't parse"));
+    }
+
+    #[test]
+    fn infeasible_is_logged() {
+        let spec = PasswordSpec::new()
+            .length(4)
+            .include(Charset::Upper.at_least(5));
+        let output = capture(|| assert!(spec.generate().is_none()));
+        assert!(output.contains("spec is infeasible"));
+    }
+
+    #[test]
+    fn rejections_name_the_constraint() {
+        let seen = Arc::new(Mutex::new(vec![]));
+        let record = seen.clone();
+        let options = GenerateOptions::new().constraint(Constraint::new(
+            "third time lucky",
+            move |password: &str| {
+                let mut seen = record.lock().unwrap();
+                seen.push(password.to_string());
+                seen.len() >= 3
+            },
+        ));
+        let spec = PasswordSpec::new().length(16).upper_at_least(1);
+        let output = capture(|| {
+            let mut rng = StdRng::seed_from_u64(7);
+            spec.generate_with_options(&options, &mut rng).unwrap();
+        });
+        assert_eq!(output.matches("candidate rejected").count(), 2);
+        assert!(output.contains("third time lucky"));
+        for password in seen.lock().unwrap().iter() {
+            assert!(!output.contains(password.as_str()));
+        }
+    }
+
+    #[test]
+    fn never_logs_passwords() {
+        let spec: PasswordSpec = "12//2+|:upper://2+|:lower://1+|:number:".parse().unwrap();
+        let spec = spec
+            .position_rule(0, PositionRule::MustBe(Charset::Upper))
+            .no_adjacent_from(Charset::Number);
+        let mut passwords = vec![];
+        let output = capture(|| {
+            let mut rng = StdRng::seed_from_u64(11);
+            for _ in 0..10 {
+                passwords.push(spec.generate_with_rng(&mut rng).unwrap());
+            }
+            for password in spec.stream(10, StdRng::seed_from_u64(12)) {
+                passwords.push(password.unwrap());
+            }
+            let mut rng = StdRng::seed_from_u64(13);
+            passwords.extend(spec.generate_batch_with_try_rng(10, &mut rng).unwrap());
+        });
+        assert!(!output.is_empty());
+        assert!(output.contains("batch progress"));
+        for password in &passwords {
+            assert!(!output.contains(password.as_str()));
+        }
+    }
+}
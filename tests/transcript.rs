@@ -0,0 +1,164 @@
+#[cfg(all(test, feature = "transcript"))]
+mod tests {
+    use pants_gen::{
+        metadata::RngSource,
+        password::PasswordSpec,
+        transcript::{verify, Transcript, TranscriptError},
+    };
+
+    fn spec() -> PasswordSpec {
+        "16//2+|:upper://2+|:lower://2+|:number:".parse().unwrap()
+    }
+
+    fn recorded(source: RngSource) -> (String, Transcript) {
+        let (generated, metadata) = spec().generate_with_metadata(source).unwrap();
+        let transcript = Transcript::record(&generated, &spec(), &metadata);
+        (generated.password(), transcript)
+    }
+
+    #[test]
+    fn round_trip() {
+        let (password, transcript) = recorded(RngSource::Os);
+        assert!(verify(&transcript, &password));
+        assert!(transcript.passed());
+        assert_eq!(transcript.rng_source, "os");
+        assert_eq!(transcript.spec_canonical, Some(spec().to_string()));
+        assert_eq!(transcript.generator_version, env!("CARGO_PKG_VERSION"));
+        assert!(transcript.recorded_at >= transcript.generated_at);
+        let names: Vec<&str> = transcript
+            .checks
+            .iter()
+            .map(|check| check.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "prefix",
+                "suffix",
+                "length",
+                "class::upper:",
+                "class::lower:",
+                "class::number:",
+                "allowed",
+                "adjacent",
+                "position"
+            ]
+        );
+        let parsed: Transcript = transcript.to_string().parse().unwrap();
+        assert_eq!(parsed, transcript);
+        assert!(verify(&parsed, &password));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let (password, transcript) = recorded(RngSource::Thread);
+        let path =
+            std::env::temp_dir().join(format!("pants-gen-transcript-{}.txt", std::process::id()));
+        transcript.save(&path).unwrap();
+        let loaded = Transcript::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(verify(&loaded, &password));
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let (password, transcript) = recorded(RngSource::Seeded(490));
+        let mut other = password.clone().into_bytes();
+        other[0] = if other[0] == b'A' { b'B' } else { b'A' };
+        assert!(!verify(&transcript, &String::from_utf8(other).unwrap()));
+        assert!(!verify(&transcript, ""));
+
+        let mut changed = transcript.clone();
+        changed.rng_source = "os".to_string();
+        assert!(!verify(&changed, &password));
+        let mut changed = transcript.clone();
+        changed.generated_at += 1;
+        assert!(!verify(&changed, &password));
+        let mut changed = transcript.clone();
+        changed.checks.pop();
+        assert!(!verify(&changed, &password));
+
+        let serialized = transcript.to_string();
+        let edited = serialized.replace(
+            &format!("spec: {}", spec()),
+            "spec: 16//2+|:upper://2+|:lower://2+|:symbol:",
+        );
+        assert_ne!(edited, serialized);
+        assert!(!verify(&edited.parse().unwrap(), &password));
+    }
+
+    #[test]
+    fn failed_checks_are_recorded() {
+        let (generated, metadata) = spec().generate_with_metadata(RngSource::Os).unwrap();
+        let other: PasswordSpec = "16//2+|:upper://2+|:lower://2+|:symbol:".parse().unwrap();
+        let transcript = Transcript::record(&generated, &other, &metadata);
+        assert!(!transcript.passed());
+        let failed: Vec<&str> = transcript
+            .checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.name.as_str())
+            .collect();
+        assert!(failed.contains(&"class::symbol:"));
+        assert!(failed.contains(&"allowed"));
+        assert!(verify(&transcript, &generated.password()));
+    }
+
+    #[test]
+    fn unrecordable_spec() {
+        // the prefix isn't part of the spec string, so no spec string is recorded
+        let prefixed = spec().prefix("id-");
+        let (generated, metadata) = prefixed.generate_with_metadata(RngSource::Os).unwrap();
+        let transcript = Transcript::record(&generated, &prefixed, &metadata);
+        assert_eq!(transcript.spec_canonical, None);
+        let serialized = transcript.to_string();
+        assert!(!serialized.contains("spec:"));
+        let parsed: Transcript = serialized.parse().unwrap();
+        assert_eq!(parsed, transcript);
+        assert!(verify(&parsed, &generated.password()));
+    }
+
+    #[test]
+    fn never_holds_the_password() {
+        for seed in 0..50 {
+            let (password, transcript) = recorded(RngSource::Seeded(seed));
+            let serialized = transcript.to_string();
+            assert!(!serialized.contains(&password));
+            assert!(!format!("{:?}", transcript).contains(&password));
+        }
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(matches!(
+            "not a transcript".parse::<Transcript>(),
+            Err(TranscriptError::BadHeader)
+        ));
+        let (_, transcript) = recorded(RngSource::Os);
+        let serialized = transcript.to_string();
+        let truncated: String = serialized
+            .lines()
+            .filter(|line| !line.starts_with("commitment"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        assert!(matches!(
+            truncated.parse::<Transcript>(),
+            Err(TranscriptError::Missing("commitment"))
+        ));
+        // a sign is not a hex digit
+        let salt = serialized
+            .lines()
+            .find_map(|line| line.strip_prefix("salt: "))
+            .unwrap();
+        let signed = serialized.replace(salt, &format!("+{}", &salt[1..]));
+        assert!(matches!(
+            signed.parse::<Transcript>(),
+            Err(TranscriptError::BadLine(_))
+        ));
+        let extra = format!("{}color: blue\n", serialized);
+        assert!(matches!(
+            extra.parse::<Transcript>(),
+            Err(TranscriptError::BadLine(_))
+        ));
+    }
+}
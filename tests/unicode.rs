@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{lint::LintWarning, unicode::Normalization, Charset, Interval, PasswordSpec};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const PRECOMPOSED: &str = "\u{e9}";
+    const DECOMPOSED: &str = "e\u{301}";
+
+    #[test]
+    fn either_form_validates() {
+        for charset in [PRECOMPOSED, DECOMPOSED] {
+            let spec: PasswordSpec = format!("3//1+|{}a", charset).parse().unwrap();
+            for form in [PRECOMPOSED, DECOMPOSED] {
+                let password = format!("a{}a", form);
+                assert!(spec.matches(&password), "{:?} in {:?}", form, charset);
+                assert!(spec.violations(&password).is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn builders_normalize() {
+        let spec = PasswordSpec::new()
+            .length(2)
+            .custom(DECOMPOSED.chars().collect(), Interval::exactly(2))
+            .prefix(DECOMPOSED);
+        assert!(spec.matches(&format!("{}{}{}", DECOMPOSED, PRECOMPOSED, DECOMPOSED)));
+        assert!(spec.matches(&format!("{}{}{}", PRECOMPOSED, DECOMPOSED, PRECOMPOSED)));
+    }
+
+    #[test]
+    fn generated_in_nfc() {
+        let spec: PasswordSpec = format!("12//1+|{}a\u{301}b\u{308}", DECOMPOSED)
+            .parse()
+            .unwrap();
+        let mut rng = StdRng::seed_from_u64(4);
+        for _ in 0..50 {
+            let password = spec.generate_with_rng(&mut rng).unwrap();
+            assert_eq!(Normalization::Nfc.apply(&password), password);
+            assert!(spec.matches(&password));
+        }
+    }
+
+    #[test]
+    fn mixed_forms_repeat() {
+        let spec: PasswordSpec = format!("8//1+|{}{}x", PRECOMPOSED, DECOMPOSED)
+            .parse()
+            .unwrap();
+        let warnings = spec.lint();
+        assert!(warnings.iter().any(|warning| matches!(
+            warning,
+            LintWarning::RepeatedChars { repeated, .. } if repeated == &vec!['\u{e9}']
+        )));
+        let distinct: PasswordSpec = format!("8//1+|{}x", DECOMPOSED).parse().unwrap();
+        assert!(!distinct
+            .lint()
+            .iter()
+            .any(|warning| matches!(warning, LintWarning::RepeatedChars { .. })));
+    }
+
+    #[test]
+    fn opt_out_keeps_raw() {
+        let spec = PasswordSpec::parse_with(&format!("3//1+|{}a", DECOMPOSED), Normalization::None)
+            .unwrap();
+        assert_eq!(
+            spec.choices().next().unwrap().chars,
+            Charset::Custom(vec!['e', '\u{301}', 'a'])
+        );
+        assert!(!spec.matches(&format!("a{}a", PRECOMPOSED)));
+        assert!(spec.matches("aea"));
+        let raw = PasswordSpec::new()
+            .normalization(Normalization::None)
+            .length(2)
+            .custom(DECOMPOSED.chars().collect(), Interval::exactly(2));
+        assert!(raw.matches(DECOMPOSED));
+        assert!(!raw.matches(PRECOMPOSED));
+    }
+}
@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        password::{GenerateError, PasswordSpec},
+        units::{LengthUnit, MAX_CLUSTER_CHARS, MAX_MEASURED_LENGTH},
+        violation::Violation,
+        Charset,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn spec(s: &str) -> PasswordSpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        for (s, unit) in [
+            ("32//1+|:lower:", LengthUnit::Chars),
+            ("64B//1+|:lower-latin1:", LengthUnit::Bytes),
+            ("24g//2+|:upper://0+|:lower:", LengthUnit::Graphemes),
+        ] {
+            let parsed = spec(s);
+            assert_eq!(parsed.unit(), unit);
+            assert_eq!(parsed.to_string(), s);
+            assert_eq!(spec(&parsed.to_string()), parsed);
+        }
+        // characters are the default and written without their unit
+        assert_eq!(spec("32c//1+|:lower:"), spec("32//1+|:lower:"));
+        assert_eq!(spec("32c//1+|:lower:").to_string(), "32//1+|:lower:");
+        assert_eq!(spec("v2:16B//1+|a\\/").to_string(), "16B//1+|a/");
+        assert_eq!(spec("80b//1+|:lower:").unit(), LengthUnit::Chars);
+        assert!("16x//1+|:lower:".parse::<PasswordSpec>().is_err());
+        assert!("B//1+|:lower:".parse::<PasswordSpec>().is_err());
+    }
+
+    #[test]
+    fn bytes_of_multibyte_charset() {
+        let spec = spec("40B//1+|:lower-latin1:");
+        let mut rng = StdRng::seed_from_u64(504);
+        let mut char_counts = vec![];
+        for _ in 0..50 {
+            let password = spec.try_generate_with_rng(&mut rng).unwrap();
+            assert_eq!(password.len(), 40);
+            assert!(spec.matches(&password));
+            char_counts.push(password.chars().count());
+        }
+        // the accented letters take two bytes so the number of characters varies
+        assert!(char_counts.iter().all(|count| (20..40).contains(count)));
+        assert!(char_counts.iter().any(|count| *count != char_counts[0]));
+
+        let affixed = PasswordSpec::new()
+            .length(12)
+            .length_unit(LengthUnit::Bytes)
+            .include(Charset::LowerLatin1.at_least(1))
+            .prefix("é-")
+            .length_includes_affixes(true);
+        let password = affixed.try_generate_with_rng(&mut rng).unwrap();
+        assert_eq!(password.len(), 12);
+        assert!(password.starts_with("é-"));
+        assert!(affixed.matches(&password));
+    }
+
+    #[test]
+    fn bytes_out_of_reach() {
+        // characters of two bytes each can't make an odd number of bytes
+        let spec = PasswordSpec::new()
+            .length(9)
+            .length_unit(LengthUnit::Bytes)
+            .include(Charset::Custom(vec!['é', 'ü']).at_least(1));
+        assert!(matches!(
+            spec.try_generate(),
+            Err(GenerateError::Infeasible)
+        ));
+        let spec = PasswordSpec::new().length(8).length_unit(LengthUnit::Bytes);
+        assert!(matches!(spec.try_generate(), Err(GenerateError::NoChoices)));
+    }
+
+    #[test]
+    fn graphemes_of_joining_characters() {
+        // pairs of regional indicators are one flag each
+        let spec = spec("3g//1+|🇫🇷🇩🇪");
+        let mut rng = StdRng::seed_from_u64(24);
+        for _ in 0..20 {
+            let password = spec.try_generate_with_rng(&mut rng).unwrap();
+            assert_eq!(LengthUnit::Graphemes.measure(&password), 3);
+            assert!(password.chars().count() > 3);
+            assert!(spec.matches(&password));
+        }
+        // letters never join, a grapheme is a character
+        let letters = PasswordSpec::new()
+            .length(16)
+            .length_unit(LengthUnit::Graphemes)
+            .include(Charset::Lower.at_least(1));
+        let password = letters.try_generate_with_rng(&mut rng).unwrap();
+        assert_eq!(password.chars().count(), 16);
+    }
+
+    #[test]
+    fn graphemes_of_stacked_marks() {
+        // a mark stacks onto a letter, graphemes go up to `MAX_CLUSTER_CHARS` characters
+        let mut rng = StdRng::seed_from_u64(25);
+        let stacked = spec("1g//1|x//7|\u{20dd}");
+        let password = stacked.try_generate_with_rng(&mut rng).unwrap();
+        assert_eq!(LengthUnit::Graphemes.measure(&password), 1);
+        assert_eq!(password.chars().count(), MAX_CLUSTER_CHARS);
+        assert!(spec("1g//1|x//8|\u{20dd}").try_generate().is_err());
+    }
+
+    #[test]
+    fn validation_counts_in_the_unit() {
+        let bytes = spec("4B//0+|:lower-latin1:");
+        assert!(bytes.matches("éé"));
+        assert!(bytes.matches("abcd"));
+        assert!(!bytes.matches("abcé"));
+        assert!(bytes.matches("abé"));
+        assert_eq!(
+            bytes.violations("abc"),
+            [Violation::TooShort {
+                expected: 4,
+                actual: 3,
+                unit: LengthUnit::Bytes,
+            }]
+        );
+        assert_eq!(
+            bytes.violations("abcé"),
+            [Violation::TooLong {
+                expected: 4,
+                actual: 5,
+                unit: LengthUnit::Bytes,
+            }]
+        );
+        assert_eq!(
+            bytes.violations("abcé")[0].to_string(),
+            "Has 5 bytes, allows only 4"
+        );
+
+        let graphemes = spec("2g//0+|a🇫🇷");
+        assert!(graphemes.matches("a🇫🇷"));
+        assert!(graphemes.matches("aa"));
+        assert_eq!(
+            graphemes.violations("aaa"),
+            [Violation::TooLong {
+                expected: 2,
+                actual: 3,
+                unit: LengthUnit::Graphemes,
+            }]
+        );
+    }
+
+    #[test]
+    fn byte_cap_with_a_length_in_characters() {
+        let spec = PasswordSpec::new()
+            .length(10)
+            .include(Charset::LowerLatin1.at_least(1))
+            .max_bytes(12);
+        let mut rng = StdRng::seed_from_u64(12);
+        for _ in 0..20 {
+            let password = spec.try_generate_with_rng(&mut rng).unwrap();
+            assert_eq!(password.chars().count(), 10);
+            assert!(password.len() <= 12);
+            assert!(spec.matches(&password));
+        }
+        let accented = "éééééééééé";
+        assert!(!spec.matches(accented));
+        assert_eq!(
+            spec.violations(accented),
+            [Violation::TooManyBytes {
+                max: 12,
+                actual: 20
+            }]
+        );
+        assert_eq!(spec.to_string(), "10//1+|:lower-latin1:");
+    }
+
+    #[test]
+    fn measured_length_limited() {
+        let mut rng = StdRng::seed_from_u64(13);
+        let longest = spec("0B//0+|:lower:").length(MAX_MEASURED_LENGTH);
+        let password = longest.try_generate_with_rng(&mut rng).unwrap();
+        assert_eq!(password.len(), MAX_MEASURED_LENGTH);
+        for (s, unit) in [
+            ("0B//0+|:lower-latin1:", LengthUnit::Bytes),
+            ("0g//0+|:lower:", LengthUnit::Graphemes),
+        ] {
+            for length in [MAX_MEASURED_LENGTH + 1, usize::MAX] {
+                let err = spec(s)
+                    .length(length)
+                    .try_generate_with_rng(&mut rng)
+                    .unwrap_err();
+                assert!(matches!(
+                    err,
+                    GenerateError::MeasuredTooLong { unit: u, length: l, max: MAX_MEASURED_LENGTH }
+                        if u == unit && l == length
+                ));
+            }
+        }
+        // a length in characters with a byte cap isn't measured the same way
+        let capped = PasswordSpec::new()
+            .length(MAX_MEASURED_LENGTH + 1)
+            .include(Charset::Lower.at_least(0))
+            .max_bytes(MAX_MEASURED_LENGTH + 1);
+        assert!(capped.try_generate_with_rng(&mut rng).is_ok());
+    }
+}
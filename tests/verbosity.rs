@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        output::{Columns, OutputContext},
+        verbosity::{Level, Logger, Verbosity},
+        PasswordSpec,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const LEVELS: [Verbosity; 4] = [
+        Verbosity::Quiet,
+        Verbosity::Normal,
+        Verbosity::Verbose,
+        Verbosity::Debug,
+    ];
+
+    // a spec with a lint warning, a single character repeated
+    fn spec() -> PasswordSpec {
+        "12//1+|:lower://1+|aab".parse().unwrap()
+    }
+
+    fn logged(verbosity: Verbosity, log: impl FnOnce(&mut Logger<Vec<u8>>)) -> String {
+        let mut logger = Logger::new(verbosity, vec![]);
+        log(&mut logger);
+        String::from_utf8(logger.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn flags() {
+        assert_eq!(Verbosity::from_flags(false, 0), Verbosity::Normal);
+        assert_eq!(Verbosity::from_flags(false, 1), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_flags(false, 2), Verbosity::Debug);
+        assert_eq!(Verbosity::from_flags(false, 5), Verbosity::Debug);
+        assert_eq!(Verbosity::from_flags(true, 2), Verbosity::Quiet);
+        assert_eq!("verbose".parse::<Verbosity>().unwrap(), Verbosity::Verbose);
+        assert!("loud".parse::<Verbosity>().is_err());
+    }
+
+    #[test]
+    fn levels() {
+        let all = |logger: &mut Logger<Vec<u8>>| {
+            logger.error("broke");
+            logger.warn("careful");
+            logger.info("resolved");
+            logger.debug("details");
+        };
+        assert_eq!(logged(Verbosity::Quiet, all), "error: broke\n");
+        assert_eq!(
+            logged(Verbosity::Normal, all),
+            "error: broke\nwarning: careful\n"
+        );
+        assert_eq!(
+            logged(Verbosity::Verbose, all),
+            "error: broke\nwarning: careful\nresolved\n"
+        );
+        assert_eq!(
+            logged(Verbosity::Debug, all),
+            "error: broke\nwarning: careful\nresolved\ndebug: details\n"
+        );
+        assert!(Verbosity::Normal.shows(Level::Warn));
+        assert!(!Verbosity::Normal.shows(Level::Info));
+    }
+
+    #[test]
+    fn spec_notes() {
+        let spec = spec();
+        let notes: Vec<String> = LEVELS
+            .iter()
+            .map(|verbosity| logged(*verbosity, |logger| logger.spec(&spec)))
+            .collect();
+        assert_eq!(notes[0], "");
+        assert!(notes[1].starts_with("warning: "));
+        assert!(!notes[1].contains("entropy"));
+        assert!(notes[2].contains("spec: 12//1+|:lower://1+|aab\n"));
+        assert!(notes[2].contains("entropy: "));
+        assert!(!notes[2].contains("debug: "));
+        assert!(notes[3].contains("debug: PasswordSpec { length: Chars(12)"));
+        // each level adds to the one before
+        for pair in notes.windows(2) {
+            assert!(pair[1].starts_with(&pair[0]));
+        }
+        let rejected = |logger: &mut Logger<Vec<u8>>| logger.rejected(3, "no repeats");
+        assert_eq!(logged(Verbosity::Verbose, rejected), "");
+        assert_eq!(
+            logged(Verbosity::Debug, rejected),
+            "debug: attempt 3 rejected by `no repeats`\n"
+        );
+    }
+
+    #[test]
+    fn stdout_is_the_same_at_every_level() {
+        let spec = spec();
+        let outputs: Vec<(String, String)> = LEVELS
+            .iter()
+            .map(|verbosity| {
+                let context = OutputContext::new(false, true).verbosity(*verbosity);
+                let mut logger = context.logger(vec![]);
+                logger.spec(&spec);
+                let passwords = spec
+                    .generate_n_with_rng(20, &mut StdRng::seed_from_u64(508))
+                    .unwrap();
+                let stdout = context.layout(&passwords, Columns::Auto);
+                let stderr = String::from_utf8(logger.into_inner()).unwrap();
+                // nothing generated is logged
+                assert!(passwords
+                    .iter()
+                    .all(|password| !stderr.contains(password.as_str())));
+                (stdout, stderr)
+            })
+            .collect();
+        assert!(outputs.iter().all(|(stdout, _)| stdout == &outputs[0].0));
+        assert!(outputs.windows(2).all(|pair| pair[0].1 != pair[1].1));
+    }
+
+    #[test]
+    fn quiet_hides_progress() {
+        let context = OutputContext::new(true, true);
+        assert!(context.progress(5000));
+        assert!(!context.verbosity(Verbosity::Quiet).progress(5000));
+        assert!(context.verbosity(Verbosity::Debug).progress(5000));
+    }
+}
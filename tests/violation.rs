@@ -0,0 +1,159 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        charset::Charset, password::PasswordSpec, units::LengthUnit, violation::Violation,
+    };
+
+    fn spec() -> PasswordSpec {
+        "8//1+|:upper://2-|:number://0,2|:symbol://0+|:lower:"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn every_code() {
+        let cases = [
+            (
+                Violation::MissingPrefix("id-".to_string()),
+                "missing_prefix",
+            ),
+            (Violation::MissingSuffix("!".to_string()), "missing_suffix"),
+            (
+                Violation::TooShort {
+                    expected: 8,
+                    actual: 4,
+                    unit: LengthUnit::Chars,
+                },
+                "too_short",
+            ),
+            (
+                Violation::TooLong {
+                    expected: 8,
+                    actual: 9,
+                    unit: LengthUnit::Chars,
+                },
+                "too_long",
+            ),
+            (
+                Violation::TooManyBytes {
+                    max: 16,
+                    actual: 18,
+                },
+                "too_many_bytes",
+            ),
+            (
+                Violation::MissingClass {
+                    charset: Charset::Upper,
+                    label: None,
+                    min: 1,
+                    count: 0,
+                },
+                "missing_class:upper",
+            ),
+            (
+                Violation::ExcessClass {
+                    charset: Charset::Number,
+                    label: None,
+                    max: 2,
+                    count: 3,
+                },
+                "excess_class:number",
+            ),
+            (
+                Violation::CountNotAllowed {
+                    charset: Charset::Symbol,
+                    label: None,
+                    count: 1,
+                },
+                "count_not_allowed:symbol",
+            ),
+            (
+                Violation::MissingClass {
+                    charset: Charset::Lower,
+                    label: None,
+                    min: 1,
+                    count: 0,
+                },
+                "missing_class:lower",
+            ),
+            (
+                Violation::MissingClass {
+                    charset: Charset::Base58,
+                    label: None,
+                    min: 1,
+                    count: 0,
+                },
+                "missing_class:base58",
+            ),
+            (
+                Violation::ExcessClass {
+                    charset: Charset::Custom(vec!['a']),
+                    label: None,
+                    max: 0,
+                    count: 1,
+                },
+                "excess_class:custom",
+            ),
+            (
+                Violation::DisallowedChar {
+                    c: '"',
+                    position: 3,
+                },
+                "disallowed_char",
+            ),
+        ];
+        for (violation, code) in cases {
+            assert_eq!(violation.code(), code);
+        }
+    }
+
+    #[test]
+    fn crafted_passwords() {
+        let spec = spec();
+        assert!(spec.violations("ABcdef12").is_empty());
+        assert!(spec.matches("ABcdef12"));
+        let codes = |password: &str| {
+            spec.violations(password)
+                .iter()
+                .map(Violation::code)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(codes("abc"), vec!["too_short", "missing_class:upper"]);
+        assert_eq!(codes("Abcdef123"), vec!["too_long", "excess_class:number"]);
+        assert_eq!(codes("Abcdefg!"), vec!["count_not_allowed:symbol"]);
+        assert_eq!(codes("Abcdef\"g"), vec!["disallowed_char"]);
+        assert_eq!(
+            spec.violations("Abcdef\"g")[0].to_string(),
+            "Character 7 `\"` isn't allowed"
+        );
+
+        let affixed = spec.prefix("id-");
+        assert_eq!(
+            affixed.violations("ABcdef12"),
+            vec![Violation::MissingPrefix("id-".to_string())]
+        );
+        assert!(affixed.violations("id-ABcdef12").is_empty());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_shape() {
+        let spec = spec();
+        let json: Vec<_> = spec
+            .violations("abc\"")
+            .iter()
+            .map(Violation::to_json)
+            .collect();
+        assert_eq!(json.len(), 3);
+        assert_eq!(json[0]["code"], "too_short");
+        assert_eq!(json[0]["detail"]["expected"], 8);
+        assert_eq!(json[0]["detail"]["actual"], 4);
+        assert_eq!(json[1]["code"], "missing_class:upper");
+        assert_eq!(json[1]["detail"]["charset"], ":upper:");
+        assert_eq!(json[1]["detail"]["min"], 1);
+        assert_eq!(json[2]["code"], "disallowed_char");
+        assert_eq!(json[2]["detail"]["char"], "\"");
+        assert_eq!(json[2]["detail"]["position"], 4);
+        assert!(json.iter().all(|v| v["message"].is_string()));
+    }
+}
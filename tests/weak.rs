@@ -0,0 +1,199 @@
+#[cfg(test)]
+mod tests {
+    use pants_gen::{
+        password::PasswordSpec,
+        units::LengthUnit,
+        violation::Violation,
+        weak::{generate, WeakError, WeaknessKind, DICTIONARY_WORDS, SEQUENCE_LENGTH},
+        Charset,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn spec() -> PasswordSpec {
+        "16//1+|:upper://1+|:lower://1+|:number://1+|:symbol:"
+            .parse()
+            .unwrap()
+    }
+
+    fn has_run(password: &str) -> bool {
+        let chars: Vec<char> = password.chars().collect();
+        chars.windows(SEQUENCE_LENGTH).any(|run| {
+            run.iter().all(|c| c.is_ascii_alphanumeric())
+                && run.windows(2).all(|w| w[1] as u32 == w[0] as u32 + 1)
+        })
+    }
+
+    #[test]
+    fn too_short() {
+        let mut rng = StdRng::seed_from_u64(491);
+        for _ in 0..20 {
+            let password = generate(&WeaknessKind::TooShort, &spec(), &mut rng).unwrap();
+            assert_eq!(
+                spec().violations(&password),
+                [Violation::TooShort {
+                    expected: 16,
+                    actual: 15,
+                    unit: LengthUnit::Chars,
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn too_short_keeps_affixes() {
+        let spec = spec()
+            .prefix("ab-")
+            .suffix("!")
+            .length_includes_affixes(true);
+        let mut rng = StdRng::seed_from_u64(1);
+        let password = generate(&WeaknessKind::TooShort, &spec, &mut rng).unwrap();
+        assert!(password.starts_with("ab-") && password.ends_with('!'));
+        assert_eq!(
+            spec.violations(&password),
+            [Violation::TooShort {
+                expected: 12,
+                actual: 11,
+                unit: LengthUnit::Chars,
+            }]
+        );
+    }
+
+    #[test]
+    fn too_short_lowers_minimums_when_needed() {
+        let spec: PasswordSpec = "4//2|:upper://2|:number:".parse().unwrap();
+        let mut rng = StdRng::seed_from_u64(2);
+        let password = generate(&WeaknessKind::TooShort, &spec, &mut rng).unwrap();
+        let violations = spec.violations(&password);
+        assert_eq!(
+            violations[0],
+            Violation::TooShort {
+                expected: 4,
+                actual: 3,
+                unit: LengthUnit::Chars,
+            }
+        );
+        assert_eq!(violations.len(), 2);
+        assert_eq!(
+            generate(
+                &WeaknessKind::TooShort,
+                &PasswordSpec::new().length(0),
+                &mut rng
+            )
+            .unwrap_err()
+            .to_string(),
+            WeakError::NoLength.to_string()
+        );
+    }
+
+    #[test]
+    fn missing_class() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for charset in [Charset::Upper, Charset::Number, Charset::Symbol] {
+            for _ in 0..10 {
+                let kind = WeaknessKind::MissingClass(charset.clone());
+                let password = generate(&kind, &spec(), &mut rng).unwrap();
+                assert_eq!(
+                    spec().violations(&password),
+                    [Violation::MissingClass {
+                        charset: charset.clone(),
+                        label: None,
+                        min: 1,
+                        count: 0
+                    }]
+                );
+            }
+        }
+        let spec: PasswordSpec = "16//1+|:upper://0+|:symbol:".parse().unwrap();
+        assert!(matches!(
+            generate(
+                &WeaknessKind::MissingClass(Charset::Symbol),
+                &spec,
+                &mut rng
+            ),
+            Err(WeakError::NotRequired(Charset::Symbol))
+        ));
+    }
+
+    #[test]
+    fn dictionary_word() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for _ in 0..20 {
+            let password =
+                generate(&WeaknessKind::ContainsDictionaryWord, &spec(), &mut rng).unwrap();
+            assert!(spec().violations(&password).is_empty());
+            let lower = password.to_lowercase();
+            assert!(DICTIONARY_WORDS.iter().any(|word| lower.contains(word)));
+        }
+        let short: PasswordSpec = "4//1+|:lower:".parse().unwrap();
+        assert!(matches!(
+            generate(&WeaknessKind::ContainsDictionaryWord, &short, &mut rng),
+            Err(WeakError::TooShortFor(_))
+        ));
+    }
+
+    #[test]
+    fn dictionary_word_follows_allowed_case() {
+        let spec: PasswordSpec = "12//1+|:upper://1+|:number:".parse().unwrap();
+        let mut rng = StdRng::seed_from_u64(5);
+        let password = generate(&WeaknessKind::ContainsDictionaryWord, &spec, &mut rng).unwrap();
+        assert!(spec.violations(&password).is_empty());
+        assert!(DICTIONARY_WORDS
+            .iter()
+            .any(|word| password.contains(&word.to_uppercase())));
+    }
+
+    #[test]
+    fn sequential() {
+        let mut rng = StdRng::seed_from_u64(6);
+        for _ in 0..20 {
+            let password = generate(&WeaknessKind::Sequential, &spec(), &mut rng).unwrap();
+            assert!(spec().violations(&password).is_empty());
+            assert!(has_run(&password));
+        }
+        let digits: PasswordSpec = "8//8|:number:".parse().unwrap();
+        let password = generate(&WeaknessKind::Sequential, &digits, &mut rng).unwrap();
+        assert!(digits.violations(&password).is_empty());
+        assert!(has_run(&password));
+    }
+
+    #[test]
+    fn sequential_measured_lengths() {
+        let mut rng = StdRng::seed_from_u64(16);
+        // 16 bytes of two-byte letters is 8 characters to write a run over
+        let bytes: PasswordSpec = "16B//0+|\u{e9}\u{e8}\u{ea}\u{eb}".parse().unwrap();
+        for _ in 0..5 {
+            let password = generate(&WeaknessKind::Sequential, &bytes, &mut rng).unwrap();
+            assert_eq!(password.chars().count(), 8);
+            assert!(has_run(&password));
+        }
+        // 2 flags are 4 regional indicators, long enough for a run though only 2 graphemes
+        let graphemes: PasswordSpec = "2g//0+|\u{1f1e6}\u{1f1e7}".parse().unwrap();
+        let password = generate(&WeaknessKind::Sequential, &graphemes, &mut rng).unwrap();
+        assert_eq!(password.chars().count(), 4);
+        assert!(has_run(&password));
+    }
+
+    #[test]
+    fn kinds_parse() {
+        for kind in [
+            WeaknessKind::TooShort,
+            WeaknessKind::MissingClass(Charset::Symbol),
+            WeaknessKind::ContainsDictionaryWord,
+            WeaknessKind::Sequential,
+        ] {
+            assert_eq!(kind.to_string().parse::<WeaknessKind>().unwrap(), kind);
+        }
+        assert_eq!(
+            "missing-class:symbol".parse::<WeaknessKind>().unwrap(),
+            WeaknessKind::MissingClass(Charset::Symbol)
+        );
+        assert_eq!(
+            "missing-class:!@#".parse::<WeaknessKind>().unwrap(),
+            WeaknessKind::MissingClass(Charset::Custom(vec!['!', '@', '#']))
+        );
+        assert!(matches!(
+            "weakest".parse::<WeaknessKind>(),
+            Err(WeakError::UnknownKind(_))
+        ));
+    }
+}
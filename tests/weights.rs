@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pants_gen::{
+        charset::Charset,
+        password::PasswordSpec,
+        weights::{Weights, WeightsError},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn frequencies_track_weights() {
+        let weights = Weights::new(vec![('a', 1.0), ('c', 2.0)]).unwrap();
+        let pool = vec!['a', 'b', 'c'];
+        let mut rng = StdRng::seed_from_u64(42);
+        let samples = 40_000;
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for _ in 0..samples {
+            *counts
+                .entry(weights.choose(&pool, &mut rng).unwrap())
+                .or_default() += 1;
+        }
+        // b isn't in the table so gets the default weight of 1.0
+        for (c, expected) in [('a', 0.25), ('b', 0.25), ('c', 0.5)] {
+            let freq = counts[&c] as f64 / samples as f64;
+            assert!((freq - expected).abs() < 0.02, "{c}: {freq}");
+        }
+    }
+
+    #[test]
+    fn bad_weights_rejected() {
+        assert!(matches!(
+            Weights::new(vec![('a', 0.0)]),
+            Err(WeightsError::BadWeight('a', _))
+        ));
+        assert!(matches!(
+            Weights::new(vec![('a', -1.0)]),
+            Err(WeightsError::BadWeight('a', _))
+        ));
+        assert!(matches!(
+            Weights::new(vec![('a', 1.0), ('a', 2.0)]),
+            Err(WeightsError::Duplicate('a'))
+        ));
+        // each is fine but they can't be added up
+        assert!(matches!(
+            Weights::new(vec![('a', 1e308), ('b', 1e308)]),
+            Err(WeightsError::TotalTooLarge(_))
+        ));
+        assert!(Weights::new(vec![('a', 1e307), ('b', 1e307)]).is_ok());
+    }
+
+    #[test]
+    fn entropy_of_weights() {
+        // p = 1/4, 1/4, 1/2 -> 1/4*2 + 1/4*2 + 1/2*1 = 1.5 bits
+        let weights = Weights::new(vec![('a', 1.0), ('b', 1.0), ('c', 2.0)]).unwrap();
+        assert!((weights.entropy(&['a', 'b', 'c']) - 1.5).abs() < 1e-12);
+
+        let choice = Charset::Custom(vec!['a', 'b', 'c', 'd'])
+            .at_least(1)
+            .weighted(Weights::new(vec![('a', 1.0), ('b', 1.0), ('c', 1.0), ('d', 1.0)]).unwrap());
+        assert!((choice.entropy() - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn weights_from_csv() {
+        let path = std::env::temp_dir().join("pants_gen_weights_from_csv.csv");
+        std::fs::write(&path, "a,2.5\n\n,,0.5\n").unwrap();
+        let weights = Weights::from_csv(&path).unwrap();
+        assert_eq!(weights.weight('a'), 2.5);
+        assert_eq!(weights.weight(','), 0.5);
+        assert_eq!(weights.weight('z'), 1.0);
+
+        // comments and the escaped hash as in every other file
+        std::fs::write(&path, "# frequencies\n  a,2.5 # common\n\\#,3\n").unwrap();
+        let weights = Weights::from_csv(&path).unwrap();
+        assert_eq!(weights.weight('a'), 2.5);
+        assert_eq!(weights.weight('#'), 3.0);
+
+        std::fs::write(&path, "# header\na;2.5\n").unwrap();
+        assert!(matches!(
+            Weights::from_csv(&path),
+            Err(WeightsError::BadLine(2, _))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn weighted_choice_generates() {
+        let weights = Weights::new(vec![('x', 100.0)]).unwrap();
+        let spec = PasswordSpec::new()
+            .length(8)
+            .include(Charset::Custom(vec!['x', 'y']).exactly(8).weighted(weights));
+        let gen = spec.generate().unwrap();
+        assert_eq!(gen.chars().count(), 8);
+        assert!(gen.chars().all(|c| c == 'x' || c == 'y'));
+    }
+}
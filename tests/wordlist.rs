@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, path::Path};
+
+    use pants_gen::wordlist::{Wordlist, WordlistError, RECOMMENDED_MIN_WORDS};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn numbered(n: usize) -> String {
+        (0..n).map(|i| format!("word{}\n", i)).collect()
+    }
+
+    #[test]
+    fn words_from_stdin() {
+        let stdin = Cursor::new(numbered(RECOMMENDED_MIN_WORDS));
+        let list = Wordlist::from_sources(None, Some(stdin)).unwrap();
+        assert_eq!(list.len(), RECOMMENDED_MIN_WORDS);
+        assert!(list.size_warning().is_none());
+
+        let mut rng = StdRng::seed_from_u64(5);
+        let passphrase = list.passphrase_with_rng(5, "-", &mut rng);
+        let words: Vec<_> = passphrase.split('-').collect();
+        assert_eq!(words.len(), 5);
+        assert!(words.iter().all(|w| list.words().iter().any(|l| l == w)));
+    }
+
+    #[test]
+    fn tiny_list() {
+        let stdin = Cursor::new("  apple\nbanana\n\napple\ncherry  \nbanana\n");
+        let list = Wordlist::from_sources(None, Some(stdin)).unwrap();
+        assert_eq!(list.words(), ["apple", "banana", "cherry"]);
+        let warning = list.size_warning().unwrap();
+        assert!(warning.contains("only has 3 distinct words"));
+
+        let empty = Wordlist::from_reader(Cursor::new("\n  \n"));
+        assert!(matches!(empty, Err(WordlistError::Empty)));
+    }
+
+    #[test]
+    fn conflicting_sources() {
+        let stdin = Cursor::new("apple\n");
+        let err = Wordlist::from_sources(Some(Path::new("words.txt")), Some(stdin)).unwrap_err();
+        assert!(matches!(err, WordlistError::ConflictingSources));
+        assert_eq!(
+            err.to_string(),
+            "Words can come from a wordlist file or stdin, not both"
+        );
+        assert!(matches!(
+            Wordlist::from_sources::<Cursor<&str>>(None, None),
+            Err(WordlistError::NoSource)
+        ));
+    }
+}